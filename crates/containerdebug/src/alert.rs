@@ -0,0 +1,207 @@
+//! Threshold rules that are checked against every collected [`Snapshot`], and logged at warn level
+//! when violated.
+use std::{fmt::Display, str::FromStr};
+
+use serde_json::Value;
+use snafu::{OptionExt, ResultExt, Snafu};
+
+use crate::snapshot::{FieldPattern, ParsePatternError, Snapshot};
+
+#[derive(Debug, PartialEq, Eq, Snafu)]
+pub enum ParseRuleError {
+    #[snafu(display(
+        "alert rule {rule:?} has no comparison operator, expected one of >, >=, <, <=, == or !="
+    ))]
+    MissingOperator { rule: String },
+
+    #[snafu(display("alert rule {rule:?} has an invalid field pattern"))]
+    InvalidPattern {
+        source: ParsePatternError,
+        rule: String,
+    },
+
+    #[snafu(display(
+        "alert rule {rule:?} compares with {operator}, which requires a numeric threshold"
+    ))]
+    NonNumericThreshold { rule: String, operator: Operator },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
+    GreaterThan,
+    GreaterThanOrEqual,
+    LessThan,
+    LessThanOrEqual,
+    Equal,
+    NotEqual,
+}
+
+impl Operator {
+    /// All operators, ordered so that two-character operators are matched before their prefixes.
+    const ALL: [(&str, Self); 6] = [
+        (">=", Self::GreaterThanOrEqual),
+        ("<=", Self::LessThanOrEqual),
+        ("==", Self::Equal),
+        ("!=", Self::NotEqual),
+        (">", Self::GreaterThan),
+        ("<", Self::LessThan),
+    ];
+
+    fn is_ordering(self) -> bool {
+        !matches!(self, Self::Equal | Self::NotEqual)
+    }
+}
+
+impl Display for Operator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (symbol, _) = Self::ALL
+            .iter()
+            .find(|(_, operator)| operator == self)
+            .ok_or(std::fmt::Error)?;
+        f.write_str(symbol)
+    }
+}
+
+/// A rule of the form `<field pattern> <operator> <threshold>`, such as
+/// `disks[*].usage_percent > 90` or `resources.available_memory < 1073741824`.
+///
+/// The rule is violated by every field matching the pattern for which the comparison is true.
+/// Ordering operators (`>`, `>=`, `<`, `<=`) only apply to numeric fields, `==` and `!=` compare
+/// with the threshold parsed as JSON (falling back to a plain string, so `os.name == Alpine` works).
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlertRule {
+    rule: String,
+    pattern: FieldPattern,
+    operator: Operator,
+    threshold: Value,
+}
+
+impl FromStr for AlertRule {
+    type Err = ParseRuleError;
+
+    fn from_str(rule: &str) -> Result<Self, Self::Err> {
+        let (index, symbol, operator) = rule
+            .char_indices()
+            .find_map(|(index, _)| {
+                Operator::ALL
+                    .iter()
+                    .find(|(symbol, _)| rule[index..].starts_with(symbol))
+                    .map(|(symbol, operator)| (index, *symbol, *operator))
+            })
+            .context(MissingOperatorSnafu { rule })?;
+
+        let pattern = rule[..index]
+            .trim()
+            .parse()
+            .context(InvalidPatternSnafu { rule })?;
+        let threshold = rule[index + symbol.len()..].trim();
+        let threshold =
+            serde_json::from_str(threshold).unwrap_or_else(|_| Value::String(threshold.to_owned()));
+        if operator.is_ordering() && !threshold.is_number() {
+            return NonNumericThresholdSnafu { rule, operator }.fail();
+        }
+
+        Ok(Self {
+            rule: rule.to_owned(),
+            pattern,
+            operator,
+            threshold,
+        })
+    }
+}
+
+impl Display for AlertRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.rule)
+    }
+}
+
+impl AlertRule {
+    /// Logs a warning for every field in `snapshot` that violates the rule, and returns the number
+    /// of violations.
+    pub fn check(&self, snapshot: &Snapshot) -> usize {
+        snapshot
+            .matching(&self.pattern)
+            .filter(|(_, value)| self.is_violated_by(value))
+            .inspect(|(path, value)| {
+                tracing::warn!(
+                    alert.rule = %self,
+                    alert.field = %path,
+                    alert.value = %value,
+                    "alert rule violated"
+                );
+            })
+            .count()
+    }
+
+    fn is_violated_by(&self, value: &Value) -> bool {
+        let compare =
+            |violated: fn(f64, f64) -> bool| match (value.as_f64(), self.threshold.as_f64()) {
+                (Some(value), Some(threshold)) => violated(value, threshold),
+                _ => false,
+            };
+        match self.operator {
+            Operator::Equal => *value == self.threshold,
+            Operator::NotEqual => *value != self.threshold,
+            Operator::GreaterThan => compare(|value, threshold| value > threshold),
+            Operator::GreaterThanOrEqual => compare(|value, threshold| value >= threshold),
+            Operator::LessThan => compare(|value, threshold| value < threshold),
+            Operator::LessThanOrEqual => compare(|value, threshold| value <= threshold),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn parse_rules() {
+        let rule: AlertRule = "disks[*].usage_percent>=90".parse().unwrap();
+        assert_eq!(rule.operator, Operator::GreaterThanOrEqual);
+        assert_eq!(rule.threshold, json!(90));
+
+        let rule: AlertRule = "os.name == Alpine Linux".parse().unwrap();
+        assert_eq!(rule.operator, Operator::Equal);
+        assert_eq!(rule.threshold, json!("Alpine Linux"));
+
+        assert!(matches!(
+            "resources.available_memory".parse::<AlertRule>(),
+            Err(ParseRuleError::MissingOperator { .. })
+        ));
+        assert!(matches!(
+            "os.name > Alpine".parse::<AlertRule>(),
+            Err(ParseRuleError::NonNumericThreshold { .. })
+        ));
+        assert!(matches!(
+            " < 10".parse::<AlertRule>(),
+            Err(ParseRuleError::InvalidPattern { .. })
+        ));
+    }
+
+    #[test]
+    fn check_rules() {
+        let snapshot = Snapshot::new(&json!({
+            "resources": { "available_memory": 512 },
+            "disks": [
+                { "mount_point": "/", "usage_percent": 50.0 },
+                { "mount_point": "/data", "usage_percent": 95.0 },
+                { "mount_point": "/logs", "usage_percent": 91.5 },
+            ],
+            "os": { "name": "Alpine" },
+        }));
+
+        let violations = |rule: &str| rule.parse::<AlertRule>().unwrap().check(&snapshot);
+        assert_eq!(violations("disks[*].usage_percent > 90"), 2);
+        assert_eq!(violations("disks[/].usage_percent > 90"), 0);
+        assert_eq!(violations("resources.available_memory < 1024"), 1);
+        assert_eq!(violations("resources.available_memory <= 512"), 1);
+        assert_eq!(violations("resources.available_memory < 512"), 0);
+        assert_eq!(violations("os.name != Alpine"), 0);
+        assert_eq!(violations("os.name == Alpine"), 1);
+        // Ordering comparisons don't apply to non-numeric fields
+        assert_eq!(violations("os.name > 0"), 0);
+    }
+}
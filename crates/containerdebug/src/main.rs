@@ -3,9 +3,15 @@ use std::{path::PathBuf, time::Instant};
 use clap::Parser;
 use stackable_telemetry::{Tracing, tracing::TelemetryOptions};
 
-use crate::system_information::SystemInformation;
+use crate::{
+    alert::AlertRule,
+    snapshot::{DEFAULT_DIFF_IGNORE, FieldPattern, Snapshot},
+    system_information::{SystemInformation, connectivity::ProbeTarget},
+};
 
+mod alert;
 mod error;
+mod snapshot;
 mod system_information;
 
 const APP_NAME: &str = "containerdebug";
@@ -27,6 +33,26 @@ struct Opts {
     #[clap(long, short = 'o')]
     output: Option<PathBuf>,
 
+    /// Probe the connectivity to TARGET, which is either `host:port` or a URL with one of the
+    /// schemes `tcp`, `tls`, `http` or `https` (e.g. `tls://kafka:9093?ca_file=/path/to/ca.crt`)
+    #[clap(long = "probe", value_name = "TARGET")]
+    probe_targets: Vec<ProbeTarget>,
+
+    /// Probe the connectivity to all targets listed in FILE, as a JSON list of objects with the
+    /// keys `host`, `port`, `tls` (with the optional keys `ca_file` and `server_name`) and `http_path`
+    #[clap(long = "probe-file", value_name = "FILE")]
+    probe_targets_file: Option<PathBuf>,
+
+    /// Log a warning whenever RULE is violated, where RULE has the format `FIELD OPERATOR THRESHOLD`
+    /// (e.g. `disks[*].usage_percent > 90` or `resources.available_memory < 1073741824`)
+    #[clap(long = "alert", value_name = "RULE")]
+    alert_rules: Vec<AlertRule>,
+
+    /// Don't report changes of fields matching PATTERN between runs in `--loop` mode
+    /// (e.g. `network.reverse_lookups` or `**.latency_ms`)
+    #[clap(long = "diff-ignore", value_name = "PATTERN")]
+    diff_ignore: Vec<FieldPattern>,
+
     #[clap(flatten)]
     pub telemetry_arguments: TelemetryOptions,
 }
@@ -55,7 +81,27 @@ async fn main() {
         name = built_info::PKG_NAME
     );
 
-    let mut collect_ctx = SystemInformation::init();
+    let mut probe_targets = opts.probe_targets;
+    if let Some(probe_targets_file) = &opts.probe_targets_file {
+        match ProbeTarget::load_from_file(probe_targets_file) {
+            Ok(targets) => probe_targets.extend(targets),
+            Err(err) => tracing::error!(
+                error = &err as &dyn std::error::Error,
+                "failed to load probe targets, ignoring..."
+            ),
+        }
+    }
+
+    let mut collect_ctx = SystemInformation::init(probe_targets);
+
+    let mut diff_ignore = opts.diff_ignore;
+    diff_ignore.extend(
+        DEFAULT_DIFF_IGNORE
+            .iter()
+            .filter_map(|pattern| pattern.parse().ok()),
+    );
+    // The snapshot of the previous run, to report what changed in `--loop` mode
+    let mut previous_snapshot: Option<Snapshot> = None;
 
     let mut next_run = Instant::now();
 
@@ -92,6 +138,32 @@ async fn main() {
             }
         }
 
+        match serde_json::to_value(&system_information) {
+            Ok(value) => {
+                let snapshot = Snapshot::new(&value);
+                if let Some(previous_snapshot) = &previous_snapshot {
+                    let changes = snapshot.diff(previous_snapshot, &diff_ignore);
+                    changes.iter().for_each(|change| change.log());
+                    tracing::info!(changes = changes.len(), "compared with the previous run");
+                }
+                let violations: usize = opts
+                    .alert_rules
+                    .iter()
+                    .map(|rule| rule.check(&snapshot))
+                    .sum();
+                if violations > 0 {
+                    tracing::warn!(violations, "alert rules violated");
+                }
+                previous_snapshot = Some(snapshot);
+            }
+            Err(err) => {
+                tracing::error!(
+                    error = &err as &dyn std::error::Error,
+                    "failed to snapshot system information, skipping comparison and alerts"
+                );
+            }
+        }
+
         match opts.loop_interval {
             Some(interval) => next_run += interval,
             None => break,
@@ -0,0 +1,418 @@
+//! Compares the collected information between consecutive runs, so that changes (such as a disk
+//! filling up or a DNS answer flipping) stand out in `--loop` mode.
+use std::{collections::BTreeMap, fmt::Display, str::FromStr};
+
+use serde_json::Value;
+use snafu::{Snafu, ensure};
+
+/// Fields that are expected to change on every run, and would drown out interesting changes.
+pub const DEFAULT_DIFF_IGNORE: &[&str] = &[
+    "**.latency_ms",
+    "cgroup.cpu.usage_usec",
+    "cgroup.cpu.nr_periods",
+    "processes.processes[*].memory",
+    "processes.processes[*].virtual_memory",
+    "processes.processes[*].run_time",
+];
+
+/// Keys that identify an element of a list of objects, in order of preference.
+///
+/// List elements are addressed by these instead of by their index, so that reordering a list (or
+/// removing one of its elements) doesn't show up as a change of every following element.
+const IDENTITY_KEYS: &[&str] = &["mount_point", "target", "pid", "name"];
+
+#[derive(Debug, PartialEq, Eq, Snafu)]
+pub enum ParsePatternError {
+    #[snafu(display("field pattern must not be empty"))]
+    Empty,
+
+    #[snafu(display("field pattern {pattern:?} contains an empty segment"))]
+    EmptySegment { pattern: String },
+
+    #[snafu(display("field pattern {pattern:?} contains an unterminated list element"))]
+    UnterminatedElement { pattern: String },
+}
+
+/// A single segment of a [`FieldPath`].
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Segment {
+    /// A key of an object.
+    Key(String),
+    /// An element of a list, identified by one of the [`IDENTITY_KEYS`] or its index.
+    Element(String),
+}
+
+/// The path of a field within the collected information, such as
+/// `disks[/var/lib/data].usage_percent`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct FieldPath(Vec<Segment>);
+
+impl Display for FieldPath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (index, segment) in self.0.iter().enumerate() {
+            match segment {
+                Segment::Key(key) if index == 0 => write!(f, "{key}")?,
+                Segment::Key(key) => write!(f, ".{key}")?,
+                Segment::Element(element) => write!(f, "[{element}]")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A single segment of a [`FieldPattern`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum PatternSegment {
+    Key(String),
+    Element(String),
+    /// `*` or `[*]`, matches exactly one segment.
+    AnyOne,
+    /// `**`, matches any number of segments (including none).
+    AnyMany,
+}
+
+/// A pattern that matches [`FieldPath`]s, using the same syntax as their [`Display`]
+/// implementation, with `*` (or `[*]`) matching any single segment and `**` matching any number of
+/// segments. A pattern also matches all fields nested below the matched path.
+///
+/// For example, `disks[*].usage_percent` matches the usage of all disks, and `**.latency_ms`
+/// matches all latencies.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldPattern {
+    pattern: String,
+    segments: Vec<PatternSegment>,
+}
+
+impl FromStr for FieldPattern {
+    type Err = ParsePatternError;
+
+    fn from_str(pattern: &str) -> Result<Self, Self::Err> {
+        ensure!(!pattern.is_empty(), EmptySnafu);
+
+        let mut segments = Vec::new();
+        let mut rest = pattern;
+        while !rest.is_empty() {
+            if let Some(element) = rest.strip_prefix('[') {
+                let (element, remainder) = element
+                    .split_once(']')
+                    .ok_or_else(|| UnterminatedElementSnafu { pattern }.build())?;
+                segments.push(match element {
+                    "*" => PatternSegment::AnyOne,
+                    element => PatternSegment::Element(element.to_owned()),
+                });
+                rest = remainder;
+            } else {
+                let key = rest.strip_prefix('.').unwrap_or(rest);
+                let end = key.find(['.', '[']).unwrap_or(key.len());
+                let (key, remainder) = key.split_at(end);
+                segments.push(match key {
+                    "" => return EmptySegmentSnafu { pattern }.fail(),
+                    "*" => PatternSegment::AnyOne,
+                    "**" => PatternSegment::AnyMany,
+                    key => PatternSegment::Key(key.to_owned()),
+                });
+                rest = remainder;
+            }
+        }
+
+        Ok(Self {
+            pattern: pattern.to_owned(),
+            segments,
+        })
+    }
+}
+
+impl Display for FieldPattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.pattern)
+    }
+}
+
+impl FieldPattern {
+    /// Returns whether the pattern matches `path` or any of its parents.
+    pub fn matches(&self, path: &FieldPath) -> bool {
+        fn matches_prefix(pattern: &[PatternSegment], path: &[Segment]) -> bool {
+            match (pattern.split_first(), path.split_first()) {
+                (None, _) => true,
+                (Some((PatternSegment::AnyMany, pattern_rest)), _) => {
+                    (0..=path.len()).any(|skip| matches_prefix(pattern_rest, &path[skip..]))
+                }
+                (Some(_), None) => false,
+                (Some((pattern_segment, pattern_rest)), Some((segment, path_rest))) => {
+                    let segment_matches = match (pattern_segment, segment) {
+                        (PatternSegment::AnyOne, _) => true,
+                        (PatternSegment::Key(expected), Segment::Key(key)) => expected == key,
+                        (PatternSegment::Element(expected), Segment::Element(element)) => {
+                            expected == element
+                        }
+                        _ => false,
+                    };
+                    segment_matches && matches_prefix(pattern_rest, path_rest)
+                }
+            }
+        }
+        matches_prefix(&self.segments, &path.0)
+    }
+}
+
+/// A flattened view of the collected information, mapping the path of every leaf field to its
+/// value.
+#[derive(Debug, Default)]
+pub struct Snapshot {
+    fields: BTreeMap<FieldPath, Value>,
+}
+
+/// A difference between two consecutive [`Snapshot`]s.
+#[derive(Debug, PartialEq)]
+pub enum Change {
+    Added {
+        path: FieldPath,
+        value: Value,
+    },
+    Removed {
+        path: FieldPath,
+        value: Value,
+    },
+    Changed {
+        path: FieldPath,
+        old: Value,
+        new: Value,
+    },
+}
+
+impl Snapshot {
+    pub fn new(value: &Value) -> Self {
+        let mut snapshot = Self::default();
+        snapshot.flatten(value, &mut FieldPath::default());
+        snapshot
+    }
+
+    /// Iterates over all leaf fields that match `pattern`.
+    pub fn matching<'a>(
+        &'a self,
+        pattern: &'a FieldPattern,
+    ) -> impl Iterator<Item = (&'a FieldPath, &'a Value)> + 'a {
+        self.fields.iter().filter(|(path, _)| pattern.matches(path))
+    }
+
+    /// Lists all fields that changed from `previous` to `self`, skipping fields matching any of
+    /// the `ignore` patterns.
+    pub fn diff(&self, previous: &Self, ignore: &[FieldPattern]) -> Vec<Change> {
+        let is_ignored = |path: &FieldPath| ignore.iter().any(|pattern| pattern.matches(path));
+
+        let removed = previous
+            .fields
+            .iter()
+            .filter(|(path, _)| !self.fields.contains_key(*path))
+            .map(|(path, value)| Change::Removed {
+                path: path.clone(),
+                value: value.clone(),
+            });
+        let added_or_changed =
+            self.fields
+                .iter()
+                .filter_map(|(path, value)| match previous.fields.get(path) {
+                    None => Some(Change::Added {
+                        path: path.clone(),
+                        value: value.clone(),
+                    }),
+                    Some(old) if old != value => Some(Change::Changed {
+                        path: path.clone(),
+                        old: old.clone(),
+                        new: value.clone(),
+                    }),
+                    Some(_) => None,
+                });
+
+        let mut changes: Vec<Change> = removed
+            .chain(added_or_changed)
+            .filter(|change| !is_ignored(change.path()))
+            .collect();
+        changes.sort_by(|a, b| a.path().cmp(b.path()));
+        changes
+    }
+
+    fn flatten(&mut self, value: &Value, path: &mut FieldPath) {
+        match value {
+            Value::Object(object) if !object.is_empty() => {
+                for (key, value) in object {
+                    path.0.push(Segment::Key(key.clone()));
+                    self.flatten(value, path);
+                    path.0.pop();
+                }
+            }
+            Value::Array(array) if !array.is_empty() => {
+                let mut seen_identities = BTreeMap::<String, usize>::new();
+                for (index, value) in array.iter().enumerate() {
+                    let identity = match element_identity(value) {
+                        Some(identity) => {
+                            // Identities are not necessarily unique (for example when multiple
+                            // filesystems are mounted on top of each other), so disambiguate them
+                            let seen = seen_identities.entry(identity.clone()).or_default();
+                            *seen += 1;
+                            match seen {
+                                1 => identity,
+                                seen => format!("{identity}#{seen}"),
+                            }
+                        }
+                        None => index.to_string(),
+                    };
+                    path.0.push(Segment::Element(identity));
+                    self.flatten(value, path);
+                    path.0.pop();
+                }
+            }
+            leaf => {
+                self.fields.insert(path.clone(), leaf.clone());
+            }
+        }
+    }
+}
+
+impl Change {
+    pub fn path(&self) -> &FieldPath {
+        match self {
+            Self::Added { path, .. } | Self::Removed { path, .. } | Self::Changed { path, .. } => {
+                path
+            }
+        }
+    }
+
+    /// Emits the change as a tracing event.
+    pub fn log(&self) {
+        match self {
+            Self::Added { path, value } => {
+                tracing::info!(change.path = %path, change.new = %value, "field added");
+            }
+            Self::Removed { path, value } => {
+                tracing::info!(change.path = %path, change.old = %value, "field removed");
+            }
+            Self::Changed { path, old, new } => {
+                tracing::info!(
+                    change.path = %path,
+                    change.old = %old,
+                    change.new = %new,
+                    "field changed"
+                );
+            }
+        }
+    }
+}
+
+fn element_identity(value: &Value) -> Option<String> {
+    let object = value.as_object()?;
+    IDENTITY_KEYS
+        .iter()
+        .find_map(|key| match object.get(*key)? {
+            Value::String(identity) => Some(identity.clone()),
+            Value::Number(identity) => Some(identity.to_string()),
+            _ => None,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn path(pattern: &str) -> FieldPath {
+        let pattern: FieldPattern = pattern.parse().unwrap();
+        FieldPath(
+            pattern
+                .segments
+                .into_iter()
+                .map(|segment| match segment {
+                    PatternSegment::Key(key) => Segment::Key(key),
+                    PatternSegment::Element(element) => Segment::Element(element),
+                    _ => panic!("paths must not contain wildcards"),
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn match_patterns() {
+        let usage = path("disks[/data].usage_percent");
+        assert_eq!(usage.to_string(), "disks[/data].usage_percent");
+
+        let matches = |pattern: &str, path: &FieldPath| {
+            pattern.parse::<FieldPattern>().unwrap().matches(path)
+        };
+        assert!(matches("disks[*].usage_percent", &usage));
+        assert!(matches("disks[/data]", &usage));
+        assert!(matches("disks", &usage));
+        assert!(matches("**.usage_percent", &usage));
+        assert!(matches("*.*.usage_percent", &usage));
+        assert!(!matches("disks[/].usage_percent", &usage));
+        assert!(!matches("disks[*].total_space", &usage));
+        assert!(!matches("**.latency_ms", &usage));
+
+        assert_eq!("".parse::<FieldPattern>(), Err(ParsePatternError::Empty));
+        assert!("disks..name".parse::<FieldPattern>().is_err());
+        assert!("disks[*.name".parse::<FieldPattern>().is_err());
+    }
+
+    #[test]
+    fn diff_snapshots() {
+        let previous = Snapshot::new(&json!({
+            "resources": { "total_memory_cgroup": 1024, "free_memory": 100 },
+            "disks": [
+                { "mount_point": "/", "usage_percent": 50.0 },
+                { "mount_point": "/data", "usage_percent": 80.0 },
+            ],
+            "network": { "forward_lookups": { "pod": ["10.0.0.1"] } },
+            "connectivity": [{ "target": "tcp://zk:2181", "tcp": { "latency_ms": 1.0 } }],
+        }));
+        let current = Snapshot::new(&json!({
+            "resources": { "total_memory_cgroup": 2048, "free_memory": 100 },
+            "disks": [
+                { "mount_point": "/data", "usage_percent": 95.0 },
+                { "mount_point": "/", "usage_percent": 50.0 },
+            ],
+            "network": { "forward_lookups": { "pod": ["10.0.0.2"] } },
+            "connectivity": [{ "target": "tcp://zk:2181", "tcp": { "latency_ms": 2.0 } }],
+        }));
+
+        let ignore = ["**.latency_ms".parse().unwrap()];
+        assert_eq!(
+            current.diff(&previous, &ignore),
+            vec![
+                Change::Changed {
+                    path: path("disks[/data].usage_percent"),
+                    old: json!(80.0),
+                    new: json!(95.0),
+                },
+                Change::Changed {
+                    path: path("network.forward_lookups.pod[0]"),
+                    old: json!("10.0.0.1"),
+                    new: json!("10.0.0.2"),
+                },
+                Change::Changed {
+                    path: path("resources.total_memory_cgroup"),
+                    old: json!(1024),
+                    new: json!(2048),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn disambiguate_duplicate_identities() {
+        let snapshot = Snapshot::new(&json!({
+            "mounts": [
+                { "mount_point": "/", "filesystem_type": "ext4" },
+                { "mount_point": "/", "filesystem_type": "overlay" },
+            ],
+        }));
+        let pattern = "mounts[*].filesystem_type".parse().unwrap();
+        let paths: Vec<String> = snapshot
+            .matching(&pattern)
+            .map(|(path, _)| path.to_string())
+            .collect();
+        assert_eq!(
+            paths,
+            ["mounts[/].filesystem_type", "mounts[/#2].filesystem_type"]
+        );
+    }
+}
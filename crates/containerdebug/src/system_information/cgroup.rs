@@ -0,0 +1,317 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use serde::Serialize;
+use snafu::{OptionExt, ResultExt, Snafu};
+
+const PROC_SELF_CGROUP: &str = "/proc/self/cgroup";
+const CGROUP_MOUNT_POINT: &str = "/sys/fs/cgroup";
+
+#[derive(Debug, Snafu)]
+pub enum Error {
+    #[snafu(display("failed to read {path:?}"))]
+    ReadFile {
+        source: std::io::Error,
+        path: PathBuf,
+    },
+
+    #[snafu(display(
+        "no unified (v2) cgroup hierarchy found in {PROC_SELF_CGROUP:?}, only cgroup v2 is supported"
+    ))]
+    NoUnifiedHierarchy,
+}
+type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// Limits and usage statistics of the cgroup (v2) that the current process is running in.
+///
+/// Values that are not available (for example because the controller is not enabled for the cgroup)
+/// are reported as [`None`]. Limits that are set to `max` are reported as [`CgroupLimit::Unlimited`].
+#[derive(Debug, Serialize)]
+pub struct Cgroup {
+    /// The path of the cgroup, relative to the cgroup root.
+    pub path: String,
+    pub cpu: CgroupCpu,
+    pub memory: CgroupMemory,
+    pub pids: CgroupPids,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CgroupCpu {
+    /// The CPU time (in microseconds) that the cgroup may use per [`Self::period_usec`].
+    pub quota_usec: Option<CgroupLimit>,
+    pub period_usec: Option<u64>,
+    /// The effective number of CPUs, calculated as `quota / period`.
+    pub effective_cpus: Option<f64>,
+    pub weight: Option<u64>,
+
+    pub usage_usec: Option<u64>,
+    pub nr_periods: Option<u64>,
+    pub nr_throttled: Option<u64>,
+    pub throttled_usec: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CgroupMemory {
+    pub max: Option<CgroupLimit>,
+    pub high: Option<CgroupLimit>,
+    pub current: Option<u64>,
+    pub peak: Option<u64>,
+    pub swap_max: Option<CgroupLimit>,
+    pub swap_current: Option<u64>,
+
+    /// The number of times the cgroup hit its memory limit (`memory.events`).
+    pub events_max: Option<u64>,
+    pub events_oom: Option<u64>,
+    pub events_oom_kill: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CgroupPids {
+    pub max: Option<CgroupLimit>,
+    pub current: Option<u64>,
+}
+
+/// A cgroup limit, which is either a concrete value or `max`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CgroupLimit {
+    Limited(u64),
+    Unlimited,
+}
+
+impl Serialize for CgroupLimit {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Limited(value) => serializer.serialize_u64(*value),
+            Self::Unlimited => serializer.serialize_str("max"),
+        }
+    }
+}
+
+impl CgroupLimit {
+    fn parse(value: &str) -> Option<Self> {
+        match value.trim() {
+            "max" => Some(Self::Unlimited),
+            value => value.parse().ok().map(Self::Limited),
+        }
+    }
+
+    pub fn as_limited(&self) -> Option<u64> {
+        match self {
+            Self::Limited(value) => Some(*value),
+            Self::Unlimited => None,
+        }
+    }
+}
+
+impl Cgroup {
+    #[tracing::instrument(name = "Cgroup::collect")]
+    pub fn collect() -> Result<Self> {
+        let proc_cgroup = read_to_string(Path::new(PROC_SELF_CGROUP))?;
+        // cgroup v2 entries have the format `0::<path>`, v1 entries have a non-empty controller list
+        let path = proc_cgroup
+            .lines()
+            .find_map(|line| line.strip_prefix("0::"))
+            .context(NoUnifiedHierarchySnafu)?
+            .to_owned();
+        // Inside of a container the cgroup namespace root is usually mounted at the mount point directly
+        let mut dir = Path::new(CGROUP_MOUNT_POINT).join(path.trim_start_matches('/'));
+        if !dir.join("cgroup.controllers").exists() {
+            dir = PathBuf::from(CGROUP_MOUNT_POINT);
+        }
+        tracing::info!(cgroup.path = path, cgroup.dir = %dir.display(), "cgroup");
+
+        let cgroup = Self {
+            path,
+            cpu: CgroupCpu::collect(&dir),
+            memory: CgroupMemory::collect(&dir),
+            pids: CgroupPids::collect(&dir),
+        };
+        Ok(cgroup)
+    }
+}
+
+impl CgroupCpu {
+    fn collect(dir: &Path) -> Self {
+        // cpu.max has the format `<quota|max> <period>`
+        let (quota_usec, period_usec) = read_optional(&dir.join("cpu.max"))
+            .map(|cpu_max| {
+                let mut parts = cpu_max.split_whitespace();
+                let quota = parts.next().and_then(CgroupLimit::parse);
+                let period = parts.next().and_then(|period| period.parse().ok());
+                (quota, period)
+            })
+            .unwrap_or_default();
+        let effective_cpus = match (quota_usec.and_then(|quota| quota.as_limited()), period_usec) {
+            (Some(quota), Some(period)) if period > 0 => Some(quota as f64 / period as f64),
+            _ => None,
+        };
+        let weight = read_optional(&dir.join("cpu.weight")).and_then(|w| w.trim().parse().ok());
+        let stat = read_flat_keyed(&dir.join("cpu.stat"));
+
+        let cpu = Self {
+            quota_usec,
+            period_usec,
+            effective_cpus,
+            weight,
+            usage_usec: stat.get("usage_usec").copied(),
+            nr_periods: stat.get("nr_periods").copied(),
+            nr_throttled: stat.get("nr_throttled").copied(),
+            throttled_usec: stat.get("throttled_usec").copied(),
+        };
+
+        let throttled = cpu.nr_throttled.is_some_and(|throttled| throttled > 0);
+        if throttled {
+            tracing::warn!(
+                cgroup.cpu.quota_usec = ?cpu.quota_usec,
+                cgroup.cpu.period_usec = cpu.period_usec,
+                cgroup.cpu.effective_cpus = cpu.effective_cpus,
+                cgroup.cpu.nr_periods = cpu.nr_periods,
+                cgroup.cpu.nr_throttled = cpu.nr_throttled,
+                cgroup.cpu.throttled_usec = cpu.throttled_usec,
+                "cgroup cpu has been throttled"
+            );
+        } else {
+            tracing::info!(
+                cgroup.cpu.quota_usec = ?cpu.quota_usec,
+                cgroup.cpu.period_usec = cpu.period_usec,
+                cgroup.cpu.effective_cpus = cpu.effective_cpus,
+                cgroup.cpu.weight = cpu.weight,
+                cgroup.cpu.usage_usec = cpu.usage_usec,
+                "cgroup cpu"
+            );
+        }
+        cpu
+    }
+}
+
+impl CgroupMemory {
+    fn collect(dir: &Path) -> Self {
+        let read_limit =
+            |file: &str| read_optional(&dir.join(file)).and_then(|v| CgroupLimit::parse(&v));
+        let read_u64 =
+            |file: &str| read_optional(&dir.join(file)).and_then(|v| v.trim().parse().ok());
+        let events = read_flat_keyed(&dir.join("memory.events"));
+
+        let memory = Self {
+            max: read_limit("memory.max"),
+            high: read_limit("memory.high"),
+            current: read_u64("memory.current"),
+            peak: read_u64("memory.peak"),
+            swap_max: read_limit("memory.swap.max"),
+            swap_current: read_u64("memory.swap.current"),
+            events_max: events.get("max").copied(),
+            events_oom: events.get("oom").copied(),
+            events_oom_kill: events.get("oom_kill").copied(),
+        };
+
+        let oom_killed = memory.events_oom_kill.is_some_and(|kills| kills > 0);
+        if oom_killed {
+            tracing::warn!(
+                cgroup.memory.max = ?memory.max,
+                cgroup.memory.current = memory.current,
+                cgroup.memory.events.oom = memory.events_oom,
+                cgroup.memory.events.oom_kill = memory.events_oom_kill,
+                "processes in cgroup have been OOM killed"
+            );
+        } else {
+            tracing::info!(
+                cgroup.memory.max = ?memory.max,
+                cgroup.memory.high = ?memory.high,
+                cgroup.memory.current = memory.current,
+                cgroup.memory.peak = memory.peak,
+                cgroup.memory.swap.max = ?memory.swap_max,
+                cgroup.memory.swap.current = memory.swap_current,
+                "cgroup memory"
+            );
+        }
+        memory
+    }
+}
+
+impl CgroupPids {
+    fn collect(dir: &Path) -> Self {
+        let pids = Self {
+            max: read_optional(&dir.join("pids.max")).and_then(|v| CgroupLimit::parse(&v)),
+            current: read_optional(&dir.join("pids.current")).and_then(|v| v.trim().parse().ok()),
+        };
+        tracing::info!(
+            cgroup.pids.max = ?pids.max,
+            cgroup.pids.current = pids.current,
+            "cgroup pids"
+        );
+        pids
+    }
+}
+
+fn read_to_string(path: &Path) -> Result<String> {
+    std::fs::read_to_string(path).context(ReadFileSnafu { path })
+}
+
+/// Reads a cgroup interface file that may legitimately be missing (for example because the
+/// corresponding controller is not enabled).
+fn read_optional(path: &Path) -> Option<String> {
+    match read_to_string(path) {
+        Ok(content) => Some(content),
+        Err(err) => {
+            tracing::debug!(
+                error = &err as &dyn std::error::Error,
+                "cgroup file not available, skipping"
+            );
+            None
+        }
+    }
+}
+
+/// Reads a "flat keyed" cgroup file, consisting of lines with the format `<key> <value>`.
+fn read_flat_keyed(path: &Path) -> BTreeMap<String, u64> {
+    read_optional(path)
+        .map(|content| parse_flat_keyed(&content))
+        .unwrap_or_default()
+}
+
+fn parse_flat_keyed(content: &str) -> BTreeMap<String, u64> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let (key, value) = line.split_once(' ')?;
+            Some((key.to_owned(), value.trim().parse().ok()?))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_limit() {
+        assert!(matches!(
+            CgroupLimit::parse("max\n"),
+            Some(CgroupLimit::Unlimited)
+        ));
+        assert!(matches!(
+            CgroupLimit::parse("1073741824\n"),
+            Some(CgroupLimit::Limited(1_073_741_824))
+        ));
+        assert!(CgroupLimit::parse("").is_none());
+        assert!(CgroupLimit::parse("-1").is_none());
+    }
+
+    #[test]
+    fn parse_flat_keyed_file() {
+        let stat = parse_flat_keyed(
+            "usage_usec 2183\nnr_periods 10\nnr_throttled 3\ninvalid\nnot_a_number abc\n",
+        );
+
+        assert_eq!(
+            stat,
+            BTreeMap::from([
+                ("usage_usec".to_owned(), 2183),
+                ("nr_periods".to_owned(), 10),
+                ("nr_throttled".to_owned(), 3),
+            ])
+        );
+    }
+}
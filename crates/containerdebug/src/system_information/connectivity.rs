@@ -0,0 +1,1034 @@
+//! Probes the connectivity to declared dependencies (such as ZooKeeper, S3 or a database), to
+//! diagnose whether they are reachable from inside of the container.
+use std::{
+    fmt::Display,
+    net::{IpAddr, SocketAddr},
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant, SystemTime},
+};
+
+use serde::{Deserialize, Serialize};
+use snafu::{OptionExt, ResultExt, Snafu};
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
+    net::TcpStream,
+    task::JoinSet,
+};
+use tokio_rustls::{
+    TlsConnector,
+    rustls::{
+        self, ClientConfig, DigitallySignedStruct, RootCertStore, SignatureScheme,
+        client::{
+            WebPkiServerVerifier,
+            danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier},
+        },
+        crypto::{CryptoProvider, ring},
+        pki_types::{CertificateDer, ServerName, UnixTime, pem::PemObject},
+    },
+};
+use url::{Host, Url, form_urlencoded};
+use x509_cert::{
+    Certificate,
+    der::Decode,
+    ext::pkix::{SubjectAltName, name::GeneralName},
+};
+
+use crate::error::ComponentResult;
+
+/// The time after which a single probe stage (connecting, TLS handshake, HTTP request) is aborted.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Certificates that expire within this many days are reported as a warning.
+const CERTIFICATE_EXPIRY_WARNING_DAYS: i64 = 30;
+
+#[derive(Debug, Snafu)]
+pub enum ParseTargetError {
+    #[snafu(display("failed to parse probe target {target:?} as URL"))]
+    ParseUrl {
+        source: url::ParseError,
+        target: String,
+    },
+
+    #[snafu(display("probe target {target:?} has no host"))]
+    MissingHost { target: String },
+
+    #[snafu(display("probe target {target:?} has no port"))]
+    MissingPort { target: String },
+
+    #[snafu(display(
+        "probe target {target:?} has unsupported scheme {scheme:?}, supported schemes are tcp, tls, http and https"
+    ))]
+    UnsupportedScheme { target: String, scheme: String },
+
+    #[snafu(display("probe target {target:?} has unsupported query parameter {parameter:?}"))]
+    UnsupportedQueryParameter { target: String, parameter: String },
+}
+
+#[derive(Debug, Snafu)]
+pub enum LoadTargetsError {
+    #[snafu(display("failed to read probe targets from {path:?}"))]
+    ReadTargetsFile {
+        source: std::io::Error,
+        path: PathBuf,
+    },
+
+    #[snafu(display("failed to parse probe targets from {path:?}"))]
+    ParseTargetsFile {
+        source: serde_json::Error,
+        path: PathBuf,
+    },
+}
+
+#[derive(Debug, Snafu)]
+pub enum Error {
+    #[snafu(display("failed to connect to {target}"))]
+    Connect {
+        source: std::io::Error,
+        target: String,
+    },
+
+    #[snafu(display("timed out connecting to {target} after {PROBE_TIMEOUT:?}"))]
+    ConnectTimeout { target: String },
+
+    #[snafu(display("failed to read CA certificates from {path:?}"))]
+    ReadCaFile {
+        source: rustls::pki_types::pem::Error,
+        path: PathBuf,
+    },
+
+    #[snafu(display("invalid TLS server name {server_name:?}"))]
+    InvalidServerName {
+        source: rustls::pki_types::InvalidDnsNameError,
+        server_name: String,
+    },
+
+    #[snafu(display("failed to set TLS protocol versions"))]
+    SetTlsProtocolVersions { source: rustls::Error },
+
+    #[snafu(display("TLS handshake failed"))]
+    TlsHandshake { source: std::io::Error },
+
+    #[snafu(display("timed out during TLS handshake after {PROBE_TIMEOUT:?}"))]
+    TlsHandshakeTimeout,
+
+    #[snafu(display("failed to send HTTP request"))]
+    HttpRequest { source: std::io::Error },
+
+    #[snafu(display("timed out waiting for HTTP response after {PROBE_TIMEOUT:?}"))]
+    HttpTimeout,
+
+    #[snafu(display("received invalid HTTP status line {status_line:?}"))]
+    InvalidHttpStatusLine { status_line: String },
+}
+type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// A dependency to probe.
+///
+/// Targets can be given on the command line (see [`ProbeTarget::from_str`]) or as a JSON list in a
+/// file (see [`ProbeTarget::load_from_file`]).
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProbeTarget {
+    pub host: String,
+    pub port: u16,
+
+    /// Perform a TLS handshake after connecting.
+    #[serde(default)]
+    pub tls: Option<TlsProbeOptions>,
+
+    /// Send an HTTP `GET` request for this path, including the query string if any (over TLS if
+    /// [`Self::tls`] is set).
+    #[serde(default)]
+    pub http_path: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TlsProbeOptions {
+    /// PEM file containing the CA certificates to verify the server against. If not set, the
+    /// system trust store is used.
+    #[serde(default)]
+    pub ca_file: Option<PathBuf>,
+
+    /// The server name to send (SNI) and verify the certificate against. Defaults to the host.
+    #[serde(default)]
+    pub server_name: Option<String>,
+}
+
+impl FromStr for ProbeTarget {
+    type Err = ParseTargetError;
+
+    /// Parses a probe target, which is either `host:port` (plain TCP) or a URL with one of the
+    /// schemes `tcp`, `tls`, `http` or `https`, for example:
+    ///
+    /// - `zookeeper:2181`
+    /// - `tls://kafka:9093?ca_file=/stackable/tls/ca.crt`
+    /// - `https://minio:9000/minio/health/live?server_name=minio.default.svc`
+    ///
+    /// Query parameters other than the TLS options are kept as part of the HTTP path.
+    fn from_str(target: &str) -> Result<Self, Self::Err> {
+        let url = if target.contains("://") {
+            Url::parse(target)
+        } else {
+            Url::parse(&format!("tcp://{target}"))
+        }
+        .context(ParseUrlSnafu { target })?;
+
+        let host = match url.host().context(MissingHostSnafu { target })? {
+            Host::Domain(domain) => domain.to_owned(),
+            Host::Ipv4(ip) => ip.to_string(),
+            Host::Ipv6(ip) => ip.to_string(),
+        };
+        let port = url
+            .port_or_known_default()
+            .context(MissingPortSnafu { target })?;
+
+        let (tls, http) = match url.scheme() {
+            "tcp" => (false, false),
+            "tls" => (true, false),
+            "http" => (false, true),
+            "https" => (true, true),
+            scheme => {
+                return UnsupportedSchemeSnafu { target, scheme }.fail();
+            }
+        };
+
+        let mut tls_options = TlsProbeOptions::default();
+        let mut http_query = Vec::new();
+        for pair in url.query().unwrap_or_default().split('&') {
+            let Some((parameter, value)) = form_urlencoded::parse(pair.as_bytes()).next() else {
+                continue;
+            };
+            match parameter.as_ref() {
+                "ca_file" if tls => tls_options.ca_file = Some(PathBuf::from(value.as_ref())),
+                "server_name" if tls => tls_options.server_name = Some(value.into_owned()),
+                // Keep the parameter as it was given, so that exactly the configured URL is probed
+                _ if http => http_query.push(pair),
+                parameter => return UnsupportedQueryParameterSnafu { target, parameter }.fail(),
+            }
+        }
+
+        let http_path = http.then(|| match http_query.as_slice() {
+            [] => url.path().to_owned(),
+            query => format!("{path}?{query}", path = url.path(), query = query.join("&")),
+        });
+
+        Ok(Self {
+            host,
+            port,
+            tls: tls.then_some(tls_options),
+            http_path,
+        })
+    }
+}
+
+impl Display for ProbeTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let scheme = match (self.tls.is_some(), self.http_path.is_some()) {
+            (false, false) => "tcp",
+            (true, false) => "tls",
+            (false, true) => "http",
+            (true, true) => "https",
+        };
+        let host = match self.host.parse::<IpAddr>() {
+            Ok(IpAddr::V6(ip)) => format!("[{ip}]"),
+            _ => self.host.clone(),
+        };
+        write!(f, "{scheme}://{host}:{port}", port = self.port)?;
+        if let Some(http_path) = &self.http_path {
+            write!(f, "{http_path}")?;
+        }
+        Ok(())
+    }
+}
+
+impl ProbeTarget {
+    /// Loads a JSON list of probe targets, for example:
+    ///
+    /// ```json
+    /// [
+    ///   { "host": "zookeeper", "port": 2181 },
+    ///   { "host": "minio", "port": 9000, "tls": { "ca_file": "/stackable/tls/ca.crt" }, "http_path": "/minio/health/live" }
+    /// ]
+    /// ```
+    pub fn load_from_file(path: &Path) -> Result<Vec<Self>, LoadTargetsError> {
+        let content = std::fs::read(path).context(ReadTargetsFileSnafu { path })?;
+        serde_json::from_slice(&content).context(ParseTargetsFileSnafu { path })
+    }
+}
+
+/// The results of probing a single [`ProbeTarget`].
+///
+/// Later stages are only attempted if the previous stage succeeded.
+#[derive(Debug, Serialize)]
+pub struct ProbeResult {
+    pub target: String,
+    pub tcp: ComponentResult<TcpProbe>,
+    pub tls: Option<ComponentResult<TlsProbe>>,
+    pub http: Option<ComponentResult<HttpProbe>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TcpProbe {
+    pub peer_address: SocketAddr,
+    pub latency_ms: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TlsProbe {
+    pub server_name: String,
+    pub protocol_version: Option<String>,
+    pub cipher_suite: Option<String>,
+    pub latency_ms: f64,
+
+    /// Why the presented certificate chain is not trusted, if it isn't.
+    ///
+    /// The handshake is completed regardless, so that untrusted chains can still be inspected.
+    pub verification_error: Option<String>,
+    /// Whether the leaf certificate contains a SAN matching the server name.
+    pub san_match: bool,
+    /// The certificate chain as presented by the server, starting with the leaf certificate.
+    pub chain: Vec<CertificateInfo>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CertificateInfo {
+    pub subject: String,
+    pub issuer: String,
+    pub not_before: String,
+    pub not_after: String,
+    /// Negative if the certificate has already expired.
+    pub expires_in_days: i64,
+    pub subject_alternative_names: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct HttpProbe {
+    pub status_code: u16,
+    pub status_line: String,
+    pub latency_ms: f64,
+}
+
+/// Probes all `targets` concurrently.
+#[tracing::instrument(name = "connectivity::probe_all", skip(targets))]
+pub async fn probe_all(targets: &[ProbeTarget]) -> Vec<ProbeResult> {
+    let mut probe_tasks = JoinSet::new();
+    for (index, target) in targets.iter().cloned().enumerate() {
+        probe_tasks.spawn(async move { (index, probe(target).await) });
+    }
+    let mut results = probe_tasks.join_all().await;
+    // Report the results in the order the targets were declared in
+    results.sort_by_key(|(index, _)| *index);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+#[tracing::instrument(name = "connectivity::probe", fields(%target))]
+async fn probe(target: ProbeTarget) -> ProbeResult {
+    let target_name = target.to_string();
+
+    let (tcp, stream) = match probe_tcp(&target, &target_name).await {
+        Ok((stream, tcp)) => (Ok(tcp), Some(stream)),
+        Err(err) => (Err(err), None),
+    };
+
+    let mut tls = None;
+    let mut http = None;
+    if let Some(stream) = stream {
+        match &target.tls {
+            None => {
+                if let Some(http_path) = &target.http_path {
+                    http = Some(probe_http(stream, &target.host, http_path).await);
+                }
+            }
+            Some(tls_options) => match probe_tls(stream, &target, tls_options).await {
+                Ok((stream, tls_probe)) => {
+                    tls = Some(Ok(tls_probe));
+                    if let Some(http_path) = &target.http_path {
+                        http = Some(probe_http(stream, &target.host, http_path).await);
+                    }
+                }
+                Err(err) => tls = Some(Err(err)),
+            },
+        }
+    }
+
+    ProbeResult {
+        target: target_name,
+        tcp: ComponentResult::report_from_result("probe_tcp", tcp),
+        tls: tls.map(|tls| ComponentResult::report_from_result("probe_tls", tls)),
+        http: http.map(|http| ComponentResult::report_from_result("probe_http", http)),
+    }
+}
+
+async fn probe_tcp(target: &ProbeTarget, target_name: &str) -> Result<(TcpStream, TcpProbe)> {
+    let start = Instant::now();
+    let stream = tokio::time::timeout(
+        PROBE_TIMEOUT,
+        TcpStream::connect((target.host.as_str(), target.port)),
+    )
+    .await
+    .ok()
+    .context(ConnectTimeoutSnafu {
+        target: target_name,
+    })?
+    .context(ConnectSnafu {
+        target: target_name,
+    })?;
+    let latency = start.elapsed();
+
+    let tcp = TcpProbe {
+        peer_address: stream.peer_addr().context(ConnectSnafu {
+            target: target_name,
+        })?,
+        latency_ms: as_millis(latency),
+    };
+    tracing::info!(
+        probe.tcp.peer_address = %tcp.peer_address,
+        probe.tcp.latency_ms = tcp.latency_ms,
+        "connected to target"
+    );
+    Ok((stream, tcp))
+}
+
+async fn probe_tls(
+    stream: TcpStream,
+    target: &ProbeTarget,
+    tls_options: &TlsProbeOptions,
+) -> Result<(tokio_rustls::client::TlsStream<TcpStream>, TlsProbe)> {
+    let server_name = tls_options
+        .server_name
+        .clone()
+        .unwrap_or_else(|| target.host.clone());
+
+    let provider = Arc::new(ring::default_provider());
+    let verifier = Arc::new(RecordingVerifier::new(
+        load_root_certificates(tls_options.ca_file.as_deref())?,
+        provider.clone(),
+    ));
+    let config = ClientConfig::builder_with_provider(provider)
+        .with_safe_default_protocol_versions()
+        .context(SetTlsProtocolVersionsSnafu)?
+        .dangerous()
+        .with_custom_certificate_verifier(verifier.clone())
+        .with_no_client_auth();
+
+    let start = Instant::now();
+    let stream = tokio::time::timeout(
+        PROBE_TIMEOUT,
+        TlsConnector::from(Arc::new(config)).connect(
+            ServerName::try_from(server_name.clone()).context(InvalidServerNameSnafu {
+                server_name: &server_name,
+            })?,
+            stream,
+        ),
+    )
+    .await
+    .ok()
+    .context(TlsHandshakeTimeoutSnafu)?
+    .context(TlsHandshakeSnafu)?;
+    let latency = start.elapsed();
+
+    let (_, connection) = stream.get_ref();
+    let chain: Vec<CertificateInfo> = connection
+        .peer_certificates()
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|certificate| CertificateInfo::parse(certificate))
+        .collect();
+    let san_match = chain
+        .first()
+        .is_some_and(|leaf| leaf.matches_server_name(&server_name));
+
+    let tls = TlsProbe {
+        protocol_version: connection
+            .protocol_version()
+            .map(|version| format!("{version:?}")),
+        cipher_suite: connection
+            .negotiated_cipher_suite()
+            .map(|suite| format!("{:?}", suite.suite())),
+        latency_ms: as_millis(latency),
+        verification_error: verifier.take_error().map(|err| err.to_string()),
+        san_match,
+        chain,
+        server_name,
+    };
+
+    if let Some(verification_error) = &tls.verification_error {
+        tracing::warn!(
+            probe.tls.server_name = tls.server_name,
+            probe.tls.verification_error = verification_error,
+            "TLS certificate chain is not trusted"
+        );
+    }
+    if !tls.san_match {
+        tracing::warn!(
+            probe.tls.server_name = tls.server_name,
+            probe.tls.subject_alternative_names = ?tls.chain.first().map(|leaf| &leaf.subject_alternative_names),
+            "TLS certificate does not match server name"
+        );
+    }
+    for certificate in &tls.chain {
+        if certificate.expires_in_days < CERTIFICATE_EXPIRY_WARNING_DAYS {
+            tracing::warn!(
+                probe.tls.certificate.subject = certificate.subject,
+                probe.tls.certificate.not_after = certificate.not_after,
+                probe.tls.certificate.expires_in_days = certificate.expires_in_days,
+                "TLS certificate expires soon"
+            );
+        }
+    }
+    tracing::info!(
+        probe.tls.server_name = tls.server_name,
+        probe.tls.protocol_version = tls.protocol_version,
+        probe.tls.cipher_suite = tls.cipher_suite,
+        probe.tls.latency_ms = tls.latency_ms,
+        probe.tls.chain = ?tls.chain.iter().map(|certificate| &certificate.subject).collect::<Vec<_>>(),
+        "completed TLS handshake"
+    );
+    Ok((stream, tls))
+}
+
+/// Sends a minimal HTTP/1.1 request and reads the status line of the response.
+async fn probe_http<S>(mut stream: S, host: &str, http_path: &str) -> Result<HttpProbe>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let request = format!("GET {http_path} HTTP/1.1\r\nHost: {host}\r\nConnection: close\r\n\r\n");
+
+    let start = Instant::now();
+    let response = tokio::time::timeout(PROBE_TIMEOUT, async {
+        stream.write_all(request.as_bytes()).await?;
+        let mut response = Vec::new();
+        let mut buffer = [0; 1024];
+        // Only the status line is of interest, so stop reading after the first line
+        while !response.contains(&b'\n') {
+            match stream.read(&mut buffer).await? {
+                0 => break,
+                read => response.extend_from_slice(&buffer[..read]),
+            }
+        }
+        Ok(response)
+    })
+    .await
+    .ok()
+    .context(HttpTimeoutSnafu)?
+    .context(HttpRequestSnafu)?;
+    let latency = start.elapsed();
+
+    let status_line = String::from_utf8_lossy(&response)
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .to_owned();
+    // The status line has the format `HTTP/1.1 200 OK`
+    let status_code = status_line
+        .split(' ')
+        .nth(1)
+        .and_then(|status_code| status_code.parse().ok())
+        .context(InvalidHttpStatusLineSnafu {
+            status_line: &status_line,
+        })?;
+
+    let http = HttpProbe {
+        status_code,
+        status_line,
+        latency_ms: as_millis(latency),
+    };
+    tracing::info!(
+        probe.http.path = http_path,
+        probe.http.status_code = http.status_code,
+        probe.http.latency_ms = http.latency_ms,
+        "received HTTP response"
+    );
+    Ok(http)
+}
+
+/// Loads the trusted CA certificates from `ca_file`, or from the system trust store if not given.
+fn load_root_certificates(ca_file: Option<&Path>) -> Result<RootCertStore> {
+    let certificates = match ca_file {
+        Some(ca_file) => CertificateDer::pem_file_iter(ca_file)
+            .and_then(|certificates| certificates.collect::<Result<Vec<_>, _>>())
+            .context(ReadCaFileSnafu { path: ca_file })?,
+        None => {
+            let native_certs = rustls_native_certs::load_native_certs();
+            for err in &native_certs.errors {
+                tracing::warn!(
+                    error = err as &dyn std::error::Error,
+                    "failed to load some system CA certificates"
+                );
+            }
+            native_certs.certs
+        }
+    };
+
+    let mut roots = RootCertStore::empty();
+    let (added, ignored) = roots.add_parsable_certificates(certificates);
+    tracing::debug!(added, ignored, "loaded trusted CA certificates");
+    Ok(roots)
+}
+
+/// Verifies the server certificate like rustls normally would, but records the verification
+/// result instead of aborting the handshake, so that the presented chain can always be inspected.
+#[derive(Debug)]
+struct RecordingVerifier {
+    /// [`None`] if there are no trusted CA certificates to verify against.
+    inner: Option<Arc<WebPkiServerVerifier>>,
+    provider: Arc<CryptoProvider>,
+    error: Mutex<Option<rustls::Error>>,
+}
+
+impl RecordingVerifier {
+    fn new(roots: RootCertStore, provider: Arc<CryptoProvider>) -> Self {
+        let inner =
+            match WebPkiServerVerifier::builder_with_provider(Arc::new(roots), provider.clone())
+                .build()
+            {
+                Ok(inner) => Some(inner),
+                Err(err) => {
+                    tracing::warn!(
+                        error = &err as &dyn std::error::Error,
+                        "failed to build certificate verifier, certificates will not be verified"
+                    );
+                    None
+                }
+            };
+        Self {
+            inner,
+            provider,
+            error: Mutex::new(None),
+        }
+    }
+
+    fn take_error(&self) -> Option<rustls::Error> {
+        self.error
+            .lock()
+            .map(|mut error| error.take())
+            .unwrap_or_default()
+    }
+}
+
+impl ServerCertVerifier for RecordingVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        intermediates: &[CertificateDer<'_>],
+        server_name: &ServerName<'_>,
+        ocsp_response: &[u8],
+        now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        let result = match &self.inner {
+            Some(inner) => inner
+                .verify_server_cert(end_entity, intermediates, server_name, ocsp_response, now)
+                .map(|_| ()),
+            None => Err(rustls::Error::General(
+                "no trusted CA certificates available".to_owned(),
+            )),
+        };
+        if let (Err(err), Ok(mut error)) = (result, self.error.lock()) {
+            *error = Some(err);
+        }
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls12_signature(
+            message,
+            cert,
+            dss,
+            &self.provider.signature_verification_algorithms,
+        )
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls13_signature(
+            message,
+            cert,
+            dss,
+            &self.provider.signature_verification_algorithms,
+        )
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.provider
+            .signature_verification_algorithms
+            .supported_schemes()
+    }
+}
+
+impl CertificateInfo {
+    fn parse(certificate: &CertificateDer<'_>) -> Option<Self> {
+        let certificate = match Certificate::from_der(certificate) {
+            Ok(certificate) => certificate,
+            Err(err) => {
+                tracing::warn!(
+                    error = &err as &dyn std::error::Error,
+                    "failed to parse certificate presented by server, skipping"
+                );
+                return None;
+            }
+        };
+        let tbs_certificate = &certificate.tbs_certificate;
+
+        let subject_alternative_names = match tbs_certificate.get::<SubjectAltName>() {
+            Ok(Some((_, SubjectAltName(names)))) => names
+                .iter()
+                .filter_map(|name| match name {
+                    GeneralName::DnsName(dns_name) => Some(format!("DNS:{dns_name}")),
+                    GeneralName::IpAddress(ip) => {
+                        let ip = match ip.as_bytes().len() {
+                            4 => <[u8; 4]>::try_from(ip.as_bytes()).ok().map(IpAddr::from),
+                            16 => <[u8; 16]>::try_from(ip.as_bytes()).ok().map(IpAddr::from),
+                            _ => None,
+                        }?;
+                        Some(format!("IP:{ip}"))
+                    }
+                    _ => None,
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+
+        let not_after = tbs_certificate.validity.not_after;
+        let now = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default();
+        let expires_in_days = (not_after.to_unix_duration().as_secs() as i64
+            - now.as_secs() as i64)
+            .div_euclid(60 * 60 * 24);
+
+        Some(Self {
+            subject: tbs_certificate.subject.to_string(),
+            issuer: tbs_certificate.issuer.to_string(),
+            not_before: tbs_certificate.validity.not_before.to_string(),
+            not_after: not_after.to_string(),
+            expires_in_days,
+            subject_alternative_names,
+        })
+    }
+
+    /// Checks whether any of the SANs matches `server_name`, including single-label wildcards.
+    fn matches_server_name(&self, server_name: &str) -> bool {
+        if let Ok(ip) = server_name.parse::<IpAddr>() {
+            return self
+                .subject_alternative_names
+                .iter()
+                .any(|san| san.strip_prefix("IP:") == Some(ip.to_string().as_str()));
+        }
+
+        let server_name = server_name.trim_end_matches('.').to_lowercase();
+        self.subject_alternative_names
+            .iter()
+            .filter_map(|san| san.strip_prefix("DNS:"))
+            .map(|dns_name| dns_name.trim_end_matches('.').to_lowercase())
+            .any(|dns_name| match dns_name.strip_prefix("*.") {
+                Some(suffix) => server_name
+                    .split_once('.')
+                    .is_some_and(|(_, server_suffix)| server_suffix == suffix),
+                None => dns_name == server_name,
+            })
+    }
+}
+
+fn as_millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use stackable_certs::ca::CertificateAuthority;
+    use stackable_shared::time::Duration;
+    use tokio::net::TcpListener;
+    use tokio_rustls::{TlsAcceptor, rustls::ServerConfig};
+    use x509_cert::der::{EncodePem, pem::LineEnding};
+
+    use super::*;
+
+    /// Starts an HTTPS server on localhost with a certificate for `localhost` and returns its port
+    /// and the PEM encoded CA certificate.
+    async fn start_tls_server() -> (u16, String) {
+        let mut ca = CertificateAuthority::new_ecdsa().unwrap();
+        let leaf = ca
+            .generate_ecdsa_leaf_certificate(
+                "Leaf",
+                "containerdebug",
+                ["localhost"],
+                Duration::from_hours_unchecked(1),
+            )
+            .unwrap();
+        let ca_pem = ca.ca_cert().to_pem(LineEnding::LF).unwrap();
+
+        let config = ServerConfig::builder_with_provider(Arc::new(ring::default_provider()))
+            .with_safe_default_protocol_versions()
+            .unwrap()
+            .with_no_client_auth()
+            .with_single_cert(
+                vec![leaf.certificate_der().unwrap()],
+                leaf.private_key_der().unwrap(),
+            )
+            .unwrap();
+        let acceptor = TlsAcceptor::from(Arc::new(config));
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            loop {
+                let (stream, _) = listener.accept().await.unwrap();
+                let acceptor = acceptor.clone();
+                tokio::spawn(async move {
+                    let mut stream = acceptor.accept(stream).await.unwrap();
+                    let mut request = [0; 1024];
+                    let read = stream.read(&mut request).await.unwrap();
+                    let status_line = if request[..read].starts_with(b"GET /health?verbose=true ") {
+                        "HTTP/1.1 200 OK"
+                    } else {
+                        "HTTP/1.1 404 Not Found"
+                    };
+                    stream
+                        .write_all(format!("{status_line}\r\nContent-Length: 0\r\n\r\n").as_bytes())
+                        .await
+                        .unwrap();
+                    stream.shutdown().await.unwrap();
+                });
+            }
+        });
+
+        (port, ca_pem)
+    }
+
+    #[test]
+    fn parse_targets() {
+        assert_eq!(
+            "zookeeper:2181".parse::<ProbeTarget>().unwrap(),
+            ProbeTarget {
+                host: "zookeeper".to_owned(),
+                port: 2181,
+                tls: None,
+                http_path: None,
+            }
+        );
+        assert_eq!(
+            "tls://[::1]:9093?ca_file=/stackable/tls/ca.crt"
+                .parse::<ProbeTarget>()
+                .unwrap(),
+            ProbeTarget {
+                host: "::1".to_owned(),
+                port: 9093,
+                tls: Some(TlsProbeOptions {
+                    ca_file: Some(PathBuf::from("/stackable/tls/ca.crt")),
+                    server_name: None,
+                }),
+                http_path: None,
+            }
+        );
+        assert_eq!(
+            "https://minio/minio/health/live?server_name=minio.default.svc"
+                .parse::<ProbeTarget>()
+                .unwrap(),
+            ProbeTarget {
+                host: "minio".to_owned(),
+                port: 443,
+                tls: Some(TlsProbeOptions {
+                    ca_file: None,
+                    server_name: Some("minio.default.svc".to_owned()),
+                }),
+                http_path: Some("/minio/health/live".to_owned()),
+            }
+        );
+
+        assert!("zookeeper".parse::<ProbeTarget>().is_err());
+        assert!("ftp://server:21".parse::<ProbeTarget>().is_err());
+        assert!(
+            "zookeeper:2181?ca_file=ca.crt"
+                .parse::<ProbeTarget>()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn parse_target_with_http_query() {
+        let target: ProbeTarget =
+            "https://127.0.0.1:8443/health?verbose=true&server_name=localhost&filter=a%20b"
+                .parse()
+                .unwrap();
+
+        assert_eq!(
+            target.http_path.as_deref(),
+            Some("/health?verbose=true&filter=a%20b")
+        );
+        assert_eq!(
+            target.tls.unwrap().server_name.as_deref(),
+            Some("localhost")
+        );
+        assert!(
+            "tls://kafka:9093?verbose=true"
+                .parse::<ProbeTarget>()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn display_target() {
+        let target: ProbeTarget = "https://[::1]:8443/health".parse().unwrap();
+        assert_eq!(target.to_string(), "https://[::1]:8443/health");
+    }
+
+    #[tokio::test]
+    async fn probe_reachable_tcp_target() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+
+        let results = probe_all(&[format!("127.0.0.1:{}", address.port()).parse().unwrap()]).await;
+
+        let [result] = results.as_slice() else {
+            panic!("expected exactly one probe result");
+        };
+        let ComponentResult::Ok(tcp) = &result.tcp else {
+            panic!("expected target to be reachable");
+        };
+        assert_eq!(tcp.peer_address, address);
+        assert!(result.tls.is_none());
+        assert!(result.http.is_none());
+    }
+
+    #[tokio::test]
+    async fn probe_unreachable_tcp_target() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        drop(listener);
+
+        let results = probe_all(&[format!("127.0.0.1:{port}").parse().unwrap()]).await;
+
+        assert!(matches!(results[0].tcp, ComponentResult::Err { .. }));
+        assert!(results[0].http.is_none());
+    }
+
+    #[tokio::test]
+    async fn probe_http_target() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = [0; 1024];
+            let _ = stream.read(&mut request).await.unwrap();
+            stream
+                .write_all(b"HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\n\r\n")
+                .await
+                .unwrap();
+        });
+
+        let results =
+            probe_all(&[format!("http://127.0.0.1:{port}/health").parse().unwrap()]).await;
+
+        let Some(ComponentResult::Ok(http)) = &results[0].http else {
+            panic!("expected HTTP probe to succeed");
+        };
+        assert_eq!(http.status_code, 503);
+        assert_eq!(http.status_line, "HTTP/1.1 503 Service Unavailable");
+    }
+
+    #[tokio::test]
+    async fn probe_untrusted_tls_target() {
+        let (port, _) = start_tls_server().await;
+
+        let results = probe_all(&[format!(
+            "https://127.0.0.1:{port}/health?verbose=true&server_name=localhost"
+        )
+        .parse()
+        .unwrap()])
+        .await;
+
+        let Some(ComponentResult::Ok(tls)) = &results[0].tls else {
+            panic!("expected TLS handshake to succeed");
+        };
+        // The CA is not part of the system trust store, but the handshake is completed anyway
+        assert!(tls.verification_error.is_some());
+        assert!(tls.san_match);
+        assert_eq!(
+            tls.chain[0].subject,
+            "CN=Leaf Certificate for containerdebug"
+        );
+        assert_eq!(tls.chain[0].subject_alternative_names, ["DNS:localhost"]);
+
+        let Some(ComponentResult::Ok(http)) = &results[0].http else {
+            panic!("expected HTTP probe to succeed");
+        };
+        assert_eq!(http.status_code, 200);
+    }
+
+    #[tokio::test]
+    async fn probe_trusted_tls_target() {
+        let (port, ca_pem) = start_tls_server().await;
+        let ca_file = std::env::temp_dir().join(format!("containerdebug-test-ca-{port}.crt"));
+        std::fs::write(&ca_file, ca_pem).unwrap();
+
+        let results = probe_all(&[ProbeTarget {
+            host: "127.0.0.1".to_owned(),
+            port,
+            tls: Some(TlsProbeOptions {
+                ca_file: Some(ca_file.clone()),
+                server_name: Some("localhost".to_owned()),
+            }),
+            http_path: None,
+        }])
+        .await;
+        std::fs::remove_file(ca_file).unwrap();
+
+        let Some(ComponentResult::Ok(tls)) = &results[0].tls else {
+            panic!("expected TLS handshake to succeed");
+        };
+        assert_eq!(tls.verification_error, None);
+        assert!(tls.san_match);
+        assert!(tls.protocol_version.is_some());
+        assert!(results[0].http.is_none());
+    }
+
+    #[tokio::test]
+    async fn probe_tls_target_with_wrong_server_name() {
+        let (port, _) = start_tls_server().await;
+
+        let results = probe_all(&[format!("tls://127.0.0.1:{port}?server_name=kafka")
+            .parse()
+            .unwrap()])
+        .await;
+
+        let Some(ComponentResult::Ok(tls)) = &results[0].tls else {
+            panic!("expected TLS handshake to succeed");
+        };
+        assert!(!tls.san_match);
+    }
+
+    #[test]
+    fn match_server_name() {
+        let certificate = CertificateInfo {
+            subject: "CN=kafka".to_owned(),
+            issuer: "CN=ca".to_owned(),
+            not_before: String::new(),
+            not_after: String::new(),
+            expires_in_days: 365,
+            subject_alternative_names: vec![
+                "DNS:kafka.default.svc.cluster.local".to_owned(),
+                "DNS:*.kafka-broker.default.svc.cluster.local".to_owned(),
+                "IP:10.0.0.1".to_owned(),
+            ],
+        };
+
+        assert!(certificate.matches_server_name("kafka.default.svc.cluster.local."));
+        assert!(certificate.matches_server_name("KAFKA-0.kafka-broker.default.svc.cluster.local"));
+        assert!(certificate.matches_server_name("10.0.0.1"));
+        assert!(!certificate.matches_server_name("a.b.kafka-broker.default.svc.cluster.local"));
+        assert!(!certificate.matches_server_name("kafka"));
+        assert!(!certificate.matches_server_name("10.0.0.2"));
+    }
+}
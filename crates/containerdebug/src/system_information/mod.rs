@@ -2,10 +2,16 @@ use serde::Serialize;
 
 use crate::error::ComponentResult;
 
+pub mod cgroup;
+pub mod connectivity;
 pub mod disk;
+pub mod environment;
+pub mod mounts;
 pub mod network;
 pub mod os;
+pub mod process;
 pub mod resources;
+pub mod security;
 pub mod user;
 
 #[derive(Debug, Serialize, Default)]
@@ -16,17 +22,19 @@ pub struct SystemInformation {
     pub current_user: Option<ComponentResult<user::User>>,
     pub disks: Option<Vec<disk::Disk>>,
     pub network: Option<ComponentResult<network::SystemNetworkInfo>>,
+    pub cgroup: Option<ComponentResult<cgroup::Cgroup>>,
+    pub security_context: Option<ComponentResult<security::SecurityContext>>,
+    pub processes: Option<process::Processes>,
+    pub environment: Option<environment::Environment>,
+    pub mounts: Option<ComponentResult<Vec<mounts::Mount>>>,
+    pub connectivity: Option<Vec<connectivity::ProbeResult>>,
     // TODO:
     //  Current time
-    //  SElinux/AppArmor
-    //  Maybe env variables (may contain secrets)
     //  dmesg/syslog?
-    //  capabilities?
     //  downward API
     //  Somehow get the custom resources logged?
 
     // Things left out for now because it doesn't seem too useful:
-    // - Running processes
     // - Uptime/boot time
     // - Load average
     // - Network utilization
@@ -36,16 +44,18 @@ pub struct SystemInformation {
 /// Common data that is cached between [`SystemInformation::collect`] calls.
 pub struct CollectContext {
     system: sysinfo::System,
+    probe_targets: Vec<connectivity::ProbeTarget>,
 }
 
 impl SystemInformation {
     /// Collects static information that doesn't need to be refreshed.
-    #[tracing::instrument(name = "SystemInformation::init")]
-    pub fn init() -> CollectContext {
+    #[tracing::instrument(name = "SystemInformation::init", skip(probe_targets))]
+    pub fn init(probe_targets: Vec<connectivity::ProbeTarget>) -> CollectContext {
         tracing::debug!("initializing");
         let mut ctx = CollectContext {
             // Each module is responsible for updating the information that it cares about.
             system: sysinfo::System::new(),
+            probe_targets,
         };
         if let Err(err) = user::User::init(&mut ctx.system) {
             tracing::error!(
@@ -74,6 +84,25 @@ impl SystemInformation {
                 "SystemNetworkInfo::collect",
                 network::SystemNetworkInfo::collect().await,
             )),
+            cgroup: Some(ComponentResult::report_from_result(
+                "Cgroup::collect",
+                cgroup::Cgroup::collect(),
+            )),
+            security_context: Some(ComponentResult::report_from_result(
+                "SecurityContext::collect",
+                security::SecurityContext::collect(),
+            )),
+            processes: Some(process::Processes::collect(&mut ctx.system)),
+            environment: Some(environment::Environment::collect()),
+            mounts: Some(ComponentResult::report_from_result(
+                "Mount::collect_all",
+                mounts::Mount::collect_all(),
+            )),
+            // Connectivity is only probed if any targets have been declared
+            connectivity: match ctx.probe_targets.as_slice() {
+                [] => None,
+                targets => Some(connectivity::probe_all(targets).await),
+            },
             // ..Default::default()
         };
 
@@ -0,0 +1,158 @@
+use std::path::PathBuf;
+
+use serde::Serialize;
+use snafu::{ResultExt, Snafu};
+
+const PROC_SELF_MOUNTINFO: &str = "/proc/self/mountinfo";
+
+#[derive(Debug, Snafu)]
+pub enum Error {
+    #[snafu(display("failed to read {path:?}"))]
+    ReadFile {
+        source: std::io::Error,
+        path: PathBuf,
+    },
+}
+type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// A filesystem mounted into the mount namespace of the current process.
+///
+/// Unlike [`super::disk::Disk`], this includes pseudo-filesystems (such as `tmpfs`, `proc` or
+/// the bind-mounts used for ConfigMaps, Secrets and the Downward API).
+#[derive(Debug, Serialize)]
+pub struct Mount {
+    pub mount_point: String,
+    pub root: String,
+    pub filesystem_type: String,
+    pub source: String,
+    pub read_only: bool,
+    pub options: Vec<String>,
+}
+
+impl Mount {
+    #[tracing::instrument(name = "Mount::collect_all")]
+    pub fn collect_all() -> Result<Vec<Self>> {
+        let mountinfo = std::fs::read_to_string(PROC_SELF_MOUNTINFO).context(ReadFileSnafu {
+            path: PROC_SELF_MOUNTINFO,
+        })?;
+        let mounts = mountinfo
+            .lines()
+            .filter_map(Self::parse_mountinfo_line)
+            .inspect(|mount| {
+                tracing::info!(
+                    mount.mount_point,
+                    mount.root,
+                    mount.filesystem_type,
+                    mount.source,
+                    mount.read_only,
+                    "found mount"
+                );
+            })
+            .collect();
+        Ok(mounts)
+    }
+
+    /// Parses a line of `/proc/self/mountinfo`, see `proc_pid_mountinfo(5)`:
+    ///
+    /// ```text
+    /// 36 35 98:0 /mnt1 /mnt2 rw,noatime master:1 - ext3 /dev/root rw,errors=continue
+    /// ```
+    fn parse_mountinfo_line(line: &str) -> Option<Self> {
+        let (mount_fields, filesystem_fields) = line.split_once(" - ")?;
+        let mut mount_fields = mount_fields.split(' ').skip(3);
+        let root = unescape_octal(mount_fields.next()?);
+        let mount_point = unescape_octal(mount_fields.next()?);
+        let mount_options = mount_fields.next()?;
+
+        let mut filesystem_fields = filesystem_fields.split(' ');
+        let filesystem_type = filesystem_fields.next()?.to_owned();
+        let source = unescape_octal(filesystem_fields.next()?);
+        let super_options = filesystem_fields.next().unwrap_or_default();
+
+        let options: Vec<String> = mount_options
+            .split(',')
+            .chain(super_options.split(','))
+            .filter(|option| !option.is_empty())
+            .map(str::to_owned)
+            .collect();
+        let read_only = mount_options.split(',').any(|option| option == "ro");
+
+        Some(Self {
+            mount_point,
+            root,
+            filesystem_type,
+            source,
+            read_only,
+            options,
+        })
+    }
+}
+
+/// Replaces the octal escape sequences (such as `\040` for spaces) used in `/proc/self/mountinfo`.
+fn unescape_octal(field: &str) -> String {
+    let mut unescaped = Vec::with_capacity(field.len());
+    let mut bytes = field.as_bytes();
+    while let Some((&byte, rest)) = bytes.split_first() {
+        let escaped = rest
+            .get(..3)
+            .filter(|_| byte == b'\\')
+            .and_then(|digits| std::str::from_utf8(digits).ok())
+            .and_then(|digits| u8::from_str_radix(digits, 8).ok());
+        match escaped {
+            Some(escaped) => {
+                unescaped.push(escaped);
+                bytes = &rest[3..];
+            }
+            None => {
+                unescaped.push(byte);
+                bytes = rest;
+            }
+        }
+    }
+    String::from_utf8_lossy(&unescaped).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_mountinfo() {
+        let mount = Mount::parse_mountinfo_line(
+            "36 35 98:0 /mnt1 /mnt2 rw,noatime master:1 - ext3 /dev/root rw,errors=continue",
+        )
+        .unwrap();
+
+        assert_eq!(mount.root, "/mnt1");
+        assert_eq!(mount.mount_point, "/mnt2");
+        assert_eq!(mount.filesystem_type, "ext3");
+        assert_eq!(mount.source, "/dev/root");
+        assert!(!mount.read_only);
+        assert_eq!(mount.options, ["rw", "noatime", "rw", "errors=continue"]);
+    }
+
+    #[test]
+    fn parse_mountinfo_read_only_with_escapes() {
+        let mount = Mount::parse_mountinfo_line(
+            "812 790 0:52 / /stackable/my\\040config ro,relatime - tmpfs tmpfs ro,size=4k",
+        )
+        .unwrap();
+
+        assert_eq!(mount.mount_point, "/stackable/my config");
+        assert!(mount.read_only);
+    }
+
+    #[test]
+    fn parse_invalid_mountinfo() {
+        assert!(Mount::parse_mountinfo_line("").is_none());
+        assert!(Mount::parse_mountinfo_line("36 35 98:0 /mnt1 /mnt2 rw").is_none());
+    }
+
+    #[test]
+    fn unescape() {
+        assert_eq!(unescape_octal("a\\040b\\011c"), "a b\tc");
+        assert_eq!(unescape_octal("no escapes"), "no escapes");
+        assert_eq!(unescape_octal("trailing\\04"), "trailing\\04");
+        assert_eq!(unescape_octal("invalid\\089"), "invalid\\089");
+    }
+}
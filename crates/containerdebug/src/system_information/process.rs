@@ -0,0 +1,211 @@
+use std::{
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    path::Path,
+};
+
+use serde::Serialize;
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, Uid, UpdateKind};
+
+/// The processes running in the container, and the sockets that they are listening on.
+#[derive(Debug, Serialize)]
+pub struct Processes {
+    pub processes: Vec<Process>,
+    pub listening_sockets: Vec<ListeningSocket>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Process {
+    pub pid: Pid,
+    pub parent_pid: Option<Pid>,
+    pub name: String,
+    pub exe: Option<String>,
+    pub uid: Option<Uid>,
+    pub status: String,
+    pub memory: u64,
+    pub virtual_memory: u64,
+    pub run_time: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListeningSocket {
+    pub protocol: SocketProtocol,
+    pub address: IpAddr,
+    pub port: u16,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SocketProtocol {
+    Tcp,
+    Udp,
+}
+
+/// `TCP_LISTEN`, see `include/net/tcp_states.h`.
+const TCP_STATE_LISTEN: &str = "0A";
+/// `TCP_CLOSE`, which is what unconnected (bound) UDP sockets report.
+const UDP_STATE_UNCONNECTED: &str = "07";
+
+impl Processes {
+    #[tracing::instrument(name = "Processes::collect", skip(sys))]
+    pub fn collect(sys: &mut sysinfo::System) -> Self {
+        // The command line and environment are deliberately not collected, since they regularly
+        // contain secrets.
+        sys.refresh_processes_specifics(
+            ProcessesToUpdate::All,
+            true,
+            ProcessRefreshKind::nothing()
+                .with_memory()
+                .with_exe(UpdateKind::OnlyIfNotSet)
+                .with_user(UpdateKind::OnlyIfNotSet),
+        );
+
+        let processes = sys
+            .processes()
+            .values()
+            .map(|process| {
+                let process = Process {
+                    pid: process.pid(),
+                    parent_pid: process.parent(),
+                    name: process.name().to_string_lossy().into_owned(),
+                    exe: process.exe().map(|exe| exe.to_string_lossy().into_owned()),
+                    uid: process.user_id().cloned(),
+                    status: process.status().to_string(),
+                    memory: process.memory(),
+                    virtual_memory: process.virtual_memory(),
+                    run_time: process.run_time(),
+                };
+                tracing::info!(
+                    process.pid = %process.pid,
+                    process.parent_pid = process.parent_pid.map(|pid| pid.to_string()),
+                    process.name,
+                    process.exe,
+                    process.uid = process.uid.as_ref().map(|uid| format!("{uid:?}")),
+                    process.status,
+                    process.memory,
+                    "found process"
+                );
+                process
+            })
+            .collect();
+
+        let listening_sockets = [
+            ("/proc/net/tcp", SocketProtocol::Tcp, TCP_STATE_LISTEN),
+            ("/proc/net/tcp6", SocketProtocol::Tcp, TCP_STATE_LISTEN),
+            ("/proc/net/udp", SocketProtocol::Udp, UDP_STATE_UNCONNECTED),
+            ("/proc/net/udp6", SocketProtocol::Udp, UDP_STATE_UNCONNECTED),
+        ]
+        .into_iter()
+        .flat_map(|(path, protocol, state)| {
+            ListeningSocket::collect_from(Path::new(path), protocol, state)
+        })
+        .collect();
+
+        Self {
+            processes,
+            listening_sockets,
+        }
+    }
+}
+
+impl ListeningSocket {
+    /// Parses a `/proc/net/{tcp,udp}{,6}` table, keeping only the sockets in the given `state`.
+    fn collect_from(path: &Path, protocol: SocketProtocol, state: &str) -> Vec<Self> {
+        let table = match std::fs::read_to_string(path) {
+            Ok(table) => table,
+            Err(err) => {
+                tracing::warn!(
+                    path = %path.display(),
+                    error = &err as &dyn std::error::Error,
+                    "failed to read socket table, skipping"
+                );
+                return Vec::new();
+            }
+        };
+
+        table
+            .lines()
+            // The first line is the table header
+            .skip(1)
+            .filter_map(|line| {
+                // Columns: sl local_address rem_address st ...
+                let mut columns = line.split_whitespace().skip(1);
+                let local_address = columns.next()?;
+                let socket_state = columns.nth(1)?;
+                if socket_state != state {
+                    return None;
+                }
+                let (address, port) = parse_proc_net_address(local_address)?;
+                let socket = Self {
+                    protocol,
+                    address,
+                    port,
+                };
+                tracing::info!(
+                    socket.protocol = ?socket.protocol,
+                    socket.address = %socket.address,
+                    socket.port,
+                    "found listening socket"
+                );
+                Some(socket)
+            })
+            .collect()
+    }
+}
+
+/// Parses an address in the `/proc/net` format, which is `<hex address>:<hex port>`, where the
+/// address is stored as (groups of) 32-bit words in host byte order.
+fn parse_proc_net_address(address: &str) -> Option<(IpAddr, u16)> {
+    let (address, port) = address.split_once(':')?;
+    let port = u16::from_str_radix(port, 16).ok()?;
+    let words = (0..address.len())
+        .step_by(8)
+        .map(|i| {
+            let word = u32::from_str_radix(address.get(i..i + 8)?, 16).ok()?;
+            Some(word.to_ne_bytes())
+        })
+        .collect::<Option<Vec<_>>>()?;
+    let address = match words.as_slice() {
+        [word] => IpAddr::V4(Ipv4Addr::from(*word)),
+        [_, _, _, _] => {
+            let mut octets = [0; 16];
+            for (chunk, word) in octets.chunks_exact_mut(4).zip(&words) {
+                chunk.copy_from_slice(word);
+            }
+            IpAddr::V6(Ipv6Addr::from(octets))
+        }
+        _ => return None,
+    };
+    Some((address, port))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The addresses are stored in host byte order, the test cases are written for little endian.
+    #[cfg(target_endian = "little")]
+    #[test]
+    fn parse_proc_net_addresses() {
+        assert_eq!(
+            parse_proc_net_address("0100007F:1F90"),
+            Some((IpAddr::V4(Ipv4Addr::LOCALHOST), 8080))
+        );
+        assert_eq!(
+            parse_proc_net_address("00000000:0016"),
+            Some((IpAddr::V4(Ipv4Addr::UNSPECIFIED), 22))
+        );
+        assert_eq!(
+            parse_proc_net_address("00000000000000000000000001000000:0050"),
+            Some((IpAddr::V6(Ipv6Addr::LOCALHOST), 80))
+        );
+    }
+
+    #[test]
+    fn parse_invalid_proc_net_addresses() {
+        assert_eq!(parse_proc_net_address(""), None);
+        assert_eq!(parse_proc_net_address("0100007F"), None);
+        assert_eq!(parse_proc_net_address("0100007F:XYZ"), None);
+        assert_eq!(parse_proc_net_address("0100007F00:0050"), None);
+        assert_eq!(parse_proc_net_address("0000000000000000:0050"), None);
+    }
+}
@@ -0,0 +1,208 @@
+use std::{collections::BTreeMap, path::PathBuf};
+
+use serde::Serialize;
+use snafu::{ResultExt, Snafu};
+
+const PROC_SELF_STATUS: &str = "/proc/self/status";
+
+#[derive(Debug, Snafu)]
+pub enum Error {
+    #[snafu(display("failed to read {path:?}"))]
+    ReadFile {
+        source: std::io::Error,
+        path: PathBuf,
+    },
+}
+type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// The security context that the current process is running with.
+#[derive(Debug, Serialize)]
+pub struct SecurityContext {
+    pub capabilities: Capabilities,
+    pub no_new_privileges: Option<bool>,
+    pub seccomp: Option<SeccompMode>,
+    /// The AppArmor profile (for example `cri-containerd.apparmor.d (enforce)`).
+    pub apparmor_profile: Option<String>,
+    /// The SELinux context (for example `system_u:system_r:container_t:s0:c1,c2`).
+    pub selinux_context: Option<String>,
+}
+
+/// The capability sets of the current process, see `capabilities(7)`.
+#[derive(Debug, Serialize)]
+pub struct Capabilities {
+    pub inheritable: Vec<String>,
+    pub permitted: Vec<String>,
+    pub effective: Vec<String>,
+    pub bounding: Vec<String>,
+    pub ambient: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SeccompMode {
+    Disabled,
+    Strict,
+    Filter,
+}
+
+/// Capability names, indexed by their bit number (see `include/uapi/linux/capability.h`).
+const CAPABILITY_NAMES: &[&str] = &[
+    "CAP_CHOWN",
+    "CAP_DAC_OVERRIDE",
+    "CAP_DAC_READ_SEARCH",
+    "CAP_FOWNER",
+    "CAP_FSETID",
+    "CAP_KILL",
+    "CAP_SETGID",
+    "CAP_SETUID",
+    "CAP_SETPCAP",
+    "CAP_LINUX_IMMUTABLE",
+    "CAP_NET_BIND_SERVICE",
+    "CAP_NET_BROADCAST",
+    "CAP_NET_ADMIN",
+    "CAP_NET_RAW",
+    "CAP_IPC_LOCK",
+    "CAP_IPC_OWNER",
+    "CAP_SYS_MODULE",
+    "CAP_SYS_RAWIO",
+    "CAP_SYS_CHROOT",
+    "CAP_SYS_PTRACE",
+    "CAP_SYS_PACCT",
+    "CAP_SYS_ADMIN",
+    "CAP_SYS_BOOT",
+    "CAP_SYS_NICE",
+    "CAP_SYS_RESOURCE",
+    "CAP_SYS_TIME",
+    "CAP_SYS_TTY_CONFIG",
+    "CAP_MKNOD",
+    "CAP_LEASE",
+    "CAP_AUDIT_WRITE",
+    "CAP_AUDIT_CONTROL",
+    "CAP_SETFCAP",
+    "CAP_MAC_OVERRIDE",
+    "CAP_MAC_ADMIN",
+    "CAP_SYSLOG",
+    "CAP_WAKE_ALARM",
+    "CAP_BLOCK_SUSPEND",
+    "CAP_AUDIT_READ",
+    "CAP_PERFMON",
+    "CAP_BPF",
+    "CAP_CHECKPOINT_RESTORE",
+];
+
+impl SecurityContext {
+    #[tracing::instrument(name = "SecurityContext::collect")]
+    pub fn collect() -> Result<Self> {
+        let status = read_to_string(PROC_SELF_STATUS)?;
+        let status: BTreeMap<&str, &str> = status
+            .lines()
+            .filter_map(|line| {
+                let (key, value) = line.split_once(':')?;
+                Some((key, value.trim()))
+            })
+            .collect();
+
+        let capability_set = |key: &str| {
+            status
+                .get(key)
+                .and_then(|mask| u64::from_str_radix(mask, 16).ok())
+                .map(decode_capabilities)
+                .unwrap_or_default()
+        };
+        let capabilities = Capabilities {
+            inheritable: capability_set("CapInh"),
+            permitted: capability_set("CapPrm"),
+            effective: capability_set("CapEff"),
+            bounding: capability_set("CapBnd"),
+            ambient: capability_set("CapAmb"),
+        };
+        tracing::info!(
+            capabilities.effective = ?capabilities.effective,
+            capabilities.permitted = ?capabilities.permitted,
+            capabilities.bounding = ?capabilities.bounding,
+            capabilities.ambient = ?capabilities.ambient,
+            "capabilities"
+        );
+
+        let no_new_privileges = status.get("NoNewPrivs").map(|value| *value == "1");
+        let seccomp = status.get("Seccomp").and_then(|value| match *value {
+            "0" => Some(SeccompMode::Disabled),
+            "1" => Some(SeccompMode::Strict),
+            "2" => Some(SeccompMode::Filter),
+            _ => None,
+        });
+
+        // Newer kernels expose the AppArmor label separately, older kernels only expose the label of
+        // whichever LSM is active in attr/current. SELinux contexts always have the format
+        // `user:role:type[:level]`, which distinguishes them from AppArmor profiles.
+        let (selinux_context, current_apparmor_profile) = match read_attr("/proc/self/attr/current")
+        {
+            Some(label) if label.contains(':') => (Some(label), None),
+            label => (None, label),
+        };
+        let apparmor_profile =
+            read_attr("/proc/self/attr/apparmor/current").or(current_apparmor_profile);
+
+        let security_context = Self {
+            capabilities,
+            no_new_privileges,
+            seccomp,
+            apparmor_profile,
+            selinux_context,
+        };
+        tracing::info!(
+            security.no_new_privileges = security_context.no_new_privileges,
+            security.seccomp = ?security_context.seccomp,
+            security.apparmor_profile = security_context.apparmor_profile,
+            security.selinux_context = security_context.selinux_context,
+            "security context"
+        );
+        Ok(security_context)
+    }
+}
+
+fn decode_capabilities(mask: u64) -> Vec<String> {
+    (0..64)
+        .filter(|bit| mask & (1 << bit) != 0)
+        .map(|bit| match CAPABILITY_NAMES.get(bit) {
+            Some(name) => (*name).to_owned(),
+            None => format!("CAP_UNKNOWN_{bit}"),
+        })
+        .collect()
+}
+
+/// Reads an LSM attribute, returning [`None`] if the LSM is not active.
+fn read_attr(path: &str) -> Option<String> {
+    match read_to_string(path) {
+        Ok(label) => {
+            let label = label.trim_end_matches(['\0', '\n']).to_owned();
+            Some(label).filter(|label| !label.is_empty())
+        }
+        Err(err) => {
+            tracing::debug!(
+                error = &err as &dyn std::error::Error,
+                "LSM attribute not available, skipping"
+            );
+            None
+        }
+    }
+}
+
+fn read_to_string(path: &str) -> Result<String> {
+    std::fs::read_to_string(path).context(ReadFileSnafu { path })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode() {
+        assert!(decode_capabilities(0).is_empty());
+        assert_eq!(
+            decode_capabilities((1 << 0) | (1 << 21)),
+            ["CAP_CHOWN", "CAP_SYS_ADMIN"]
+        );
+        assert_eq!(decode_capabilities(1 << 63), ["CAP_UNKNOWN_63"]);
+    }
+}
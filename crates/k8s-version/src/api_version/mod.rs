@@ -61,11 +61,17 @@ impl FromStr for ApiVersion {
 
 impl PartialOrd for ApiVersion {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        match self.group.partial_cmp(&other.group) {
-            Some(Ordering::Equal) => {}
-            _ => return None,
-        }
-        self.version.partial_cmp(&other.version)
+        Some(self.cmp(other))
+    }
+}
+
+/// API versions are ordered by their group first (with the core group, which has no name, being
+/// ordered first), and by their [`Version`] second.
+impl Ord for ApiVersion {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.group
+            .cmp(&other.group)
+            .then_with(|| self.version.cmp(&other.version))
     }
 }
 
@@ -91,6 +97,36 @@ impl ApiVersion {
 
         Ok(Self { group, version })
     }
+
+    /// Compares two API versions by their priority, see [`Version::priority_cmp`].
+    ///
+    /// API versions of different groups are ordered by their group, like [`Ord`] does.
+    pub fn priority_cmp(&self, other: &Self) -> Ordering {
+        self.group
+            .cmp(&other.group)
+            .then_with(|| self.version.priority_cmp(&other.version))
+    }
+
+    /// Returns the API version with the highest priority (see [`Version::priority_cmp`]) out of
+    /// all `api_versions` belonging to `group`, where [`None`] selects the core group.
+    ///
+    /// ```
+    /// use k8s_version::ApiVersion;
+    ///
+    /// let served = ["apps/v1beta2", "apps/v1", "batch/v2"].map(|version| version.parse().unwrap());
+    /// let preferred = ApiVersion::preferred(served, Some(&"apps".parse().unwrap())).unwrap();
+    ///
+    /// assert_eq!(preferred.to_string(), "apps/v1");
+    /// ```
+    pub fn preferred(
+        api_versions: impl IntoIterator<Item = Self>,
+        group: Option<&Group>,
+    ) -> Option<Self> {
+        api_versions
+            .into_iter()
+            .filter(|api_version| api_version.group.as_ref() == group)
+            .max_by(|lhs, rhs| lhs.version.priority_cmp(&rhs.version))
+    }
 }
 
 #[cfg(test)]
@@ -128,4 +164,49 @@ mod test {
     fn partial_ord(#[case] input: Version, #[case] other: Version, #[case] expected: Ordering) {
         assert_eq!(input.partial_cmp(&other), Some(expected));
     }
+
+    #[rstest]
+    #[case("v1", "apps/v1", Ordering::Less)]
+    #[case("apps/v1", "batch/v1", Ordering::Less)]
+    #[case("apps/v1", "apps/v1beta1", Ordering::Greater)]
+    #[case("apps/v2alpha1", "apps/v1", Ordering::Greater)]
+    fn ord(#[case] input: &str, #[case] other: &str, #[case] expected: Ordering) {
+        let input = ApiVersion::from_str(input).expect("valid Kubernetes api version");
+        let other = ApiVersion::from_str(other).expect("valid Kubernetes api version");
+        assert_eq!(input.cmp(&other), expected);
+        assert_eq!(input.partial_cmp(&other), Some(expected));
+    }
+
+    #[rstest]
+    #[case("apps/v2alpha1", "apps/v1", Ordering::Less)]
+    #[case("apps/v1", "apps/v1beta1", Ordering::Greater)]
+    #[case("apps/v1", "batch/v1", Ordering::Less)]
+    fn priority_cmp(#[case] input: &str, #[case] other: &str, #[case] expected: Ordering) {
+        let input = ApiVersion::from_str(input).expect("valid Kubernetes api version");
+        let other = ApiVersion::from_str(other).expect("valid Kubernetes api version");
+        assert_eq!(input.priority_cmp(&other), expected);
+    }
+
+    #[rstest]
+    #[case(Some("apps"), Some("apps/v1"))]
+    #[case(Some("batch"), Some("batch/v2alpha1"))]
+    #[case(None, Some("v1"))]
+    #[case(Some("extensions"), None)]
+    fn preferred(#[case] group: Option<&str>, #[case] expected: Option<&str>) {
+        let api_versions = [
+            "apps/v1beta1",
+            "apps/v1",
+            "apps/v2alpha1",
+            "batch/v2alpha1",
+            "v1",
+        ]
+        .map(|api_version| {
+            ApiVersion::from_str(api_version).expect("valid Kubernetes api version")
+        });
+        let group = group.map(|group| Group::from_str(group).expect("valid group"));
+
+        let preferred = ApiVersion::preferred(api_versions, group.as_ref())
+            .map(|api_version| api_version.to_string());
+        assert_eq!(preferred.as_deref(), expected);
+    }
 }
@@ -0,0 +1,190 @@
+//! Resolution of preferred API versions based on the results of the Kubernetes API discovery.
+//!
+//! This module is only available if the `k8s-openapi` feature is enabled.
+use std::str::FromStr;
+
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::{APIGroup, APIResourceList};
+
+use crate::{ApiVersion, Group, GroupVersionKind, Kind};
+
+impl ApiVersion {
+    /// Resolves the preferred API version of `group` (where [`None`] selects the core group) out of
+    /// all API versions served according to `resource_lists`, as returned by the
+    /// `/api/<VERSION>` and `/apis/<GROUP>/<VERSION>` discovery endpoints.
+    ///
+    /// API versions which don't follow the Kubernetes version format are ignored.
+    pub fn preferred_from_resource_lists<'a>(
+        resource_lists: impl IntoIterator<Item = &'a APIResourceList>,
+        group: Option<&Group>,
+    ) -> Option<Self> {
+        let served = resource_lists
+            .into_iter()
+            .filter_map(|resource_list| Self::from_str(&resource_list.group_version).ok());
+
+        Self::preferred(served, group)
+    }
+
+    /// Resolves the preferred API version of an [`APIGroup`], as returned by the `/apis`
+    /// discovery endpoint.
+    ///
+    /// The preferred version reported by the API server is used if it is set, otherwise the
+    /// version with the highest priority (see [`Version::priority_cmp`](crate::Version::priority_cmp))
+    /// is used.
+    pub fn preferred_from_api_group(api_group: &APIGroup) -> Option<Self> {
+        let reported = api_group
+            .preferred_version
+            .as_ref()
+            .and_then(|preferred| Self::from_str(&preferred.group_version).ok());
+
+        reported.or_else(|| {
+            let group = Group::from_str(&api_group.name).ok();
+            let served = api_group
+                .versions
+                .iter()
+                .filter_map(|version| Self::from_str(&version.group_version).ok());
+
+            Self::preferred(served, group.as_ref())
+        })
+    }
+}
+
+impl Group {
+    /// Resolves the preferred API version of this group, see
+    /// [`ApiVersion::preferred_from_resource_lists`].
+    pub fn preferred_api_version<'a>(
+        &self,
+        resource_lists: impl IntoIterator<Item = &'a APIResourceList>,
+    ) -> Option<ApiVersion> {
+        ApiVersion::preferred_from_resource_lists(resource_lists, Some(self))
+    }
+}
+
+impl GroupVersionKind {
+    /// Resolves the preferred API version of `kind` in `group` (where [`None`] selects the core
+    /// group) out of all API versions that serve the kind according to `resource_lists`.
+    ///
+    /// Unlike [`ApiVersion::preferred_from_resource_lists`], this only takes API versions into
+    /// account which actually serve the kind, which is relevant for kinds that are not available
+    /// in every version of their group.
+    pub fn preferred_from_resource_lists<'a>(
+        resource_lists: impl IntoIterator<Item = &'a APIResourceList>,
+        group: Option<&Group>,
+        kind: &Kind,
+    ) -> Option<Self> {
+        let served = resource_lists
+            .into_iter()
+            .filter(|resource_list| {
+                resource_list
+                    .resources
+                    .iter()
+                    .any(|resource| resource.kind == **kind)
+            })
+            .filter_map(|resource_list| ApiVersion::from_str(&resource_list.group_version).ok());
+
+        ApiVersion::preferred(served, group).map(|api_version| Self::new(api_version, kind.clone()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use k8s_openapi::apimachinery::pkg::apis::meta::v1::{APIResource, GroupVersionForDiscovery};
+    use rstest::rstest;
+
+    use super::*;
+
+    fn resource_list(group_version: &str, kinds: &[&str]) -> APIResourceList {
+        APIResourceList {
+            group_version: group_version.to_owned(),
+            resources: kinds
+                .iter()
+                .map(|kind| APIResource {
+                    kind: (*kind).to_owned(),
+                    name: kind.to_lowercase(),
+                    ..Default::default()
+                })
+                .collect(),
+        }
+    }
+
+    fn resource_lists() -> Vec<APIResourceList> {
+        vec![
+            resource_list("v1", &["ConfigMap", "Pod"]),
+            resource_list("autoscaling/v1", &["HorizontalPodAutoscaler"]),
+            resource_list("autoscaling/v2", &["HorizontalPodAutoscaler"]),
+            resource_list("autoscaling/v2beta2", &["HorizontalPodAutoscaler"]),
+            resource_list("flowcontrol.apiserver.k8s.io/v1beta3", &["FlowSchema"]),
+            resource_list(
+                "flowcontrol.apiserver.k8s.io/v1alpha1",
+                &["FlowSchema", "Example"],
+            ),
+            resource_list("metrics.k8s.io/v1.2", &["PodMetrics"]),
+        ]
+    }
+
+    #[rstest]
+    #[case(None, Some("v1"))]
+    #[case(Some("autoscaling"), Some("autoscaling/v2"))]
+    #[case(
+        Some("flowcontrol.apiserver.k8s.io"),
+        Some("flowcontrol.apiserver.k8s.io/v1beta3")
+    )]
+    #[case(Some("metrics.k8s.io"), None)]
+    #[case(Some("apps"), None)]
+    fn preferred_from_resource_lists(#[case] group: Option<&str>, #[case] expected: Option<&str>) {
+        let group = group.map(|group| Group::from_str(group).expect("valid group"));
+        let preferred =
+            ApiVersion::preferred_from_resource_lists(&resource_lists(), group.as_ref())
+                .map(|api_version| api_version.to_string());
+        assert_eq!(preferred.as_deref(), expected);
+    }
+
+    #[rstest]
+    #[case(
+        Some("flowcontrol.apiserver.k8s.io"),
+        "FlowSchema",
+        Some("flowcontrol.apiserver.k8s.io/v1beta3/FlowSchema")
+    )]
+    #[case(
+        Some("flowcontrol.apiserver.k8s.io"),
+        "Example",
+        Some("flowcontrol.apiserver.k8s.io/v1alpha1/Example")
+    )]
+    #[case(None, "Pod", Some("v1/Pod"))]
+    #[case(None, "Deployment", None)]
+    fn gvk_preferred_from_resource_lists(
+        #[case] group: Option<&str>,
+        #[case] kind: &str,
+        #[case] expected: Option<&str>,
+    ) {
+        let group = group.map(|group| Group::from_str(group).expect("valid group"));
+        let kind = Kind::from_str(kind).expect("valid kind");
+        let preferred = GroupVersionKind::preferred_from_resource_lists(
+            &resource_lists(),
+            group.as_ref(),
+            &kind,
+        )
+        .map(|gvk| gvk.to_string());
+        assert_eq!(preferred.as_deref(), expected);
+    }
+
+    #[test]
+    fn preferred_from_api_group() {
+        let version = |version: &str| GroupVersionForDiscovery {
+            group_version: format!("autoscaling/{version}"),
+            version: version.to_owned(),
+        };
+        let mut api_group = APIGroup {
+            name: "autoscaling".to_owned(),
+            preferred_version: Some(version("v1")),
+            versions: vec![version("v2beta2"), version("v1"), version("v2")],
+            ..Default::default()
+        };
+
+        let preferred = ApiVersion::preferred_from_api_group(&api_group).map(|v| v.to_string());
+        assert_eq!(preferred.as_deref(), Some("autoscaling/v1"));
+
+        api_group.preferred_version = None;
+        let preferred = ApiVersion::preferred_from_api_group(&api_group).map(|v| v.to_string());
+        assert_eq!(preferred.as_deref(), Some("autoscaling/v2"));
+    }
+}
@@ -36,7 +36,7 @@ pub enum ParseGroupError {
 ///
 /// - <https://github.com/kubernetes/community/blob/master/contributors/devel/sig-architecture/api-conventions.md#api-conventions>
 #[cfg_attr(feature = "serde", derive(::serde::Deserialize, ::serde::Serialize))]
-#[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd)]
+#[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Group(String);
 
 impl FromStr for Group {
@@ -0,0 +1,41 @@
+use std::str::FromStr;
+
+use darling::FromMeta;
+
+use crate::GroupVersionKind;
+
+impl FromMeta for GroupVersionKind {
+    fn from_string(value: &str) -> darling::Result<Self> {
+        Self::from_str(value).map_err(darling::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use quote::quote;
+    use rstest::rstest;
+
+    use super::*;
+
+    fn parse_meta(tokens: &proc_macro2::TokenStream) -> syn::Meta {
+        let attribute: syn::Attribute = syn::parse_quote!(#[#tokens]);
+        attribute.meta
+    }
+
+    #[rstest]
+    #[case(quote!(ignore = "extensions/v1beta1/Extension"), "extensions/v1beta1", "Extension")]
+    #[case(quote!(ignore = "v1/ConfigMap"), "v1", "ConfigMap")]
+    fn from_meta(
+        #[case] input: proc_macro2::TokenStream,
+        #[case] api_version: &str,
+        #[case] kind: &str,
+    ) {
+        let meta = parse_meta(&input);
+        let gvk = GroupVersionKind::from_meta(&meta)
+            .expect("group version kind must parse from attribute");
+        assert_eq!(
+            gvk,
+            GroupVersionKind::try_new(api_version, kind).expect("valid group version kind")
+        );
+    }
+}
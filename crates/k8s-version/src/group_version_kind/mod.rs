@@ -0,0 +1,145 @@
+use std::{fmt::Display, str::FromStr};
+
+use snafu::{OptionExt, ResultExt, Snafu};
+
+use crate::{ApiVersion, Kind, ParseApiVersionError, ParseKindError};
+
+#[cfg(feature = "serde")]
+mod serde;
+
+#[cfg(feature = "darling")]
+mod darling;
+
+/// Error variants which can be encountered when creating a new [`GroupVersionKind`]
+/// from unparsed input.
+#[derive(Debug, PartialEq, Eq, Snafu)]
+pub enum ParseGroupVersionKindError {
+    #[snafu(display(
+        "invalid group version kind format, expected (<GROUP>/)<VERSION>/<KIND> or (<GROUP>)/<VERSION>, Kind=<KIND>"
+    ))]
+    InvalidFormat,
+
+    #[snafu(display("failed to parse api version"))]
+    ParseApiVersion { source: ParseApiVersionError },
+
+    #[snafu(display("failed to parse kind"))]
+    ParseKind { source: ParseKindError },
+}
+
+/// A fully qualified Kubernetes kind, consisting of an [`ApiVersion`] and a [`Kind`].
+///
+/// It can be parsed from the `apiVersion` and `kind` joined by a slash, like `apps/v1/Deployment`
+/// or `v1/ConfigMap`, as well as from the format used by the Go `schema.GroupVersionKind` type,
+/// like `apps/v1, Kind=Deployment` or `/v1, Kind=ConfigMap` (for the core group). It is always
+/// displayed in the former format.
+///
+/// ```
+/// use k8s_version::GroupVersionKind;
+///
+/// let gvk: GroupVersionKind = "apps/v1, Kind=Deployment".parse().unwrap();
+///
+/// assert_eq!(gvk.api_version.to_string(), "apps/v1");
+/// assert_eq!(gvk.kind.to_string(), "Deployment");
+/// assert_eq!(gvk.to_string(), "apps/v1/Deployment");
+/// ```
+#[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct GroupVersionKind {
+    pub api_version: ApiVersion,
+    pub kind: Kind,
+}
+
+impl FromStr for GroupVersionKind {
+    type Err = ParseGroupVersionKindError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let (api_version, kind) = match input.split_once(", Kind=") {
+            // The Go format uses an empty group for the core group, like `/v1, Kind=ConfigMap`
+            Some((api_version, kind)) => {
+                (api_version.strip_prefix('/').unwrap_or(api_version), kind)
+            }
+            None => input.rsplit_once('/').context(InvalidFormatSnafu)?,
+        };
+
+        let api_version = ApiVersion::from_str(api_version).context(ParseApiVersionSnafu)?;
+        let kind = Kind::from_str(kind).context(ParseKindSnafu)?;
+
+        Ok(Self { api_version, kind })
+    }
+}
+
+impl Display for GroupVersionKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{api_version}/{kind}",
+            api_version = self.api_version,
+            kind = self.kind
+        )
+    }
+}
+
+impl GroupVersionKind {
+    /// Create a new fully qualified Kubernetes kind.
+    pub fn new(api_version: ApiVersion, kind: Kind) -> Self {
+        Self { api_version, kind }
+    }
+
+    /// Try to create a new fully qualified Kubernetes kind based on the unvalidated `api_version`
+    /// and `kind` strings, like they are found in the `apiVersion` and `kind` fields of an object.
+    pub fn try_new(api_version: &str, kind: &str) -> Result<Self, ParseGroupVersionKindError> {
+        let api_version = ApiVersion::from_str(api_version).context(ParseApiVersionSnafu)?;
+        let kind = Kind::from_str(kind).context(ParseKindSnafu)?;
+
+        Ok(Self { api_version, kind })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rstest::rstest;
+
+    use super::*;
+    use crate::{Level, Version};
+
+    #[rstest]
+    #[case("apps/v1/Deployment", "apps/v1", "Deployment")]
+    #[case("v1/ConfigMap", "v1", "ConfigMap")]
+    #[case("apps/v1, Kind=Deployment", "apps/v1", "Deployment")]
+    #[case("/v1, Kind=ConfigMap", "v1", "ConfigMap")]
+    #[case(
+        "zookeeper.stackable.tech/v1alpha1/ZookeeperCluster",
+        "zookeeper.stackable.tech/v1alpha1",
+        "ZookeeperCluster"
+    )]
+    fn valid_group_version_kind(
+        #[case] input: &str,
+        #[case] api_version: &str,
+        #[case] kind: &str,
+    ) {
+        let gvk = GroupVersionKind::from_str(input).expect("valid Kubernetes group version kind");
+        assert_eq!(
+            gvk,
+            GroupVersionKind::try_new(api_version, kind)
+                .expect("valid Kubernetes group version kind")
+        );
+    }
+
+    #[rstest]
+    #[case("Deployment", ParseGroupVersionKindError::InvalidFormat)]
+    #[case("apps/v1/deployment", ParseGroupVersionKindError::ParseKind { source: ParseKindError::InvalidFormat })]
+    #[case("apps/1/Deployment", ParseGroupVersionKindError::ParseApiVersion { source: ParseApiVersionError::ParseVersion { source: crate::ParseVersionError::InvalidFormat } })]
+    fn invalid_group_version_kind(#[case] input: &str, #[case] error: ParseGroupVersionKindError) {
+        let err =
+            GroupVersionKind::from_str(input).expect_err("invalid Kubernetes group version kind");
+        assert_eq!(err, error);
+    }
+
+    #[test]
+    fn display() {
+        let gvk = GroupVersionKind::new(
+            ApiVersion::new(None, Version::new(1, Some(Level::Beta(1)))),
+            "Event".parse().expect("valid Kubernetes kind"),
+        );
+        assert_eq!(gvk.to_string(), "v1beta1/Event");
+    }
+}
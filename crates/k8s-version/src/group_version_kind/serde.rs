@@ -0,0 +1,62 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize, de::Visitor};
+
+use crate::GroupVersionKind;
+
+impl<'de> Deserialize<'de> for GroupVersionKind {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct GroupVersionKindVisitor;
+
+        impl Visitor<'_> for GroupVersionKindVisitor {
+            type Value = GroupVersionKind;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(formatter, "a valid Kubernetes group version kind")
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                GroupVersionKind::from_str(v).map_err(serde::de::Error::custom)
+            }
+        }
+
+        deserializer.deserialize_str(GroupVersionKindVisitor)
+    }
+}
+
+impl Serialize for GroupVersionKind {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+#[cfg(feature = "serde")]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserialize() {
+        let _: GroupVersionKind = serde_yaml::from_str("extensions.k8s.io/v1alpha1/Extension")
+            .expect("group version kind is valid");
+    }
+
+    #[test]
+    fn serialize() {
+        let gvk = GroupVersionKind::from_str("extensions.k8s.io/v1alpha1, Kind=Extension")
+            .expect("group version kind is valid");
+        assert_eq!(
+            "extensions.k8s.io/v1alpha1/Extension\n",
+            serde_yaml::to_string(&gvk).expect("group version kind must serialize")
+        );
+    }
+}
@@ -0,0 +1,95 @@
+use std::{fmt, ops::Deref, str::FromStr, sync::LazyLock};
+
+use regex::Regex;
+use snafu::{Snafu, ensure};
+
+const MAX_KIND_LENGTH: usize = 63;
+
+static KIND_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^[A-Z][A-Za-z0-9]*$").expect("failed to compile kind regex"));
+
+/// Error variants which can be encountered when creating a new [`Kind`] from
+/// unparsed input.
+#[derive(Debug, PartialEq, Eq, Snafu)]
+pub enum ParseKindError {
+    #[snafu(display("kind must not be empty"))]
+    Empty,
+
+    #[snafu(display("kind must not be longer than 63 characters"))]
+    TooLong,
+
+    #[snafu(display(
+        "kind must start with an upper case character and only contain alphanumeric characters"
+    ))]
+    InvalidFormat,
+}
+
+/// A validated Kubernetes kind, like `Deployment` or `ConfigMap`.
+///
+/// The kind string must follow these rules:
+///
+/// - must be non-empty
+/// - must not be longer than 63 characters
+/// - must be in CamelCase, starting with an upper case character, and must only contain
+///   alphanumeric characters
+///
+/// ### See
+///
+/// - <https://github.com/kubernetes/community/blob/master/contributors/devel/sig-architecture/api-conventions.md#types-kinds>
+#[cfg_attr(feature = "serde", derive(::serde::Deserialize, ::serde::Serialize))]
+#[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Kind(String);
+
+impl FromStr for Kind {
+    type Err = ParseKindError;
+
+    fn from_str(kind: &str) -> Result<Self, Self::Err> {
+        ensure!(!kind.is_empty(), EmptySnafu);
+        ensure!(kind.len() <= MAX_KIND_LENGTH, TooLongSnafu);
+        ensure!(KIND_REGEX.is_match(kind), InvalidFormatSnafu);
+
+        Ok(Self(kind.to_owned()))
+    }
+}
+
+impl fmt::Display for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self)
+    }
+}
+
+impl Deref for Kind {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case("Deployment")]
+    #[case("ConfigMap")]
+    #[case("HorizontalPodAutoscaler")]
+    #[case("V1")]
+    fn valid_kind(#[case] input: &str) {
+        let kind = Kind::from_str(input).expect("valid Kubernetes kind");
+        assert_eq!(kind.to_string(), input);
+    }
+
+    #[rstest]
+    #[case("", ParseKindError::Empty)]
+    #[case("deployment", ParseKindError::InvalidFormat)]
+    #[case("Config-Map", ParseKindError::InvalidFormat)]
+    #[case("1Deployment", ParseKindError::InvalidFormat)]
+    #[case(&"A".repeat(64), ParseKindError::TooLong)]
+    fn invalid_kind(#[case] input: &str, #[case] error: ParseKindError) {
+        let err = Kind::from_str(input).expect_err("invalid Kubernetes kind");
+        assert_eq!(err, error);
+    }
+}
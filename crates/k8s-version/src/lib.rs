@@ -42,13 +42,51 @@
 //!
 //! assert_eq!(api_version.to_string(), "extension/v1beta1");
 //! ```
+//!
+//! ### Version priority
+//!
+//! Versions implement [`Ord`] chronologically (`v1alpha1 < v1beta1 < v1 < v2alpha1`).
+//! Additionally, [`Version::priority_cmp()`] compares versions following the
+//! Kubernetes version priority rules, which prefer more stable versions
+//! (`v1 > v2beta1 > v2alpha1`). [`Version::preferred()`] and
+//! [`ApiVersion::preferred()`] use these rules to pick the version Kubernetes
+//! would prefer out of a set of served versions.
+//!
+//! ```
+//! use k8s_version::{ApiVersion, Version};
+//!
+//! let served = ["v2alpha1", "v1", "v1beta1"].map(|version| version.parse().unwrap());
+//! assert_eq!(Version::preferred(served).unwrap().to_string(), "v1");
+//! ```
+//!
+//! If the `k8s-openapi` feature is enabled, the preferred version can also be
+//! resolved from the results of the API discovery, see
+//! `ApiVersion::preferred_from_resource_lists()`.
+//!
+//! ### Group version kinds
+//!
+//! Fully qualified kinds can be parsed into a [`GroupVersionKind`].
+//!
+//! ```
+//! use k8s_version::GroupVersionKind;
+//!
+//! let gvk: GroupVersionKind = "apps/v1/Deployment".parse().unwrap();
+//! assert_eq!(gvk.kind.to_string(), "Deployment");
+//! ```
 
 mod api_version;
 mod group;
+mod group_version_kind;
+mod kind;
 mod level;
 mod version;
 
+#[cfg(feature = "k8s-openapi")]
+mod discovery;
+
 pub use api_version::*;
 pub use group::*;
+pub use group_version_kind::*;
+pub use kind::*;
 pub use level::*;
 pub use version::*;
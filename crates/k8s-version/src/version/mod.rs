@@ -115,6 +115,78 @@ impl Version {
     pub fn new(major: u64, level: Option<Level>) -> Self {
         Self { major, level }
     }
+
+    /// Returns the [`Stability`] of this version, which is determined by its level.
+    pub fn stability(&self) -> Stability {
+        match self.level {
+            None => Stability::Stable,
+            Some(Level::Beta(_)) => Stability::Beta,
+            Some(Level::Alpha(_)) => Stability::Alpha,
+        }
+    }
+
+    /// Compares two versions by their priority, following the Kubernetes version priority rules.
+    ///
+    /// Unlike [`Ord`], which orders versions chronologically (`v1alpha1 < v1beta1 < v1 <
+    /// v2alpha1`), this prefers more stable versions regardless of their major version. Stable
+    /// versions are sorted first, followed by beta and then alpha versions. Within the same
+    /// stability, versions are sorted by their major version and then by their level version,
+    /// higher versions first. This results in the following order (from highest to lowest
+    /// priority):
+    ///
+    /// `v10`, `v2`, `v1`, `v11beta2`, `v10beta3`, `v3beta1`, `v12alpha1`, `v11alpha2`
+    ///
+    /// [`Ordering::Greater`] indicates that `self` has a higher priority than `other`.
+    ///
+    /// ### See
+    ///
+    /// - <https://kubernetes.io/docs/tasks/extend-kubernetes/custom-resources/custom-resource-definition-versioning/#version-priority>
+    pub fn priority_cmp(&self, other: &Self) -> Ordering {
+        let level_version = |level: Option<Level>| match level {
+            Some(Level::Alpha(version) | Level::Beta(version)) => version,
+            None => 0,
+        };
+
+        self.stability()
+            .cmp(&other.stability())
+            .then_with(|| self.major.cmp(&other.major))
+            .then_with(|| level_version(self.level).cmp(&level_version(other.level)))
+    }
+
+    /// Returns the version with the highest priority (see [`Version::priority_cmp`]) out of
+    /// `versions`, which is the version Kubernetes would prefer when serving them.
+    ///
+    /// ```
+    /// use k8s_version::Version;
+    ///
+    /// let served = ["v1alpha1", "v2beta1", "v1"].map(|version| version.parse().unwrap());
+    /// let preferred = Version::preferred(served).unwrap();
+    ///
+    /// assert_eq!(preferred.to_string(), "v1");
+    /// ```
+    pub fn preferred(versions: impl IntoIterator<Item = Self>) -> Option<Self> {
+        versions.into_iter().max_by(Self::priority_cmp)
+    }
+
+    /// Sorts `versions` by their priority (see [`Version::priority_cmp`]), starting with the
+    /// version with the highest priority.
+    pub fn sort_by_priority(versions: &mut [Self]) {
+        versions.sort_by(|lhs, rhs| rhs.priority_cmp(lhs));
+    }
+}
+
+/// The stability of a [`Version`], ordered from least ([`Stability::Alpha`]) to most stable
+/// ([`Stability::Stable`]).
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Stability {
+    /// Versions with an alpha level, like `v1alpha1`.
+    Alpha,
+
+    /// Versions with a beta level, like `v1beta1`.
+    Beta,
+
+    /// Versions without a level, like `v1`.
+    Stable,
 }
 
 #[cfg(test)]
@@ -161,4 +233,62 @@ mod test {
     fn partial_ord(input: Version, other: Version, expected: Ordering) {
         assert_eq!(input.partial_cmp(&other), Some(expected));
     }
+
+    #[rstest]
+    #[case("v1", "v1beta2", Ordering::Greater)]
+    #[case("v1beta2", "v1alpha1", Ordering::Greater)]
+    #[case("v1", "v11beta2", Ordering::Greater)]
+    #[case("v10", "v2", Ordering::Greater)]
+    #[case("v3beta1", "v10beta3", Ordering::Less)]
+    #[case("v10beta3", "v10beta2", Ordering::Greater)]
+    #[case("v12alpha1", "v1beta1", Ordering::Less)]
+    #[case("v11alpha2", "v11alpha2", Ordering::Equal)]
+    fn priority_cmp(#[case] input: &str, #[case] other: &str, #[case] expected: Ordering) {
+        let input = Version::from_str(input).expect("valid Kubernetes version");
+        let other = Version::from_str(other).expect("valid Kubernetes version");
+        assert_eq!(input.priority_cmp(&other), expected);
+    }
+
+    #[test]
+    fn sort_by_priority() {
+        let mut versions = [
+            "v11alpha2",
+            "v1",
+            "v3beta1",
+            "v12alpha1",
+            "v10",
+            "v11beta2",
+            "v2",
+            "v10beta3",
+        ]
+        .map(|version| Version::from_str(version).expect("valid Kubernetes version"));
+
+        Version::sort_by_priority(&mut versions);
+
+        assert_eq!(
+            versions.map(|version| version.to_string()),
+            [
+                "v10",
+                "v2",
+                "v1",
+                "v11beta2",
+                "v10beta3",
+                "v3beta1",
+                "v12alpha1",
+                "v11alpha2",
+            ]
+        );
+    }
+
+    #[rstest]
+    #[case(&["v1alpha1", "v1beta1", "v1"], Some("v1"))]
+    #[case(&["v2alpha1", "v1beta1"], Some("v1beta1"))]
+    #[case(&[], None)]
+    fn preferred(#[case] versions: &[&str], #[case] expected: Option<&str>) {
+        let versions = versions
+            .iter()
+            .map(|version| Version::from_str(version).expect("valid Kubernetes version"));
+        let preferred = Version::preferred(versions).map(|version| version.to_string());
+        assert_eq!(preferred.as_deref(), expected);
+    }
 }
@@ -4,8 +4,8 @@ use indexmap::IndexMap;
 use k8s_openapi::{
     api::core::v1::{
         Affinity, Container, LocalObjectReference, NodeAffinity, Pod, PodAffinity, PodAntiAffinity,
-        PodCondition, PodSecurityContext, PodSpec, PodStatus, PodTemplateSpec,
-        ResourceRequirements, Toleration, Volume,
+        PodCondition, PodSchedulingGate, PodSecurityContext, PodSpec, PodStatus, PodTemplateSpec,
+        ResourceRequirements, Toleration, TopologySpreadConstraint, Volume,
     },
     apimachinery::pkg::{api::resource::Quantity, apis::meta::v1::ObjectMeta},
 };
@@ -24,6 +24,7 @@ use crate::{
             ComputeResource, LIMIT_REQUEST_RATIO_CPU, LIMIT_REQUEST_RATIO_MEMORY,
             ResourceRequirementsExt, ResourceRequirementsType,
         },
+        scheduling::SchedulingConfig,
     },
     kvp::Labels,
 };
@@ -79,6 +80,7 @@ pub struct PodBuilder {
     status: Option<PodStatus>,
     security_context: Option<PodSecurityContext>,
     tolerations: Option<Vec<Toleration>>,
+    topology_spread_constraints: Option<Vec<TopologySpreadConstraint>>,
 
     /// The key is the volume name.
     volumes: IndexMap<String, Volume>,
@@ -86,6 +88,9 @@ pub struct PodBuilder {
     image_pull_secrets: Option<Vec<LocalObjectReference>>,
     restart_policy: Option<String>,
     termination_grace_period_seconds: Option<i64>,
+    priority_class_name: Option<String>,
+    runtime_class_name: Option<String>,
+    scheduling_gates: Option<Vec<PodSchedulingGate>>,
 }
 
 impl PodBuilder {
@@ -176,12 +181,32 @@ impl PodBuilder {
         self
     }
 
+    pub fn topology_spread_constraints(
+        &mut self,
+        topology_spread_constraints: Vec<TopologySpreadConstraint>,
+    ) -> &mut Self {
+        self.topology_spread_constraints = Some(topology_spread_constraints);
+        self
+    }
+
+    pub fn topology_spread_constraints_opt(
+        &mut self,
+        topology_spread_constraints: Option<Vec<TopologySpreadConstraint>>,
+    ) -> &mut Self {
+        self.topology_spread_constraints = topology_spread_constraints;
+        self
+    }
+
     pub fn affinity(&mut self, affinities: &StackableAffinity) -> &mut Self {
         self.pod_affinity.clone_from(&affinities.pod_affinity);
         self.pod_anti_affinity
             .clone_from(&affinities.pod_anti_affinity);
         self.node_affinity.clone_from(&affinities.node_affinity);
         self.node_selector = affinities.node_selector.clone().map(|ns| ns.node_selector);
+        self.topology_spread_constraints = affinities
+            .topology_spread_constraints
+            .clone()
+            .map(|tsc| tsc.topology_spread_constraints);
         self
     }
 
@@ -260,6 +285,56 @@ impl PodBuilder {
         self
     }
 
+    pub fn add_scheduling_gates(&mut self, scheduling_gates: Vec<PodSchedulingGate>) -> &mut Self {
+        self.scheduling_gates
+            .get_or_insert_with(Vec::new)
+            .extend(scheduling_gates);
+        self
+    }
+
+    pub fn priority_class_name(&mut self, priority_class_name: impl Into<String>) -> &mut Self {
+        self.priority_class_name = Some(priority_class_name.into());
+        self
+    }
+
+    pub fn runtime_class_name(&mut self, runtime_class_name: impl Into<String>) -> &mut Self {
+        self.runtime_class_name = Some(runtime_class_name.into());
+        self
+    }
+
+    /// Applies the (merged) [`SchedulingConfig`] of a role group.
+    ///
+    /// Tolerations and scheduling gates are added to the ones already present, all other settings
+    /// are only overwritten if they are set in the given config.
+    /// The names in the config are not checked here, use [`SchedulingConfig::validate`] for that.
+    pub fn scheduling(&mut self, scheduling: &SchedulingConfig) -> Result<&mut Self> {
+        let SchedulingConfig {
+            priority_class_name,
+            tolerations,
+            scheduling_gates,
+            runtime_class_name,
+            termination_grace_period,
+        } = scheduling;
+
+        if let Some(priority_class_name) = priority_class_name {
+            self.priority_class_name(priority_class_name);
+        }
+        if let Some(tolerations) = tolerations {
+            self.add_tolerations(tolerations.clone());
+        }
+        if let Some(scheduling_gates) = scheduling_gates {
+            self.add_scheduling_gates(scheduling_gates.clone());
+        }
+        if let Some(runtime_class_name) = runtime_class_name {
+            self.runtime_class_name(runtime_class_name);
+        }
+        if let Some(termination_grace_period) = termination_grace_period {
+            self.termination_grace_period(termination_grace_period)?;
+        }
+
+        Ok(self)
+    }
+
     pub fn security_context(
         &mut self,
         security_context: impl Into<PodSecurityContext>,
@@ -595,6 +670,7 @@ impl PodBuilder {
             }),
             security_context: self.security_context.clone(),
             tolerations: self.tolerations.clone(),
+            topology_spread_constraints: self.topology_spread_constraints.clone(),
             volumes,
             // Legacy feature for ancient Docker images
             // In practice, this just causes a bunch of unused environment variables that may conflict with other uses,
@@ -604,6 +680,9 @@ impl PodBuilder {
             image_pull_secrets: self.image_pull_secrets.clone(),
             restart_policy: self.restart_policy.clone(),
             termination_grace_period_seconds: self.termination_grace_period_seconds,
+            priority_class_name: self.priority_class_name.clone(),
+            runtime_class_name: self.runtime_class_name.clone(),
+            scheduling_gates: self.scheduling_gates.clone(),
             ..PodSpec::default()
         };
 
@@ -643,12 +722,15 @@ mod tests {
     use rstest::*;
 
     use super::*;
-    use crate::builder::{
-        meta::ObjectMetaBuilder,
-        pod::{
-            container::ContainerBuilder, resources::ResourceRequirementsBuilder,
-            volume::VolumeBuilder,
+    use crate::{
+        builder::{
+            meta::ObjectMetaBuilder,
+            pod::{
+                container::ContainerBuilder, resources::ResourceRequirementsBuilder,
+                volume::VolumeBuilder,
+            },
         },
+        commons::affinity::default_role_topology_spread_constraints,
     };
 
     // A simple [`Container`] with a name and image.
@@ -768,6 +850,25 @@ mod tests {
         assert_eq!(pod_spec.termination_grace_period_seconds, Some(42));
     }
 
+    #[rstest]
+    fn builder_affinity(mut pod_builder_with_name_and_container: PodBuilder) {
+        let topology_spread_constraints =
+            default_role_topology_spread_constraints("zookeeper", "simple-zk", "server");
+
+        let pod = pod_builder_with_name_and_container
+            .affinity(&StackableAffinity {
+                topology_spread_constraints: Some(topology_spread_constraints.clone()),
+                ..StackableAffinity::default()
+            })
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            pod.spec.unwrap().topology_spread_constraints,
+            Some(topology_spread_constraints.topology_spread_constraints)
+        );
+    }
+
     #[rstest]
     fn builder_image_pull_secrets(mut pod_builder_with_name_and_container: PodBuilder) {
         let pod = pod_builder_with_name_and_container
@@ -792,6 +893,52 @@ mod tests {
         assert_eq!(pod.spec.unwrap().restart_policy.unwrap(), "Always");
     }
 
+    #[rstest]
+    fn builder_scheduling(mut pod_builder_with_name_and_container: PodBuilder) {
+        let scheduling: SchedulingConfig = serde_yaml::from_str(
+            "
+            priorityClassName: high-priority
+            runtimeClassName: gvisor
+            tolerations:
+              - key: dedicated
+                operator: Exists
+            schedulingGates:
+              - name: example.com/quota
+            terminationGracePeriod: 2m
+            ",
+        )
+        .unwrap();
+
+        let pod = pod_builder_with_name_and_container
+            .add_tolerations(vec![Toleration {
+                key: Some("operator-generated".to_owned()),
+                ..Toleration::default()
+            }])
+            .scheduling(&scheduling)
+            .unwrap()
+            .build()
+            .unwrap();
+
+        let pod_spec = pod.spec.unwrap();
+        assert_eq!(
+            pod_spec.priority_class_name.as_deref(),
+            Some("high-priority")
+        );
+        assert_eq!(pod_spec.runtime_class_name.as_deref(), Some("gvisor"));
+        assert_eq!(
+            pod_spec.tolerations.unwrap().len(),
+            2,
+            "tolerations must be added to the existing ones"
+        );
+        assert_eq!(
+            pod_spec.scheduling_gates,
+            Some(vec![PodSchedulingGate {
+                name: "example.com/quota".to_owned()
+            }])
+        );
+        assert_eq!(pod_spec.termination_grace_period_seconds, Some(120));
+    }
+
     #[test]
     fn builder_too_long_termination_grace_period() {
         let too_long_duration = Duration::from_secs(i64::MAX as u64 + 1);
@@ -0,0 +1,1798 @@
+//! An in-memory stand-in for the Kubernetes API server to test reconcilers without a cluster.
+//!
+//! The [`FakeApiServer`] implements the REST API which is used by [`kube::Client`], so that a
+//! [`Client`] backed by it can be passed to reconcilers, [`ClusterResources`] and other helpers:
+//!
+//! ```
+//! use k8s_openapi::api::core::v1::ConfigMap;
+//! use kube::api::ObjectMeta;
+//! use stackable_operator::client::fake::FakeApiServer;
+//!
+//! # #[tokio::main(flavor = "current_thread")]
+//! # async fn main() {
+//! let server = FakeApiServer::new();
+//! let client = server.client();
+//!
+//! let config_map = ConfigMap {
+//!     metadata: ObjectMeta {
+//!         name: Some("my-config".to_string()),
+//!         namespace: Some("default".to_string()),
+//!         ..ObjectMeta::default()
+//!     },
+//!     ..ConfigMap::default()
+//! };
+//! client.create(&config_map).await.unwrap();
+//!
+//! assert_eq!(server.objects::<ConfigMap>().len(), 1);
+//! # }
+//! ```
+//!
+//! The following operations are supported: get, list, watch, create, update, merge patch, JSON
+//! patch, server-side apply, delete and delete collection including label and field selectors,
+//! resource versions, finalizers and the garbage collection of dependents via owner references.
+//!
+//! The fake does not know the schemas of the resources and therefore differs from a real API
+//! server in some aspects:
+//!
+//! * All paths are accepted, unknown resources are just empty.
+//! * Objects are not validated and defaults are not applied.
+//! * Writes to the main resource never change the `status` and writes to the `status`
+//!   subresource only change the `status`, as if all resources had a status subresource.
+//! * Strategic merge patches are applied as JSON merge patches.
+//! * Server-side apply merges objects recursively and replaces lists. Fields which were removed
+//!   from the previously applied configuration of the same field manager are removed. Conflicts
+//!   between field managers are not detected.
+//! * Dependents are garbage collected immediately and in the background.
+//!
+//! [`ClusterResources`]: crate::cluster_resources::ClusterResources
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    convert::Infallible,
+    sync::{Arc, Mutex, MutexGuard},
+    task::{Context, Poll},
+};
+
+use base64::Engine as _;
+use bytes::Bytes;
+use futures::{
+    StreamExt as _,
+    channel::mpsc::{UnboundedSender, unbounded},
+    future::BoxFuture,
+};
+use http::{Method, Request, Response, StatusCode, header::CONTENT_TYPE};
+use http_body::Frame;
+use http_body_util::{BodyExt as _, Full, StreamBody, combinators::UnsyncBoxBody};
+use kube::{
+    client::Body,
+    core::{ApiResource, DynamicObject, GroupVersionKind, Status},
+};
+use serde::{Serialize, de::DeserializeOwned};
+use serde_json::{Map, Value, json};
+
+use crate::{
+    client::{Client, KubeClient},
+    utils::cluster_info::KubernetesClusterInfo,
+};
+
+/// The namespace used as the default namespace of the [`Client`] returned by
+/// [`FakeApiServer::client`]
+pub const DEFAULT_NAMESPACE: &str = "default";
+
+/// The field manager used by the [`Client`] returned by [`FakeApiServer::client`]
+pub const FIELD_MANAGER: &str = "fake-operator";
+
+type ResponseBody = UnsyncBoxBody<Bytes, Infallible>;
+
+/// An in-memory stand-in for the Kubernetes API server
+///
+/// Clones share the same state. See the [module documentation](self) for the supported features
+/// and the differences to a real API server.
+#[derive(Clone, Default)]
+pub struct FakeApiServer {
+    state: Arc<Mutex<State>>,
+}
+
+impl FakeApiServer {
+    /// Creates an API server without any objects.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a [`Client`] backed by this API server with the default namespace
+    /// [`DEFAULT_NAMESPACE`] and the cluster domain `cluster.local`.
+    ///
+    /// This function must be called within a Tokio runtime.
+    pub fn client(&self) -> Client {
+        Client::new(
+            self.kube_client(),
+            Some(FIELD_MANAGER.to_string()),
+            DEFAULT_NAMESPACE.to_string(),
+            KubernetesClusterInfo {
+                cluster_domain: "cluster.local"
+                    .parse()
+                    .expect("the hard-coded cluster domain is valid"),
+            },
+        )
+    }
+
+    /// Returns a [`KubeClient`] backed by this API server with the default namespace
+    /// [`DEFAULT_NAMESPACE`].
+    ///
+    /// This function must be called within a Tokio runtime.
+    pub fn kube_client(&self) -> KubeClient {
+        KubeClient::new(self.clone(), DEFAULT_NAMESPACE)
+    }
+
+    /// Stores the given object as if it was created with the API and returns the stored object.
+    ///
+    /// This can be used to prepare the state of a test. In contrast to the API, the `status` of
+    /// the given object is stored as well.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the object cannot be created, e.g. because it already exists.
+    pub fn insert<K>(&self, object: &K) -> K
+    where
+        K: kube::Resource<DynamicType = ()> + Serialize + DeserializeOwned,
+    {
+        let object = serde_json::to_value(object).expect("the object can be serialized");
+        let created = self.insert_value(&Collection::of::<K>(), object);
+
+        serde_json::from_value(created).expect("the created object can be deserialized")
+    }
+
+    /// Stores the given object of any type like [`FakeApiServer::insert`].
+    ///
+    /// The plural of the resource is guessed from the kind of the object like in
+    /// [`ApiResource::from_gvk`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the object has no type information or cannot be created.
+    pub fn insert_dynamic(&self, object: &DynamicObject) -> DynamicObject {
+        let type_meta = object
+            .types
+            .as_ref()
+            .expect("the object has an apiVersion and kind");
+        let gvk = GroupVersionKind::try_from(type_meta).expect("the apiVersion is valid");
+        let api_resource = ApiResource::from_gvk(&gvk);
+        let collection = Collection {
+            api_version: api_resource.api_version,
+            plural: api_resource.plural,
+        };
+
+        let object = serde_json::to_value(object).expect("the object can be serialized");
+        let created = self.insert_value(&collection, object);
+
+        serde_json::from_value(created).expect("the created object can be deserialized")
+    }
+
+    fn insert_value(&self, collection: &Collection, object: Value) -> Value {
+        let namespace = object
+            .pointer("/metadata/namespace")
+            .and_then(Value::as_str)
+            .map(ToOwned::to_owned);
+
+        self.lock()
+            .create(collection, namespace.as_deref(), object, false)
+            .expect("the object can be created")
+    }
+
+    /// Returns all stored objects of the given type in all namespaces sorted by namespace and
+    /// name.
+    pub fn objects<K>(&self) -> Vec<K>
+    where
+        K: kube::Resource<DynamicType = ()> + DeserializeOwned,
+    {
+        let collection = Collection::of::<K>();
+
+        self.lock()
+            .objects
+            .iter()
+            .filter(|(key, _)| key.collection == collection)
+            .map(|(_, stored)| {
+                serde_json::from_value(stored.object.clone())
+                    .expect("the stored object can be deserialized")
+            })
+            .collect()
+    }
+
+    /// Rejects all requests for objects of the given type with `403 Forbidden`, as if the
+    /// operator was not allowed to access them.
+    pub fn forbid<K>(&self)
+    where
+        K: kube::Resource<DynamicType = ()>,
+    {
+        self.lock().forbidden.insert(Collection::of::<K>());
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state
+            .lock()
+            .expect("the state is not poisoned because the lock is never held while panicking")
+    }
+
+    fn handle(
+        &self,
+        method: &Method,
+        uri: &http::Uri,
+        content_type: &str,
+        body: &[u8],
+    ) -> Response<ResponseBody> {
+        let Some(path) = ResourcePath::parse(uri.path()) else {
+            return status_response(
+                StatusCode::NOT_FOUND,
+                "NotFound",
+                &format!("the path {path:?} is not supported", path = uri.path()),
+            );
+        };
+        let query = Query::parse(uri.query().unwrap_or_default());
+
+        if self.lock().forbidden.contains(&path.collection) {
+            return status_response(
+                StatusCode::FORBIDDEN,
+                "Forbidden",
+                &format!("access to {} is forbidden", path.collection.plural),
+            );
+        }
+
+        let result = match (method, &path.name) {
+            (&Method::GET, Some(name)) => self.lock().get(&path.key(name)),
+            (&Method::GET, None) if query.watch => return self.watch(&path, &query),
+            (&Method::GET, None) => Ok(self.lock().list(&path, &query)),
+            (&Method::POST, None) => parse_body(body).and_then(|mut object| {
+                // The status cannot be set on creation.
+                set_field(&mut object, "status", None);
+                self.lock().create(
+                    &path.collection,
+                    path.namespace.as_deref(),
+                    object,
+                    query.dry_run,
+                )
+            }),
+            (&Method::PUT, Some(name)) => parse_body(body).and_then(|object| {
+                self.lock().update(
+                    &path.key(name),
+                    path.subresource.as_deref(),
+                    object,
+                    query.dry_run,
+                )
+            }),
+            (&Method::PATCH, Some(name)) => parse_body(body)
+                .and_then(|patch| self.lock().patch(&path, name, &query, content_type, &patch)),
+            (&Method::DELETE, Some(name)) => {
+                let options = if body.is_empty() {
+                    Ok(Value::Null)
+                } else {
+                    parse_body(body)
+                };
+                options.and_then(|options| {
+                    self.lock().delete(&path.key(name), &options, query.dry_run)
+                })
+            }
+            (&Method::DELETE, None) => Ok(self.lock().delete_collection(&path, &query)),
+            _ => Err(ApiError::method_not_allowed(method)),
+        };
+
+        match result {
+            Ok(object) => json_response(StatusCode::OK, &object),
+            Err(error) => status_response(error.code, error.reason, &error.message),
+        }
+    }
+
+    fn watch(&self, path: &ResourcePath, query: &Query) -> Response<ResponseBody> {
+        let (sender, receiver) = unbounded();
+
+        let mut state = self.lock();
+        let watch = Watch {
+            collection: path.collection.clone(),
+            namespace: path.namespace.clone(),
+            label_selector: query.label_selector.clone(),
+            field_selector: query.field_selector.clone(),
+            sender,
+        };
+
+        let initial_events: Vec<Event> = match query.resource_version {
+            // Without a resource version, the watch starts with synthetic ADDED events of all
+            // existing objects.
+            None | Some(0) => state
+                .objects
+                .iter()
+                .map(|(key, stored)| Event {
+                    key: key.clone(),
+                    resource_version: 0,
+                    change: "ADDED",
+                    object: stored.object.clone(),
+                })
+                .collect(),
+            Some(resource_version) => state
+                .events
+                .iter()
+                .filter(|event| event.resource_version > resource_version)
+                .cloned()
+                .collect(),
+        };
+
+        for event in &initial_events {
+            watch.send(event);
+        }
+        state.watches.push(watch);
+        drop(state);
+
+        let body = StreamBody::new(receiver.map(|line: Bytes| Ok(Frame::data(line))));
+
+        Response::builder()
+            .status(StatusCode::OK)
+            .header(CONTENT_TYPE, "application/json")
+            .body(body.boxed_unsync())
+            .expect("the response is valid")
+    }
+}
+
+impl tower::Service<Request<Body>> for FakeApiServer {
+    type Error = Infallible;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+    type Response = Response<ResponseBody>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        let server = self.clone();
+
+        Box::pin(async move {
+            let (parts, body) = request.into_parts();
+            let content_type = parts
+                .headers
+                .get(CONTENT_TYPE)
+                .and_then(|value| value.to_str().ok())
+                .unwrap_or_default()
+                .to_owned();
+
+            let response = match body.collect_bytes().await {
+                Ok(body) => server.handle(&parts.method, &parts.uri, &content_type, &body),
+                Err(error) => status_response(
+                    StatusCode::BAD_REQUEST,
+                    "BadRequest",
+                    &format!("failed to read the request body: {error}"),
+                ),
+            };
+
+            Ok(response)
+        })
+    }
+}
+
+#[derive(Default)]
+struct State {
+    /// The resource version of the last change
+    resource_version: u64,
+    objects: BTreeMap<ObjectKey, StoredObject>,
+    /// All changes, so that watches can be started at any resource version
+    events: Vec<Event>,
+    watches: Vec<Watch>,
+    /// The collections which cannot be accessed
+    forbidden: BTreeSet<Collection>,
+}
+
+impl State {
+    fn get(&self, key: &ObjectKey) -> Result<Value, ApiError> {
+        self.objects
+            .get(key)
+            .map(|stored| stored.object.clone())
+            .ok_or_else(|| ApiError::not_found(key))
+    }
+
+    fn list(&self, path: &ResourcePath, query: &Query) -> Value {
+        let items = self
+            .objects
+            .iter()
+            .filter(|(key, stored)| {
+                path.matches(key)
+                    && query.label_selector.matches(&stored.object)
+                    && query.field_selector.matches(&stored.object)
+            })
+            .map(|(_, stored)| stored.object.clone())
+            .collect::<Vec<_>>();
+
+        json!({
+            "apiVersion": "v1",
+            "kind": "List",
+            "metadata": { "resourceVersion": self.resource_version.to_string() },
+            "items": items,
+        })
+    }
+
+    fn create(
+        &mut self,
+        collection: &Collection,
+        namespace: Option<&str>,
+        mut object: Value,
+        dry_run: bool,
+    ) -> Result<Value, ApiError> {
+        let metadata = metadata_mut(&mut object)?;
+
+        if metadata
+            .get("resourceVersion")
+            .is_some_and(|rv| !rv.is_null())
+        {
+            return Err(ApiError::bad_request(
+                "resourceVersion should not be set on objects to be created",
+            ));
+        }
+
+        match (namespace, metadata.get("namespace").and_then(Value::as_str)) {
+            (Some(namespace), Some(object_namespace)) if namespace != object_namespace => {
+                return Err(ApiError::bad_request(
+                    "the namespace of the provided object does not match the namespace sent on the request",
+                ));
+            }
+            (Some(namespace), _) => {
+                metadata.insert("namespace".to_string(), json!(namespace));
+            }
+            (None, _) => {
+                metadata.remove("namespace");
+            }
+        }
+
+        let name = match (
+            metadata.get("name").and_then(Value::as_str),
+            metadata.get("generateName").and_then(Value::as_str),
+        ) {
+            (Some(name), _) if !name.is_empty() => name.to_owned(),
+            (_, Some(generate_name)) => {
+                format!(
+                    "{generate_name}{}",
+                    generated_suffix(self.resource_version + 1)
+                )
+            }
+            _ => return Err(ApiError::invalid("name or generateName is required")),
+        };
+        metadata.insert("name".to_string(), json!(name));
+        // The UIDs are derived from the resource version, so that the tests are deterministic.
+        metadata.insert(
+            "uid".to_string(),
+            json!(uuid::Uuid::from_u128(u128::from(self.resource_version + 1)).to_string()),
+        );
+        metadata.insert("creationTimestamp".to_string(), json!(now()));
+        metadata.insert("generation".to_string(), json!(1));
+        metadata.remove("deletionTimestamp");
+
+        let key = ObjectKey {
+            collection: collection.clone(),
+            namespace: namespace.map(ToOwned::to_owned),
+            name,
+        };
+        if self.objects.contains_key(&key) {
+            return Err(ApiError::already_exists(&key));
+        }
+
+        normalize_secret_data(collection, &mut object);
+
+        if dry_run {
+            return Ok(object);
+        }
+
+        Ok(self.store(key, object, "ADDED", BTreeMap::new()))
+    }
+
+    fn update(
+        &mut self,
+        key: &ObjectKey,
+        subresource: Option<&str>,
+        object: Value,
+        dry_run: bool,
+    ) -> Result<Value, ApiError> {
+        let current = self.get(key)?;
+
+        if let Some(resource_version) = object.pointer("/metadata/resourceVersion")
+            && !resource_version.is_null()
+            && current.pointer("/metadata/resourceVersion") != Some(resource_version)
+        {
+            return Err(ApiError::conflict(key));
+        }
+
+        self.replace(key, subresource, &current, object, dry_run, None)
+    }
+
+    fn patch(
+        &mut self,
+        resource_path: &ResourcePath,
+        name: &str,
+        query: &Query,
+        content_type: &str,
+        patch: &Value,
+    ) -> Result<Value, ApiError> {
+        let key = resource_path.key(name);
+        let subresource = resource_path.subresource.as_deref();
+
+        match content_type {
+            "application/apply-patch+yaml" => {
+                let field_manager = query.field_manager.as_deref().ok_or_else(|| {
+                    ApiError::bad_request("fieldManager is required for apply patch")
+                })?;
+                // The previously applied configurations of the main resource and the status
+                // subresource are tracked separately.
+                let manager = match subresource {
+                    Some(subresource) => format!("{field_manager}/{subresource}"),
+                    None => field_manager.to_owned(),
+                };
+
+                match self.objects.get(&key) {
+                    Some(stored) => {
+                        let current = stored.object.clone();
+                        let mut object = current.clone();
+                        apply_configuration(&mut object, stored.applied.get(&manager), patch);
+                        self.replace(
+                            &key,
+                            subresource,
+                            &current,
+                            object,
+                            query.dry_run,
+                            Some((manager, patch.clone())),
+                        )
+                    }
+                    None if subresource.is_some() => Err(ApiError::not_found(&key)),
+                    None => {
+                        let created = self.create(
+                            &resource_path.collection,
+                            resource_path.namespace.as_deref(),
+                            patch.clone(),
+                            query.dry_run,
+                        )?;
+                        if let Some(stored) = self.objects.get_mut(&key) {
+                            stored.applied.insert(manager, patch.clone());
+                        }
+                        Ok(created)
+                    }
+                }
+            }
+            "application/json-patch+json" => {
+                let current = self.get(&key)?;
+                let json_patch: json_patch::Patch = serde_json::from_value(patch.clone())
+                    .map_err(|error| ApiError::bad_request(&error.to_string()))?;
+                let mut object = current.clone();
+                json_patch::patch(&mut object, &json_patch)
+                    .map_err(|error| ApiError::invalid(&error.to_string()))?;
+                self.replace(&key, subresource, &current, object, query.dry_run, None)
+            }
+            // Strategic merge patches are approximated with JSON merge patches.
+            "application/merge-patch+json" | "application/strategic-merge-patch+json" => {
+                let current = self.get(&key)?;
+
+                if let Some(resource_version) = patch.pointer("/metadata/resourceVersion")
+                    && current.pointer("/metadata/resourceVersion") != Some(resource_version)
+                {
+                    return Err(ApiError::conflict(&key));
+                }
+
+                let mut object = current.clone();
+                json_patch::merge(&mut object, patch);
+                self.replace(&key, subresource, &current, object, query.dry_run, None)
+            }
+            _ => Err(ApiError {
+                code: StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                reason: "UnsupportedMediaType",
+                message: format!("the media type {content_type:?} is not supported"),
+            }),
+        }
+    }
+
+    /// Replaces the current object with the given one while keeping the fields which cannot be
+    /// changed by the client.
+    fn replace(
+        &mut self,
+        key: &ObjectKey,
+        subresource: Option<&str>,
+        current: &Value,
+        mut object: Value,
+        dry_run: bool,
+        applied: Option<(String, Value)>,
+    ) -> Result<Value, ApiError> {
+        if subresource == Some("status") {
+            let status = object.get("status").cloned();
+            object = current.clone();
+            set_field(&mut object, "status", status);
+        } else {
+            set_field(&mut object, "status", current.get("status").cloned());
+        }
+
+        metadata_mut(&mut object)?;
+        for field in [
+            "name",
+            "namespace",
+            "uid",
+            "creationTimestamp",
+            "deletionTimestamp",
+            "generation",
+            "resourceVersion",
+        ] {
+            let value = current
+                .get("metadata")
+                .and_then(|metadata| metadata.get(field));
+            set_field(&mut object["metadata"], field, value.cloned());
+        }
+
+        normalize_secret_data(&key.collection, &mut object);
+
+        if without_metadata_and_status(&object) != without_metadata_and_status(current) {
+            let generation = current
+                .pointer("/metadata/generation")
+                .and_then(Value::as_u64)
+                .unwrap_or_default();
+            object["metadata"]["generation"] = json!(generation + 1);
+        }
+
+        let stored = self
+            .objects
+            .get_mut(key)
+            .ok_or_else(|| ApiError::not_found(key))?;
+        let mut applied_configurations = stored.applied.clone();
+        if let Some((manager, configuration)) = applied {
+            applied_configurations.insert(manager, configuration);
+        }
+
+        if dry_run {
+            return Ok(object);
+        }
+
+        // Unchanged objects are neither stored nor reported to watches.
+        if &object == current {
+            stored.applied = applied_configurations;
+            return Ok(object);
+        }
+
+        let is_terminating = object.pointer("/metadata/deletionTimestamp").is_some();
+        let object = self.store(key.clone(), object, "MODIFIED", applied_configurations);
+
+        if is_terminating && finalizers(&object).is_empty() {
+            return Ok(self.remove(key, false).unwrap_or(object));
+        }
+
+        Ok(object)
+    }
+
+    fn delete(
+        &mut self,
+        key: &ObjectKey,
+        options: &Value,
+        dry_run: bool,
+    ) -> Result<Value, ApiError> {
+        let current = self.get(key)?;
+
+        if let Some(uid) = options.pointer("/preconditions/uid")
+            && current.pointer("/metadata/uid") != Some(uid)
+        {
+            return Err(ApiError::conflict(key));
+        }
+
+        if dry_run {
+            return Ok(current);
+        }
+
+        let orphan_dependents = options.get("propagationPolicy") == Some(&json!("Orphan"));
+
+        if finalizers(&current).is_empty() {
+            self.remove(key, orphan_dependents)
+                .ok_or_else(|| ApiError::not_found(key))
+        } else if current.pointer("/metadata/deletionTimestamp").is_some() {
+            Ok(current)
+        } else {
+            let mut object = current;
+            object["metadata"]["deletionTimestamp"] = json!(now());
+            let applied = self
+                .objects
+                .get(key)
+                .map(|stored| stored.applied.clone())
+                .unwrap_or_default();
+            Ok(self.store(key.clone(), object, "MODIFIED", applied))
+        }
+    }
+
+    fn delete_collection(&mut self, path: &ResourcePath, query: &Query) -> Value {
+        let keys = self
+            .objects
+            .iter()
+            .filter(|(key, stored)| {
+                path.matches(key)
+                    && query.label_selector.matches(&stored.object)
+                    && query.field_selector.matches(&stored.object)
+            })
+            .map(|(key, _)| key.clone())
+            .collect::<Vec<_>>();
+
+        let items = keys
+            .iter()
+            .filter_map(|key| self.delete(key, &Value::Null, query.dry_run).ok())
+            .collect::<Vec<_>>();
+
+        json!({
+            "apiVersion": "v1",
+            "kind": "List",
+            "metadata": { "resourceVersion": self.resource_version.to_string() },
+            "items": items,
+        })
+    }
+
+    /// Removes the object and garbage collects or orphans its dependents.
+    fn remove(&mut self, key: &ObjectKey, orphan_dependents: bool) -> Option<Value> {
+        let removed = self.objects.remove(key)?;
+        let mut object = removed.object;
+        self.resource_version += 1;
+        object["metadata"]["resourceVersion"] = json!(self.resource_version.to_string());
+        self.emit(Event {
+            key: key.clone(),
+            resource_version: self.resource_version,
+            change: "DELETED",
+            object: object.clone(),
+        });
+
+        let Some(uid) = object.pointer("/metadata/uid").cloned() else {
+            return Some(object);
+        };
+        let dependents = self
+            .objects
+            .iter()
+            .filter(|(_, stored)| owner_uids(&stored.object).contains(&uid))
+            .map(|(key, _)| key.clone())
+            .collect::<Vec<_>>();
+
+        for dependent in dependents {
+            if orphan_dependents {
+                if let Some(stored) = self.objects.get(&dependent) {
+                    let mut orphan = stored.object.clone();
+                    let applied = stored.applied.clone();
+                    if let Some(owner_references) = orphan
+                        .pointer_mut("/metadata/ownerReferences")
+                        .and_then(Value::as_array_mut)
+                    {
+                        owner_references.retain(|owner| owner.get("uid") != Some(&uid));
+                    }
+                    self.store(dependent, orphan, "MODIFIED", applied);
+                }
+            } else {
+                // Errors are ignored, because the dependent could have been removed by a
+                // recursive deletion already.
+                let _ = self.delete(&dependent, &Value::Null, false);
+            }
+        }
+
+        Some(object)
+    }
+
+    /// Stores the object with a new resource version and notifies the watches.
+    fn store(
+        &mut self,
+        key: ObjectKey,
+        mut object: Value,
+        change: &'static str,
+        applied: BTreeMap<String, Value>,
+    ) -> Value {
+        self.resource_version += 1;
+        object["metadata"]["resourceVersion"] = json!(self.resource_version.to_string());
+
+        self.objects.insert(
+            key.clone(),
+            StoredObject {
+                object: object.clone(),
+                applied,
+            },
+        );
+        self.emit(Event {
+            key,
+            resource_version: self.resource_version,
+            change,
+            object: object.clone(),
+        });
+
+        object
+    }
+
+    fn emit(&mut self, event: Event) {
+        self.watches.retain(|watch| watch.send(&event));
+        self.events.push(event);
+    }
+}
+
+struct StoredObject {
+    object: Value,
+    /// The last configurations applied with server-side apply per field manager
+    applied: BTreeMap<String, Value>,
+}
+
+#[derive(Clone)]
+struct Event {
+    key: ObjectKey,
+    resource_version: u64,
+    change: &'static str,
+    object: Value,
+}
+
+struct Watch {
+    collection: Collection,
+    namespace: Option<String>,
+    label_selector: Selector,
+    field_selector: Selector,
+    sender: UnboundedSender<Bytes>,
+}
+
+impl Watch {
+    /// Sends the event if it matches this watch and returns whether the watch is still open.
+    fn send(&self, event: &Event) -> bool {
+        let object = &event.object;
+        let matches = event.key.collection == self.collection
+            && (self.namespace.is_none() || event.key.namespace == self.namespace)
+            && self.label_selector.matches(object)
+            && self.field_selector.matches(object);
+
+        if !matches {
+            return !self.sender.is_closed();
+        }
+
+        let mut line = serde_json::to_vec(&json!({
+            "type": event.change,
+            "object": object,
+        }))
+        .expect("the event can be serialized");
+        line.push(b'\n');
+
+        self.sender.unbounded_send(Bytes::from(line)).is_ok()
+    }
+}
+
+/// A collection of resources of the same type, e.g. `apps/v1` `statefulsets`
+#[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
+struct Collection {
+    api_version: String,
+    plural: String,
+}
+
+impl Collection {
+    fn of<K>() -> Self
+    where
+        K: kube::Resource<DynamicType = ()>,
+    {
+        Self {
+            api_version: K::api_version(&()).into_owned(),
+            plural: K::plural(&()).into_owned(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
+struct ObjectKey {
+    collection: Collection,
+    namespace: Option<String>,
+    name: String,
+}
+
+/// The parsed path of a request, e.g. `/apis/apps/v1/namespaces/default/statefulsets/my-sts`
+struct ResourcePath {
+    collection: Collection,
+    namespace: Option<String>,
+    name: Option<String>,
+    subresource: Option<String>,
+}
+
+impl ResourcePath {
+    fn parse(path: &str) -> Option<Self> {
+        let segments = path.trim_matches('/').split('/').collect::<Vec<_>>();
+
+        let (api_version, rest) = match segments.as_slice() {
+            ["api", version, rest @ ..] => ((*version).to_owned(), rest),
+            ["apis", group, version, rest @ ..] => (format!("{group}/{version}"), rest),
+            _ => return None,
+        };
+
+        let (namespace, rest) = match rest {
+            ["namespaces", namespace, rest @ ..] if !rest.is_empty() => {
+                (Some((*namespace).to_owned()), rest)
+            }
+            rest => (None, rest),
+        };
+
+        let (plural, name, subresource) = match rest {
+            [plural] => (plural, None, None),
+            [plural, name] => (plural, Some(name), None),
+            [plural, name, subresource] => (plural, Some(name), Some(subresource)),
+            _ => return None,
+        };
+
+        Some(Self {
+            collection: Collection {
+                api_version,
+                plural: (*plural).to_owned(),
+            },
+            namespace,
+            name: name.map(|name| (*name).to_owned()),
+            subresource: subresource.map(|subresource| (*subresource).to_owned()),
+        })
+    }
+
+    fn key(&self, name: &str) -> ObjectKey {
+        ObjectKey {
+            collection: self.collection.clone(),
+            namespace: self.namespace.clone(),
+            name: name.to_owned(),
+        }
+    }
+
+    /// Returns whether the given key is part of the collection addressed by this path.
+    fn matches(&self, key: &ObjectKey) -> bool {
+        key.collection == self.collection
+            && (self.namespace.is_none() || key.namespace == self.namespace)
+    }
+}
+
+/// The supported query parameters of a request
+#[derive(Default)]
+struct Query {
+    watch: bool,
+    dry_run: bool,
+    resource_version: Option<u64>,
+    field_manager: Option<String>,
+    label_selector: Selector,
+    field_selector: Selector,
+}
+
+impl Query {
+    fn parse(query: &str) -> Self {
+        let mut parsed = Self::default();
+
+        for (key, value) in url::form_urlencoded::parse(query.as_bytes()) {
+            match key.as_ref() {
+                "watch" => parsed.watch = value == "true" || value == "1",
+                "dryRun" => parsed.dry_run = value == "All",
+                "resourceVersion" => parsed.resource_version = value.parse().ok(),
+                "fieldManager" => parsed.field_manager = Some(value.into_owned()),
+                "labelSelector" => parsed.label_selector = Selector::parse(&value, true),
+                "fieldSelector" => parsed.field_selector = Selector::parse(&value, false),
+                _ => {}
+            }
+        }
+
+        parsed
+    }
+}
+
+/// A label or field selector
+#[derive(Clone, Default)]
+struct Selector {
+    requirements: Vec<Requirement>,
+    /// Whether the keys are labels, otherwise they are field paths like `metadata.name`
+    is_label_selector: bool,
+}
+
+#[derive(Clone)]
+enum Requirement {
+    In(String, BTreeSet<String>),
+    NotIn(String, BTreeSet<String>),
+    Exists(String),
+    DoesNotExist(String),
+}
+
+impl Selector {
+    fn parse(selector: &str, is_label_selector: bool) -> Self {
+        let mut requirements = Vec::new();
+
+        for expression in split_selector(selector) {
+            let expression = expression.trim();
+            let requirement = if let Some((key, values)) = expression.split_once(" notin ") {
+                Requirement::NotIn(key.trim().to_owned(), parse_set(values))
+            } else if let Some((key, values)) = expression.split_once(" in ") {
+                Requirement::In(key.trim().to_owned(), parse_set(values))
+            } else if let Some((key, value)) = expression.split_once("!=") {
+                Requirement::NotIn(
+                    key.trim().to_owned(),
+                    BTreeSet::from([value.trim().to_owned()]),
+                )
+            } else if let Some((key, value)) = expression
+                .split_once("==")
+                .or_else(|| expression.split_once('='))
+            {
+                Requirement::In(
+                    key.trim().to_owned(),
+                    BTreeSet::from([value.trim().to_owned()]),
+                )
+            } else if let Some(key) = expression.strip_prefix('!') {
+                Requirement::DoesNotExist(key.trim().to_owned())
+            } else if !expression.is_empty() {
+                Requirement::Exists(expression.to_owned())
+            } else {
+                continue;
+            };
+            requirements.push(requirement);
+        }
+
+        Self {
+            requirements,
+            is_label_selector,
+        }
+    }
+
+    fn matches(&self, object: &Value) -> bool {
+        self.requirements.iter().all(|requirement| {
+            let value = |key: &str| {
+                if self.is_label_selector {
+                    object
+                        .pointer("/metadata/labels")
+                        .and_then(|labels| labels.get(key))
+                        .and_then(Value::as_str)
+                        .map(ToOwned::to_owned)
+                } else {
+                    key.split('.')
+                        .try_fold(object, |value, field| value.get(field))
+                        .map(|value| match value {
+                            Value::String(value) => value.clone(),
+                            value => value.to_string(),
+                        })
+                }
+            };
+
+            match requirement {
+                Requirement::In(key, values) => value(key).is_some_and(|v| values.contains(&v)),
+                Requirement::NotIn(key, values) => !value(key).is_some_and(|v| values.contains(&v)),
+                Requirement::Exists(key) => value(key).is_some(),
+                Requirement::DoesNotExist(key) => value(key).is_none(),
+            }
+        })
+    }
+}
+
+/// Splits the selector at the commas which are not enclosed in parentheses.
+fn split_selector(selector: &str) -> Vec<&str> {
+    let mut expressions = Vec::new();
+    let mut depth = 0_u32;
+    let mut start = 0;
+
+    for (index, character) in selector.char_indices() {
+        match character {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                expressions.push(&selector[start..index]);
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    expressions.push(&selector[start..]);
+
+    expressions
+}
+
+/// Parses a set of values like `(a, b)`.
+fn parse_set(values: &str) -> BTreeSet<String> {
+    values
+        .trim()
+        .trim_start_matches('(')
+        .trim_end_matches(')')
+        .split(',')
+        .map(|value| value.trim().to_owned())
+        .filter(|value| !value.is_empty())
+        .collect()
+}
+
+#[derive(Debug)]
+struct ApiError {
+    code: StatusCode,
+    reason: &'static str,
+    message: String,
+}
+
+impl ApiError {
+    fn not_found(key: &ObjectKey) -> Self {
+        Self {
+            code: StatusCode::NOT_FOUND,
+            reason: "NotFound",
+            message: format!("{} {:?} not found", key.collection.plural, key.name),
+        }
+    }
+
+    fn already_exists(key: &ObjectKey) -> Self {
+        Self {
+            code: StatusCode::CONFLICT,
+            reason: "AlreadyExists",
+            message: format!("{} {:?} already exists", key.collection.plural, key.name),
+        }
+    }
+
+    fn conflict(key: &ObjectKey) -> Self {
+        Self {
+            code: StatusCode::CONFLICT,
+            reason: "Conflict",
+            message: format!(
+                "Operation cannot be fulfilled on {} {:?}: the object has been modified; please \
+                apply your changes to the latest version and try again",
+                key.collection.plural, key.name
+            ),
+        }
+    }
+
+    fn bad_request(message: &str) -> Self {
+        Self {
+            code: StatusCode::BAD_REQUEST,
+            reason: "BadRequest",
+            message: message.to_owned(),
+        }
+    }
+
+    fn invalid(message: &str) -> Self {
+        Self {
+            code: StatusCode::UNPROCESSABLE_ENTITY,
+            reason: "Invalid",
+            message: message.to_owned(),
+        }
+    }
+
+    fn method_not_allowed(method: &Method) -> Self {
+        Self {
+            code: StatusCode::METHOD_NOT_ALLOWED,
+            reason: "MethodNotAllowed",
+            message: format!("the method {method} is not supported on this path"),
+        }
+    }
+}
+
+fn parse_body(body: &[u8]) -> Result<Value, ApiError> {
+    serde_json::from_slice(body).map_err(|error| ApiError::bad_request(&error.to_string()))
+}
+
+fn json_response(code: StatusCode, value: &Value) -> Response<ResponseBody> {
+    let body = serde_json::to_vec(value).expect("the value can be serialized");
+
+    Response::builder()
+        .status(code)
+        .header(CONTENT_TYPE, "application/json")
+        .body(Full::new(Bytes::from(body)).boxed_unsync())
+        .expect("the response is valid")
+}
+
+fn status_response(code: StatusCode, reason: &str, message: &str) -> Response<ResponseBody> {
+    let status = Status::failure(message, reason).with_code(code.as_u16());
+    json_response(
+        code,
+        &serde_json::to_value(status).expect("the status can be serialized"),
+    )
+}
+
+fn metadata_mut(object: &mut Value) -> Result<&mut Map<String, Value>, ApiError> {
+    let object = object
+        .as_object_mut()
+        .ok_or_else(|| ApiError::bad_request("the body must be an object"))?;
+
+    object
+        .entry("metadata")
+        .or_insert_with(|| json!({}))
+        .as_object_mut()
+        .ok_or_else(|| ApiError::bad_request("metadata must be an object"))
+}
+
+/// Sets the field of the given object or removes it if the value is `None`.
+fn set_field(object: &mut Value, field: &str, value: Option<Value>) {
+    if let Some(object) = object.as_object_mut() {
+        match value {
+            Some(value) => object.insert(field.to_owned(), value),
+            None => object.remove(field),
+        };
+    }
+}
+
+fn without_metadata_and_status(object: &Value) -> Value {
+    let mut object = object.clone();
+    set_field(&mut object, "metadata", None);
+    set_field(&mut object, "status", None);
+    object
+}
+
+fn finalizers(object: &Value) -> Vec<Value> {
+    object
+        .pointer("/metadata/finalizers")
+        .and_then(Value::as_array)
+        .cloned()
+        .unwrap_or_default()
+}
+
+fn owner_uids(object: &Value) -> Vec<Value> {
+    object
+        .pointer("/metadata/ownerReferences")
+        .and_then(Value::as_array)
+        .map(|owner_references| {
+            owner_references
+                .iter()
+                .filter_map(|owner| owner.get("uid").cloned())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Applies the configuration like server-side apply.
+///
+/// Fields which were part of the previously applied configuration but are not part of the new
+/// one are removed. Afterwards, the configuration is merged recursively into the object, whereby
+/// lists are replaced.
+fn apply_configuration(object: &mut Value, previous: Option<&Value>, configuration: &Value) {
+    if let Some(previous) = previous {
+        remove_unapplied_fields(object, previous, configuration);
+    }
+    merge_applied_fields(object, configuration);
+}
+
+fn remove_unapplied_fields(object: &mut Value, previous: &Value, configuration: &Value) {
+    let (Some(object), Some(previous)) = (object.as_object_mut(), previous.as_object()) else {
+        return;
+    };
+
+    for (field, previous_value) in previous {
+        match configuration.get(field) {
+            Some(value) => {
+                if let Some(object_value) = object.get_mut(field) {
+                    remove_unapplied_fields(object_value, previous_value, value);
+                }
+            }
+            None => {
+                object.remove(field);
+            }
+        }
+    }
+}
+
+fn merge_applied_fields(object: &mut Value, configuration: &Value) {
+    match (object.as_object_mut(), configuration.as_object()) {
+        (Some(object), Some(configuration)) => {
+            for (field, value) in configuration {
+                if value.is_null() {
+                    object.remove(field);
+                } else {
+                    merge_applied_fields(object.entry(field).or_insert(Value::Null), value);
+                }
+            }
+        }
+        _ => *object = configuration.clone(),
+    }
+}
+
+/// Moves the `stringData` of Secrets into `data` like the API server.
+fn normalize_secret_data(collection: &Collection, object: &mut Value) {
+    if collection.api_version != "v1" || collection.plural != "secrets" {
+        return;
+    }
+
+    let Some(string_data) = object
+        .as_object_mut()
+        .and_then(|object| object.remove("stringData"))
+    else {
+        return;
+    };
+
+    if let Some(string_data) = string_data.as_object() {
+        let data = object
+            .as_object_mut()
+            .map(|object| object.entry("data").or_insert_with(|| json!({})));
+        if let Some(Value::Object(data)) = data {
+            for (key, value) in string_data {
+                let value = value.as_str().unwrap_or_default();
+                data.insert(
+                    key.clone(),
+                    json!(base64::engine::general_purpose::STANDARD.encode(value)),
+                );
+            }
+        }
+    }
+}
+
+/// Returns the suffix of a generated name which is derived from the given number, so that the
+/// tests are deterministic.
+fn generated_suffix(mut number: u64) -> String {
+    // The API server uses the same characters for generated names.
+    const CHARACTERS: &[u8] = b"bcdfghjklmnpqrstvwxz2456789";
+    let base = CHARACTERS.len() as u64;
+
+    (0..5)
+        .map(|_| {
+            let character = CHARACTERS[usize::try_from(number % base).unwrap_or_default()];
+            number /= base;
+            char::from(character)
+        })
+        .collect()
+}
+
+fn now() -> String {
+    jiff::Timestamp::now()
+        .round(jiff::Unit::Second)
+        .expect("rounding to seconds does not overflow")
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeMap, time::Duration};
+
+    use k8s_openapi::{
+        ByteString,
+        api::{
+            apps::v1::{StatefulSet, StatefulSetSpec, StatefulSetStatus},
+            core::v1::{ConfigMap, Pod, Secret, Service},
+        },
+        apimachinery::pkg::apis::meta::v1::{LabelSelector, OwnerReference},
+    };
+    use kube::{
+        Api,
+        api::{DeleteParams, ListParams, ObjectMeta},
+        runtime::watcher,
+    };
+
+    use super::*;
+    use crate::{
+        builder::meta::ObjectMetaBuilder,
+        client::Error,
+        cluster_resources::{ClusterResourceApplyStrategy, ClusterResources},
+        commons::random_secret_creation::create_random_secret_if_not_exists,
+        deep_merger::ObjectOverrides,
+    };
+
+    fn config_map(name: &str, labels: &[(&str, &str)], data: &[(&str, &str)]) -> ConfigMap {
+        ConfigMap {
+            metadata: ObjectMeta {
+                name: Some(name.to_owned()),
+                namespace: Some(DEFAULT_NAMESPACE.to_owned()),
+                labels: Some(
+                    labels
+                        .iter()
+                        .map(|(key, value)| ((*key).to_owned(), (*value).to_owned()))
+                        .collect(),
+                ),
+                ..ObjectMeta::default()
+            },
+            data: Some(
+                data.iter()
+                    .map(|(key, value)| ((*key).to_owned(), (*value).to_owned()))
+                    .collect(),
+            ),
+            ..ConfigMap::default()
+        }
+    }
+
+    fn owned_by(mut config_map: ConfigMap, owner: &ConfigMap) -> ConfigMap {
+        config_map.metadata.owner_references = Some(vec![OwnerReference {
+            api_version: "v1".to_owned(),
+            kind: "ConfigMap".to_owned(),
+            name: owner.metadata.name.clone().unwrap_or_default(),
+            uid: owner.metadata.uid.clone().unwrap_or_default(),
+            ..OwnerReference::default()
+        }]);
+        config_map
+    }
+
+    fn names<K: kube::Resource>(objects: &[K]) -> Vec<String> {
+        objects.iter().map(kube::ResourceExt::name_any).collect()
+    }
+
+    #[tokio::test]
+    async fn create_get_and_update() {
+        let server = FakeApiServer::new();
+        let client = server.client();
+
+        let created = client
+            .create(&config_map("test", &[], &[("key", "value")]))
+            .await
+            .expect("the ConfigMap can be created");
+        assert_eq!(created.metadata.resource_version.as_deref(), Some("1"));
+        assert_eq!(
+            created.metadata.uid.as_deref(),
+            Some("00000000-0000-0000-0000-000000000001")
+        );
+
+        let error = client
+            .create(&config_map("test", &[], &[]))
+            .await
+            .expect_err("the ConfigMap already exists");
+        assert!(matches!(
+            error,
+            Error::CreateResource { source: kube::Error::Api(status), .. } if status.is_already_exists()
+        ));
+
+        let fetched = client
+            .get::<ConfigMap>("test", DEFAULT_NAMESPACE)
+            .await
+            .expect("the ConfigMap exists");
+        assert_eq!(fetched, created);
+        assert_eq!(
+            client
+                .get_opt::<ConfigMap>("missing", DEFAULT_NAMESPACE)
+                .await
+                .expect("a missing ConfigMap is not an error"),
+            None
+        );
+
+        let mut changed = created.clone();
+        changed.data = Some(BTreeMap::from([("key".to_owned(), "changed".to_owned())]));
+        let updated = client
+            .update(&changed)
+            .await
+            .expect("the ConfigMap can be updated");
+        assert_eq!(updated.metadata.resource_version.as_deref(), Some("2"));
+
+        // The resource version of the first update is outdated now.
+        let error = client
+            .update(&changed)
+            .await
+            .expect_err("the resource version is outdated");
+        assert!(matches!(
+            error,
+            Error::UpdateResource { source: kube::Error::Api(status), .. } if status.is_conflict()
+        ));
+
+        let mut generated = config_map("", &[], &[]);
+        generated.metadata.name = None;
+        generated.metadata.generate_name = Some("generated-".to_owned());
+        let generated = client
+            .create(&generated)
+            .await
+            .expect("the ConfigMap can be created with a generated name");
+        assert_eq!(generated.metadata.name.as_deref(), Some("generated-fbbbb"));
+    }
+
+    #[tokio::test]
+    async fn list_with_label_selectors() {
+        let server = FakeApiServer::new();
+        let client = server.client();
+        server.insert(&config_map("a", &[("app", "a"), ("tier", "web")], &[]));
+        server.insert(&config_map("b", &[("app", "b")], &[]));
+        server.insert(&config_map("c", &[("app", "c"), ("tier", "db")], &[]));
+
+        let list = |selector: &str| {
+            let client = client.clone();
+            let list_params = ListParams::default().labels(selector);
+            async move {
+                names(
+                    &client
+                        .list::<ConfigMap>(DEFAULT_NAMESPACE, &list_params)
+                        .await
+                        .expect("the ConfigMaps can be listed"),
+                )
+            }
+        };
+
+        assert_eq!(list("").await, ["a", "b", "c"]);
+        assert_eq!(list("app=a").await, ["a"]);
+        assert_eq!(list("app!=a").await, ["b", "c"]);
+        assert_eq!(list("app in (a, b)").await, ["a", "b"]);
+        assert_eq!(list("app notin (a, b),tier").await, ["c"]);
+        assert_eq!(list("!tier").await, ["b"]);
+
+        let selected = client
+            .list_with_label_selector::<ConfigMap>(
+                DEFAULT_NAMESPACE,
+                &LabelSelector {
+                    match_labels: Some(BTreeMap::from([("tier".to_owned(), "db".to_owned())])),
+                    ..LabelSelector::default()
+                },
+            )
+            .await
+            .expect("the ConfigMaps can be listed");
+        assert_eq!(names(&selected), ["c"]);
+
+        let other_namespace = client
+            .list::<ConfigMap>("other", &ListParams::default())
+            .await
+            .expect("the ConfigMaps can be listed");
+        assert!(other_namespace.is_empty());
+    }
+
+    #[tokio::test]
+    async fn server_side_apply() {
+        let server = FakeApiServer::new();
+        let client = server.client();
+
+        let applied = client
+            .apply_patch(
+                "controller",
+                &config_map("test", &[], &[]),
+                config_map("test", &[("app", "test")], &[("a", "1"), ("b", "2")]),
+            )
+            .await
+            .expect("the ConfigMap can be created with server-side apply");
+        assert_eq!(applied.metadata.resource_version.as_deref(), Some("1"));
+
+        // Reapplying the same configuration does not change the object.
+        let reapplied = client
+            .apply_patch(
+                "controller",
+                &applied,
+                config_map("test", &[("app", "test")], &[("a", "1"), ("b", "2")]),
+            )
+            .await
+            .expect("the ConfigMap can be applied again");
+        assert_eq!(reapplied, applied);
+
+        // Fields which are not applied anymore are removed, fields of other managers are kept.
+        client
+            .merge_patch(&applied, json!({ "data": { "c": "3" } }))
+            .await
+            .expect("the ConfigMap can be patched");
+        let reapplied = client
+            .apply_patch(
+                "controller",
+                &applied,
+                config_map("test", &[("app", "test")], &[("a", "changed")]),
+            )
+            .await
+            .expect("the ConfigMap can be applied again");
+        assert_eq!(
+            reapplied.data,
+            Some(BTreeMap::from([
+                ("a".to_owned(), "changed".to_owned()),
+                ("c".to_owned(), "3".to_owned()),
+            ]))
+        );
+        assert_eq!(reapplied.metadata.resource_version.as_deref(), Some("3"));
+    }
+
+    #[tokio::test]
+    async fn patches_and_status() {
+        let server = FakeApiServer::new();
+        let client = server.client();
+
+        let mut stateful_set = StatefulSet {
+            metadata: ObjectMetaBuilder::new()
+                .name("test")
+                .namespace(DEFAULT_NAMESPACE)
+                .build(),
+            spec: Some(StatefulSetSpec {
+                replicas: Some(1),
+                ..StatefulSetSpec::default()
+            }),
+            ..StatefulSet::default()
+        };
+        stateful_set.status = Some(StatefulSetStatus {
+            replicas: 1,
+            ..StatefulSetStatus::default()
+        });
+        let stateful_set = server.insert(&stateful_set);
+        // The status of inserted objects is kept to be able to prepare the state of tests.
+        assert_eq!(
+            stateful_set.status.as_ref().map(|status| status.replicas),
+            Some(1)
+        );
+        assert_eq!(stateful_set.metadata.generation, Some(1));
+
+        let patched = client
+            .json_patch(
+                &stateful_set,
+                serde_json::from_value(json!([
+                    { "op": "replace", "path": "/spec/replicas", "value": 3 }
+                ]))
+                .expect("the JSON patch is valid"),
+            )
+            .await
+            .expect("the StatefulSet can be patched");
+        assert_eq!(patched.spec.and_then(|spec| spec.replicas), Some(3));
+        assert_eq!(patched.metadata.generation, Some(2));
+
+        let status = StatefulSetStatus {
+            replicas: 3,
+            ready_replicas: Some(2),
+            ..StatefulSetStatus::default()
+        };
+        let patched = client
+            .apply_patch_status("controller", &stateful_set, &status)
+            .await
+            .expect("the status can be applied");
+        assert_eq!(patched.status, Some(status));
+        assert_eq!(patched.metadata.generation, Some(2));
+
+        let patched = client
+            .merge_patch(
+                &stateful_set,
+                json!({ "status": null, "metadata": { "labels": { "a": "b" } } }),
+            )
+            .await
+            .expect("the StatefulSet can be patched");
+        assert!(patched.status.is_some(), "the status is kept");
+        assert_eq!(patched.metadata.generation, Some(2));
+
+        let error = client
+            .json_patch(
+                &stateful_set,
+                serde_json::from_value(json!([
+                    { "op": "remove", "path": "/spec/missing" }
+                ]))
+                .expect("the JSON patch is valid"),
+            )
+            .await
+            .expect_err("the path does not exist");
+        assert!(matches!(
+            error,
+            Error::PatchResource { source: kube::Error::Api(status), .. } if status.is_invalid()
+        ));
+    }
+
+    #[tokio::test]
+    async fn delete_with_finalizers_and_garbage_collection() {
+        let server = FakeApiServer::new();
+        let client = server.client();
+
+        let mut owner = config_map("owner", &[], &[]);
+        owner.metadata.finalizers = Some(vec!["test/finalizer".to_owned()]);
+        let owner = server.insert(&owner);
+        let dependent = server.insert(&owned_by(config_map("dependent", &[], &[]), &owner));
+        server.insert(&owned_by(config_map("transitive", &[], &[]), &dependent));
+        server.insert(&config_map("unrelated", &[], &[]));
+
+        // The finalizer blocks the deletion.
+        client
+            .delete(&owner)
+            .await
+            .expect("the owner can be deleted");
+        let terminating = client
+            .get::<ConfigMap>("owner", DEFAULT_NAMESPACE)
+            .await
+            .expect("the owner still exists");
+        assert!(terminating.metadata.deletion_timestamp.is_some());
+        assert_eq!(server.objects::<ConfigMap>().len(), 4);
+
+        // Removing the finalizer deletes the owner and its dependents recursively.
+        client
+            .merge_patch(&terminating, json!({ "metadata": { "finalizers": null } }))
+            .await
+            .expect("the finalizer can be removed");
+        assert_eq!(names(&server.objects::<ConfigMap>()), ["unrelated"]);
+    }
+
+    #[tokio::test]
+    async fn orphan_dependents() {
+        let server = FakeApiServer::new();
+        let owner = server.insert(&config_map("owner", &[], &[]));
+        server.insert(&owned_by(config_map("dependent", &[], &[]), &owner));
+
+        Api::<ConfigMap>::namespaced(server.kube_client(), DEFAULT_NAMESPACE)
+            .delete("owner", &DeleteParams::orphan())
+            .await
+            .expect("the owner can be deleted");
+
+        let objects = server.objects::<ConfigMap>();
+        assert_eq!(names(&objects), ["dependent"]);
+        assert_eq!(objects[0].metadata.owner_references, Some(vec![]));
+    }
+
+    #[tokio::test]
+    async fn watch() {
+        let server = FakeApiServer::new();
+        let client = server.client();
+
+        let wait_created = tokio::spawn({
+            let client = client.clone();
+            async move {
+                client
+                    .wait_created::<Pod>(
+                        DEFAULT_NAMESPACE,
+                        watcher::Config::default().fields("metadata.name=test"),
+                    )
+                    .await;
+            }
+        });
+
+        server.insert(&Pod {
+            metadata: ObjectMetaBuilder::new()
+                .name("other")
+                .namespace(DEFAULT_NAMESPACE)
+                .build(),
+            ..Pod::default()
+        });
+        server.insert(&Pod {
+            metadata: ObjectMetaBuilder::new()
+                .name("test")
+                .namespace(DEFAULT_NAMESPACE)
+                .build(),
+            ..Pod::default()
+        });
+
+        tokio::time::timeout(Duration::from_secs(10), wait_created)
+            .await
+            .expect("the creation of the Pod is observed")
+            .expect("the task does not panic");
+    }
+
+    #[tokio::test]
+    async fn cluster_resources() {
+        let server = FakeApiServer::new();
+        let client = server.client();
+        let cluster = server.insert(&config_map("cluster", &[], &[]));
+        let object_overrides = ObjectOverrides::default();
+
+        let cluster_resources = |apply_strategy| {
+            ClusterResources::new(
+                "app",
+                "app.stackable.tech",
+                "appcluster",
+                &kube::Resource::object_ref(&cluster, &()),
+                apply_strategy,
+                &object_overrides,
+            )
+            .expect("the cluster reference is complete")
+        };
+        let resource = |name: &str, labels: &BTreeMap<String, String>| ConfigMap {
+            metadata: ObjectMetaBuilder::new()
+                .name(name)
+                .namespace(DEFAULT_NAMESPACE)
+                .ownerreference_from_resource(&cluster, None, Some(true))
+                .expect("the cluster has a name and UID")
+                .with_labels(labels.clone().try_into().expect("the labels are valid"))
+                .build(),
+            ..ConfigMap::default()
+        };
+
+        let mut first = cluster_resources(ClusterResourceApplyStrategy::Default);
+        let labels = first
+            .get_required_labels()
+            .expect("the labels are valid")
+            .into();
+        first
+            .add(&client, resource("first", &labels))
+            .await
+            .expect("the resource can be added");
+        first
+            .add(&client, resource("second", &labels))
+            .await
+            .expect("the resource can be added");
+        Box::pin(first.delete_orphaned_resources(&client))
+            .await
+            .expect("there are no orphaned resources");
+        assert_eq!(
+            names(&server.objects::<ConfigMap>()),
+            ["cluster", "first", "second"]
+        );
+
+        // Resources which cannot be listed are skipped.
+        server.forbid::<Service>();
+
+        let mut second = cluster_resources(ClusterResourceApplyStrategy::Default);
+        second
+            .add(&client, resource("second", &labels))
+            .await
+            .expect("the resource can be added");
+        Box::pin(second.delete_orphaned_resources(&client))
+            .await
+            .expect("the orphaned resources can be deleted");
+        assert_eq!(names(&server.objects::<ConfigMap>()), ["cluster", "second"]);
+    }
+
+    #[tokio::test]
+    async fn random_secret_creation() {
+        let server = FakeApiServer::new();
+        let client = server.client();
+        let stacklet = server.insert(&config_map("cluster", &[], &[]));
+
+        create_random_secret_if_not_exists("random", "key", 16, &stacklet, &client)
+            .await
+            .expect("the Secret can be created");
+        let secrets = server.objects::<Secret>();
+        let data = secrets[0].data.clone().unwrap_or_default();
+        assert_eq!(data.len(), 1);
+        // The 16 random bytes are stored base64 encoded.
+        assert_eq!(
+            data.get("key").map(|ByteString(value)| value.len()),
+            Some(24)
+        );
+
+        // Immutable Secrets are recreated as mutable ones with the same contents.
+        Api::<Secret>::namespaced(server.kube_client(), DEFAULT_NAMESPACE)
+            .delete("random", &DeleteParams::default())
+            .await
+            .expect("the Secret can be deleted");
+        let mut immutable = secrets[0].clone();
+        immutable.immutable = Some(true);
+        immutable.metadata.resource_version = None;
+        server.insert(&immutable);
+
+        create_random_secret_if_not_exists("random", "key", 16, &stacklet, &client)
+            .await
+            .expect("the Secret can be recreated");
+        let secrets = server.objects::<Secret>();
+        assert_eq!(secrets[0].immutable, Some(false));
+        assert_eq!(secrets[0].data.clone().unwrap_or_default(), data);
+    }
+}
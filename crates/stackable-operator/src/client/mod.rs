@@ -26,6 +26,8 @@ use crate::{
     utils::cluster_info::{KubernetesClusterInfo, KubernetesClusterInfoOptions},
 };
 
+#[cfg(any(feature = "test-support", test))]
+pub mod fake;
 #[cfg(feature = "client-feature-gates")]
 pub mod feature_gates;
 
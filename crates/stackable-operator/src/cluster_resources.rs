@@ -18,7 +18,7 @@ use k8s_openapi::{
             ConfigMap, ObjectReference, PodSpec, PodTemplateSpec, Secret, Service, ServiceAccount,
         },
         policy::v1::PodDisruptionBudget,
-        rbac::v1::RoleBinding,
+        rbac::v1::{Role, RoleBinding},
     },
     apimachinery::pkg::apis::meta::v1::{LabelSelector, LabelSelectorRequirement},
 };
@@ -216,6 +216,7 @@ impl ClusterResource for ConfigMap {}
 impl ClusterResource for Secret {}
 impl ClusterResource for Service {}
 impl ClusterResource for ServiceAccount {}
+impl ClusterResource for Role {}
 impl ClusterResource for RoleBinding {}
 impl ClusterResource for PodDisruptionBudget {}
 #[cfg(feature = "crds")]
@@ -673,6 +674,7 @@ impl<'a> ClusterResources<'a> {
             self.delete_orphaned_resources_of_kind::<ConfigMap>(client),
             self.delete_orphaned_resources_of_kind::<Secret>(client),
             self.delete_orphaned_resources_of_kind::<ServiceAccount>(client),
+            self.delete_orphaned_resources_of_kind::<Role>(client),
             self.delete_orphaned_resources_of_kind::<RoleBinding>(client),
             self.delete_orphaned_resources_of_kind::<PodDisruptionBudget>(client),
             delete_listeners
@@ -2,7 +2,8 @@ use std::collections::BTreeMap;
 
 use k8s_openapi::{
     api::core::v1::{
-        NodeAffinity, PodAffinity, PodAffinityTerm, PodAntiAffinity, WeightedPodAffinityTerm,
+        NodeAffinity, PodAffinity, PodAffinityTerm, PodAntiAffinity, TopologySpreadConstraint,
+        WeightedPodAffinityTerm,
     },
     apimachinery::pkg::apis::meta::v1::LabelSelector,
 };
@@ -13,10 +14,18 @@ use stackable_operator_derive::Fragment;
 use crate::{
     config::merge::{Atomic, Merge},
     kvp::consts::{K8S_APP_COMPONENT_KEY, K8S_APP_INSTANCE_KEY, K8S_APP_NAME_KEY},
-    utils::crds::raw_optional_object_schema,
+    utils::crds::{raw_optional_object_list_schema, raw_optional_object_schema},
 };
 
 pub const TOPOLOGY_KEY_HOSTNAME: &str = "kubernetes.io/hostname";
+pub const TOPOLOGY_KEY_ZONE: &str = "topology.kubernetes.io/zone";
+
+/// The labels which Kubernetes sets on Pods to identify the revision of their StatefulSet
+/// (`controller-revision-hash`) or Deployment (`pod-template-hash`).
+///
+/// Used as `matchLabelKeys` of topology spread constraints, so that only Pods of the same revision
+/// are spread during a rolling update. Keys which are not set on a Pod are ignored by Kubernetes.
+pub const REVISION_LABEL_KEYS: [&str; 2] = ["controller-revision-hash", "pod-template-hash"];
 
 /// These configuration settings control
 /// [Pod placement](DOCS_BASE_URL_PLACEHOLDER/concepts/operations/pod_placement).
@@ -51,6 +60,13 @@ pub struct StackableAffinity {
 
     // This schema isn't big, so it can stay
     pub node_selector: Option<StackableNodeSelector>,
+
+    /// Same as the `spec.topologySpreadConstraints` field on the Pod, see the [Kubernetes docs](https://kubernetes.io/docs/concepts/scheduling-eviction/topology-spread-constraints)
+    #[fragment_attrs(
+        serde(default),
+        schemars(schema_with = "raw_optional_object_list_schema")
+    )]
+    pub topology_spread_constraints: Option<StackableTopologySpreadConstraints>,
 }
 
 /// Simple key-value pairs forming a nodeSelector, see the [Kubernetes docs](https://kubernetes.io/docs/concepts/scheduling-eviction/assign-pod-node)
@@ -70,6 +86,82 @@ pub struct StackableNodeSelector {
 
 impl Atomic for StackableNodeSelector {}
 
+/// A list of topology spread constraints, see the [Kubernetes docs](https://kubernetes.io/docs/concepts/scheduling-eviction/topology-spread-constraints)
+//
+// We can not simply use [`Vec<TopologySpreadConstraint>`] in [`StackableAffinity`], as the fields need to be [`Atomic`].
+#[derive(Clone, Debug, Default, Deserialize, JsonSchema, PartialEq, Serialize)]
+#[serde(transparent)]
+pub struct StackableTopologySpreadConstraints {
+    pub topology_spread_constraints: Vec<TopologySpreadConstraint>,
+}
+
+impl Atomic for StackableTopologySpreadConstraints {}
+
+/// What the scheduler does with a Pod if it doesn't satisfy a topology spread constraint
+#[derive(Clone, Copy, Debug, Eq, PartialEq, strum::Display)]
+pub enum WhenUnsatisfiable {
+    /// The Pod is not scheduled, so the constraint is a hard requirement.
+    DoNotSchedule,
+
+    /// The Pod is scheduled anyway, but nodes which reduce the skew are preferred.
+    ScheduleAnyway,
+}
+
+/// Creates a `TopologySpreadConstraint`, which spreads all Pods of the given product (`app_name`) instance (`cluster_name`) role (`role`)
+/// across the domains of the `topology_key`, e.g. [`TOPOLOGY_KEY_ZONE`] or [`TOPOLOGY_KEY_HOSTNAME`].
+/// The number of Pods in any two domains differs by at most `max_skew`.
+/// Only Pods of the same revision are taken into account, see [`REVISION_LABEL_KEYS`].
+pub fn spread_role_pods(
+    app_name: &str,
+    cluster_name: &str,
+    role: &str,
+    topology_key: &str,
+    max_skew: i32,
+    when_unsatisfiable: WhenUnsatisfiable,
+) -> TopologySpreadConstraint {
+    TopologySpreadConstraint {
+        label_selector: Some(LabelSelector {
+            match_expressions: None,
+            match_labels: Some(BTreeMap::from([
+                (K8S_APP_NAME_KEY.to_string(), app_name.to_string()),
+                (K8S_APP_INSTANCE_KEY.to_string(), cluster_name.to_string()),
+                (K8S_APP_COMPONENT_KEY.to_string(), role.to_string()),
+                // We don't include the role-group label here, as the Pods of all rolegroups of the given role should be spread
+            ])),
+        }),
+        match_label_keys: Some(REVISION_LABEL_KEYS.map(String::from).to_vec()),
+        max_skew,
+        topology_key: topology_key.to_string(),
+        when_unsatisfiable: when_unsatisfiable.to_string(),
+        ..TopologySpreadConstraint::default()
+    }
+}
+
+/// Creates the default topology spread constraints for the given product (`app_name`) instance (`cluster_name`) role (`role`).
+/// The Pods are spread evenly across zones and nodes, but are still scheduled if this is not possible,
+/// e.g. in clusters without zones or with fewer nodes than replicas.
+pub fn default_role_topology_spread_constraints(
+    app_name: &str,
+    cluster_name: &str,
+    role: &str,
+) -> StackableTopologySpreadConstraints {
+    StackableTopologySpreadConstraints {
+        topology_spread_constraints: [TOPOLOGY_KEY_ZONE, TOPOLOGY_KEY_HOSTNAME]
+            .into_iter()
+            .map(|topology_key| {
+                spread_role_pods(
+                    app_name,
+                    cluster_name,
+                    role,
+                    topology_key,
+                    1,
+                    WhenUnsatisfiable::ScheduleAnyway,
+                )
+            })
+            .collect(),
+    }
+}
+
 /// Creates a `WeightedPodAffinityTerm`, which expresses a affinity towards all Pods of the given product (`app_name`) instance (`cluster_name`) role (`role`).
 /// This affinity can be used to attract towards (affinity) or away (anti-affinity) from the specified role.
 /// One common example would be to use this to distribute all the Pods of a certain role, e.g. hdfs datanodes.
@@ -151,6 +243,7 @@ mod tests {
             }),
             node_affinity: None,
             node_selector: None,
+            topology_spread_constraints: None,
         };
 
         let role_input = r"
@@ -246,6 +339,7 @@ mod tests {
                 node_selector: Some(StackableNodeSelector {
                     node_selector: BTreeMap::from([("disktype".to_string(), "ssd".to_string())])
                 }),
+                topology_spread_constraints: None,
             }
         );
     }
@@ -262,6 +356,7 @@ mod tests {
             }),
             node_affinity: None,
             node_selector: None,
+            topology_spread_constraints: None,
         };
 
         // The following anti-affinity tells k8s it *must* spread the brokers across multiple zones
@@ -311,10 +406,143 @@ mod tests {
                 }),
                 node_affinity: None,
                 node_selector: None,
+                topology_spread_constraints: None,
             }
         );
     }
 
+    #[test]
+    fn merge_topology_spread_constraints() {
+        let default_affinity = StackableAffinityFragment {
+            pod_affinity: None,
+            pod_anti_affinity: None,
+            node_affinity: None,
+            node_selector: None,
+            topology_spread_constraints: Some(default_role_topology_spread_constraints(
+                "zookeeper",
+                "simple-zk",
+                "server",
+            )),
+        };
+
+        // Without any user input, the default constraints are used
+        let mut role_affinity = StackableAffinityFragment::default();
+        role_affinity.merge(&default_affinity);
+        let merged_affinity: StackableAffinity = fragment::validate(role_affinity).unwrap();
+        assert_eq!(
+            merged_affinity.topology_spread_constraints,
+            default_affinity.topology_spread_constraints
+        );
+
+        // The following constraint tells k8s it *must* spread the servers across multiple zones
+        // It will overwrite all default constraints
+        let role_input = r"
+          topologySpreadConstraints:
+            - labelSelector:
+                matchLabels:
+                  app.kubernetes.io/name: zookeeper
+                  app.kubernetes.io/instance: simple-zk
+                  app.kubernetes.io/component: server
+              maxSkew: 1
+              topologyKey: topology.kubernetes.io/zone
+              whenUnsatisfiable: DoNotSchedule
+          ";
+        let mut role_affinity: StackableAffinityFragment =
+            serde_yaml::from_str(role_input).expect("illegal test input");
+
+        role_affinity.merge(&default_affinity);
+        let merged_affinity: StackableAffinity = fragment::validate(role_affinity).unwrap();
+
+        assert_eq!(
+            merged_affinity.topology_spread_constraints,
+            Some(StackableTopologySpreadConstraints {
+                topology_spread_constraints: vec![TopologySpreadConstraint {
+                    label_selector: Some(LabelSelector {
+                        match_expressions: None,
+                        match_labels: Some(BTreeMap::from([
+                            (
+                                "app.kubernetes.io/name".to_string(),
+                                "zookeeper".to_string()
+                            ),
+                            (
+                                "app.kubernetes.io/instance".to_string(),
+                                "simple-zk".to_string(),
+                            ),
+                            (
+                                "app.kubernetes.io/component".to_string(),
+                                "server".to_string(),
+                            )
+                        ]))
+                    }),
+                    max_skew: 1,
+                    topology_key: TOPOLOGY_KEY_ZONE.to_string(),
+                    when_unsatisfiable: "DoNotSchedule".to_string(),
+                    ..Default::default()
+                }]
+            })
+        );
+    }
+
+    #[test]
+    fn spread_between_role_pods() {
+        let constraint = spread_role_pods(
+            "zookeeper",
+            "simple-zk",
+            "server",
+            TOPOLOGY_KEY_ZONE,
+            2,
+            WhenUnsatisfiable::ScheduleAnyway,
+        );
+        assert_eq!(
+            constraint,
+            TopologySpreadConstraint {
+                label_selector: Some(LabelSelector {
+                    match_expressions: None,
+                    match_labels: Some(BTreeMap::from([
+                        (
+                            "app.kubernetes.io/name".to_string(),
+                            "zookeeper".to_string()
+                        ),
+                        (
+                            "app.kubernetes.io/instance".to_string(),
+                            "simple-zk".to_string(),
+                        ),
+                        (
+                            "app.kubernetes.io/component".to_string(),
+                            "server".to_string(),
+                        )
+                    ]))
+                }),
+                match_label_keys: Some(vec![
+                    "controller-revision-hash".to_string(),
+                    "pod-template-hash".to_string()
+                ]),
+                max_skew: 2,
+                topology_key: "topology.kubernetes.io/zone".to_string(),
+                when_unsatisfiable: "ScheduleAnyway".to_string(),
+                ..Default::default()
+            }
+        );
+
+        let default_constraints =
+            default_role_topology_spread_constraints("zookeeper", "simple-zk", "server");
+        assert_eq!(
+            default_constraints
+                .topology_spread_constraints
+                .iter()
+                .map(|constraint| (
+                    constraint.topology_key.as_str(),
+                    constraint.max_skew,
+                    constraint.when_unsatisfiable.as_str()
+                ))
+                .collect::<Vec<_>>(),
+            [
+                (TOPOLOGY_KEY_ZONE, 1, "ScheduleAnyway"),
+                (TOPOLOGY_KEY_HOSTNAME, 1, "ScheduleAnyway")
+            ]
+        );
+    }
+
     #[test]
     fn between_role_pods() {
         let app_name = "kafka";
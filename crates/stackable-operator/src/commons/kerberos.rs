@@ -0,0 +1,503 @@
+//! Client configuration for Kerberized products.
+//!
+//! The keytab and a `krb5.conf` with the realm and its KDC are provisioned by secret-operator
+//! into a volume built with [`KerberosVolumeBuilder`]. The `krb5.conf` built with
+//! [`Krb5ConfigBuilder`] includes the one of secret-operator and adds client settings on top, and
+//! [`JaasConfigBuilder`] builds the JAAS login entries for JVM based products.
+//!
+//! ```
+//! use stackable_operator::commons::kerberos::{
+//!     JaasConfigBuilder, JaasLoginEntry, Krb5ConfigBuilder, jvm_arguments,
+//! };
+//!
+//! let krb5_conf = Krb5ConfigBuilder::new().build();
+//! let jaas_conf = JaasConfigBuilder::new()
+//!     .add_entry(JaasLoginEntry::new(
+//!         "Client",
+//!         "zookeeper/simple-zk.default.svc.cluster.local@CLUSTER.LOCAL",
+//!     ))
+//!     .build();
+//!
+//! // Put both files into the ConfigMap of the role group, which is mounted at `/stackable/config`
+//! let jvm_arguments = jvm_arguments(
+//!     "/stackable/config/krb5.conf",
+//!     Some("/stackable/config/jaas.conf"),
+//! );
+//! ```
+use k8s_openapi::api::core::v1::{Volume, VolumeMount};
+use snafu::{ResultExt, Snafu};
+
+use crate::{
+    builder::{
+        self,
+        pod::{
+            PodBuilder,
+            container::ContainerBuilder,
+            volume::{
+                SecretOperatorVolumeSourceBuilder, SecretOperatorVolumeSourceBuilderError,
+                VolumeBuilder, VolumeMountBuilder,
+            },
+        },
+    },
+    commons::{
+        networking::{HostName, KerberosRealmName},
+        secret_class::SecretClassVolumeProvisionParts,
+    },
+};
+
+/// Directory in which the Kerberos volume of secret-operator is mounted.
+pub const KERBEROS_DIR: &str = "/stackable/kerberos";
+
+/// Path of the keytab provided by secret-operator.
+pub const KEYTAB_PATH: &str = "/stackable/kerberos/keytab";
+
+/// Path of the `krb5.conf` provided by secret-operator, which contains the realm and its KDC.
+pub const SECRET_OPERATOR_KRB5_CONF_PATH: &str = "/stackable/kerberos/krb5.conf";
+
+/// Environment variable which points the Kerberos libraries (of non-JVM products) to the
+/// `krb5.conf`.
+pub const KRB5_CONFIG_ENV_VAR: &str = "KRB5_CONFIG";
+
+const KERBEROS_VOLUME_NAME: &str = "kerberos";
+
+#[derive(Debug, Snafu)]
+pub enum Error {
+    #[snafu(display("failed to build the Kerberos volume of SecretClass {secret_class:?}"))]
+    KerberosVolume {
+        source: SecretOperatorVolumeSourceBuilderError,
+        secret_class: String,
+    },
+
+    #[snafu(display("failed to add the Kerberos volume"))]
+    AddVolume { source: builder::pod::Error },
+
+    #[snafu(display("failed to add the Kerberos volumeMount"))]
+    AddVolumeMount {
+        source: builder::pod::container::Error,
+    },
+}
+
+/// Builds the volume which contains the keytab with the principals of the given service names and
+/// the `krb5.conf` of secret-operator. It is mounted at [`KERBEROS_DIR`].
+///
+/// The principals have the form `<service name>/<host>@<realm>`, where the hosts are determined by
+/// the scopes of the volume.
+#[derive(Clone)]
+pub struct KerberosVolumeBuilder {
+    secret_class: String,
+    secret_operator_volume_source_builder: SecretOperatorVolumeSourceBuilder,
+}
+
+impl KerberosVolumeBuilder {
+    pub fn new(secret_class: impl Into<String>) -> Self {
+        let secret_class = secret_class.into();
+        let secret_operator_volume_source_builder = SecretOperatorVolumeSourceBuilder::new(
+            &secret_class,
+            // The keytab is a private part
+            SecretClassVolumeProvisionParts::PublicPrivate,
+        );
+
+        Self {
+            secret_class,
+            secret_operator_volume_source_builder,
+        }
+    }
+
+    pub fn with_service_name(&mut self, name: impl Into<String>) -> &mut Self {
+        self.secret_operator_volume_source_builder
+            .with_kerberos_service_name(name);
+        self
+    }
+
+    pub fn with_node_scope(&mut self) -> &mut Self {
+        self.secret_operator_volume_source_builder.with_node_scope();
+        self
+    }
+
+    pub fn with_pod_scope(&mut self) -> &mut Self {
+        self.secret_operator_volume_source_builder.with_pod_scope();
+        self
+    }
+
+    pub fn with_service_scope(&mut self, name: impl Into<String>) -> &mut Self {
+        self.secret_operator_volume_source_builder
+            .with_service_scope(name);
+        self
+    }
+
+    pub fn with_listener_volume_scope(&mut self, name: impl Into<String>) -> &mut Self {
+        self.secret_operator_volume_source_builder
+            .with_listener_volume_scope(name);
+        self
+    }
+
+    pub fn build(&self) -> Result<(Volume, VolumeMount), Error> {
+        let volume_source =
+            self.secret_operator_volume_source_builder
+                .build()
+                .context(KerberosVolumeSnafu {
+                    secret_class: &self.secret_class,
+                })?;
+
+        Ok((
+            VolumeBuilder::new(KERBEROS_VOLUME_NAME)
+                .ephemeral(volume_source)
+                .build(),
+            VolumeMountBuilder::new(KERBEROS_VOLUME_NAME, KERBEROS_DIR).build(),
+        ))
+    }
+
+    /// Adds the volume to the PodBuilder and the volume mount to all given containers.
+    pub fn add_volume_and_mounts(
+        &self,
+        pod_builder: &mut PodBuilder,
+        container_builders: Vec<&mut ContainerBuilder>,
+    ) -> Result<(), Error> {
+        let (volume, mount) = self.build()?;
+        pod_builder.add_volume(volume).context(AddVolumeSnafu)?;
+
+        for cb in container_builders {
+            cb.add_volume_mounts([mount.clone()])
+                .context(AddVolumeMountSnafu)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// An encryption type which can be used for Kerberos tickets and session keys.
+///
+/// Only the AES encryption types are offered, because the other ones are deprecated or weak.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, strum::Display)]
+pub enum EncryptionType {
+    #[strum(to_string = "aes256-cts-hmac-sha384-192")]
+    Aes256CtsHmacSha384_192,
+
+    #[strum(to_string = "aes128-cts-hmac-sha256-128")]
+    Aes128CtsHmacSha256_128,
+
+    #[strum(to_string = "aes256-cts-hmac-sha1-96")]
+    Aes256CtsHmacSha1_96,
+
+    #[strum(to_string = "aes128-cts-hmac-sha1-96")]
+    Aes128CtsHmacSha1_96,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct Krb5Realm {
+    name: KerberosRealmName,
+    kdcs: Vec<HostName>,
+    admin_server: Option<HostName>,
+}
+
+/// Builds a `krb5.conf` which includes the one of secret-operator at
+/// [`SECRET_OPERATOR_KRB5_CONF_PATH`].
+///
+/// The settings of the built file take precedence over the included ones, because the Kerberos
+/// libraries use the first value which is found.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Krb5ConfigBuilder {
+    default_realm: Option<KerberosRealmName>,
+
+    // Disabled by default, because reverse DNS lookups do not work reliably in Kubernetes, and the
+    // principals of the Pods are created for the names from the scopes anyway.
+    dns_canonicalize_hostname: bool,
+
+    encryption_types: Vec<EncryptionType>,
+    realms: Vec<Krb5Realm>,
+    domain_realms: Vec<(String, KerberosRealmName)>,
+}
+
+impl Krb5ConfigBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Overrides the default realm of secret-operator, e.g. if the services are in another realm
+    /// than the one of the keytab.
+    pub fn default_realm(&mut self, realm: KerberosRealmName) -> &mut Self {
+        self.default_realm = Some(realm);
+        self
+    }
+
+    /// Whether the host names of service principals are canonicalized with (reverse) DNS lookups,
+    /// which is disabled by default.
+    pub fn dns_canonicalize_hostname(&mut self, enabled: bool) -> &mut Self {
+        self.dns_canonicalize_hostname = enabled;
+        self
+    }
+
+    /// Restricts the permitted encryption types, in the order of preference. The defaults of the
+    /// Kerberos libraries are used if none are added.
+    pub fn add_encryption_type(&mut self, encryption_type: EncryptionType) -> &mut Self {
+        self.encryption_types.push(encryption_type);
+        self
+    }
+
+    /// Adds a realm in addition to the one of secret-operator, e.g. for cross-realm trust.
+    pub fn add_realm(
+        &mut self,
+        name: KerberosRealmName,
+        kdcs: Vec<HostName>,
+        admin_server: Option<HostName>,
+    ) -> &mut Self {
+        self.realms.push(Krb5Realm {
+            name,
+            kdcs,
+            admin_server,
+        });
+        self
+    }
+
+    /// Maps a host name (e.g. `host.example.com`) or a domain (e.g. `.example.com`) to a realm.
+    pub fn add_domain_realm(
+        &mut self,
+        domain: impl Into<String>,
+        realm: KerberosRealmName,
+    ) -> &mut Self {
+        self.domain_realms.push((domain.into(), realm));
+        self
+    }
+
+    /// Returns the content of the `krb5.conf`.
+    pub fn build(&self) -> String {
+        let mut lines = vec!["[libdefaults]".to_owned()];
+        if let Some(default_realm) = &self.default_realm {
+            lines.push(format!("default_realm = {default_realm}"));
+        }
+        lines.push(format!(
+            "dns_canonicalize_hostname = {}",
+            self.dns_canonicalize_hostname
+        ));
+        lines.push(format!("rdns = {}", self.dns_canonicalize_hostname));
+        if !self.encryption_types.is_empty() {
+            let encryption_types = self
+                .encryption_types
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(" ");
+            for relation in [
+                "default_tkt_enctypes",
+                "default_tgs_enctypes",
+                "permitted_enctypes",
+            ] {
+                lines.push(format!("{relation} = {encryption_types}"));
+            }
+        }
+
+        if !self.realms.is_empty() {
+            lines.push(String::new());
+            lines.push("[realms]".to_owned());
+            for realm in &self.realms {
+                lines.push(format!("{name} = {{", name = realm.name));
+                for kdc in &realm.kdcs {
+                    lines.push(format!("  kdc = {}", kdc.as_url_host()));
+                }
+                if let Some(admin_server) = &realm.admin_server {
+                    lines.push(format!("  admin_server = {}", admin_server.as_url_host()));
+                }
+                lines.push("}".to_owned());
+            }
+        }
+
+        if !self.domain_realms.is_empty() {
+            lines.push(String::new());
+            lines.push("[domain_realm]".to_owned());
+            for (domain, realm) in &self.domain_realms {
+                lines.push(format!("{domain} = {realm}"));
+            }
+        }
+
+        lines.push(String::new());
+        lines.push(format!("include {SECRET_OPERATOR_KRB5_CONF_PATH}"));
+        lines.push(String::new());
+
+        lines.join("\n")
+    }
+}
+
+/// A login entry of a JAAS configuration which authenticates with a principal from the keytab
+/// provided by secret-operator.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct JaasLoginEntry {
+    /// The name of the entry which is looked up by the product, e.g. `Client` for ZooKeeper
+    /// clients or `KafkaServer`.
+    pub name: String,
+
+    /// The principal, e.g. `zookeeper/simple-zk.default.svc.cluster.local@CLUSTER.LOCAL`.
+    pub principal: String,
+
+    /// Path of the keytab, defaults to [`KEYTAB_PATH`].
+    pub keytab: String,
+
+    /// Whether the key is stored in the private credentials of the subject, which is required by
+    /// services (acceptors) but not by clients.
+    pub store_key: bool,
+}
+
+impl JaasLoginEntry {
+    pub fn new(name: impl Into<String>, principal: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            principal: principal.into(),
+            keytab: KEYTAB_PATH.to_owned(),
+            store_key: true,
+        }
+    }
+}
+
+/// Builds a JAAS configuration with login entries which use the `Krb5LoginModule` of the JVM.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct JaasConfigBuilder {
+    entries: Vec<JaasLoginEntry>,
+}
+
+impl JaasConfigBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_entry(&mut self, entry: JaasLoginEntry) -> &mut Self {
+        self.entries.push(entry);
+        self
+    }
+
+    /// Returns the content of the JAAS configuration file.
+    pub fn build(&self) -> String {
+        self.entries
+            .iter()
+            .map(|entry| {
+                format!(
+                    "{name} {{
+  com.sun.security.auth.module.Krb5LoginModule required
+  useKeyTab=true
+  keyTab=\"{keytab}\"
+  storeKey={store_key}
+  useTicketCache=false
+  principal=\"{principal}\";
+}};
+",
+                    name = entry.name,
+                    keytab = escape_jaas_value(&entry.keytab),
+                    store_key = entry.store_key,
+                    principal = escape_jaas_value(&entry.principal),
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Escapes a quoted value in a JAAS configuration file.
+fn escape_jaas_value(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Returns the JVM arguments which point the JVM to the given `krb5.conf` and JAAS configuration.
+pub fn jvm_arguments(krb5_conf_path: &str, jaas_conf_path: Option<&str>) -> Vec<String> {
+    let mut arguments = vec![format!("-Djava.security.krb5.conf={krb5_conf_path}")];
+    if let Some(jaas_conf_path) = jaas_conf_path {
+        arguments.push(format!(
+            "-Djava.security.auth.login.config={jaas_conf_path}"
+        ));
+    }
+    arguments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        builder::{configmap::ConfigMapBuilder, meta::ObjectMetaBuilder},
+        test_utils::golden::ReconcileSnapshot,
+    };
+
+    const GOLDEN_FILE: &str = "fixtures/kerberos/output.yaml";
+
+    #[test]
+    fn kerberos_client_configuration() {
+        let realm = KerberosRealmName::try_from("CLUSTER.LOCAL".to_owned()).unwrap();
+        let trusted_realm = KerberosRealmName::try_from("EXAMPLE.COM".to_owned()).unwrap();
+
+        let krb5_conf = Krb5ConfigBuilder::new()
+            .default_realm(realm)
+            .add_encryption_type(EncryptionType::Aes256CtsHmacSha384_192)
+            .add_encryption_type(EncryptionType::Aes256CtsHmacSha1_96)
+            .add_realm(
+                trusted_realm.clone(),
+                vec![
+                    "kdc1.example.com".parse().unwrap(),
+                    "kdc2.example.com".parse().unwrap(),
+                ],
+                Some("kadmin.example.com".parse().unwrap()),
+            )
+            .add_domain_realm(".example.com", trusted_realm)
+            .build();
+
+        let server_entry = JaasLoginEntry::new(
+            "Server",
+            "zookeeper/simple-zk-server.default.svc.cluster.local@CLUSTER.LOCAL",
+        );
+        let mut client_entry = JaasLoginEntry::new(
+            "Client",
+            "zookeeper/simple-zk-server.default.svc.cluster.local@CLUSTER.LOCAL",
+        );
+        client_entry.store_key = false;
+        let jaas_conf = JaasConfigBuilder::new()
+            .add_entry(server_entry)
+            .add_entry(client_entry)
+            .build();
+
+        let config_map = ConfigMapBuilder::new()
+            .metadata(
+                ObjectMetaBuilder::new()
+                    .name("simple-zk-server-default")
+                    .namespace("default")
+                    .build(),
+            )
+            .add_data("krb5.conf", krb5_conf)
+            .add_data("jaas.conf", jaas_conf)
+            .build()
+            .unwrap();
+
+        let mut container_builder = ContainerBuilder::new("zookeeper").unwrap();
+        container_builder
+            .image("oci.stackable.tech/sdp/zookeeper:3.9.3-stackable0.0.0-dev")
+            .args(jvm_arguments(
+                "/stackable/config/krb5.conf",
+                Some("/stackable/config/jaas.conf"),
+            ))
+            .add_env_var(KRB5_CONFIG_ENV_VAR, "/stackable/config/krb5.conf");
+        let mut pod_builder = PodBuilder::new();
+        pod_builder.metadata(
+            ObjectMetaBuilder::new()
+                .name("simple-zk-server-default-0")
+                .namespace("default")
+                .build(),
+        );
+        KerberosVolumeBuilder::new("kerberos")
+            .with_service_name("zookeeper")
+            .with_service_scope("simple-zk-server")
+            .add_volume_and_mounts(&mut pod_builder, vec![&mut container_builder])
+            .unwrap();
+        let pod = pod_builder
+            .add_container(container_builder.build())
+            .build()
+            .unwrap();
+
+        let mut snapshot = ReconcileSnapshot::new();
+        snapshot.add(&config_map).unwrap();
+        snapshot.add(&pod).unwrap();
+        snapshot.assert_matches_golden_file(GOLDEN_FILE);
+    }
+
+    #[test]
+    fn jaas_values_are_escaped() {
+        let jaas_conf = JaasConfigBuilder::new()
+            .add_entry(JaasLoginEntry::new("Client", r#"user"name\@REALM"#))
+            .build();
+
+        assert!(jaas_conf.contains(r#"principal="user\"name\\@REALM";"#));
+    }
+}
@@ -3,6 +3,7 @@
 pub mod affinity;
 pub mod cache;
 pub mod cluster_operation;
+pub mod kerberos;
 pub mod networking;
 pub mod opa;
 pub mod pdb;
@@ -10,5 +11,8 @@ pub mod product_image_selection;
 pub mod random_secret_creation;
 pub mod rbac;
 pub mod resources;
+pub mod scheduling;
 pub mod secret_class;
+#[cfg(feature = "certs")]
+pub mod secret_generation;
 pub mod tls_verification;
@@ -1,13 +1,30 @@
 use std::collections::BTreeMap;
 
 use base64::Engine;
-use k8s_openapi::api::core::v1::Secret;
+use k8s_openapi::{ByteString, api::core::v1::Secret, jiff::Timestamp};
 use kube::{Api, Resource, ResourceExt, api::DeleteParams};
 use rand::{Rng, rngs::StdRng};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 use snafu::{OptionExt, ResultExt, Snafu};
+use stackable_shared::time::Duration;
 
 use crate::{builder::meta::ObjectMetaBuilder, client::Client};
 
+/// The annotation of a random Secret which contains the time when the current value was generated
+pub const ROTATED_AT_ANNOTATION: &str = "rotation.stackable.tech/rotated-at";
+
+/// The annotation of a random Secret which contains the time when the previous value is removed
+pub const PREVIOUS_EXPIRES_AT_ANNOTATION: &str = "rotation.stackable.tech/previous-expires-at";
+
+/// The annotation which can be set to `true` on a random Secret to request a rotation
+///
+/// The annotation is removed when the Secret is rotated.
+pub const ROTATE_ANNOTATION: &str = "rotation.stackable.tech/rotate";
+
+/// The suffix of the key which contains the previous value of a rotated random Secret
+pub const PREVIOUS_KEY_SUFFIX: &str = ".previous";
+
 #[derive(Snafu, Debug)]
 pub enum Error {
     #[snafu(display("object defines no namespace"))]
@@ -24,6 +41,58 @@ pub enum Error {
 
     #[snafu(display("object is missing metadata to build owner reference"))]
     ObjectMissingMetadataForOwnerRef { source: crate::builder::meta::Error },
+
+    #[snafu(display("failed to update random secret"))]
+    UpdateRandomSecret { source: crate::client::Error },
+}
+
+/// The policy to rotate the value of a random Secret
+#[derive(Clone, Debug, Deserialize, Eq, JsonSchema, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SecretRotationPolicy {
+    /// The maximum age of the generated value, e.g. `90d`, after which it is rotated.
+    ///
+    /// If not set, then the value is only rotated if the annotation
+    /// `rotation.stackable.tech/rotate: "true"` is set on the Secret.
+    pub max_age: Option<Duration>,
+
+    /// How long the previous value is kept in the key `<key>.previous` after a rotation, so that
+    /// clients can still use it until they are restarted; defaults to `1d`.
+    #[serde(default = "SecretRotationPolicy::default_grace_period")]
+    pub grace_period: Duration,
+}
+
+impl SecretRotationPolicy {
+    const fn default_grace_period() -> Duration {
+        Duration::from_days_unchecked(1)
+    }
+}
+
+impl Default for SecretRotationPolicy {
+    fn default() -> Self {
+        Self {
+            max_age: None,
+            grace_period: Self::default_grace_period(),
+        }
+    }
+}
+
+/// The rotation state of a random Secret returned by [`create_or_rotate_random_secret`]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RandomSecretRotation {
+    /// The time when the current value was generated
+    ///
+    /// It can be passed to
+    /// [`restarter_secret_rotation_annotations`](crate::v2::builder::statefulset::restarter_secret_rotation_annotations)
+    /// to restart the Pods after a rotation.
+    pub rotated_at: Timestamp,
+
+    /// Whether the value was rotated by this call
+    pub rotated: bool,
+
+    /// The duration after which the Secret must be reconciled again to rotate it or to remove the
+    /// previous value, if any
+    pub requeue_after: Option<std::time::Duration>,
 }
 
 /// This function creates a random Secret if it doesn't already exist.
@@ -123,6 +192,163 @@ where
     Ok(())
 }
 
+/// Creates a random Secret like [`create_random_secret_if_not_exists`] and rotates its value
+/// according to the given policy.
+///
+/// The value is rotated if it is older than [`SecretRotationPolicy::max_age`] or if the annotation
+/// [`ROTATE_ANNOTATION`] is set to `true` on the Secret. On rotation, the previous value is moved
+/// to the key `<secret_key>.previous`, where it is kept for the
+/// [`SecretRotationPolicy::grace_period`]. The times of the rotation and the end of the grace
+/// period are recorded in the annotations [`ROTATED_AT_ANNOTATION`] and
+/// [`PREVIOUS_EXPIRES_AT_ANNOTATION`]. The previous value is removed by the first call after the
+/// grace period.
+///
+/// Secrets without the annotation [`ROTATED_AT_ANNOTATION`], e.g. Secrets which were created by
+/// [`create_random_secret_if_not_exists`], are considered to be generated at their creation time.
+///
+/// The controller should requeue the stacklet after [`RandomSecretRotation::requeue_after`] at the
+/// latest, so that the Secret is rotated in time.
+pub async fn create_or_rotate_random_secret<R>(
+    secret_name: &str,
+    secret_key: &str,
+    secret_size_bytes: usize,
+    stacklet: &R,
+    client: &Client,
+    policy: &SecretRotationPolicy,
+) -> Result<RandomSecretRotation, Error>
+where
+    R: Resource<DynamicType = ()>,
+{
+    create_random_secret_if_not_exists(
+        secret_name,
+        secret_key,
+        secret_size_bytes,
+        stacklet,
+        client,
+    )
+    .await?;
+
+    let secret_namespace = stacklet.namespace().context(ObjectHasNoNamespaceSnafu)?;
+    let mut secret = client
+        .get::<Secret>(secret_name, &secret_namespace)
+        .await
+        .context(RetrieveRandomSecretSnafu)?;
+
+    let original_secret = secret.clone();
+    let rotation = rotate_random_secret(
+        &mut secret,
+        secret_key,
+        secret_size_bytes,
+        policy,
+        Timestamp::now(),
+    );
+
+    if secret != original_secret {
+        if rotation.rotated {
+            tracing::info!(
+                k8s.secret.name = secret_name,
+                k8s.secret.namespace = secret_namespace,
+                "Rotating random Secret"
+            );
+        }
+        client
+            .update(&secret)
+            .await
+            .context(UpdateRandomSecretSnafu)?;
+    }
+
+    Ok(rotation)
+}
+
+/// Rotates the value of the given Secret if required and removes the expired previous value.
+fn rotate_random_secret(
+    secret: &mut Secret,
+    secret_key: &str,
+    secret_size_bytes: usize,
+    policy: &SecretRotationPolicy,
+    now: Timestamp,
+) -> RandomSecretRotation {
+    let annotations = secret.annotations();
+    let timestamp_annotation = |key: &str| {
+        annotations
+            .get(key)
+            .and_then(|timestamp| timestamp.parse::<Timestamp>().ok())
+    };
+    let mut rotated_at = timestamp_annotation(ROTATED_AT_ANNOTATION)
+        .or_else(|| secret.creation_timestamp().map(|time| time.0))
+        .unwrap_or(now);
+    let mut previous_expires_at = timestamp_annotation(PREVIOUS_EXPIRES_AT_ANNOTATION);
+    let rotation_requested = annotations
+        .get(ROTATE_ANNOTATION)
+        .is_some_and(|value| value.eq_ignore_ascii_case("true"));
+
+    let expires_at = |timestamp: Timestamp, duration: Duration| {
+        timestamp
+            .checked_add(std::time::Duration::from(duration))
+            .unwrap_or(Timestamp::MAX)
+    };
+    let rotation_due = policy
+        .max_age
+        .is_some_and(|max_age| expires_at(rotated_at, max_age) <= now);
+
+    let previous_key = format!("{secret_key}{PREVIOUS_KEY_SUFFIX}");
+
+    // The maps are only created when a key is written, so that an unchanged Secret without data or
+    // annotations is not updated.
+    let rotated = rotation_requested || rotation_due;
+    if rotated {
+        let data = secret.data.get_or_insert_with(BTreeMap::new);
+        let annotations = secret
+            .metadata
+            .annotations
+            .get_or_insert_with(BTreeMap::new);
+
+        if let Some(value) = data.remove(secret_key) {
+            data.insert(previous_key, value);
+        }
+        data.insert(
+            secret_key.to_owned(),
+            ByteString(get_random_base64(secret_size_bytes).into_bytes()),
+        );
+
+        rotated_at = now;
+        previous_expires_at = Some(expires_at(now, policy.grace_period));
+        annotations.remove(ROTATE_ANNOTATION);
+        annotations.insert(ROTATED_AT_ANNOTATION.to_owned(), now.to_string());
+        annotations.insert(
+            PREVIOUS_EXPIRES_AT_ANNOTATION.to_owned(),
+            expires_at(now, policy.grace_period).to_string(),
+        );
+    } else if previous_expires_at.is_some_and(|previous_expires_at| previous_expires_at <= now) {
+        previous_expires_at = None;
+        if let Some(data) = &mut secret.data {
+            data.remove(&previous_key);
+        }
+        if let Some(annotations) = &mut secret.metadata.annotations {
+            annotations.remove(PREVIOUS_EXPIRES_AT_ANNOTATION);
+        }
+    }
+
+    let requeue_after = [
+        policy
+            .max_age
+            .map(|max_age| expires_at(rotated_at, max_age)),
+        previous_expires_at,
+    ]
+    .into_iter()
+    .flatten()
+    .min()
+    .map(|next_action_at| {
+        std::time::Duration::try_from(next_action_at.duration_since(now)).unwrap_or_default()
+    });
+
+    RandomSecretRotation {
+        rotated_at,
+        rotated,
+        requeue_after,
+    }
+}
+
 /// Generates a cryptographically secure base64 String with the specified size in bytes.
 fn get_random_base64(size_bytes: usize) -> String {
     // As `make_rng` takes its entropy from the OS rng, we are using `getrandom`, which should be
@@ -134,3 +360,231 @@ fn get_random_base64(size_bytes: usize) -> String {
 
     base64::engine::general_purpose::STANDARD.encode(bytes)
 }
+
+#[cfg(test)]
+mod tests {
+    use k8s_openapi::{api::core::v1::ConfigMap, apimachinery::pkg::apis::meta::v1::Time};
+    use kube::api::ObjectMeta;
+
+    use super::*;
+    use crate::client::fake::{DEFAULT_NAMESPACE, FakeApiServer};
+
+    const CREATED_AT: &str = "2026-01-01T00:00:00Z";
+
+    fn policy(max_age: Option<&str>) -> SecretRotationPolicy {
+        SecretRotationPolicy {
+            max_age: max_age.map(|max_age| max_age.parse().unwrap()),
+            grace_period: "1d".parse().unwrap(),
+        }
+    }
+
+    fn secret(annotations: &[(&str, &str)], data: &[(&str, &str)]) -> Secret {
+        Secret {
+            metadata: ObjectMeta {
+                creation_timestamp: Some(Time(CREATED_AT.parse().unwrap())),
+                annotations: Some(
+                    annotations
+                        .iter()
+                        .map(|(key, value)| ((*key).to_owned(), (*value).to_owned()))
+                        .collect(),
+                ),
+                ..ObjectMeta::default()
+            },
+            data: Some(
+                data.iter()
+                    .map(|(key, value)| ((*key).to_owned(), ByteString(value.as_bytes().to_vec())))
+                    .collect(),
+            ),
+            ..Secret::default()
+        }
+    }
+
+    fn value(secret: &Secret, key: &str) -> Option<String> {
+        secret
+            .data
+            .as_ref()
+            .and_then(|data| data.get(key))
+            .map(|ByteString(value)| String::from_utf8(value.clone()).unwrap())
+    }
+
+    #[test]
+    fn no_rotation_without_max_age() {
+        let mut secret = secret(&[], &[("key", "old")]);
+        let original_secret = secret.clone();
+
+        let rotation = rotate_random_secret(
+            &mut secret,
+            "key",
+            16,
+            &policy(None),
+            "2027-01-01T00:00:00Z".parse().unwrap(),
+        );
+
+        assert_eq!(
+            rotation,
+            RandomSecretRotation {
+                rotated_at: CREATED_AT.parse().unwrap(),
+                rotated: false,
+                requeue_after: None,
+            }
+        );
+        assert_eq!(secret, original_secret);
+    }
+
+    #[test]
+    fn no_changes_to_secret_without_data_and_annotations() {
+        let mut secret = Secret {
+            metadata: ObjectMeta {
+                creation_timestamp: Some(Time(CREATED_AT.parse().unwrap())),
+                ..ObjectMeta::default()
+            },
+            ..Secret::default()
+        };
+        let original_secret = secret.clone();
+
+        let rotation = rotate_random_secret(
+            &mut secret,
+            "key",
+            16,
+            &policy(Some("30d")),
+            "2026-01-02T00:00:00Z".parse().unwrap(),
+        );
+
+        assert!(!rotation.rotated);
+        assert_eq!(secret, original_secret);
+    }
+
+    #[test]
+    fn rotation_after_max_age() {
+        let mut secret = secret(&[], &[("key", "old")]);
+        let policy = policy(Some("30d"));
+
+        // Before the max age, the requeue is scheduled for the rotation.
+        let rotation = rotate_random_secret(
+            &mut secret,
+            "key",
+            16,
+            &policy,
+            "2026-01-21T00:00:00Z".parse().unwrap(),
+        );
+        assert!(!rotation.rotated);
+        assert_eq!(
+            rotation.requeue_after,
+            Some(std::time::Duration::from_hours(10 * 24))
+        );
+
+        // After the max age, the value is rotated and the previous value is kept.
+        let now: Timestamp = "2026-02-01T00:00:00Z".parse().unwrap();
+        let rotation = rotate_random_secret(&mut secret, "key", 16, &policy, now);
+        assert_eq!(
+            rotation,
+            RandomSecretRotation {
+                rotated_at: now,
+                rotated: true,
+                requeue_after: Some(std::time::Duration::from_hours(24)),
+            }
+        );
+        assert_eq!(value(&secret, "key.previous").as_deref(), Some("old"));
+        assert_eq!(value(&secret, "key").map(|value| value.len()), Some(24));
+        assert_eq!(
+            secret
+                .annotations()
+                .get(ROTATED_AT_ANNOTATION)
+                .map(String::as_str),
+            Some("2026-02-01T00:00:00Z")
+        );
+        assert_eq!(
+            secret
+                .annotations()
+                .get(PREVIOUS_EXPIRES_AT_ANNOTATION)
+                .map(String::as_str),
+            Some("2026-02-02T00:00:00Z")
+        );
+
+        // After the grace period, the previous value is removed.
+        let current_value = value(&secret, "key");
+        let rotation = rotate_random_secret(
+            &mut secret,
+            "key",
+            16,
+            &policy,
+            "2026-02-02T00:00:00Z".parse().unwrap(),
+        );
+        assert_eq!(
+            rotation,
+            RandomSecretRotation {
+                rotated_at: now,
+                rotated: false,
+                requeue_after: Some(std::time::Duration::from_hours(29 * 24)),
+            }
+        );
+        assert_eq!(value(&secret, "key"), current_value);
+        assert_eq!(value(&secret, "key.previous"), None);
+        assert!(
+            !secret
+                .annotations()
+                .contains_key(PREVIOUS_EXPIRES_AT_ANNOTATION)
+        );
+    }
+
+    #[test]
+    fn rotation_on_request() {
+        let mut secret = secret(&[(ROTATE_ANNOTATION, "true")], &[("key", "old")]);
+
+        let rotation = rotate_random_secret(
+            &mut secret,
+            "key",
+            16,
+            &policy(None),
+            "2026-01-02T00:00:00Z".parse().unwrap(),
+        );
+
+        assert!(rotation.rotated);
+        assert_eq!(value(&secret, "key.previous").as_deref(), Some("old"));
+        assert!(!secret.annotations().contains_key(ROTATE_ANNOTATION));
+    }
+
+    #[tokio::test]
+    async fn create_and_rotate() {
+        let server = FakeApiServer::new();
+        let client = server.client();
+        let stacklet = server.insert(&ConfigMap {
+            metadata: ObjectMeta {
+                name: Some("cluster".to_owned()),
+                namespace: Some(DEFAULT_NAMESPACE.to_owned()),
+                ..ObjectMeta::default()
+            },
+            ..ConfigMap::default()
+        });
+
+        let rotation =
+            create_or_rotate_random_secret("random", "key", 16, &stacklet, &client, &policy(None))
+                .await
+                .unwrap();
+        assert!(!rotation.rotated);
+        let created = client
+            .get::<Secret>("random", DEFAULT_NAMESPACE)
+            .await
+            .unwrap();
+
+        client
+            .merge_patch(
+                &created,
+                serde_json::json!({ "metadata": { "annotations": { ROTATE_ANNOTATION: "true" } } }),
+            )
+            .await
+            .unwrap();
+        let rotation =
+            create_or_rotate_random_secret("random", "key", 16, &stacklet, &client, &policy(None))
+                .await
+                .unwrap();
+        assert!(rotation.rotated);
+
+        let rotated = client
+            .get::<Secret>("random", DEFAULT_NAMESPACE)
+            .await
+            .unwrap();
+        assert_eq!(value(&rotated, "key.previous"), value(&created, "key"));
+        assert_ne!(value(&rotated, "key"), value(&created, "key"));
+    }
+}
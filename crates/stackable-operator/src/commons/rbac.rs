@@ -1,5 +1,11 @@
+use k8s_openapi::{
+    Resource as KubeApiResource,
+    api::rbac::v1::{ClusterRole, PolicyRule, Role},
+};
 use kube::{Resource, ResourceExt};
-use snafu::{ResultExt, Snafu};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu, ensure};
 
 use crate::{
     builder::meta::ObjectMetaBuilder,
@@ -25,6 +31,316 @@ pub enum Error {
         source: crate::builder::meta::Error,
         name: String,
     },
+
+    #[snafu(display("failed to set owner reference from resource for Role {name:?}"))]
+    RoleOwnerReferenceFromResource {
+        source: crate::builder::meta::Error,
+        name: String,
+    },
+
+    #[snafu(display(
+        "the rule for the resources {resources:?} cannot grant the verb \"create\" and be \
+        restricted to resource names at the same time, because the names of new objects are not \
+        known when they are authorized"
+    ))]
+    CreateRestrictedToResourceNames { resources: Vec<String> },
+
+    #[snafu(display("the rule for the resources {resources:?} grants no verbs"))]
+    RuleWithoutVerbs { resources: Vec<String> },
+
+    #[snafu(display(
+        "the ClusterRole {name:?} cannot be granted in the custom resource, only the ClusterRoles \
+        {allowed:?} are allowed by the operator"
+    ))]
+    ExtraClusterRoleNotAllowed { name: String, allowed: Vec<String> },
+}
+
+/// Permissions of the ServiceAccount of a stacklet in addition to the ones granted by the operator
+///
+/// This can be used in the custom resource of a product to allow users to grant permissions which
+/// are required e.g. by custom plugins.
+///
+/// Everyone who can edit the custom resource effectively gets these permissions in the namespace
+/// of the stacklet, because the pods can be configured to run arbitrary code with the
+/// ServiceAccount. Kubernetes prevents privilege escalation only partially: The operator must hold
+/// all permissions of the extra rules itself or have the `escalate` permission on Roles, and it
+/// must have the `bind` permission on the extra ClusterRoles. Therefore, an operator with these
+/// permissions lets the editors of the custom resource gain the union of them. To limit this, the
+/// extra ClusterRoles are only accepted if the operator allows them, see
+/// [`RbacPolicyBuilder::allow_extra_cluster_roles`].
+#[derive(Clone, Debug, Default, Deserialize, JsonSchema, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RbacConfig {
+    /// Additional rules which are granted in the namespace of the stacklet.
+    #[serde(default)]
+    pub extra_rules: Vec<PolicyRule>,
+
+    /// The names of existing ClusterRoles whose permissions are granted in the namespace of the
+    /// stacklet. Only the ClusterRoles which are allowed by the operator can be used.
+    #[serde(default)]
+    pub extra_cluster_roles: Vec<String>,
+}
+
+/// The RBAC objects of a stacklet which are built by [`RbacPolicyBuilder`]
+#[derive(Clone, Debug, PartialEq)]
+pub struct RbacResources {
+    pub service_account: ServiceAccount,
+
+    /// The namespaced Role with the added rules, if any rule was added
+    pub role: Option<Role>,
+
+    /// The RoleBindings of the ServiceAccount to the product ClusterRole, the Role and the
+    /// additional ClusterRoles in this order
+    pub role_bindings: Vec<RoleBinding>,
+}
+
+/// Builds the RBAC objects of a stacklet from a declarative policy.
+///
+/// The ServiceAccount `{resource_name}-serviceaccount` is bound to the following roles in the
+/// namespace of the stacklet:
+///
+/// - The ClusterRole `{product_name}-clusterrole` via the RoleBinding `{resource_name}-rolebinding`
+///   like in [`build_rbac_resources`], unless [`RbacPolicyBuilder::without_product_cluster_role`]
+///   is called.
+/// - The Role `{resource_name}-role` with all added rules via the RoleBinding
+///   `{resource_name}-role-rolebinding`, if any rule was added. Rules can be restricted to the
+///   names of the objects owned by the stacklet, see [`RbacPolicyBuilder::grant`].
+/// - Every additional ClusterRole via the RoleBinding `{resource_name}-{cluster_role}-rolebinding`.
+///
+/// ```
+/// # use k8s_openapi::api::{coordination::v1::Lease, core::v1::ConfigMap};
+/// # use stackable_operator::{commons::rbac::RbacPolicyBuilder, kvp::Labels};
+/// # fn build(cluster: &ConfigMap) -> Result<(), stackable_operator::commons::rbac::Error> {
+/// let rbac = RbacPolicyBuilder::new(cluster, "kafka", Labels::new())
+///     .grant::<ConfigMap>(&["get", "watch"], &["simple-kafka-broker-default"])
+///     .grant::<Lease>(&["get", "update"], &["simple-kafka-controller-leader"])
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct RbacPolicyBuilder<'a, T> {
+    resource: &'a T,
+    product_name: String,
+    labels: Labels,
+    bind_product_cluster_role: bool,
+    rules: Vec<PolicyRule>,
+    cluster_roles: Vec<String>,
+    extra_cluster_roles: Vec<String>,
+    allowed_extra_cluster_roles: Vec<String>,
+}
+
+impl<'a, T> RbacPolicyBuilder<'a, T>
+where
+    T: Clone + Resource<DynamicType = ()>,
+{
+    pub fn new(resource: &'a T, product_name: &str, labels: Labels) -> Self {
+        Self {
+            resource,
+            product_name: product_name.to_owned(),
+            labels,
+            bind_product_cluster_role: true,
+            rules: Vec::new(),
+            cluster_roles: Vec::new(),
+            extra_cluster_roles: Vec::new(),
+            allowed_extra_cluster_roles: Vec::new(),
+        }
+    }
+
+    /// Does not bind the ServiceAccount to the ClusterRole `{product_name}-clusterrole`, e.g.
+    /// because all required permissions are granted by rules.
+    pub fn without_product_cluster_role(&mut self) -> &mut Self {
+        self.bind_product_cluster_role = false;
+        self
+    }
+
+    /// Grants the verbs on the objects of the type `K` in the namespace of the stacklet.
+    ///
+    /// If resource names are given, then only the objects with these names can be accessed.
+    /// Objects cannot be created if the rule is restricted to resource names.
+    pub fn grant<K>(&mut self, verbs: &[&str], resource_names: &[&str]) -> &mut Self
+    where
+        K: Resource<DynamicType = ()>,
+    {
+        self.add_rule(PolicyRule {
+            api_groups: Some(vec![K::group(&()).into_owned()]),
+            resources: Some(vec![K::plural(&()).into_owned()]),
+            verbs: verbs.iter().map(|verb| (*verb).to_owned()).collect(),
+            resource_names: (!resource_names.is_empty()).then(|| {
+                resource_names
+                    .iter()
+                    .map(|name| (*name).to_owned())
+                    .collect()
+            }),
+            ..PolicyRule::default()
+        })
+    }
+
+    /// Adds a rule to the Role of the stacklet.
+    pub fn add_rule(&mut self, rule: PolicyRule) -> &mut Self {
+        if !self.rules.contains(&rule) {
+            self.rules.push(rule);
+        }
+        self
+    }
+
+    /// Binds the ServiceAccount to the existing ClusterRole with the given name in the namespace of
+    /// the stacklet.
+    pub fn add_cluster_role(&mut self, name: impl Into<String>) -> &mut Self {
+        let name = name.into();
+        if !self.cluster_roles.contains(&name) {
+            self.cluster_roles.push(name);
+        }
+        self
+    }
+
+    /// Allows the users to grant the ClusterRoles with the given names in the custom resource, see
+    /// [`RbacConfig::extra_cluster_roles`].
+    ///
+    /// The allowed ClusterRoles should be configurable by the administrator of the operator, e.g.
+    /// in the Helm chart, which must also grant the `bind` permission on them to the operator.
+    pub fn allow_extra_cluster_roles(
+        &mut self,
+        names: impl IntoIterator<Item = impl Into<String>>,
+    ) -> &mut Self {
+        self.allowed_extra_cluster_roles
+            .extend(names.into_iter().map(Into::into));
+        self
+    }
+
+    /// Adds the rules and ClusterRoles which are configured by the user in the custom resource.
+    ///
+    /// [`RbacPolicyBuilder::build`] fails if a ClusterRole is not allowed, see
+    /// [`RbacPolicyBuilder::allow_extra_cluster_roles`].
+    pub fn add_config(&mut self, config: &RbacConfig) -> &mut Self {
+        for rule in &config.extra_rules {
+            self.add_rule(rule.clone());
+        }
+        for cluster_role in &config.extra_cluster_roles {
+            self.add_cluster_role(cluster_role);
+            self.extra_cluster_roles.push(cluster_role.clone());
+        }
+        self
+    }
+
+    pub fn build(&self) -> Result<RbacResources> {
+        for rule in &self.rules {
+            let resources = rule.resources.clone().unwrap_or_default();
+            ensure!(
+                !rule.verbs.is_empty(),
+                RuleWithoutVerbsSnafu {
+                    resources: resources.clone()
+                }
+            );
+            ensure!(
+                rule.resource_names.as_ref().is_none_or(Vec::is_empty)
+                    || !rule.verbs.iter().any(|verb| verb == "create"),
+                CreateRestrictedToResourceNamesSnafu { resources }
+            );
+        }
+        for cluster_role in &self.extra_cluster_roles {
+            ensure!(
+                self.allowed_extra_cluster_roles.contains(cluster_role),
+                ExtraClusterRoleNotAllowedSnafu {
+                    name: cluster_role,
+                    allowed: self.allowed_extra_cluster_roles.clone(),
+                }
+            );
+        }
+
+        let resource_name = self.resource.name_any();
+        let sa_name = service_account_name(&resource_name);
+        let service_account = ServiceAccount {
+            metadata: ObjectMetaBuilder::new()
+                .name_and_namespace(self.resource)
+                .name(sa_name.clone())
+                .ownerreference_from_resource(self.resource, None, Some(true))
+                .with_context(|_| ServiceAccountOwnerReferenceFromResourceSnafu {
+                    name: sa_name.clone(),
+                })?
+                .with_labels(self.labels.clone())
+                .build(),
+            ..ServiceAccount::default()
+        };
+
+        let mut role_bindings = Vec::new();
+        if self.bind_product_cluster_role {
+            role_bindings.push(self.build_role_binding(
+                role_binding_name(&resource_name),
+                ClusterRole::KIND,
+                format!(
+                    "{product_name}-clusterrole",
+                    product_name = self.product_name
+                ),
+            )?);
+        }
+
+        let role = if self.rules.is_empty() {
+            None
+        } else {
+            let name = role_name(&resource_name);
+            role_bindings.push(self.build_role_binding(
+                role_binding_for_role_name(&resource_name),
+                Role::KIND,
+                name.clone(),
+            )?);
+
+            Some(Role {
+                metadata: ObjectMetaBuilder::new()
+                    .name_and_namespace(self.resource)
+                    .name(name.clone())
+                    .ownerreference_from_resource(self.resource, None, Some(true))
+                    .context(RoleOwnerReferenceFromResourceSnafu { name })?
+                    .with_labels(self.labels.clone())
+                    .build(),
+                rules: Some(self.rules.clone()),
+            })
+        };
+
+        for cluster_role in &self.cluster_roles {
+            role_bindings.push(self.build_role_binding(
+                format!("{resource_name}-{cluster_role}-rolebinding"),
+                ClusterRole::KIND,
+                cluster_role.clone(),
+            )?);
+        }
+
+        Ok(RbacResources {
+            service_account,
+            role,
+            role_bindings,
+        })
+    }
+
+    /// Builds a RoleBinding of the ServiceAccount of the stacklet to the given role.
+    fn build_role_binding(
+        &self,
+        name: String,
+        role_kind: &str,
+        role_name: String,
+    ) -> Result<RoleBinding> {
+        Ok(RoleBinding {
+            metadata: ObjectMetaBuilder::new()
+                .name_and_namespace(self.resource)
+                .name(name)
+                .ownerreference_from_resource(self.resource, None, Some(true))
+                .context(RoleBindingOwnerReferenceFromResourceSnafu {
+                    name: self.resource.name_any(),
+                })?
+                .with_labels(self.labels.clone())
+                .build(),
+            role_ref: RoleRef {
+                kind: role_kind.to_owned(),
+                name: role_name,
+                api_group: Some(ClusterRole::GROUP.to_owned()),
+            },
+            subjects: Some(vec![Subject {
+                kind: ServiceAccount::KIND.to_owned(),
+                name: service_account_name(&self.resource.name_any()),
+                namespace: self.resource.namespace(),
+                ..Subject::default()
+            }]),
+        })
+    }
 }
 
 /// Build RBAC objects for the product workloads.
@@ -43,42 +359,14 @@ pub fn build_rbac_resources<T: Clone + Resource<DynamicType = ()>>(
     product_name: &str,
     labels: Labels,
 ) -> Result<(ServiceAccount, RoleBinding)> {
-    let sa_name = service_account_name(&resource.name_any());
-    let service_account = ServiceAccount {
-        metadata: ObjectMetaBuilder::new()
-            .name_and_namespace(resource)
-            .name(sa_name.clone())
-            .ownerreference_from_resource(resource, None, Some(true))
-            .with_context(|_| ServiceAccountOwnerReferenceFromResourceSnafu {
-                name: sa_name.clone(),
-            })?
-            .with_labels(labels.clone())
-            .build(),
-        ..ServiceAccount::default()
-    };
-
-    let role_binding = RoleBinding {
-        metadata: ObjectMetaBuilder::new()
-            .name_and_namespace(resource)
-            .name(role_binding_name(&resource.name_any()))
-            .ownerreference_from_resource(resource, None, Some(true))
-            .context(RoleBindingOwnerReferenceFromResourceSnafu {
-                name: resource.name_any(),
-            })?
-            .with_labels(labels)
-            .build(),
-        role_ref: RoleRef {
-            kind: "ClusterRole".to_string(),
-            name: format!("{product_name}-clusterrole"),
-            api_group: Some("rbac.authorization.k8s.io".to_owned()),
-        },
-        subjects: Some(vec![Subject {
-            kind: "ServiceAccount".to_string(),
-            name: sa_name,
-            namespace: resource.namespace(),
-            ..Subject::default()
-        }]),
-    };
+    let RbacResources {
+        service_account,
+        mut role_bindings,
+        ..
+    } = RbacPolicyBuilder::new(resource, product_name, labels).build()?;
+    let role_binding = role_bindings
+        .pop()
+        .expect("the RoleBinding of the product ClusterRole is always built");
 
     Ok((service_account, role_binding))
 }
@@ -101,15 +389,36 @@ fn role_binding_name(rbac_prefix: &str) -> String {
     format!("{rbac_prefix}-rolebinding")
 }
 
+/// Generate the name of the Role.
+/// This is private for the same reasons as [`service_account_name`].
+fn role_name(rbac_prefix: &str) -> String {
+    format!("{rbac_prefix}-role")
+}
+
+/// Generate the name of the RoleBinding to the Role.
+/// This is private for the same reasons as [`service_account_name`].
+fn role_binding_for_role_name(rbac_prefix: &str) -> String {
+    format!("{rbac_prefix}-role-rolebinding")
+}
+
 #[cfg(test)]
 mod tests {
+    use k8s_openapi::api::{
+        coordination::v1::Lease,
+        core::v1::ConfigMap,
+        rbac::v1::{PolicyRule, RoleRef},
+    };
     use kube::CustomResource;
     use schemars::{self, JsonSchema};
     use serde::{Deserialize, Serialize};
 
     use crate::{
-        commons::rbac::{build_rbac_resources, role_binding_name, service_account_name},
+        commons::rbac::{
+            Error, RbacConfig, RbacPolicyBuilder, build_rbac_resources, role_binding_name,
+            service_account_name,
+        },
         kvp::Labels,
+        utils::yaml_from_str_singleton_map,
     };
 
     const CLUSTER_NAME: &str = "simple-cluster";
@@ -171,4 +480,161 @@ mod tests {
             "role_ref does not match"
         );
     }
+
+    #[test]
+    fn build_policy() {
+        let cluster = build_test_resource();
+        let config: RbacConfig = yaml_from_str_singleton_map(
+            "
+            extraRules:
+              - apiGroups: [\"\"]
+                resources: [secrets]
+                verbs: [get]
+            extraClusterRoles:
+              - view
+            ",
+        )
+        .unwrap();
+
+        let rbac = RbacPolicyBuilder::new(&cluster, RESOURCE_NAME, Labels::new())
+            .grant::<ConfigMap>(&["get", "watch"], &["simple-cluster-config"])
+            .grant::<Lease>(&["get", "update"], &[])
+            // Duplicate rules are ignored.
+            .grant::<Lease>(&["get", "update"], &[])
+            .allow_extra_cluster_roles(["view"])
+            .add_config(&config)
+            .build()
+            .unwrap();
+
+        let role = rbac
+            .role
+            .expect("the Role is built because rules were added");
+        assert_eq!(role.metadata.name.as_deref(), Some("simple-cluster-role"));
+        assert_eq!(
+            role.rules,
+            Some(vec![
+                PolicyRule {
+                    api_groups: Some(vec![String::new()]),
+                    resources: Some(vec!["configmaps".to_owned()]),
+                    resource_names: Some(vec!["simple-cluster-config".to_owned()]),
+                    verbs: vec!["get".to_owned(), "watch".to_owned()],
+                    ..PolicyRule::default()
+                },
+                PolicyRule {
+                    api_groups: Some(vec!["coordination.k8s.io".to_owned()]),
+                    resources: Some(vec!["leases".to_owned()]),
+                    verbs: vec!["get".to_owned(), "update".to_owned()],
+                    ..PolicyRule::default()
+                },
+                PolicyRule {
+                    api_groups: Some(vec![String::new()]),
+                    resources: Some(vec!["secrets".to_owned()]),
+                    verbs: vec!["get".to_owned()],
+                    ..PolicyRule::default()
+                },
+            ])
+        );
+
+        let role_bindings = rbac
+            .role_bindings
+            .iter()
+            .map(|role_binding| {
+                (
+                    role_binding.metadata.name.clone().unwrap(),
+                    role_binding.role_ref.clone(),
+                )
+            })
+            .collect::<Vec<_>>();
+        let role_ref = |kind: &str, name: &str| RoleRef {
+            api_group: Some("rbac.authorization.k8s.io".to_owned()),
+            kind: kind.to_owned(),
+            name: name.to_owned(),
+        };
+        assert_eq!(
+            role_bindings,
+            [
+                (
+                    "simple-cluster-rolebinding".to_owned(),
+                    role_ref("ClusterRole", "test-resource-clusterrole")
+                ),
+                (
+                    "simple-cluster-role-rolebinding".to_owned(),
+                    role_ref("Role", "simple-cluster-role")
+                ),
+                (
+                    "simple-cluster-view-rolebinding".to_owned(),
+                    role_ref("ClusterRole", "view")
+                ),
+            ]
+        );
+        for role_binding in &rbac.role_bindings {
+            let subjects = role_binding.subjects.as_deref().unwrap_or_default();
+            assert_eq!(subjects.len(), 1);
+            assert_eq!(subjects[0].name, "simple-cluster-serviceaccount");
+        }
+    }
+
+    #[test]
+    fn build_policy_with_disallowed_cluster_role() {
+        let cluster = build_test_resource();
+        let config = RbacConfig {
+            extra_cluster_roles: vec!["admin".to_owned()],
+            ..RbacConfig::default()
+        };
+
+        let error = RbacPolicyBuilder::new(&cluster, RESOURCE_NAME, Labels::new())
+            .allow_extra_cluster_roles(["view"])
+            // ClusterRoles added by the operator itself need not be allowed.
+            .add_cluster_role("edit")
+            .add_config(&config)
+            .build()
+            .unwrap_err();
+        assert_eq!(
+            error,
+            Error::ExtraClusterRoleNotAllowed {
+                name: "admin".to_owned(),
+                allowed: vec!["view".to_owned()]
+            }
+        );
+    }
+
+    #[test]
+    fn build_policy_without_rules() {
+        let cluster = build_test_resource();
+
+        let rbac = RbacPolicyBuilder::new(&cluster, RESOURCE_NAME, Labels::new())
+            .without_product_cluster_role()
+            .build()
+            .unwrap();
+
+        assert_eq!(rbac.role, None);
+        assert!(rbac.role_bindings.is_empty());
+    }
+
+    #[test]
+    fn build_invalid_policy() {
+        let cluster = build_test_resource();
+
+        let error = RbacPolicyBuilder::new(&cluster, RESOURCE_NAME, Labels::new())
+            .grant::<ConfigMap>(&["create", "get"], &["simple-cluster-config"])
+            .build()
+            .unwrap_err();
+        assert_eq!(
+            error,
+            Error::CreateRestrictedToResourceNames {
+                resources: vec!["configmaps".to_owned()]
+            }
+        );
+
+        let error = RbacPolicyBuilder::new(&cluster, RESOURCE_NAME, Labels::new())
+            .grant::<ConfigMap>(&[], &[])
+            .build()
+            .unwrap_err();
+        assert_eq!(
+            error,
+            Error::RuleWithoutVerbs {
+                resources: vec!["configmaps".to_owned()]
+            }
+        );
+    }
 }
@@ -0,0 +1,155 @@
+//! Scheduling settings of Pods which can be configured at role and role group level.
+use k8s_openapi::api::core::v1::{PodSchedulingGate, Toleration};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use stackable_shared::time::Duration;
+
+use crate::{config::merge::Merge, validation};
+
+#[derive(Debug, Snafu)]
+pub enum Error {
+    #[snafu(display("invalid priorityClassName {name:?}"))]
+    InvalidPriorityClassName {
+        source: validation::Errors,
+        name: String,
+    },
+
+    #[snafu(display("invalid runtimeClassName {name:?}"))]
+    InvalidRuntimeClassName {
+        source: validation::Errors,
+        name: String,
+    },
+}
+
+// These settings control how the Pods are scheduled and run on the nodes. Settings on role group
+// level take precedence over the ones on role level.
+//
+// No docs needed, as this struct is flattened into the `CommonConfiguration`.
+#[derive(Clone, Debug, Default, Deserialize, JsonSchema, Merge, PartialEq, Serialize)]
+#[merge(path_overrides(merge = "crate::config::merge"))]
+#[serde(rename_all = "camelCase")]
+pub struct SchedulingConfig {
+    /// The name of the PriorityClass of the Pods, which determines which Pods are preempted first
+    /// if the cluster runs out of resources, see the
+    /// [Kubernetes docs](https://kubernetes.io/docs/concepts/scheduling-eviction/pod-priority-preemption).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority_class_name: Option<String>,
+
+    /// Same as the `spec.tolerations` field on the Pod, see the
+    /// [Kubernetes docs](https://kubernetes.io/docs/concepts/scheduling-eviction/taint-and-toleration).
+    ///
+    /// The tolerations on role group level replace the ones on role level.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tolerations: Option<Vec<Toleration>>,
+
+    /// Same as the `spec.schedulingGates` field on the Pod, see the
+    /// [Kubernetes docs](https://kubernetes.io/docs/concepts/scheduling-eviction/pod-scheduling-readiness).
+    /// The Pods are only scheduled after all gates were removed, e.g. by an external controller.
+    ///
+    /// The scheduling gates on role group level replace the ones on role level.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scheduling_gates: Option<Vec<PodSchedulingGate>>,
+
+    /// The name of the RuntimeClass which runs the containers of the Pods, e.g. to use a
+    /// sandboxed container runtime, see the
+    /// [Kubernetes docs](https://kubernetes.io/docs/concepts/containers/runtime-class).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub runtime_class_name: Option<String>,
+
+    /// The time the Pods get to shut down gracefully before they are killed, e.g. `30s` or `5m`.
+    ///
+    /// Products may shut down gracefully within a separately configured timeout, which should be
+    /// shorter than this.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub termination_grace_period: Option<Duration>,
+}
+
+impl SchedulingConfig {
+    /// Validates the names of the PriorityClass and the RuntimeClass.
+    ///
+    /// The termination grace period is validated when it is applied with
+    /// [`PodBuilder::scheduling`](crate::builder::pod::PodBuilder::scheduling).
+    pub fn validate(&self) -> Result<(), Error> {
+        if let Some(name) = &self.priority_class_name {
+            validation::is_lowercase_rfc_1123_subdomain(name)
+                .context(InvalidPriorityClassNameSnafu { name })?;
+        }
+        if let Some(name) = &self.runtime_class_name {
+            validation::is_lowercase_rfc_1123_subdomain(name)
+                .context(InvalidRuntimeClassNameSnafu { name })?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::merge::merge;
+
+    #[test]
+    fn merge_role_group_over_role() {
+        let role: SchedulingConfig = serde_yaml::from_str(
+            "
+            priorityClassName: high-priority
+            tolerations:
+              - key: dedicated
+                operator: Equal
+                value: kafka
+                effect: NoSchedule
+            terminationGracePeriod: 5m
+            ",
+        )
+        .unwrap();
+        let role_group: SchedulingConfig = serde_yaml::from_str(
+            "
+            tolerations:
+              - key: spot
+                operator: Exists
+            schedulingGates:
+              - name: example.com/quota
+            ",
+        )
+        .unwrap();
+
+        let merged = merge(role_group, &role);
+
+        assert_eq!(
+            merged,
+            SchedulingConfig {
+                priority_class_name: Some("high-priority".to_owned()),
+                tolerations: Some(vec![Toleration {
+                    key: Some("spot".to_owned()),
+                    operator: Some("Exists".to_owned()),
+                    ..Toleration::default()
+                }]),
+                scheduling_gates: Some(vec![PodSchedulingGate {
+                    name: "example.com/quota".to_owned()
+                }]),
+                runtime_class_name: None,
+                termination_grace_period: Some(Duration::from_minutes_unchecked(5)),
+            }
+        );
+        merged.validate().unwrap();
+    }
+
+    #[test]
+    fn validate_names() {
+        let invalid_configs = [
+            SchedulingConfig {
+                priority_class_name: Some("High_Priority".to_owned()),
+                ..SchedulingConfig::default()
+            },
+            SchedulingConfig {
+                runtime_class_name: Some("gVisor".to_owned()),
+                ..SchedulingConfig::default()
+            },
+        ];
+
+        for config in invalid_configs {
+            assert!(config.validate().is_err(), "{config:?} must be invalid");
+        }
+    }
+}
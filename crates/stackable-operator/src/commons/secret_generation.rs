@@ -0,0 +1,1081 @@
+//! Generators for Secrets which contain generated material, such as passwords, key pairs,
+//! certificates and Java keystores.
+//!
+//! The generated material is written into a Secret by [`create_or_renew_generated_secret`],
+//! which is owned by the stacklet. Because the material is random, the Secret is only updated if
+//! the material must be renewed, e.g. because a certificate is about to expire.
+//!
+//! Random bytes without any rules can still be created with
+//! [`create_random_secret_if_not_exists`](crate::commons::random_secret_creation::create_random_secret_if_not_exists).
+use std::{collections::BTreeMap, fmt};
+
+use k8s_openapi::{ByteString, api::core::v1::Secret, jiff::Timestamp};
+use kube::{Resource, ResourceExt};
+use p12_keystore::{KeyStore, KeyStoreEntry, PrivateKeyChain};
+use rand::{RngExt, rngs::StdRng, seq::SliceRandom};
+use sha2::{Digest, Sha256};
+use snafu::{OptionExt, ResultExt, Snafu, ensure};
+use stackable_certs::{
+    ca::{CertificateAuthority, TLS_SECRET_TYPE},
+    keys::{CertificateKeypair, ecdsa, rsa},
+};
+use stackable_shared::time::Duration;
+use x509_cert::{
+    Certificate,
+    der::{DecodePem, Encode, EncodePem, pem::LineEnding},
+};
+use zeroize::Zeroizing;
+
+use crate::{
+    builder::meta::ObjectMetaBuilder,
+    client::Client,
+    commons::random_secret_creation::{ROTATE_ANNOTATION, ROTATED_AT_ANNOTATION},
+};
+
+/// The key of the PEM-encoded PKCS8 private key generated by [`KeyPairGenerator`]
+pub const PRIVATE_KEY_KEY: &str = "private.pem";
+
+/// The key of the PEM-encoded public key generated by [`KeyPairGenerator`]
+pub const PUBLIC_KEY_KEY: &str = "public.pem";
+
+/// The key of the PEM-encoded CA certificate generated by [`CertificateGenerator`]
+pub const CA_CERTIFICATE_KEY: &str = "ca.crt";
+
+/// The key of the PEM-encoded certificate generated by [`CertificateGenerator`]
+pub const CERTIFICATE_KEY: &str = "tls.crt";
+
+/// The key of the PEM-encoded PKCS8 private key of the certificate generated by
+/// [`CertificateGenerator`]
+pub const CERTIFICATE_PRIVATE_KEY_KEY: &str = "tls.key";
+
+/// The key of the PKCS#12 keystore generated by [`KeyStoreGenerator`]
+pub const KEYSTORE_KEY: &str = "keystore.p12";
+
+/// The key of the PKCS#12 truststore generated by [`KeyStoreGenerator`]
+pub const TRUSTSTORE_KEY: &str = "truststore.p12";
+
+/// Characters which are easily confused with each other when read by humans
+const AMBIGUOUS_CHARACTERS: &str = "0O1lI";
+
+#[derive(Snafu, Debug)]
+pub enum Error {
+    #[snafu(display("object defines no namespace"))]
+    ObjectHasNoNamespace,
+
+    #[snafu(display("failed to retrieve generated secret"))]
+    RetrieveGeneratedSecret { source: crate::client::Error },
+
+    #[snafu(display("failed to create generated secret"))]
+    CreateGeneratedSecret { source: crate::client::Error },
+
+    #[snafu(display("failed to update generated secret"))]
+    UpdateGeneratedSecret { source: crate::client::Error },
+
+    #[snafu(display("object is missing metadata to build owner reference"))]
+    ObjectMissingMetadataForOwnerRef { source: crate::builder::meta::Error },
+
+    #[snafu(display("failed to generate secret contents"))]
+    GenerateSecretContents { source: GenerateError },
+}
+
+#[derive(Snafu, Debug)]
+pub enum GenerateError {
+    #[snafu(display(
+        "the password length {length} is shorter than the {required} required character classes"
+    ))]
+    PasswordTooShort { length: usize, required: usize },
+
+    #[snafu(display("the password generator does not allow any characters"))]
+    PasswordWithoutCharacters,
+
+    #[snafu(display(
+        "the certificate must be renewed {renew_before} before it expires, which is not shorter than its validity {validity}"
+    ))]
+    RenewalNotWithinValidity {
+        renew_before: Duration,
+        validity: Duration,
+    },
+
+    #[snafu(display("failed to generate RSA key"))]
+    GenerateRsaKey { source: rsa::Error },
+
+    #[snafu(display("failed to generate ECDSA key"))]
+    GenerateEcdsaKey { source: ecdsa::Error },
+
+    #[snafu(display("failed to create certificate authority"))]
+    CreateCertificateAuthority { source: stackable_certs::ca::Error },
+
+    #[snafu(display("failed to create certificate"))]
+    CreateCertificate { source: stackable_certs::ca::Error },
+
+    #[snafu(display("failed to encode key"))]
+    EncodeKey {
+        source: stackable_certs::keys::EncodeError,
+    },
+
+    #[snafu(display("failed to encode certificate"))]
+    EncodeCertificate { source: x509_cert::der::Error },
+
+    #[snafu(display("failed to add certificate to keystore"))]
+    AddCertificateToKeyStore { source: p12_keystore::error::Error },
+
+    #[snafu(display("failed to write PKCS#12 keystore"))]
+    WriteKeyStore { source: p12_keystore::error::Error },
+}
+
+/// Generates the contents of a Secret
+///
+/// The generators in this module can be combined with [`create_or_renew_generated_secret`] to
+/// create Secrets with generated material.
+pub trait SecretGenerator {
+    /// The type of the generated Secret, e.g. `kubernetes.io/tls`
+    ///
+    /// Kubernetes defaults to `Opaque` if no type is given.
+    fn secret_type(&self) -> Option<&str> {
+        None
+    }
+
+    /// Generates the data of the Secret
+    fn generate(&self) -> Result<BTreeMap<String, ByteString>, GenerateError>;
+
+    /// Returns the time when the material in the given Secret data must be renewed
+    ///
+    /// Material which does not expire is never renewed, which is the default.
+    fn renew_at(&self, _data: &BTreeMap<String, ByteString>) -> Option<Timestamp> {
+        None
+    }
+}
+
+/// Generates a password which is safe to be read and typed by humans
+///
+/// The password consists of the enabled character classes and contains at least one character of
+/// every class.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PasswordGenerator {
+    /// The key of the password in the Secret
+    pub key: String,
+
+    /// The number of characters; defaults to `32`.
+    pub length: usize,
+
+    /// The classes of characters which the password contains; defaults to lowercase letters,
+    /// uppercase letters and digits.
+    ///
+    /// Symbols are not contained by default, so that the password does not need to be escaped in
+    /// configuration files.
+    pub character_classes: Vec<CharacterClass>,
+
+    /// Whether characters which are easily confused, like `0` and `O` or `1`, `l` and `I`, are
+    /// left out; defaults to `true`.
+    pub exclude_ambiguous: bool,
+}
+
+impl PasswordGenerator {
+    /// Creates a generator for alphanumeric passwords with 32 characters, which are stored in the
+    /// given key.
+    pub fn new(key: impl Into<String>) -> Self {
+        Self {
+            key: key.into(),
+            length: 32,
+            character_classes: vec![
+                CharacterClass::Lowercase,
+                CharacterClass::Uppercase,
+                CharacterClass::Digits,
+            ],
+            exclude_ambiguous: true,
+        }
+    }
+
+    /// Generates a password according to the rules of this generator.
+    pub fn generate_password(&self) -> Result<Zeroizing<String>, GenerateError> {
+        let character_classes = self.character_classes();
+        ensure!(
+            !character_classes.is_empty(),
+            PasswordWithoutCharactersSnafu
+        );
+        ensure!(
+            self.length >= character_classes.len(),
+            PasswordTooShortSnafu {
+                length: self.length,
+                required: character_classes.len(),
+            }
+        );
+
+        // As `make_rng` takes its entropy from the OS rng, we are using `getrandom`, which should be
+        // cryptographically secure
+        let mut rng: StdRng = rand::make_rng();
+        let all_characters = character_classes.concat();
+
+        // Pick one character of every class, so that the password fulfills the rules, and fill the
+        // remaining positions with characters of all classes.
+        let mut password = Zeroizing::new(Vec::with_capacity(self.length));
+        for class in &character_classes {
+            password.push(class[rng.random_range(..class.len())]);
+        }
+        while password.len() < self.length {
+            password.push(all_characters[rng.random_range(..all_characters.len())]);
+        }
+        password.shuffle(&mut rng);
+
+        Ok(Zeroizing::new(password.iter().collect()))
+    }
+
+    /// Returns the allowed characters of every class, leaving out empty classes.
+    fn character_classes(&self) -> Vec<Vec<char>> {
+        self.character_classes
+            .iter()
+            .map(|class| {
+                let mut characters = class
+                    .characters()
+                    .into_iter()
+                    .filter(|character| {
+                        !(self.exclude_ambiguous && AMBIGUOUS_CHARACTERS.contains(*character))
+                    })
+                    .collect::<Vec<_>>();
+                characters.sort_unstable();
+                characters.dedup();
+                characters
+            })
+            .filter(|characters| !characters.is_empty())
+            .collect()
+    }
+}
+
+/// A class of characters of which a generated password contains at least one
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum CharacterClass {
+    /// The lowercase letters `a` to `z`
+    Lowercase,
+
+    /// The uppercase letters `A` to `Z`
+    Uppercase,
+
+    /// The digits `0` to `9`
+    Digits,
+
+    /// The given special characters, e.g. `-_.`
+    Symbols(String),
+}
+
+impl CharacterClass {
+    fn characters(&self) -> Vec<char> {
+        match self {
+            Self::Lowercase => ('a'..='z').collect(),
+            Self::Uppercase => ('A'..='Z').collect(),
+            Self::Digits => ('0'..='9').collect(),
+            Self::Symbols(symbols) => symbols.chars().collect(),
+        }
+    }
+}
+
+impl SecretGenerator for PasswordGenerator {
+    fn generate(&self) -> Result<BTreeMap<String, ByteString>, GenerateError> {
+        let password = self.generate_password()?;
+
+        Ok(BTreeMap::from([(
+            self.key.clone(),
+            ByteString(password.as_bytes().to_vec()),
+        )]))
+    }
+}
+
+/// The algorithm of generated keys
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum KeyAlgorithm {
+    /// RSA keys with 4096 bits
+    Rsa,
+
+    /// ECDSA keys on the NIST P-256 curve
+    #[default]
+    Ecdsa,
+}
+
+/// Generates a key pair
+///
+/// The PEM-encoded private and public keys are stored in the keys [`PRIVATE_KEY_KEY`] and
+/// [`PUBLIC_KEY_KEY`].
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct KeyPairGenerator {
+    /// The algorithm of the key pair; defaults to `ecdsa`.
+    pub algorithm: KeyAlgorithm,
+}
+
+impl SecretGenerator for KeyPairGenerator {
+    fn generate(&self) -> Result<BTreeMap<String, ByteString>, GenerateError> {
+        let (private_key, public_key) = match self.algorithm {
+            KeyAlgorithm::Rsa => {
+                let key = rsa::SigningKey::new().context(GenerateRsaKeySnafu)?;
+                (key.signing_key_pkcs8_pem(), key.verifying_key_pem())
+            }
+            KeyAlgorithm::Ecdsa => {
+                let key = ecdsa::SigningKey::new().context(GenerateEcdsaKeySnafu)?;
+                (key.signing_key_pkcs8_pem(), key.verifying_key_pem())
+            }
+        };
+
+        Ok(BTreeMap::from([
+            (
+                PRIVATE_KEY_KEY.to_owned(),
+                ByteString(private_key.context(EncodeKeySnafu)?.as_bytes().to_vec()),
+            ),
+            (
+                PUBLIC_KEY_KEY.to_owned(),
+                ByteString(public_key.context(EncodeKeySnafu)?.into_bytes()),
+            ),
+        ]))
+    }
+}
+
+/// Generates a certificate for endpoints which are only used within the cluster
+///
+/// The certificate is signed by a CA, which is generated for this certificate only and whose
+/// private key is thrown away. Clients must trust the CA certificate, which is stored in the key
+/// [`CA_CERTIFICATE_KEY`] next to the certificate and its private key in [`CERTIFICATE_KEY`] and
+/// [`CERTIFICATE_PRIVATE_KEY_KEY`]. The Secret has the type `kubernetes.io/tls`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CertificateGenerator {
+    /// The name which is part of the certificate subject `{name} Certificate for {scope}`
+    pub name: String,
+
+    /// The scope which is part of the certificate subject `{name} Certificate for {scope}`
+    pub scope: String,
+
+    /// The DNS names under which the endpoint is reachable
+    pub subject_alternative_dns_names: Vec<String>,
+
+    /// The algorithm of the keys; defaults to `ecdsa`.
+    pub algorithm: KeyAlgorithm,
+
+    /// How long the certificate and the CA are valid; defaults to `365d`.
+    pub validity: Duration,
+
+    /// How long before the end of the validity the certificate and the CA are renewed; defaults to
+    /// `30d`. It must be shorter than the validity.
+    pub renew_before: Duration,
+}
+
+impl CertificateGenerator {
+    /// Creates a generator for a certificate with an ECDSA key, which is valid for one year and
+    /// renewed 30 days before it expires.
+    pub fn new(name: impl Into<String>, scope: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            scope: scope.into(),
+            subject_alternative_dns_names: Vec::new(),
+            algorithm: KeyAlgorithm::default(),
+            validity: Duration::from_days_unchecked(365),
+            renew_before: Duration::from_days_unchecked(30),
+        }
+    }
+
+    /// Generates the CA certificate, the certificate and the DER-encoded PKCS8 private key of the
+    /// certificate.
+    fn generate_certificate(&self) -> Result<GeneratedCertificate, GenerateError> {
+        ensure!(
+            self.renew_before < self.validity,
+            RenewalNotWithinValiditySnafu {
+                renew_before: self.renew_before,
+                validity: self.validity,
+            }
+        );
+
+        let serial_number = rand::random::<u64>();
+        let subject_alternative_dns_names = self
+            .subject_alternative_dns_names
+            .iter()
+            .map(String::as_str);
+
+        match self.algorithm {
+            KeyAlgorithm::Rsa => {
+                let ca_key = rsa::SigningKey::new().context(GenerateRsaKeySnafu)?;
+                let mut ca = CertificateAuthority::new_with(ca_key, serial_number, self.validity)
+                    .context(CreateCertificateAuthoritySnafu)?;
+                let certificate_pair = ca
+                    .generate_rsa_leaf_certificate(
+                        &self.name,
+                        &self.scope,
+                        subject_alternative_dns_names,
+                        self.validity,
+                    )
+                    .context(CreateCertificateSnafu)?;
+
+                Ok(GeneratedCertificate {
+                    ca_certificate: ca.ca_cert().clone(),
+                    certificate: certificate_pair.certificate().clone(),
+                    private_key: certificate_pair
+                        .key_pair()
+                        .signing_key_pkcs8_der()
+                        .context(EncodeKeySnafu)?,
+                })
+            }
+            KeyAlgorithm::Ecdsa => {
+                let ca_key = ecdsa::SigningKey::new().context(GenerateEcdsaKeySnafu)?;
+                let mut ca = CertificateAuthority::new_with(ca_key, serial_number, self.validity)
+                    .context(CreateCertificateAuthoritySnafu)?;
+                let certificate_pair = ca
+                    .generate_ecdsa_leaf_certificate(
+                        &self.name,
+                        &self.scope,
+                        subject_alternative_dns_names,
+                        self.validity,
+                    )
+                    .context(CreateCertificateSnafu)?;
+
+                Ok(GeneratedCertificate {
+                    ca_certificate: ca.ca_cert().clone(),
+                    certificate: certificate_pair.certificate().clone(),
+                    private_key: certificate_pair
+                        .key_pair()
+                        .signing_key_pkcs8_der()
+                        .context(EncodeKeySnafu)?,
+                })
+            }
+        }
+    }
+}
+
+impl SecretGenerator for CertificateGenerator {
+    fn secret_type(&self) -> Option<&str> {
+        Some(TLS_SECRET_TYPE)
+    }
+
+    fn generate(&self) -> Result<BTreeMap<String, ByteString>, GenerateError> {
+        self.generate_certificate()?.to_pem_data()
+    }
+
+    /// Returns the time which lies [`Self::renew_before`] before the end of the validity of the
+    /// stored certificate or CA, whichever expires first.
+    ///
+    /// Certificates which are missing or cannot be parsed are renewed immediately.
+    fn renew_at(&self, data: &BTreeMap<String, ByteString>) -> Option<Timestamp> {
+        let not_after = [CA_CERTIFICATE_KEY, CERTIFICATE_KEY]
+            .into_iter()
+            .map(|key| {
+                let ByteString(pem) = data.get(key)?;
+                let certificate = Certificate::from_pem(pem).ok()?;
+                Timestamp::try_from(
+                    certificate
+                        .tbs_certificate
+                        .validity
+                        .not_after
+                        .to_system_time(),
+                )
+                .ok()
+            })
+            .collect::<Option<Vec<_>>>()
+            .and_then(|not_after| not_after.into_iter().min())
+            .unwrap_or(Timestamp::MIN);
+
+        Some(
+            not_after
+                .checked_sub(std::time::Duration::from(self.renew_before))
+                .unwrap_or(Timestamp::MIN),
+        )
+    }
+}
+
+/// Generates a certificate like [`CertificateGenerator`] and additionally stores it in Java
+/// keystores
+///
+/// The PKCS#12 keystore in the key [`KEYSTORE_KEY`] contains the certificate with its private key
+/// and the PKCS#12 truststore in the key [`TRUSTSTORE_KEY`] contains the CA certificate. Both are
+/// protected with the given password, which must be passed to the JVM, e.g. with the system
+/// properties `javax.net.ssl.keyStorePassword` and `javax.net.ssl.trustStorePassword`.
+///
+/// The keystores are renewed together with the certificate.
+#[derive(Clone, Eq, PartialEq)]
+pub struct KeyStoreGenerator {
+    /// The generated certificate
+    pub certificate: CertificateGenerator,
+
+    /// The password of the keystore and the truststore
+    pub password: String,
+}
+
+// The password is left out, so that it does not end up in logs.
+impl fmt::Debug for KeyStoreGenerator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeyStoreGenerator")
+            .field("certificate", &self.certificate)
+            .finish_non_exhaustive()
+    }
+}
+
+impl SecretGenerator for KeyStoreGenerator {
+    fn secret_type(&self) -> Option<&str> {
+        Some(TLS_SECRET_TYPE)
+    }
+
+    fn generate(&self) -> Result<BTreeMap<String, ByteString>, GenerateError> {
+        let generated_certificate = self.certificate.generate_certificate()?;
+        let ca_certificate = to_keystore_certificate(&generated_certificate.ca_certificate)?;
+        let certificate = to_keystore_certificate(&generated_certificate.certificate)?;
+        let alias = &self.certificate.name;
+
+        let mut truststore = KeyStore::new();
+        truststore.add_entry(alias, KeyStoreEntry::Certificate(ca_certificate.clone()));
+
+        let mut keystore = KeyStore::new();
+        keystore.add_entry(
+            alias,
+            KeyStoreEntry::PrivateKeyChain(PrivateKeyChain::new(
+                &*generated_certificate.private_key,
+                Sha256::digest(certificate.as_der()),
+                [certificate, ca_certificate],
+            )),
+        );
+
+        let mut data = generated_certificate.to_pem_data()?;
+        for (key, store) in [(KEYSTORE_KEY, keystore), (TRUSTSTORE_KEY, truststore)] {
+            let store = store
+                .writer(&self.password)
+                .write()
+                .context(WriteKeyStoreSnafu)?;
+            data.insert(key.to_owned(), ByteString(store));
+        }
+
+        Ok(data)
+    }
+
+    fn renew_at(&self, data: &BTreeMap<String, ByteString>) -> Option<Timestamp> {
+        self.certificate.renew_at(data)
+    }
+}
+
+/// The material generated by [`CertificateGenerator`]
+struct GeneratedCertificate {
+    ca_certificate: Certificate,
+    certificate: Certificate,
+    private_key: Zeroizing<Vec<u8>>,
+}
+
+impl GeneratedCertificate {
+    /// Returns the Secret data with the PEM-encoded certificates and private key.
+    fn to_pem_data(&self) -> Result<BTreeMap<String, ByteString>, GenerateError> {
+        let to_pem = |certificate: &Certificate| {
+            certificate
+                .to_pem(LineEnding::LF)
+                .context(EncodeCertificateSnafu)
+        };
+        let private_key =
+            x509_cert::der::pem::encode_string("PRIVATE KEY", LineEnding::LF, &self.private_key)
+                .map(Zeroizing::new)
+                .map_err(x509_cert::der::Error::from)
+                .context(EncodeCertificateSnafu)?;
+
+        Ok(BTreeMap::from([
+            (
+                CA_CERTIFICATE_KEY.to_owned(),
+                ByteString(to_pem(&self.ca_certificate)?.into_bytes()),
+            ),
+            (
+                CERTIFICATE_KEY.to_owned(),
+                ByteString(to_pem(&self.certificate)?.into_bytes()),
+            ),
+            (
+                CERTIFICATE_PRIVATE_KEY_KEY.to_owned(),
+                ByteString(private_key.as_bytes().to_vec()),
+            ),
+        ]))
+    }
+}
+
+fn to_keystore_certificate(
+    certificate: &Certificate,
+) -> Result<p12_keystore::Certificate, GenerateError> {
+    let der = certificate.to_der().context(EncodeCertificateSnafu)?;
+    p12_keystore::Certificate::from_der(&der).context(AddCertificateToKeyStoreSnafu)
+}
+
+/// A generated Secret returned by [`create_or_renew_generated_secret`]
+#[derive(Clone, Debug, PartialEq)]
+pub struct GeneratedSecret {
+    /// The created, renewed or unchanged Secret
+    pub secret: Secret,
+
+    /// Whether the material was renewed by this call
+    pub renewed: bool,
+
+    /// The duration after which the Secret must be reconciled again to renew the material, if it
+    /// expires
+    pub requeue_after: Option<std::time::Duration>,
+}
+
+/// Creates a Secret with the material of the given generator if it doesn't already exist and
+/// renews the material when it is about to expire.
+///
+/// The Secret is owned by the stacklet, so that it is deleted together with the stacklet. If the
+/// Secret already exists, then it is only updated if the material must be renewed according to
+/// [`SecretGenerator::renew_at`], e.g. shortly before a certificate expires, or if the annotation
+/// [`ROTATE_ANNOTATION`] is set to `true` on the Secret. Otherwise new material would be generated
+/// on every reconcile, which would restart the Pods which use it. The time of the renewal is
+/// recorded in the annotation [`ROTATED_AT_ANNOTATION`].
+///
+/// The controller should requeue the stacklet after [`GeneratedSecret::requeue_after`] at the
+/// latest, so that the material is renewed in time.
+pub async fn create_or_renew_generated_secret<R>(
+    secret_name: &str,
+    generator: &impl SecretGenerator,
+    stacklet: &R,
+    client: &Client,
+) -> Result<GeneratedSecret, Error>
+where
+    R: Resource<DynamicType = ()>,
+{
+    let secret_namespace = stacklet.namespace().context(ObjectHasNoNamespaceSnafu)?;
+    let now = Timestamp::now();
+
+    if let Some(existing_secret) = client
+        .get_opt::<Secret>(secret_name, &secret_namespace)
+        .await
+        .context(RetrieveGeneratedSecretSnafu)?
+    {
+        let generated_secret = renew_generated_secret(existing_secret.clone(), generator, now)
+            .context(GenerateSecretContentsSnafu)?;
+
+        if generated_secret.secret == existing_secret {
+            tracing::debug!(
+                k8s.secret.name = secret_name,
+                k8s.secret.namespace = secret_namespace,
+                "Existing generated Secret detected, nothing to do"
+            );
+            return Ok(generated_secret);
+        }
+
+        tracing::info!(
+            k8s.secret.name = secret_name,
+            k8s.secret.namespace = secret_namespace,
+            "Renewing generated Secret"
+        );
+        let secret = client
+            .update(&generated_secret.secret)
+            .await
+            .context(UpdateGeneratedSecretSnafu)?;
+
+        return Ok(GeneratedSecret {
+            secret,
+            ..generated_secret
+        });
+    }
+
+    tracing::info!(
+        k8s.secret.name = secret_name,
+        k8s.secret.namespace = secret_namespace,
+        "Generated Secret missing, creating it"
+    );
+    let data = generator.generate().context(GenerateSecretContentsSnafu)?;
+    let requeue_after = requeue_after(generator, &data, now);
+    let secret = Secret {
+        metadata: ObjectMetaBuilder::new()
+            .name(secret_name)
+            .namespace_opt(stacklet.namespace())
+            .ownerreference_from_resource(stacklet, None, Some(true))
+            .context(ObjectMissingMetadataForOwnerRefSnafu)?
+            .build(),
+        type_: generator.secret_type().map(str::to_owned),
+        data: Some(data),
+        ..Secret::default()
+    };
+
+    let secret = client
+        .create(&secret)
+        .await
+        .context(CreateGeneratedSecretSnafu)?;
+
+    Ok(GeneratedSecret {
+        secret,
+        renewed: false,
+        requeue_after,
+    })
+}
+
+/// Regenerates the material of the given Secret if it must be renewed or if a renewal was
+/// requested.
+fn renew_generated_secret(
+    mut secret: Secret,
+    generator: &impl SecretGenerator,
+    now: Timestamp,
+) -> Result<GeneratedSecret, GenerateError> {
+    let renewal_requested = secret
+        .annotations()
+        .get(ROTATE_ANNOTATION)
+        .is_some_and(|value| value.eq_ignore_ascii_case("true"));
+    let renewal_due = generator
+        .renew_at(secret.data.as_ref().unwrap_or(&BTreeMap::new()))
+        .is_some_and(|renew_at| renew_at <= now);
+
+    let renewed = renewal_requested || renewal_due;
+    if renewed {
+        secret.data = Some(generator.generate()?);
+
+        let annotations = secret
+            .metadata
+            .annotations
+            .get_or_insert_with(BTreeMap::new);
+        annotations.remove(ROTATE_ANNOTATION);
+        annotations.insert(ROTATED_AT_ANNOTATION.to_owned(), now.to_string());
+    }
+
+    let requeue_after = requeue_after(
+        generator,
+        secret.data.as_ref().unwrap_or(&BTreeMap::new()),
+        now,
+    );
+
+    Ok(GeneratedSecret {
+        secret,
+        renewed,
+        requeue_after,
+    })
+}
+
+/// Returns the duration until the material in the given Secret data must be renewed.
+fn requeue_after(
+    generator: &impl SecretGenerator,
+    data: &BTreeMap<String, ByteString>,
+    now: Timestamp,
+) -> Option<std::time::Duration> {
+    generator.renew_at(data).map(|renew_at| {
+        std::time::Duration::try_from(renew_at.duration_since(now)).unwrap_or_default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use k8s_openapi::api::core::v1::ConfigMap;
+    use kube::api::ObjectMeta;
+    use rstest::rstest;
+    use x509_cert::{
+        der::{DecodePem, asn1::Ia5String},
+        ext::pkix::{SubjectAltName, name::GeneralName},
+    };
+
+    use super::*;
+    use crate::client::fake::{DEFAULT_NAMESPACE, FakeApiServer};
+
+    fn value<'a>(data: &'a BTreeMap<String, ByteString>, key: &str) -> &'a [u8] {
+        &data.get(key).unwrap().0
+    }
+
+    #[test]
+    fn password_contains_every_character_class() {
+        let generator = PasswordGenerator {
+            length: 4,
+            character_classes: vec![
+                CharacterClass::Lowercase,
+                CharacterClass::Uppercase,
+                CharacterClass::Digits,
+                CharacterClass::Symbols("-_".to_owned()),
+            ],
+            ..PasswordGenerator::new("password")
+        };
+
+        for _ in 0..100 {
+            let password = generator.generate_password().unwrap();
+
+            assert_eq!(password.len(), 4);
+            assert!(password.chars().any(|c| c.is_ascii_lowercase()));
+            assert!(password.chars().any(|c| c.is_ascii_uppercase()));
+            assert!(password.chars().any(|c| c.is_ascii_digit()));
+            assert!(password.chars().any(|c| "-_".contains(c)));
+        }
+    }
+
+    #[test]
+    fn password_without_ambiguous_characters() {
+        let generator = PasswordGenerator {
+            length: 1000,
+            ..PasswordGenerator::new("password")
+        };
+
+        let password = generator.generate_password().unwrap();
+
+        assert_eq!(password.len(), 1000);
+        assert!(password.chars().all(|c| c.is_ascii_alphanumeric()));
+        assert!(!password.chars().any(|c| AMBIGUOUS_CHARACTERS.contains(c)));
+    }
+
+    #[test]
+    fn password_rules_which_cannot_be_fulfilled() {
+        let too_short = PasswordGenerator {
+            length: 2,
+            ..PasswordGenerator::new("password")
+        };
+        assert!(matches!(
+            too_short.generate_password(),
+            Err(GenerateError::PasswordTooShort {
+                length: 2,
+                required: 3
+            })
+        ));
+
+        let only_ambiguous = PasswordGenerator {
+            character_classes: vec![CharacterClass::Symbols("0O".to_owned())],
+            ..PasswordGenerator::new("password")
+        };
+        assert!(matches!(
+            only_ambiguous.generate_password(),
+            Err(GenerateError::PasswordWithoutCharacters)
+        ));
+    }
+
+    #[test]
+    fn key_pair() {
+        let data = KeyPairGenerator::default().generate().unwrap();
+
+        let private_key = String::from_utf8(value(&data, PRIVATE_KEY_KEY).to_vec()).unwrap();
+        let key = ecdsa::SigningKey::from_pkcs8_pem(&private_key).unwrap();
+        assert_eq!(
+            value(&data, PUBLIC_KEY_KEY),
+            key.verifying_key_pem().unwrap().as_bytes()
+        );
+    }
+
+    #[test]
+    fn certificate() {
+        let generator = CertificateGenerator {
+            subject_alternative_dns_names: vec!["webhook.default.svc".to_owned()],
+            ..CertificateGenerator::new("Webhook", "default")
+        };
+
+        let data = generator.generate().unwrap();
+
+        assert_eq!(generator.secret_type(), Some("kubernetes.io/tls"));
+        let ca_certificate = Certificate::from_pem(value(&data, CA_CERTIFICATE_KEY)).unwrap();
+        let certificate = Certificate::from_pem(value(&data, CERTIFICATE_KEY)).unwrap();
+        assert_eq!(
+            certificate.tbs_certificate.issuer,
+            ca_certificate.tbs_certificate.subject
+        );
+        assert_eq!(
+            certificate.tbs_certificate.subject.to_string(),
+            "CN=Webhook Certificate for default"
+        );
+        let (_, subject_alternative_names) = certificate
+            .tbs_certificate
+            .get::<SubjectAltName>()
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            subject_alternative_names.0,
+            [GeneralName::DnsName(
+                Ia5String::new("webhook.default.svc").unwrap()
+            )]
+        );
+
+        let private_key =
+            String::from_utf8(value(&data, CERTIFICATE_PRIVATE_KEY_KEY).to_vec()).unwrap();
+        ecdsa::SigningKey::from_pkcs8_pem(&private_key).unwrap();
+    }
+
+    #[test]
+    fn keystore() {
+        let generator = KeyStoreGenerator {
+            certificate: CertificateGenerator::new("Kafka", "default"),
+            password: "changeit".to_owned(),
+        };
+
+        let data = generator.generate().unwrap();
+
+        let ca_certificate = Certificate::from_pem(value(&data, CA_CERTIFICATE_KEY))
+            .unwrap()
+            .to_der()
+            .unwrap();
+        let certificate = Certificate::from_pem(value(&data, CERTIFICATE_KEY))
+            .unwrap()
+            .to_der()
+            .unwrap();
+
+        let truststore = KeyStore::from_pkcs12(value(&data, TRUSTSTORE_KEY), "changeit").unwrap();
+        let Some(KeyStoreEntry::Certificate(trusted_certificate)) = truststore.entry("Kafka")
+        else {
+            panic!("the truststore must contain the CA certificate");
+        };
+        assert_eq!(trusted_certificate.as_der(), ca_certificate);
+
+        let keystore = KeyStore::from_pkcs12(value(&data, KEYSTORE_KEY), "changeit").unwrap();
+        let (alias, private_key_chain) = keystore.private_key_chain().unwrap();
+        assert_eq!(alias, "Kafka");
+        assert_eq!(
+            private_key_chain
+                .chain()
+                .iter()
+                .map(p12_keystore::Certificate::as_der)
+                .collect::<Vec<_>>(),
+            [certificate.as_slice(), ca_certificate.as_slice()]
+        );
+
+        assert!(KeyStore::from_pkcs12(value(&data, KEYSTORE_KEY), "wrong").is_err());
+        assert!(!format!("{generator:?}").contains("changeit"));
+    }
+
+    #[test]
+    fn renewal_must_be_within_validity() {
+        let generator = CertificateGenerator {
+            validity: Duration::from_days_unchecked(30),
+            ..CertificateGenerator::new("Cluster", "default")
+        };
+
+        assert!(matches!(
+            generator.generate(),
+            Err(GenerateError::RenewalNotWithinValidity { .. })
+        ));
+    }
+
+    #[rstest]
+    #[case::valid(100, false)]
+    #[case::near_expiry(340, true)]
+    #[case::expired(400, true)]
+    fn certificate_renewal(#[case] days_from_now: u64, #[case] expected_renewed: bool) {
+        let generator = CertificateGenerator::new("Cluster", "default");
+        let secret = Secret {
+            data: Some(generator.generate().unwrap()),
+            ..Secret::default()
+        };
+        let now = Timestamp::now()
+            .checked_add(std::time::Duration::from_hours(days_from_now * 24))
+            .unwrap();
+
+        let generated_secret = renew_generated_secret(secret.clone(), &generator, now).unwrap();
+
+        assert_eq!(generated_secret.renewed, expected_renewed);
+        assert_eq!(
+            value(
+                generated_secret.secret.data.as_ref().unwrap(),
+                CERTIFICATE_KEY
+            ) == value(secret.data.as_ref().unwrap(), CERTIFICATE_KEY),
+            !expected_renewed
+        );
+        assert_eq!(
+            generated_secret
+                .secret
+                .annotations()
+                .get(ROTATED_AT_ANNOTATION),
+            expected_renewed.then(|| now.to_string()).as_ref()
+        );
+
+        // The certificate is renewed 30 days before it expires after 365 days.
+        let renew_at = generator
+            .renew_at(generated_secret.secret.data.as_ref().unwrap())
+            .unwrap();
+        let expected_renew_at = if expected_renewed {
+            Timestamp::now()
+        } else {
+            now.checked_sub(std::time::Duration::from_hours(days_from_now * 24))
+                .unwrap()
+        }
+        .checked_add(std::time::Duration::from_hours(335 * 24))
+        .unwrap();
+        assert!(
+            renew_at.duration_since(expected_renew_at).abs() < jiff::SignedDuration::from_mins(1)
+        );
+        assert_eq!(
+            generated_secret.requeue_after,
+            Some(std::time::Duration::try_from(renew_at.duration_since(now)).unwrap_or_default())
+        );
+    }
+
+    #[test]
+    fn renewal_of_missing_certificate() {
+        let generator = CertificateGenerator::new("Cluster", "default");
+
+        let generated_secret =
+            renew_generated_secret(Secret::default(), &generator, Timestamp::now()).unwrap();
+
+        assert!(generated_secret.renewed);
+        assert!(generated_secret.requeue_after.is_some());
+    }
+
+    #[test]
+    fn no_renewal_of_passwords() {
+        let generator = PasswordGenerator::new("password");
+        let secret = Secret {
+            data: Some(generator.generate().unwrap()),
+            ..Secret::default()
+        };
+
+        let generated_secret =
+            renew_generated_secret(secret.clone(), &generator, Timestamp::MAX).unwrap();
+
+        assert_eq!(
+            generated_secret,
+            GeneratedSecret {
+                secret,
+                renewed: false,
+                requeue_after: None,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn create_generated_secret_only_once() {
+        let server = FakeApiServer::new();
+        let client = server.client();
+        let stacklet = server.insert(&ConfigMap {
+            metadata: ObjectMeta {
+                name: Some("cluster".to_owned()),
+                namespace: Some(DEFAULT_NAMESPACE.to_owned()),
+                ..ObjectMeta::default()
+            },
+            ..ConfigMap::default()
+        });
+        let generator = CertificateGenerator::new("Cluster", "default");
+
+        let created = create_or_renew_generated_secret("generated", &generator, &stacklet, &client)
+            .await
+            .unwrap();
+        let existing =
+            create_or_renew_generated_secret("generated", &generator, &stacklet, &client)
+                .await
+                .unwrap();
+
+        assert!(!existing.renewed);
+        assert!(existing.requeue_after.is_some());
+        assert_eq!(existing.secret, created.secret);
+        let created_secret = created.secret;
+        assert_eq!(
+            server.objects::<Secret>(),
+            std::slice::from_ref(&created_secret)
+        );
+        assert_eq!(created_secret.type_.as_deref(), Some("kubernetes.io/tls"));
+        assert_eq!(
+            created_secret.owner_references()[0].uid,
+            stacklet.uid().unwrap()
+        );
+        assert_eq!(
+            created_secret
+                .data
+                .as_ref()
+                .unwrap()
+                .keys()
+                .collect::<Vec<_>>(),
+            [
+                CA_CERTIFICATE_KEY,
+                CERTIFICATE_KEY,
+                CERTIFICATE_PRIVATE_KEY_KEY
+            ]
+        );
+
+        client
+            .merge_patch(
+                &created_secret,
+                serde_json::json!({ "metadata": { "annotations": { ROTATE_ANNOTATION: "true" } } }),
+            )
+            .await
+            .unwrap();
+        let renewed = create_or_renew_generated_secret("generated", &generator, &stacklet, &client)
+            .await
+            .unwrap();
+
+        assert!(renewed.renewed);
+        assert_eq!(
+            server.objects::<Secret>(),
+            std::slice::from_ref(&renewed.secret)
+        );
+        assert_ne!(
+            value(renewed.secret.data.as_ref().unwrap(), CERTIFICATE_KEY),
+            value(created_secret.data.as_ref().unwrap(), CERTIFICATE_KEY)
+        );
+        assert!(!renewed.secret.annotations().contains_key(ROTATE_ANNOTATION));
+    }
+}
@@ -1,3 +1,5 @@
+use std::collections::BTreeSet;
+
 use k8s_openapi::api::core::v1::{Volume, VolumeMount};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -6,14 +8,38 @@ use snafu::{ResultExt, Snafu};
 use crate::{
     builder::{
         self,
-        pod::{PodBuilder, container::ContainerBuilder, volume::VolumeMountBuilder},
+        pod::{
+            PodBuilder,
+            container::ContainerBuilder,
+            volume::{
+                SecretFormat, SecretOperatorVolumeSourceBuilder,
+                SecretOperatorVolumeSourceBuilderError, VolumeBuilder, VolumeMountBuilder,
+            },
+        },
     },
     commons::secret_class::{
         SecretClassVolume, SecretClassVolumeError, SecretClassVolumeProvisionParts,
     },
     constants::secret::SECRET_BASE_PATH,
+    role_utils::JvmArgumentOverrides,
 };
 
+/// Directory in which the aggregated truststore built by [`JvmTruststoreBuilder`] is placed.
+pub const JVM_TRUSTSTORE_DIR: &str = "/stackable/jvm-truststore";
+
+/// Path of the aggregated PKCS#12 truststore built by [`JvmTruststoreBuilder`].
+pub const JVM_TRUSTSTORE_PATH: &str = "/stackable/jvm-truststore/truststore.p12";
+
+/// Password of the aggregated truststore. A truststore only contains public certificates, so the
+/// password only exists because the JVM and PKCS#12 require one.
+pub const JVM_TRUSTSTORE_PASSWORD: &str = "changeit"; // pragma: allowlist secret
+
+const JVM_TRUSTSTORE_VOLUME_NAME: &str = "jvm-truststore";
+
+/// The CA bundle of the operating system in the product images, which contains the CA
+/// certificates trusted by the common web browsers.
+const SYSTEM_CA_BUNDLE_PATH: &str = "/etc/pki/tls/certs/ca-bundle.crt";
+
 #[derive(Debug, Snafu)]
 pub enum TlsClientDetailsError {
     #[snafu(display("failed to convert secret class volume into named Kubernetes volume"))]
@@ -159,6 +185,239 @@ impl TlsClientDetails {
     }
 }
 
+#[derive(Debug, Snafu)]
+pub enum JvmTruststoreError {
+    #[snafu(display(
+        "failed to build the volume with the CA certificate of SecretClass {secret_class:?}"
+    ))]
+    CaCertVolume {
+        source: SecretClassVolumeError,
+        secret_class: String,
+    },
+
+    #[snafu(display("failed to build the truststore volume of SecretClass {secret_class:?}"))]
+    TruststoreVolume {
+        source: SecretOperatorVolumeSourceBuilderError,
+        secret_class: String,
+    },
+
+    #[snafu(display("failed to add the truststore volumes"))]
+    AddTruststoreVolumes { source: builder::pod::Error },
+
+    #[snafu(display("failed to add the truststore volumeMounts"))]
+    AddTruststoreVolumeMounts {
+        source: builder::pod::container::Error,
+    },
+}
+
+/// Aggregates the CA certificates referenced by a stacklet (e.g. in the [`TlsClientDetails`] of
+/// S3 connections, LDAP and OIDC providers or databases) into a single PKCS#12 truststore for JVM
+/// based products.
+///
+/// ```
+/// use stackable_operator::commons::tls_verification::{
+///     CaCert, JvmTruststoreBuilder, Tls, TlsClientDetails, TlsServerVerification, TlsVerification,
+/// };
+///
+/// let s3_tls = TlsClientDetails {
+///     tls: Some(Tls {
+///         verification: TlsVerification::Server(TlsServerVerification {
+///             ca_cert: CaCert::SecretClass("s3-ca".to_owned()),
+///         }),
+///     }),
+/// };
+///
+/// let truststore = JvmTruststoreBuilder::new()
+///     .add_tls_client_details(&s3_tls)
+///     .add_ca_cert(CaCert::SecretClass("ldap-ca".to_owned()))
+///     .build()
+///     .unwrap()
+///     .expect("a truststore is required for the SecretClasses");
+///
+/// assert!(truststore.prepare_command().is_some());
+/// assert!(truststore.jvm_arguments().contains(
+///     &"-Djavax.net.ssl.trustStore=/stackable/jvm-truststore/truststore.p12".to_owned()
+/// ));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct JvmTruststoreBuilder {
+    ca_certs: BTreeSet<CaCert>,
+}
+
+impl JvmTruststoreBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the CA certificate used to verify the server, if TLS verification is configured.
+    pub fn add_tls_client_details(&mut self, tls_client_details: &TlsClientDetails) -> &mut Self {
+        if let Some(Tls {
+            verification: TlsVerification::Server(TlsServerVerification { ca_cert }),
+        }) = &tls_client_details.tls
+        {
+            self.add_ca_cert(ca_cert.clone());
+        }
+        self
+    }
+
+    pub fn add_ca_cert(&mut self, ca_cert: CaCert) -> &mut Self {
+        self.ca_certs.insert(ca_cert);
+        self
+    }
+
+    /// Returns `None` if no truststore is needed, i.e. if no CA certificates from SecretClasses
+    /// were added, because the default truststore of the JVM already contains the web PKI CAs.
+    ///
+    /// If exactly one SecretClass (and no web PKI) was added, then secret-operator provides the
+    /// truststore directly. Otherwise, the truststore must be assembled by running
+    /// [`JvmTruststore::prepare_command`] before the JVM is started, e.g. in an init container.
+    pub fn build(&self) -> Result<Option<JvmTruststore>, JvmTruststoreError> {
+        let secret_classes = self
+            .ca_certs
+            .iter()
+            .filter_map(|ca_cert| match ca_cert {
+                CaCert::SecretClass(secret_class) => Some(secret_class),
+                CaCert::WebPki {} => None,
+            })
+            .collect::<Vec<_>>();
+        let web_pki = self.ca_certs.contains(&CaCert::WebPki {});
+
+        let truststore = match (secret_classes.as_slice(), web_pki) {
+            ([], _) => None,
+            ([secret_class], false) => {
+                let volume_source = SecretOperatorVolumeSourceBuilder::new(
+                    *secret_class,
+                    SecretClassVolumeProvisionParts::Public,
+                )
+                .with_format(SecretFormat::TlsPkcs12)
+                .with_tls_pkcs12_password(JVM_TRUSTSTORE_PASSWORD)
+                .build()
+                .with_context(|_| TruststoreVolumeSnafu {
+                    secret_class: *secret_class,
+                })?;
+
+                Some(JvmTruststore {
+                    volumes: vec![
+                        VolumeBuilder::new(JVM_TRUSTSTORE_VOLUME_NAME)
+                            .ephemeral(volume_source)
+                            .build(),
+                    ],
+                    volume_mounts: vec![
+                        VolumeMountBuilder::new(JVM_TRUSTSTORE_VOLUME_NAME, JVM_TRUSTSTORE_DIR)
+                            .build(),
+                    ],
+                    prepare_command: None,
+                })
+            }
+            (secret_classes, web_pki) => {
+                let mut volumes = vec![
+                    VolumeBuilder::new(JVM_TRUSTSTORE_VOLUME_NAME)
+                        .with_empty_dir(None::<String>, None)
+                        .build(),
+                ];
+                let mut volume_mounts = vec![
+                    VolumeMountBuilder::new(JVM_TRUSTSTORE_VOLUME_NAME, JVM_TRUSTSTORE_DIR).build(),
+                ];
+                let mut command = vec!["cert-tools generate-pkcs12-truststore".to_owned()];
+
+                for secret_class in secret_classes {
+                    // Same volume as in [`TlsClientDetails::volumes_and_mounts`], so that both
+                    // can be added to the same Pod.
+                    let volume_name = format!("{secret_class}-ca-cert");
+                    volumes.push(
+                        SecretClassVolume::new((*secret_class).clone(), None)
+                            .to_volume(&volume_name, SecretClassVolumeProvisionParts::Public)
+                            .with_context(|_| CaCertVolumeSnafu {
+                                secret_class: *secret_class,
+                            })?,
+                    );
+                    volume_mounts.push(
+                        VolumeMountBuilder::new(
+                            volume_name,
+                            format!("{SECRET_BASE_PATH}/{secret_class}"),
+                        )
+                        .build(),
+                    );
+                    command.push(format!("--pem {SECRET_BASE_PATH}/{secret_class}/ca.crt"));
+                }
+                if web_pki {
+                    command.push(format!("--pem {SYSTEM_CA_BUNDLE_PATH}"));
+                }
+                command.push(format!("--out {JVM_TRUSTSTORE_PATH}"));
+                command.push(format!("--out-password {JVM_TRUSTSTORE_PASSWORD}"));
+
+                Some(JvmTruststore {
+                    volumes,
+                    volume_mounts,
+                    prepare_command: Some(command.join(" ")),
+                })
+            }
+        };
+
+        Ok(truststore)
+    }
+}
+
+/// A truststore built by [`JvmTruststoreBuilder`], which is available at [`JVM_TRUSTSTORE_PATH`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct JvmTruststore {
+    volumes: Vec<Volume>,
+    volume_mounts: Vec<VolumeMount>,
+    prepare_command: Option<String>,
+}
+
+impl JvmTruststore {
+    /// Adds the volumes to the PodBuilder and the volume mounts to all given containers, which
+    /// must include the container running [`Self::prepare_command`] (if any) and the container
+    /// running the JVM.
+    pub fn add_volumes_and_mounts(
+        &self,
+        pod_builder: &mut PodBuilder,
+        container_builders: Vec<&mut ContainerBuilder>,
+    ) -> Result<(), JvmTruststoreError> {
+        pod_builder
+            .add_volumes(self.volumes.clone())
+            .context(AddTruststoreVolumesSnafu)?;
+
+        for cb in container_builders {
+            cb.add_volume_mounts(self.volume_mounts.clone())
+                .context(AddTruststoreVolumeMountsSnafu)?;
+        }
+
+        Ok(())
+    }
+
+    /// It is recommended to use [`Self::add_volumes_and_mounts`], this function returns you the
+    /// volumes and mounts in case you need to add them by yourself.
+    pub fn volumes_and_mounts(&self) -> (&[Volume], &[VolumeMount]) {
+        (&self.volumes, &self.volume_mounts)
+    }
+
+    /// The shell command which assembles the truststore, if it is not provided by secret-operator
+    /// directly.
+    ///
+    /// The command uses `cert-tools` from the product images and must finish before the JVM is
+    /// started, so either run it in an init container or prepend it to the container command.
+    pub fn prepare_command(&self) -> Option<&str> {
+        self.prepare_command.as_deref()
+    }
+
+    /// The JVM arguments which configure the truststore as the default one of the JVM.
+    pub fn jvm_arguments(&self) -> Vec<String> {
+        vec![
+            format!("-Djavax.net.ssl.trustStore={JVM_TRUSTSTORE_PATH}"),
+            format!("-Djavax.net.ssl.trustStorePassword={JVM_TRUSTSTORE_PASSWORD}"),
+            "-Djavax.net.ssl.trustStoreType=pkcs12".to_owned(),
+        ]
+    }
+
+    /// The [`Self::jvm_arguments`] as operator generated [`JvmArgumentOverrides`], which can be
+    /// passed to [`Role::get_merged_jvm_argument_overrides`](crate::role_utils::Role::get_merged_jvm_argument_overrides).
+    pub fn jvm_argument_overrides(&self) -> JvmArgumentOverrides {
+        JvmArgumentOverrides::new_with_only_additions(self.jvm_arguments())
+    }
+}
+
 #[derive(
     Clone, Debug, Deserialize, Eq, Hash, JsonSchema, Ord, PartialEq, PartialOrd, Serialize,
 )]
@@ -240,6 +499,104 @@ mod tests {
         );
     }
 
+    fn secret_class_tls(secret_class: &str) -> TlsClientDetails {
+        TlsClientDetails {
+            tls: Some(Tls {
+                verification: TlsVerification::Server(TlsServerVerification {
+                    ca_cert: CaCert::SecretClass(secret_class.to_owned()),
+                }),
+            }),
+        }
+    }
+
+    #[test]
+    fn jvm_truststore_not_needed_without_secret_classes() {
+        let truststore = JvmTruststoreBuilder::new()
+            .add_tls_client_details(&TlsClientDetails { tls: None })
+            .add_tls_client_details(&TlsClientDetails {
+                tls: Some(Tls {
+                    verification: TlsVerification::None {},
+                }),
+            })
+            .add_ca_cert(CaCert::WebPki {})
+            .build()
+            .unwrap();
+
+        assert_eq!(truststore, None);
+    }
+
+    #[test]
+    fn jvm_truststore_from_single_secret_class() {
+        let truststore = JvmTruststoreBuilder::new()
+            .add_tls_client_details(&secret_class_tls("s3-ca"))
+            .add_tls_client_details(&secret_class_tls("s3-ca"))
+            .build()
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(truststore.prepare_command(), None);
+
+        let (volumes, mounts) = truststore.volumes_and_mounts();
+        assert_eq!(volumes.len(), 1);
+        let annotations = volumes[0]
+            .ephemeral
+            .as_ref()
+            .and_then(|ephemeral| ephemeral.volume_claim_template.as_ref())
+            .and_then(|template| template.metadata.as_ref())
+            .and_then(|metadata| metadata.annotations.as_ref())
+            .unwrap();
+        assert_eq!(
+            annotations.get("secrets.stackable.tech/class"),
+            Some(&"s3-ca".to_owned())
+        );
+        assert_eq!(
+            annotations.get("secrets.stackable.tech/format"),
+            Some(&"tls-pkcs12".to_owned())
+        );
+        assert_eq!(mounts[0].mount_path, JVM_TRUSTSTORE_DIR);
+    }
+
+    #[test]
+    fn jvm_truststore_from_multiple_ca_certs() {
+        let truststore = JvmTruststoreBuilder::new()
+            .add_tls_client_details(&secret_class_tls("s3-ca"))
+            .add_tls_client_details(&secret_class_tls("ldap-ca"))
+            .add_ca_cert(CaCert::WebPki {})
+            .build()
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(
+            truststore.prepare_command(),
+            Some(
+                "cert-tools generate-pkcs12-truststore \
+                --pem /stackable/secrets/ldap-ca/ca.crt \
+                --pem /stackable/secrets/s3-ca/ca.crt \
+                --pem /etc/pki/tls/certs/ca-bundle.crt \
+                --out /stackable/jvm-truststore/truststore.p12 \
+                --out-password changeit"
+            )
+        );
+        assert_eq!(
+            truststore.jvm_arguments(),
+            [
+                "-Djavax.net.ssl.trustStore=/stackable/jvm-truststore/truststore.p12",
+                "-Djavax.net.ssl.trustStorePassword=changeit",
+                "-Djavax.net.ssl.trustStoreType=pkcs12",
+            ]
+        );
+
+        // The CA cert volumes must not collide with the ones of the TlsClientDetails
+        let mut pod_builder = PodBuilder::new();
+        let mut container_builder = ContainerBuilder::new("main").unwrap();
+        secret_class_tls("s3-ca")
+            .add_volumes_and_mounts(&mut pod_builder, vec![&mut container_builder])
+            .unwrap();
+        truststore
+            .add_volumes_and_mounts(&mut pod_builder, vec![&mut container_builder])
+            .unwrap();
+    }
+
     #[test]
     #[allow(clippy::explicit_auto_deref)]
     fn tls_client_details_with_secure_defaults_deref() {
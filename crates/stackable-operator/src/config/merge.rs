@@ -3,10 +3,11 @@
 use std::{
     collections::{BTreeMap, HashMap, btree_map, hash_map},
     hash::{BuildHasher, Hash},
+    num::NonZeroU32,
 };
 
 use k8s_openapi::{
-    api::core::v1::{NodeAffinity, PodAffinity, PodAntiAffinity},
+    api::core::v1::{NodeAffinity, PodAffinity, PodAntiAffinity, PodSchedulingGate, Toleration},
     apimachinery::pkg::{api::resource::Quantity, apis::meta::v1::LabelSelector},
 };
 pub use stackable_operator_derive::Merge;
@@ -129,6 +130,7 @@ pub trait Atomic: Clone {}
 impl Atomic for u8 {}
 impl Atomic for u16 {}
 impl Atomic for u32 {}
+impl Atomic for NonZeroU32 {}
 impl Atomic for u64 {}
 impl Atomic for u128 {}
 impl Atomic for usize {}
@@ -147,6 +149,8 @@ impl Atomic for LabelSelector {}
 impl Atomic for PodAffinity {}
 impl Atomic for PodAntiAffinity {}
 impl Atomic for NodeAffinity {}
+impl Atomic for Vec<Toleration> {}
+impl Atomic for Vec<PodSchedulingGate> {}
 
 impl<T: Atomic> Merge for Option<T> {
     fn merge(&mut self, defaults: &Self) {
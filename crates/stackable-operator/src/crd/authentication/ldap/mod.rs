@@ -14,7 +14,7 @@ mod v1alpha1_impl;
 pub mod versioned {
     pub mod v1alpha1 {
         // Re-export the v1alpha1-specific error type from the private impl module.
-        pub use v1alpha1_impl::Error;
+        pub use v1alpha1_impl::{Error, UrlListFormat};
     }
 
     #[derive(
@@ -28,6 +28,15 @@ pub mod versioned {
         /// Port of the LDAP server. If TLS is used defaults to 636 otherwise to 389.
         port: Option<u16>,
 
+        /// Further servers of a clustered LDAP setup, which share the configuration (e.g. the
+        /// search base, bind credentials and TLS settings) of the server given in `hostname`.
+        #[serde(default)]
+        pub additional_servers: Vec<Server>,
+
+        /// How the servers are selected if `additionalServers` are given. Defaults to `Failover`.
+        #[serde(default)]
+        pub server_selection: ServerSelection,
+
         /// LDAP search base, for example: `ou=users,dc=example,dc=org`.
         #[serde(default)]
         pub search_base: String,
@@ -43,11 +52,55 @@ pub mod versioned {
         /// In case you need a special account for searching the LDAP server you can specify it here.
         bind_credentials: Option<SecretClassVolume>,
 
+        /// Name of a Secret with the keys `user` and `password` in the namespace of the stacklet,
+        /// which contains the bind credentials. It can be used instead of `bindCredentials`.
+        ///
+        /// Unlike `bindCredentials`, changes of the Secret (e.g. a rotated password) are propagated
+        /// into the running Pods. Products which re-read the credentials pick them up without a
+        /// restart, all other products are restarted.
+        bind_credentials_secret: Option<String>,
+
         /// Use a TLS connection. If not specified no TLS will be used.
         #[serde(flatten)]
         pub tls: TlsClientDetails,
     }
 
+    #[derive(
+        Clone, Debug, Deserialize, Eq, Hash, JsonSchema, Ord, PartialEq, PartialOrd, Serialize,
+    )]
+    #[serde(rename_all = "camelCase")]
+    pub struct Server {
+        /// Host of the LDAP server, for example: `my.ldap.server` or `127.0.0.1`.
+        pub hostname: HostName,
+
+        /// Port of the LDAP server. If TLS is used defaults to 636 otherwise to 389.
+        pub port: Option<u16>,
+    }
+
+    #[derive(
+        Clone,
+        Copy,
+        Debug,
+        Default,
+        Deserialize,
+        Eq,
+        Hash,
+        JsonSchema,
+        Ord,
+        PartialEq,
+        PartialOrd,
+        Serialize,
+    )]
+    pub enum ServerSelection {
+        /// The servers are tried in the given order, starting with the one in `hostname`.
+        #[default]
+        Failover,
+
+        /// The load is distributed across the servers by starting with a different server per
+        /// replica, and the other servers are tried in the given order on failure.
+        RoundRobin,
+    }
+
     #[derive(
         Clone, Debug, Deserialize, Eq, Hash, JsonSchema, Ord, PartialEq, PartialOrd, Serialize,
     )]
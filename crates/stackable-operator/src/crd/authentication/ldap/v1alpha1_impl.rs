@@ -1,18 +1,26 @@
 use k8s_openapi::api::core::v1::{Volume, VolumeMount};
-use snafu::{ResultExt as _, Snafu};
+use sha2::{Digest as _, Sha256};
+use snafu::{ResultExt as _, Snafu, ensure};
 use url::Url;
 
 use crate::{
     builder::{
         self,
-        pod::{PodBuilder, container::ContainerBuilder, volume::VolumeMountBuilder},
+        pod::{
+            PodBuilder,
+            container::ContainerBuilder,
+            volume::{VolumeBuilder, VolumeMountBuilder},
+        },
     },
     commons::{
+        networking::HostName,
         secret_class::{SecretClassVolumeError, SecretClassVolumeProvisionParts},
         tls_verification::TlsClientDetailsError,
     },
     constants::secret::SECRET_BASE_PATH,
-    crd::authentication::ldap::v1alpha1::{AuthenticationProvider, FieldNames},
+    crd::authentication::ldap::v1alpha1::{
+        AuthenticationProvider, FieldNames, Server, ServerSelection,
+    },
 };
 
 pub type Result<T, E = Error> = std::result::Result<T, E>;
@@ -27,6 +35,9 @@ pub enum Error {
     #[snafu(display("failed to parse LDAP endpoint url"))]
     ParseLdapEndpointUrl { source: url::ParseError },
 
+    #[snafu(display("only one of bindCredentials and bindCredentialsSecret can be set"))]
+    ConflictingBindCredentials,
+
     #[snafu(display("failed to add LDAP TLS client details volumes and volume mounts"))]
     AddLdapTlsClientDetailsVolumes { source: TlsClientDetailsError },
 
@@ -39,17 +50,78 @@ pub enum Error {
     },
 }
 
+/// The format in which a product expects a list of LDAP URLs.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum UrlListFormat {
+    /// The URLs are separated by spaces, as in `java.naming.provider.url` of JNDI, Spring LDAP
+    /// or `ldap_initialize` of OpenLDAP.
+    SpaceSeparated,
+
+    /// The URLs are separated by commas, as in `hadoop.security.group.mapping.ldap.url`.
+    CommaSeparated,
+}
+
+impl UrlListFormat {
+    pub fn render(self, urls: &[Url]) -> String {
+        let separator = match self {
+            Self::SpaceSeparated => " ",
+            Self::CommaSeparated => ",",
+        };
+
+        urls.iter()
+            .map(Url::as_str)
+            .collect::<Vec<_>>()
+            .join(separator)
+    }
+}
+
 impl AuthenticationProvider {
-    /// Returns the LDAP endpoint [`Url`].
+    /// Returns the LDAP endpoint [`Url`] of the server given in `hostname`.
+    ///
+    /// Use [`Self::endpoint_urls`] to also get the URLs of the `additionalServers`.
     pub fn endpoint_url(&self) -> Result<Url> {
+        self.server_url(&self.hostname, self.port())
+    }
+
+    /// Returns the LDAP endpoint [`Url`]s of all servers, starting with the one given in
+    /// `hostname`, followed by the `additionalServers` in the given order.
+    pub fn endpoint_urls(&self) -> Result<Vec<Url>> {
+        std::iter::once(self.endpoint_url())
+            .chain(
+                self.additional_servers
+                    .iter()
+                    .map(|Server { hostname, port }| {
+                        self.server_url(hostname, port.unwrap_or_else(|| self.default_port()))
+                    }),
+            )
+            .collect()
+    }
+
+    /// Returns the LDAP endpoint [`Url`]s of all servers in the order in which the given replica
+    /// should try them.
+    ///
+    /// With [`ServerSelection::Failover`], this is the same order as in [`Self::endpoint_urls`].
+    /// With [`ServerSelection::RoundRobin`], the list is rotated by the given replica index (e.g.
+    /// the index of the Pod in the StatefulSet or of the role group), so that the replicas start
+    /// with different servers but can still fail over to all other ones.
+    pub fn endpoint_urls_for_replica(&self, replica: usize) -> Result<Vec<Url>> {
+        let mut urls = self.endpoint_urls()?;
+        if self.server_selection == ServerSelection::RoundRobin {
+            let len = urls.len();
+            urls.rotate_left(replica % len);
+        }
+        Ok(urls)
+    }
+
+    fn server_url(&self, hostname: &HostName, port: u16) -> Result<Url> {
         let url = Url::parse(&format!(
             "{protocol}{server_hostname}:{server_port}",
             protocol = match self.tls.tls {
                 None => "ldap://",
                 Some(_) => "ldaps://",
             },
-            server_hostname = self.hostname,
-            server_port = self.port()
+            server_hostname = hostname.as_url_host(),
+            server_port = port
         ))
         .context(ParseLdapEndpointUrlSnafu)?;
 
@@ -58,8 +130,11 @@ impl AuthenticationProvider {
 
     /// Returns the port to be used, which is either user configured or defaulted based upon TLS usage
     pub fn port(&self) -> u16 {
-        self.port
-            .unwrap_or_else(|| if self.tls.uses_tls() { 636 } else { 389 })
+        self.port.unwrap_or_else(|| self.default_port())
+    }
+
+    fn default_port(&self) -> u16 {
+        if self.tls.uses_tls() { 636 } else { 389 }
     }
 
     /// This functions adds
@@ -71,6 +146,11 @@ impl AuthenticationProvider {
     ///
     /// * Bind credentials needed to connect to LDAP server
     /// * Tls secret class used to verify the cert of the LDAP server
+    ///
+    /// If the bind credentials are provided by a `bindCredentialsSecret` and the product re-reads
+    /// them at runtime, then consider adding
+    /// [`restarter_ignore_secret_annotations`](crate::v2::builder::statefulset::restarter_ignore_secret_annotations)
+    /// for [`Self::bind_credentials_secret`] to avoid restarts on rotations.
     pub fn add_volumes_and_mounts(
         &self,
         pod_builder: &mut PodBuilder,
@@ -93,6 +173,22 @@ impl AuthenticationProvider {
         let mut volumes = Vec::new();
         let mut mounts = Vec::new();
 
+        self.validate_bind_credentials()?;
+
+        if let Some(secret) = &self.bind_credentials_secret {
+            // The Secret is mounted directly (and without a subPath), so that the kubelet updates
+            // the files when the Secret changes.
+            let volume_name = bind_credentials_secret_volume_name(secret);
+            volumes.push(
+                VolumeBuilder::new(&volume_name)
+                    .with_secret(secret, false)
+                    .build(),
+            );
+            mounts.push(
+                VolumeMountBuilder::new(volume_name, bind_credentials_secret_dir(secret)).build(),
+            );
+        }
+
         if let Some(bind_credentials) = &self.bind_credentials {
             let secret_class = &bind_credentials.secret_class;
             let volume_name = format!("{secret_class}-bind-credentials");
@@ -121,21 +217,60 @@ impl AuthenticationProvider {
 
     /// Returns the path of the files containing bind user and password.
     /// This will be None if there are no credentials for this LDAP connection.
-    pub fn bind_credentials_mount_paths(&self) -> Option<(String, String)> {
-        self.bind_credentials.as_ref().map(|bind_credentials| {
-            let secret_class = &bind_credentials.secret_class;
-            (
-                format!("{SECRET_BASE_PATH}/{secret_class}/user"),
-                format!("{SECRET_BASE_PATH}/{secret_class}/password"),
-            )
-        })
+    ///
+    /// The files provided by a `bindCredentialsSecret` are updated when the Secret changes, so
+    /// products should re-read them (e.g. on an authentication failure) if possible.
+    pub fn bind_credentials_mount_paths(&self) -> Result<Option<(String, String)>> {
+        self.validate_bind_credentials()?;
+
+        let dir = match (&self.bind_credentials, &self.bind_credentials_secret) {
+            (Some(bind_credentials), _) => {
+                format!("{SECRET_BASE_PATH}/{}", bind_credentials.secret_class)
+            }
+            (None, Some(secret)) => bind_credentials_secret_dir(secret),
+            (None, None) => return Ok(None),
+        };
+
+        Ok(Some((format!("{dir}/user"), format!("{dir}/password"))))
+    }
+
+    /// Checks that the bind credentials are not given by both `bindCredentials` and
+    /// `bindCredentialsSecret`.
+    pub fn validate_bind_credentials(&self) -> Result<()> {
+        ensure!(
+            self.bind_credentials.is_none() || self.bind_credentials_secret.is_none(),
+            ConflictingBindCredentialsSnafu
+        );
+
+        Ok(())
     }
 
     pub fn has_bind_credentials(&self) -> bool {
-        self.bind_credentials.is_some()
+        self.bind_credentials.is_some() || self.bind_credentials_secret.is_some()
+    }
+
+    /// Returns the name of the Secret which contains the bind credentials, if they are not
+    /// provided by a SecretClass.
+    pub fn bind_credentials_secret(&self) -> Option<&str> {
+        self.bind_credentials_secret.as_deref()
     }
 }
 
+fn bind_credentials_secret_dir(secret: &str) -> String {
+    format!("{SECRET_BASE_PATH}/bind-credentials-secrets/{secret}")
+}
+
+/// Volume names must not exceed 63 characters, whereas Secret names can be up to 253 characters
+/// long, so the name of the Secret is hashed.
+fn bind_credentials_secret_volume_name(secret: &str) -> String {
+    let mut secret_hash = [0; 8];
+    secret_hash.copy_from_slice(&Sha256::digest(secret.as_bytes())[..8]);
+    let secret_hash = u64::from_be_bytes(secret_hash);
+
+    // Prefix with zeros to have a consistent length of 16 characters.
+    format!("bind-credentials-secret-{secret_hash:016x}")
+}
+
 impl FieldNames {
     pub(super) fn default_uid() -> String {
         "uid".to_string()
@@ -203,6 +338,136 @@ mod tests {
         .unwrap();
     }
 
+    #[test]
+    fn multiple_servers() {
+        let ldap = yaml_from_str_singleton_map::<AuthenticationProvider>(
+            "
+            hostname: ldap-1.example.com
+            additionalServers:
+              - hostname: ldap-2.example.com
+                port: 1636
+              - hostname: 2001:db8::3
+            serverSelection: RoundRobin
+            tls:
+              verification:
+                none: {}
+            ",
+        )
+        .unwrap();
+
+        let urls = ldap.endpoint_urls().unwrap();
+        assert_eq!(
+            UrlListFormat::SpaceSeparated.render(&urls),
+            "ldaps://ldap-1.example.com:636 ldaps://ldap-2.example.com:1636 ldaps://[2001:db8::3]:636"
+        );
+        assert_eq!(
+            UrlListFormat::CommaSeparated.render(&ldap.endpoint_urls_for_replica(4).unwrap()),
+            "ldaps://ldap-2.example.com:1636,ldaps://[2001:db8::3]:636,ldaps://ldap-1.example.com:636"
+        );
+        assert_eq!(ldap.endpoint_url().unwrap(), urls[0]);
+    }
+
+    #[test]
+    fn failover_keeps_server_order() {
+        let ldap = serde_yaml::from_str::<AuthenticationProvider>(
+            "
+            hostname: ldap-1.example.com
+            additionalServers:
+              - hostname: ldap-2.example.com
+            ",
+        )
+        .unwrap();
+
+        assert_eq!(
+            ldap.endpoint_urls_for_replica(1).unwrap(),
+            ldap.endpoint_urls().unwrap()
+        );
+    }
+
+    #[test]
+    fn bind_credentials_secret() {
+        let ldap = serde_yaml::from_str::<AuthenticationProvider>(
+            "
+            hostname: my.ldap.server
+            bindCredentialsSecret: ldap-bind-user
+            ",
+        )
+        .unwrap();
+
+        assert!(ldap.has_bind_credentials());
+        assert_eq!(ldap.bind_credentials_secret(), Some("ldap-bind-user"));
+        assert_eq!(
+            ldap.bind_credentials_mount_paths().unwrap(),
+            Some((
+                "/stackable/secrets/bind-credentials-secrets/ldap-bind-user/user".to_owned(),
+                "/stackable/secrets/bind-credentials-secrets/ldap-bind-user/password".to_owned()
+            ))
+        );
+
+        let (volumes, mounts) = ldap.volumes_and_mounts().unwrap();
+        assert_eq!(
+            volumes,
+            vec![
+                VolumeBuilder::new("bind-credentials-secret-d7e4e69e60a21eed")
+                    .with_secret("ldap-bind-user", false)
+                    .build()
+            ]
+        );
+        assert_eq!(
+            mounts,
+            vec![
+                VolumeMountBuilder::new(
+                    "bind-credentials-secret-d7e4e69e60a21eed",
+                    "/stackable/secrets/bind-credentials-secrets/ldap-bind-user"
+                )
+                .build()
+            ]
+        );
+    }
+
+    #[test]
+    fn conflicting_bind_credentials() {
+        let ldap = serde_yaml::from_str::<AuthenticationProvider>(
+            "
+            hostname: my.ldap.server
+            bindCredentials:
+              secretClass: openldap-bind-credentials
+            bindCredentialsSecret: ldap-bind-user
+            ",
+        )
+        .unwrap();
+
+        assert!(matches!(
+            ldap.volumes_and_mounts(),
+            Err(Error::ConflictingBindCredentials)
+        ));
+        assert!(matches!(
+            ldap.bind_credentials_mount_paths(),
+            Err(Error::ConflictingBindCredentials)
+        ));
+    }
+
+    #[test]
+    fn long_bind_credentials_secret_name() {
+        let secret = "a".repeat(253);
+        let ldap = serde_yaml::from_str::<AuthenticationProvider>(&format!(
+            "
+            hostname: my.ldap.server
+            bindCredentialsSecret: {secret}
+            "
+        ))
+        .unwrap();
+
+        let (volumes, mounts) = ldap.volumes_and_mounts().unwrap();
+
+        assert_eq!(volumes[0].name.len(), 40);
+        assert_eq!(mounts[0].name, volumes[0].name);
+        assert_ne!(
+            volumes[0].name,
+            bind_credentials_secret_volume_name(&"a".repeat(252))
+        );
+    }
+
     #[test]
     fn full() {
         let input = r"
@@ -255,7 +520,7 @@ mod tests {
 
         assert!(ldap.has_bind_credentials());
         assert_eq!(
-            ldap.bind_credentials_mount_paths(),
+            ldap.bind_credentials_mount_paths().unwrap(),
             Some((
                 "/stackable/secrets/openldap-bind-credentials/user".to_string(),
                 "/stackable/secrets/openldap-bind-credentials/password".to_string()
@@ -1,6 +1,6 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-#[cfg(doc)]
+use stackable_shared::time::Duration;
 use url::Url;
 
 use crate::{
@@ -13,6 +13,7 @@ mod v1alpha1_impl;
 // FIXME (@Techassi): These constants should also be versioned
 pub const CLIENT_ID_SECRET_KEY: &str = "clientId";
 pub const CLIENT_SECRET_SECRET_KEY: &str = "clientSecret";
+pub const CLIENT_PRIVATE_KEY_SECRET_KEY: &str = "privateKey";
 
 /// Do *not* use this for [`Url::join`], as the leading slash will erase the existing path!
 const DEFAULT_WELLKNOWN_OIDC_CONFIG_PATH: &str = "/.well-known/openid-configuration";
@@ -76,6 +77,82 @@ pub mod versioned {
         /// intended to be used (via the `.well-known` discovery).
         #[serde(default)]
         pub provider_hint: Option<IdentityProviderHint>,
+
+        /// Settings used by products to validate access tokens which are presented to them, e.g.
+        /// by API clients.
+        #[serde(default)]
+        pub token_validation: TokenValidation,
+    }
+
+    /// Settings to validate access tokens.
+    ///
+    /// Tokens are either validated locally as JWTs with the keys of the identity provider or
+    /// remotely at the token introspection endpoint, if `introspection` is configured.
+    #[derive(
+        Clone,
+        Debug,
+        Default,
+        Deserialize,
+        Eq,
+        Hash,
+        JsonSchema,
+        Ord,
+        PartialEq,
+        PartialOrd,
+        Serialize,
+    )]
+    #[serde(rename_all = "camelCase")]
+    pub struct TokenValidation {
+        /// The URL of the JSON Web Key Set (JWKS) used to verify the token signatures. Defaults to
+        /// the `jwks_uri` announced by the well-known OIDC configuration.
+        pub jwks_uri: Option<Url>,
+
+        /// The accepted values of the `aud` claim. If empty, then products usually accept tokens
+        /// issued for their own client ID.
+        #[serde(default)]
+        pub audiences: Vec<String>,
+
+        /// The accepted values of the `iss` claim. Defaults to the endpoint URL of the identity
+        /// provider.
+        #[serde(default)]
+        pub issuers: Vec<String>,
+
+        /// The tolerated clock skew when validating the time based claims `exp`, `nbf` and `iat`,
+        /// e.g. `30s`. Defaults to 60 seconds.
+        pub clock_skew: Option<Duration>,
+
+        /// Validates opaque tokens at the OAuth 2.0 token introspection endpoint as defined in
+        /// [RFC 7662](https://www.rfc-editor.org/rfc/rfc7662) instead of validating them locally.
+        pub introspection: Option<TokenIntrospection>,
+    }
+
+    /// Settings for the OAuth 2.0 token introspection.
+    #[derive(
+        Clone, Debug, Deserialize, Eq, Hash, JsonSchema, Ord, PartialEq, PartialOrd, Serialize,
+    )]
+    #[serde(rename_all = "camelCase")]
+    pub struct TokenIntrospection {
+        /// The URL of the introspection endpoint. Defaults to the `introspection_endpoint`
+        /// announced by the well-known OIDC configuration.
+        pub endpoint: Option<Url>,
+
+        /// A reference to the Secret containing the credentials of the client which calls the
+        /// introspection endpoint.
+        ///
+        /// The Secret must contain the client id. Depending on the
+        /// `clientAuthenticationMethod`, it must additionally contain the client secret or the
+        /// private key.
+        #[serde(rename = "clientCredentialsSecret")]
+        pub client_credentials_secret_ref: String,
+
+        /// The client authentication method used when calling the introspection endpoint.
+        /// Defaults to `client_secret_basic`.
+        #[serde(default)]
+        pub client_authentication_method: ClientAuthenticationMethod,
+
+        /// The SecretClass providing the client certificate, which is required for the client
+        /// authentication method `tls_client_auth`.
+        pub client_cert_secret_class: Option<String>,
     }
 
     /// An enum of supported OIDC or identity providers which can serve as a hint
@@ -128,6 +205,10 @@ pub mod versioned {
         /// Authenticate using a JWT signed with the client's private key.
         PrivateKeyJwt,
 
+        /// Authenticate using mutual TLS with a client certificate issued by a PKI, as defined in
+        /// [RFC 8705](https://www.rfc-editor.org/rfc/rfc8705).
+        TlsClientAuth,
+
         /// No client authentication (for public clients or implicit flow).
         None,
     }
@@ -173,7 +254,8 @@ pub mod versioned {
         /// The contents and format of the `clientCredentialsSecret` depend on the selected
         /// method. For example, [`ClientAuthenticationMethod::ClientSecretBasic`] and
         /// [`ClientAuthenticationMethod::ClientSecretPost`] require a client secret string, whereas
-        /// [`ClientAuthenticationMethod::PrivateKeyJwt`] requires a private key.
+        /// [`ClientAuthenticationMethod::PrivateKeyJwt`] requires a private key and
+        /// [`ClientAuthenticationMethod::TlsClientAuth`] only the client id.
         ///
         /// See [`ClientAuthenticationMethod`] for available options.
         #[schemars(
@@ -181,9 +263,15 @@ pub mod versioned {
             endpoint. Defaults to `client_secret_basic`. The required contents of \
             `clientCredentialsSecret` depend on the chosen method: secret-based methods \
             (`client_secret_basic`, `client_secret_post`, `client_secret_jwt`) expect a client \
-            secret, while `private_key_jwt` expects a private key."
+            secret, while `private_key_jwt` expects a private key and `tls_client_auth` only the \
+            client id."
         )]
         #[serde(default)]
         pub client_authentication_method: ClientAuthenticationMethod,
+
+        /// The SecretClass providing the client certificate, which is required for the client
+        /// authentication method `tls_client_auth`.
+        #[serde(default)]
+        pub client_cert_secret_class: Option<String>,
     }
 }
@@ -3,16 +3,28 @@ use std::{
     hash::{Hash, Hasher},
 };
 
-use k8s_openapi::api::core::v1::{EnvVar, EnvVarSource, SecretKeySelector};
-use snafu::{ResultExt as _, Snafu};
+use k8s_openapi::api::core::v1::{EnvVar, EnvVarSource, SecretKeySelector, Volume, VolumeMount};
+use snafu::{OptionExt as _, ResultExt as _, Snafu};
+use stackable_shared::time::Duration;
 use url::{ParseError, Url};
 
 use crate::{
-    commons::{networking::HostName, tls_verification::TlsClientDetails},
+    builder::pod::volume::{VolumeBuilder, VolumeMountBuilder},
+    commons::{
+        networking::HostName,
+        secret_class::{
+            SecretClassVolume, SecretClassVolumeError, SecretClassVolumeProvisionParts,
+        },
+        tls_verification::TlsClientDetails,
+    },
     constants::secret::SECRET_BASE_PATH,
     crd::authentication::oidc::{
-        CLIENT_ID_SECRET_KEY, CLIENT_SECRET_SECRET_KEY, DEFAULT_WELLKNOWN_OIDC_CONFIG_PATH,
-        v1alpha1::{AuthenticationProvider, IdentityProviderHint},
+        CLIENT_ID_SECRET_KEY, CLIENT_PRIVATE_KEY_SECRET_KEY, CLIENT_SECRET_SECRET_KEY,
+        DEFAULT_WELLKNOWN_OIDC_CONFIG_PATH,
+        v1alpha1::{
+            AuthenticationProvider, ClientAuthenticationMethod, IdentityProviderHint,
+            TokenIntrospection, TokenValidation,
+        },
     },
 };
 
@@ -27,6 +39,16 @@ pub enum Error {
         "failed to set OIDC endpoint scheme '{scheme}' for endpoint url \"{endpoint}\""
     ))]
     SetOidcEndpointScheme { endpoint: Url, scheme: String },
+
+    #[snafu(display(
+        "the client authentication method \"tls_client_auth\" requires a SecretClass providing the client certificate"
+    ))]
+    MissingClientCertSecretClass,
+
+    #[snafu(display(
+        "failed to convert client certificate SecretClass volume into named Kubernetes volume"
+    ))]
+    ClientCertSecretClassVolume { source: SecretClassVolumeError },
 }
 
 impl AuthenticationProvider {
@@ -47,6 +69,7 @@ impl AuthenticationProvider {
             principal_claim,
             scopes,
             provider_hint,
+            token_validation: TokenValidation::default(),
         }
     }
 
@@ -178,11 +201,156 @@ impl AuthenticationProvider {
         ]
     }
 
+    /// Returns the accepted values of the `iss` claim, which default to the [`Self::endpoint_url`].
+    pub fn accepted_issuers(&self) -> Result<Vec<String>> {
+        if self.token_validation.issuers.is_empty() {
+            Ok(vec![self.endpoint_url()?.to_string()])
+        } else {
+            Ok(self.token_validation.issuers.clone())
+        }
+    }
+
+    /// Returns the tolerated clock skew when validating tokens.
+    pub fn clock_skew(&self) -> Duration {
+        self.token_validation
+            .clock_skew
+            .unwrap_or(TokenValidation::DEFAULT_CLOCK_SKEW)
+    }
+
+    /// Returns the path of the file containing the private key used by
+    /// [`ClientAuthenticationMethod::PrivateKeyJwt`].
+    pub fn client_private_key_mount_path(secret_name: &str) -> String {
+        let volume_mount_path = Self::client_credentials_volume_mount_path(secret_name);
+        format!("{volume_mount_path}/{CLIENT_PRIVATE_KEY_SECRET_KEY}")
+    }
+
+    /// Returns the paths of the client certificate and its private key used by
+    /// [`ClientAuthenticationMethod::TlsClientAuth`].
+    pub fn client_cert_mount_paths(secret_class: &str) -> (String, String) {
+        let volume_mount_path = format!("{SECRET_BASE_PATH}/{secret_class}-client-cert");
+
+        (
+            format!("{volume_mount_path}/tls.crt"),
+            format!("{volume_mount_path}/tls.key"),
+        )
+    }
+
+    /// Returns the env variables with the client credentials which are required by the given
+    /// client authentication method.
+    ///
+    /// Secret-based methods require the client id and secret, see
+    /// [`Self::client_credentials_env_var_mounts`]. All other methods only require the client id,
+    /// the private key and client certificate are mounted as files instead, see
+    /// [`Self::client_authentication_volumes_and_mounts`].
+    pub fn client_authentication_env_var_mounts(
+        secret_name: String,
+        method: ClientAuthenticationMethod,
+    ) -> Vec<EnvVar> {
+        let (client_id_env_var, _) = Self::client_credentials_env_names(&secret_name);
+        let env_vars = Self::client_credentials_env_var_mounts(secret_name);
+
+        match method {
+            ClientAuthenticationMethod::ClientSecretBasic
+            | ClientAuthenticationMethod::ClientSecretPost
+            | ClientAuthenticationMethod::ClientSecretJwt => env_vars,
+            ClientAuthenticationMethod::PrivateKeyJwt
+            | ClientAuthenticationMethod::TlsClientAuth
+            | ClientAuthenticationMethod::None => env_vars
+                .into_iter()
+                .filter(|env_var| env_var.name == client_id_env_var)
+                .collect(),
+        }
+    }
+
+    /// Returns the volumes and mounts which are required by the given client authentication
+    /// method.
+    ///
+    /// * [`ClientAuthenticationMethod::PrivateKeyJwt`] mounts the client credentials Secret, so
+    ///   that the private key is available at [`Self::client_private_key_mount_path`].
+    /// * [`ClientAuthenticationMethod::TlsClientAuth`] mounts a client certificate from the given
+    ///   SecretClass at [`Self::client_cert_mount_paths`].
+    pub fn client_authentication_volumes_and_mounts(
+        secret_name: &str,
+        method: ClientAuthenticationMethod,
+        client_cert_secret_class: Option<&str>,
+    ) -> Result<(Vec<Volume>, Vec<VolumeMount>)> {
+        let mut volumes = Vec::new();
+        let mut mounts = Vec::new();
+
+        match method {
+            ClientAuthenticationMethod::PrivateKeyJwt => {
+                let volume_name = format!("{secret_name}-client-credentials");
+                volumes.push(
+                    VolumeBuilder::new(&volume_name)
+                        .with_secret(secret_name, false)
+                        .build(),
+                );
+                mounts.push(
+                    VolumeMountBuilder::new(
+                        volume_name,
+                        Self::client_credentials_volume_mount_path(secret_name),
+                    )
+                    .build(),
+                );
+            }
+            ClientAuthenticationMethod::TlsClientAuth => {
+                let secret_class =
+                    client_cert_secret_class.context(MissingClientCertSecretClassSnafu)?;
+                let volume_name = format!("{secret_class}-client-cert");
+                let volume = SecretClassVolume::new(secret_class.to_owned(), None)
+                    .to_volume(&volume_name, SecretClassVolumeProvisionParts::PublicPrivate)
+                    .context(ClientCertSecretClassVolumeSnafu)?;
+
+                volumes.push(volume);
+                mounts.push(
+                    VolumeMountBuilder::new(
+                        volume_name,
+                        format!("{SECRET_BASE_PATH}/{secret_class}-client-cert"),
+                    )
+                    .build(),
+                );
+            }
+            ClientAuthenticationMethod::ClientSecretBasic
+            | ClientAuthenticationMethod::ClientSecretPost
+            | ClientAuthenticationMethod::ClientSecretJwt
+            | ClientAuthenticationMethod::None => {}
+        }
+
+        Ok((volumes, mounts))
+    }
+
     pub(super) fn default_root_path() -> String {
         "/".to_string()
     }
 }
 
+impl TokenValidation {
+    pub const DEFAULT_CLOCK_SKEW: Duration = Duration::from_secs(60);
+}
+
+impl TokenIntrospection {
+    /// Returns the env variables with the credentials of the introspection client.
+    ///
+    /// See [`AuthenticationProvider::client_authentication_env_var_mounts`] for details.
+    pub fn client_credentials_env_var_mounts(&self) -> Vec<EnvVar> {
+        AuthenticationProvider::client_authentication_env_var_mounts(
+            self.client_credentials_secret_ref.clone(),
+            self.client_authentication_method,
+        )
+    }
+
+    /// Returns the volumes and mounts required to authenticate the introspection client.
+    ///
+    /// See [`AuthenticationProvider::client_authentication_volumes_and_mounts`] for details.
+    pub fn volumes_and_mounts(&self) -> Result<(Vec<Volume>, Vec<VolumeMount>)> {
+        AuthenticationProvider::client_authentication_volumes_and_mounts(
+            &self.client_credentials_secret_ref,
+            self.client_authentication_method,
+            self.client_cert_secret_class.as_deref(),
+        )
+    }
+}
+
 #[cfg(test)]
 mod test {
     use rstest::rstest;
@@ -378,4 +546,158 @@ mod test {
             ],
         );
     }
+
+    #[test]
+    fn token_validation_defaults() {
+        let oidc = serde_yaml::from_str::<AuthenticationProvider>(
+            "
+            hostname: my.keycloak.server
+            rootPath: /realms/sdp
+            scopes: [openid]
+            principalClaim: preferred_username
+            ",
+        )
+        .unwrap();
+
+        assert_eq!(oidc.token_validation, TokenValidation::default());
+        assert_eq!(
+            oidc.accepted_issuers().unwrap(),
+            vec!["http://my.keycloak.server/realms/sdp".to_string()]
+        );
+        assert_eq!(oidc.clock_skew(), Duration::from_secs(60));
+    }
+
+    #[test]
+    fn token_validation() {
+        let oidc = serde_yaml::from_str::<AuthenticationProvider>(
+            "
+            hostname: my.keycloak.server
+            scopes: [openid]
+            principalClaim: preferred_username
+            tokenValidation:
+              jwksUri: https://my.keycloak.server/realms/sdp/protocol/openid-connect/certs
+              audiences: [trino]
+              issuers: [https://my.keycloak.server/realms/sdp]
+              clockSkew: 30s
+              introspection:
+                clientCredentialsSecret: my-introspection-client
+                clientAuthenticationMethod: tls_client_auth
+                clientCertSecretClass: tls
+            ",
+        )
+        .unwrap();
+
+        assert_eq!(
+            oidc.token_validation.jwks_uri.as_ref().map(Url::as_str),
+            Some("https://my.keycloak.server/realms/sdp/protocol/openid-connect/certs")
+        );
+        assert_eq!(oidc.token_validation.audiences, vec!["trino".to_string()]);
+        assert_eq!(
+            oidc.accepted_issuers().unwrap(),
+            vec!["https://my.keycloak.server/realms/sdp".to_string()]
+        );
+        assert_eq!(oidc.clock_skew(), Duration::from_secs(30));
+
+        let introspection = oidc.token_validation.introspection.unwrap();
+        assert_eq!(introspection.endpoint, None);
+        assert_eq!(
+            introspection
+                .client_credentials_env_var_mounts()
+                .iter()
+                .map(|e| e.name.clone())
+                .collect::<Vec<_>>(),
+            vec![AuthenticationProvider::client_credentials_env_names("my-introspection-client").0],
+        );
+
+        let (volumes, mounts) = introspection.volumes_and_mounts().unwrap();
+        assert_eq!(
+            volumes.iter().map(|v| v.name.clone()).collect::<Vec<_>>(),
+            vec!["tls-client-cert".to_string()],
+        );
+        assert_eq!(
+            mounts
+                .iter()
+                .map(|m| m.mount_path.clone())
+                .collect::<Vec<_>>(),
+            vec!["/stackable/secrets/tls-client-cert".to_string()],
+        );
+        assert_eq!(
+            AuthenticationProvider::client_cert_mount_paths("tls"),
+            (
+                "/stackable/secrets/tls-client-cert/tls.crt".to_string(),
+                "/stackable/secrets/tls-client-cert/tls.key".to_string()
+            )
+        );
+    }
+
+    #[rstest]
+    #[case(ClientAuthenticationMethod::ClientSecretBasic, 2)]
+    #[case(ClientAuthenticationMethod::ClientSecretPost, 2)]
+    #[case(ClientAuthenticationMethod::ClientSecretJwt, 2)]
+    #[case(ClientAuthenticationMethod::PrivateKeyJwt, 1)]
+    #[case(ClientAuthenticationMethod::TlsClientAuth, 1)]
+    #[case(ClientAuthenticationMethod::None, 1)]
+    fn client_authentication_env_vars(
+        #[case] method: ClientAuthenticationMethod,
+        #[case] expected_env_var_count: usize,
+    ) {
+        let env_var_mounts = AuthenticationProvider::client_authentication_env_var_mounts(
+            "my-keycloak-client".to_string(),
+            method,
+        );
+        let (client_id_env_var, _) =
+            AuthenticationProvider::client_credentials_env_names("my-keycloak-client");
+        assert_eq!(env_var_mounts.len(), expected_env_var_count);
+        assert!(
+            env_var_mounts
+                .iter()
+                .any(|env_var| env_var.name == client_id_env_var)
+        );
+    }
+
+    #[test]
+    fn client_authentication_volumes_and_mounts() {
+        let (volumes, mounts) = AuthenticationProvider::client_authentication_volumes_and_mounts(
+            "my-keycloak-client",
+            ClientAuthenticationMethod::PrivateKeyJwt,
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            volumes
+                .iter()
+                .map(|v| v.secret.clone().unwrap().secret_name.unwrap())
+                .collect::<Vec<_>>(),
+            vec!["my-keycloak-client".to_string()],
+        );
+        assert_eq!(
+            mounts
+                .iter()
+                .map(|m| m.mount_path.clone())
+                .collect::<Vec<_>>(),
+            vec!["/stackable/secrets/my-keycloak-client".to_string()],
+        );
+        assert_eq!(
+            AuthenticationProvider::client_private_key_mount_path("my-keycloak-client"),
+            "/stackable/secrets/my-keycloak-client/privateKey"
+        );
+
+        assert_eq!(
+            AuthenticationProvider::client_authentication_volumes_and_mounts(
+                "my-keycloak-client",
+                ClientAuthenticationMethod::ClientSecretBasic,
+                Some("tls"),
+            ),
+            Ok((vec![], vec![]))
+        );
+
+        assert_eq!(
+            AuthenticationProvider::client_authentication_volumes_and_mounts(
+                "my-keycloak-client",
+                ClientAuthenticationMethod::TlsClientAuth,
+                None,
+            ),
+            Err(Error::MissingClientCertSecretClass)
+        );
+    }
 }
@@ -22,7 +22,7 @@ pub mod versioned {
         pub use v1alpha1_impl::{Error, GitSyncResources};
     }
     pub mod v1alpha2 {
-        pub use v1alpha2_impl::{Error, GitSyncResources};
+        pub use v1alpha2_impl::{Error, GitSyncCommitStatus, GitSyncResources};
     }
 
     #[derive(Clone, Debug, Deserialize, JsonSchema, PartialEq, Eq, Serialize)]
@@ -63,6 +63,60 @@ pub mod versioned {
         #[serde(default)]
         pub git_sync_conf: BTreeMap<String, String>,
 
+        /// Paths of the repository to check out; defaults to the whole repository.
+        ///
+        /// The paths are patterns in the non-cone mode of the [sparse checkout], e.g. `/dags/` or
+        /// `!/dags/tests/`. Only the matching files are checked out, which reduces the size of the
+        /// checkout of large monorepos.
+        ///
+        /// Since git-sync v4.x.x this field is mapped to the flag `--sparse-checkout-file`.
+        ///
+        /// [sparse checkout]: https://git-scm.com/docs/git-sparse-checkout#_internalsnon_cone_problems
+        #[versioned(added(since = "v1alpha2"))]
+        #[serde(default)]
+        pub sparse_checkout: Vec<String>,
+
+        /// How Git submodules are synchronized; defaults to `recursive`.
+        ///
+        /// Since git-sync v4.x.x this field is mapped to the flag `--submodules`.
+        #[versioned(added(since = "v1alpha2"))]
+        pub submodules: Option<Submodules>,
+
+        /// Pins the synchronization to a fixed commit or tag instead of the head of the branch,
+        /// which makes deployments reproducible. If set, then the branch is ignored.
+        ///
+        /// Since git-sync v4.x.x this field is mapped to the flag `--ref`.
+        #[versioned(added(since = "v1alpha2"))]
+        pub pin: Option<Pin>,
+
+        /// Reports the synchronized commit in the annotation `git-sync.stackable.tech/commit-<index>`
+        /// of the Pod, where `<index>` is the position of this entry in the list of GitSyncs.
+        ///
+        /// The annotation is set by an exechook of git-sync after every successful synchronization,
+        /// which patches the Pod with `curl` and the token of the ServiceAccount. Therefore, `curl`
+        /// must be available in the git-sync image, the ServiceAccount token must be mounted into
+        /// the Pod and the ServiceAccount must be allowed to `patch` Pods.
+        #[versioned(added(since = "v1alpha2"))]
+        #[serde(default)]
+        pub report_commit: bool,
+
+        /// The port of the git-sync HTTP server, which serves the health endpoint and Prometheus
+        /// metrics. The server is disabled if no port is given.
+        ///
+        /// Since git-sync v4.x.x this field is mapped to the flags `--http-bind` and `--http-metrics`.
+        #[versioned(added(since = "v1alpha2"))]
+        pub http_port: Option<u16>,
+
+        /// Triggers a synchronization whenever the annotation
+        /// `git-sync.stackable.tech/sync-requested` of the Pod changes, e.g. when a push
+        /// notification was received, instead of waiting for the next synchronization interval.
+        ///
+        /// The kubelet propagates changed annotations into the container with a delay of up to a
+        /// minute.
+        #[versioned(added(since = "v1alpha2"))]
+        #[serde(default)]
+        pub sync_on_request: bool,
+
         /// An optional secret used for git access.
         //
         // FIXME: The roundtrip looses data when private keys are used.
@@ -101,6 +155,92 @@ pub mod versioned {
         ///
         /// [documentation]: https://github.com/kubernetes/git-sync/tree/v4.2.4?tab=readme-ov-file#manual
         SshPrivateKeySecretName(String),
+
+        /// Authentication as a GitHub App installation.
+        ///
+        /// git-sync requests short-lived installation tokens with the private key of the GitHub App
+        /// and refreshes them before they expire. This requires git-sync v4.4.0 or newer.
+        GithubApp(GithubAppCredentials),
+
+        /// Authentication with a token which is read from a Secret before every Git operation.
+        ///
+        /// The token can be rotated by an external agent. Changes of the Secret are propagated to
+        /// the mounted file by Kubernetes and picked up without restarting git-sync.
+        TokenFile(TokenFileCredentials),
+    }
+
+    /// Credentials of a GitHub App installation
+    #[derive(Clone, Debug, Deserialize, Eq, JsonSchema, PartialEq, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct GithubAppCredentials {
+        /// The ID of the GitHub App.
+        pub application_id: u64,
+
+        /// The ID of the installation of the GitHub App in the organization or repository.
+        pub installation_id: u64,
+
+        /// The name of the Secret containing the private key of the GitHub App.
+        ///
+        /// The referenced Secret must include the field `privateKey` with the PEM-encoded key.
+        pub private_key_secret_name: String,
+
+        /// The URL of the GitHub API; defaults to `https://api.github.com/`.
+        ///
+        /// It must be set for GitHub Enterprise Server, e.g. `https://github.example.com/api/v3/`.
+        pub base_url: Option<Url>,
+    }
+
+    /// Credentials with a token read from a file
+    #[derive(Clone, Debug, Deserialize, Eq, JsonSchema, PartialEq, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct TokenFileCredentials {
+        /// The name of the Secret containing the token.
+        ///
+        /// The referenced Secret must include the field `token`.
+        pub secret_name: String,
+
+        /// The user name which is sent along with the token; defaults to `x-access-token`.
+        ///
+        /// The default works for GitHub tokens. GitLab accepts any user name together with
+        /// personal access tokens, but requires `oauth2` for OAuth tokens.
+        #[serde(default = "default_token_user")]
+        pub user: String,
+    }
+
+    /// How Git submodules are synchronized; defaults to `recursive`.
+    ///
+    /// Since git-sync v4.x.x this field is mapped to the flag `--submodules`.
+    // This description replaces the one of the `submodules` field in the CRD, so it must be complete.
+    #[derive(
+        strum::Display, Clone, Copy, Debug, Deserialize, Eq, JsonSchema, PartialEq, Serialize,
+    )]
+    #[serde(rename_all = "camelCase")]
+    #[strum(serialize_all = "camelCase")]
+    pub enum Submodules {
+        /// Synchronize all submodules recursively.
+        Recursive,
+
+        /// Synchronize only the submodules of the repository but not their submodules.
+        Shallow,
+
+        /// Do not synchronize submodules.
+        Off,
+    }
+
+    #[derive(Clone, Debug, Deserialize, Eq, JsonSchema, PartialEq, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    #[schemars(rename_all = "camelCase")]
+    pub enum Pin {
+        /// The full SHA-1 or SHA-256 hash of the commit to synchronize.
+        ///
+        /// Abbreviated hashes are not supported.
+        Commit(String),
+
+        /// The name of the tag to synchronize, e.g. `v1.0.0`.
+        ///
+        /// The name must be a valid Git reference name and may only contain alphanumeric
+        /// characters, `.`, `_`, `-`, `+` and `/`.
+        Tag(String),
     }
 }
 
@@ -120,6 +260,10 @@ pub(crate) fn default_wait() -> Duration {
     Duration::from_secs(20)
 }
 
+pub(crate) fn default_token_user() -> String {
+    "x-access-token".to_string()
+}
+
 pub fn credentials_to_secret(input: Option<Credentials>) -> Option<String> {
     if let Some(Credentials::BasicAuthSecretName(credentials_secret)) = input {
         Some(credentials_secret)
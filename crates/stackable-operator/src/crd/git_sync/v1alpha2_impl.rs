@@ -1,12 +1,26 @@
-use std::{collections::BTreeMap, fmt::Write as _, path::PathBuf, str::FromStr};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::PathBuf,
+    str::FromStr,
+};
 
-use k8s_openapi::api::core::v1::{Container, EmptyDirVolumeSource, EnvVar, Volume, VolumeMount};
+use k8s_openapi::api::{
+    core::v1::{
+        Container, DownwardAPIVolumeFile, DownwardAPIVolumeSource, EmptyDirVolumeSource, EnvVar,
+        ObjectFieldSelector, Pod, Volume, VolumeMount,
+    },
+    rbac::v1::PolicyRule,
+};
+use kube::ResourceExt;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 use snafu::{ResultExt, Snafu, ensure};
 use strum::{EnumDiscriminants, IntoStaticStr};
+use url::Url;
 
 use crate::{
     builder::pod::{
-        container::ContainerBuilder,
+        container::{ContainerBuilder, FieldPathEnvVar},
         resources::ResourceRequirementsBuilder,
         volume::{VolumeBuilder, VolumeMountBuilder},
     },
@@ -16,7 +30,7 @@ use crate::{
         secret_class::{SecretClassVolume, SecretClassVolumeProvisionParts},
         tls_verification::{CaCert, TlsServerVerification, TlsVerification},
     },
-    crd::git_sync::v1alpha2::{Credentials, GitSync},
+    crd::git_sync::v1alpha2::{Credentials, GitSync, Pin},
     product_logging::{
         framework::capture_shell_output,
         spec::{ContainerLogConfig, ContainerLogConfigChoice},
@@ -40,6 +54,17 @@ pub const CA_CERT_VOLUME_NAME_PREFIX: &str = "ca-cert";
 pub const CA_CERT_MOUNT_PATH_PREFIX: &str = "/stackable/gitca";
 pub const GIT_SSL_CA_INFO_CONFIG_KEY: &str = "http.sslCAInfo";
 pub const GIT_SSL_VERIFY: &str = "http.sslverify";
+pub const GIT_SYNC_SPARSE_CHECKOUT_FILE: &str = "/tmp/sparse-checkout";
+pub const GITHUB_APP_VOLUME_NAME_PREFIX: &str = "github-app-key";
+pub const GITHUB_APP_MOUNT_PATH_PREFIX: &str = "/stackable/gitapp";
+pub const TOKEN_FILE_VOLUME_NAME_PREFIX: &str = "git-token";
+pub const TOKEN_FILE_MOUNT_PATH_PREFIX: &str = "/stackable/gittoken";
+pub const GIT_CREDENTIAL_HELPER_CONFIG_KEY: &str = "credential.helper";
+pub const COMMIT_ANNOTATION_KEY_PREFIX: &str = "git-sync.stackable.tech/commit";
+pub const SYNC_REQUESTED_ANNOTATION_KEY: &str = "git-sync.stackable.tech/sync-requested";
+pub const REPORT_COMMIT_EXECHOOK: &str = "/tmp/report-commit";
+pub const SYNC_TRIGGER_VOLUME_NAME: &str = "git-sync-trigger";
+pub const SYNC_TRIGGER_MOUNT_PATH: &str = "/stackable/git-sync-trigger";
 
 #[derive(Snafu, Debug, EnumDiscriminants)]
 #[strum_discriminants(derive(IntoStaticStr))]
@@ -82,6 +107,30 @@ pub enum Error {
 
     #[snafu(display("scheme does not match tls setting"))]
     SchemeMismatch { scheme: String },
+
+    #[snafu(display(
+        "invalid sparse checkout path {path:?}, it must neither be empty nor contain line breaks"
+    ))]
+    InvalidSparseCheckoutPath { path: String },
+
+    #[snafu(display(
+        "invalid commit {commit:?}, it must be a full SHA-1 or SHA-256 hash in hexadecimal notation"
+    ))]
+    InvalidCommitHash { commit: String },
+
+    #[snafu(display("invalid tag name {tag:?}"))]
+    InvalidTagName { tag: String },
+
+    #[snafu(display(
+        "invalid token user {user:?}, it must only contain alphanumeric characters, `.`, `_`, `-` and `@`"
+    ))]
+    InvalidTokenUser { user: String },
+
+    #[snafu(display("invalid HTTP port {port}, it must not be 0"))]
+    InvalidHttpPort { port: u16 },
+
+    #[snafu(display("the HTTP port {port} is used by several git-sync containers"))]
+    DuplicateHttpPort { port: u16 },
 }
 
 /// Kubernetes resources generated from `GitSync` specifications which should be added to the Pod.
@@ -107,6 +156,16 @@ pub struct GitSyncResources {
 
     // GitSync volumes containing Ca certificates
     pub git_ca_cert_volumes: Vec<Volume>,
+
+    /// GitSync volumes containing the private keys of GitHub Apps and the token files
+    pub git_credential_volumes: Vec<Volume>,
+
+    /// GitSync volume exposing the Pod annotations which trigger a synchronization
+    pub git_sync_trigger_volumes: Vec<Volume>,
+
+    /// RBAC rules which must be granted to the ServiceAccount of the Pod, e.g. with
+    /// [`RbacPolicyBuilder::add_rule`](crate::commons::rbac::RbacPolicyBuilder::add_rule)
+    pub git_sync_policy_rules: Vec<PolicyRule>,
 }
 
 impl GitSyncResources {
@@ -135,6 +194,15 @@ impl GitSyncResources {
         log_volume_name: &str,
         container_log_config: &ContainerLogConfig,
     ) -> Result<Self, Error> {
+        let mut http_ports = BTreeSet::new();
+        for git_sync in git_syncs {
+            Self::validate_git_sync(git_sync)?;
+
+            if let Some(port) = git_sync.http_port {
+                ensure!(http_ports.insert(port), DuplicateHttpPortSnafu { port });
+            }
+        }
+
         let mut resources = Self::default();
 
         for (i, git_sync) in git_syncs.iter().enumerate() {
@@ -178,6 +246,53 @@ impl GitSyncResources {
                         format!("{SSH_MOUNT_PATH_PREFIX}-{i}/knownHosts"),
                     );
             }
+            if let Some(Credentials::GithubApp(github_app)) = &git_sync.credentials {
+                env_vars = env_vars
+                    .with_value(
+                        &EnvVarName::from_str("GITSYNC_GITHUB_APP_APPLICATION_ID")
+                            .context(InvalidEnvVarNameSnafu)?,
+                        github_app.application_id.to_string(),
+                    )
+                    .with_value(
+                        &EnvVarName::from_str("GITSYNC_GITHUB_APP_INSTALLATION_ID")
+                            .context(InvalidEnvVarNameSnafu)?,
+                        github_app.installation_id.to_string(),
+                    )
+                    .with_value(
+                        &EnvVarName::from_str("GITSYNC_GITHUB_APP_PRIVATE_KEY_FILE")
+                            .context(InvalidEnvVarNameSnafu)?,
+                        format!("{GITHUB_APP_MOUNT_PATH_PREFIX}-{i}/privateKey"),
+                    );
+                if let Some(base_url) = &github_app.base_url {
+                    env_vars = env_vars.with_value(
+                        &EnvVarName::from_str("GITSYNC_GITHUB_BASE_URL")
+                            .context(InvalidEnvVarNameSnafu)?,
+                        base_url.as_str(),
+                    );
+                }
+            }
+
+            if git_sync.report_commit {
+                let policy_rule = Self::report_commit_policy_rule();
+                if !resources.git_sync_policy_rules.contains(&policy_rule) {
+                    resources.git_sync_policy_rules.push(policy_rule);
+                }
+
+                env_vars = env_vars
+                    .with_field_path(
+                        &EnvVarName::from_str("POD_NAME").context(InvalidEnvVarNameSnafu)?,
+                        &FieldPathEnvVar::Name,
+                    )
+                    .with_field_path(
+                        &EnvVarName::from_str("POD_NAMESPACE").context(InvalidEnvVarNameSnafu)?,
+                        &FieldPathEnvVar::Namespace,
+                    )
+                    .with_value(
+                        &EnvVarName::from_str("COMMIT_ANNOTATION_KEY")
+                            .context(InvalidEnvVarNameSnafu)?,
+                        format!("{COMMIT_ANNOTATION_KEY_PREFIX}-{i}"),
+                    );
+            }
 
             for extra_env_var in extra_env_vars {
                 env_vars = env_vars
@@ -214,6 +329,36 @@ impl GitSyncResources {
                 git_sync_container_volume_mounts.push(ssh_volume_mount);
             }
 
+            if let Some(Credentials::GithubApp(_)) = git_sync.credentials {
+                let github_app_volume_mount = VolumeMountBuilder::new(
+                    format!("{GITHUB_APP_VOLUME_NAME_PREFIX}-{i}"),
+                    format!("{GITHUB_APP_MOUNT_PATH_PREFIX}-{i}"),
+                )
+                .build();
+                git_sync_container_volume_mounts.push(github_app_volume_mount);
+            }
+
+            // The Secret must not be mounted with a subPath, otherwise updates of the token are not
+            // propagated to the container.
+            let token_file_path = if let Some(Credentials::TokenFile(_)) = git_sync.credentials {
+                let token_volume_mount = VolumeMountBuilder::new(
+                    format!("{TOKEN_FILE_VOLUME_NAME_PREFIX}-{i}"),
+                    format!("{TOKEN_FILE_MOUNT_PATH_PREFIX}-{i}"),
+                )
+                .build();
+                git_sync_container_volume_mounts.push(token_volume_mount);
+                Some(format!("{TOKEN_FILE_MOUNT_PATH_PREFIX}-{i}/token"))
+            } else {
+                None
+            };
+
+            if git_sync.sync_on_request {
+                let sync_trigger_volume_mount =
+                    VolumeMountBuilder::new(SYNC_TRIGGER_VOLUME_NAME, SYNC_TRIGGER_MOUNT_PATH)
+                        .build();
+                git_sync_container_volume_mounts.push(sync_trigger_volume_mount);
+            }
+
             // Check tls/scheme compatibility early
             let scheme = git_sync.repo.scheme();
             let ca_cert_path = match &git_sync.tls.tls {
@@ -261,6 +406,7 @@ impl GitSyncResources {
                 &git_sync_container_volume_mounts,
                 container_log_config,
                 ca_cert_path.as_deref(),
+                token_file_path.as_deref(),
             )?;
 
             let init_container = Self::create_git_sync_container(
@@ -272,6 +418,7 @@ impl GitSyncResources {
                 &git_sync_container_volume_mounts,
                 container_log_config,
                 ca_cert_path.as_deref(),
+                token_file_path.as_deref(),
             )?;
 
             let volume = VolumeBuilder::new(volume_name.clone())
@@ -311,6 +458,26 @@ impl GitSyncResources {
                 resources.git_ssh_volumes.push(ssh_secret_volume);
             }
 
+            match &git_sync.credentials {
+                Some(Credentials::GithubApp(github_app)) => {
+                    let github_app_secret_volume =
+                        VolumeBuilder::new(format!("{GITHUB_APP_VOLUME_NAME_PREFIX}-{i}"))
+                            .with_secret(&github_app.private_key_secret_name, false)
+                            .build();
+                    resources
+                        .git_credential_volumes
+                        .push(github_app_secret_volume);
+                }
+                Some(Credentials::TokenFile(token_file)) => {
+                    let token_secret_volume =
+                        VolumeBuilder::new(format!("{TOKEN_FILE_VOLUME_NAME_PREFIX}-{i}"))
+                            .with_secret(&token_file.secret_name, false)
+                            .build();
+                    resources.git_credential_volumes.push(token_secret_volume);
+                }
+                _ => {}
+            }
+
             if let Some(secret_class) = git_sync.tls.tls_ca_cert_secret_class() {
                 let secret_class_volume = SecretClassVolume::new(secret_class.clone(), None);
                 let volume_name = format!("{CA_CERT_VOLUME_NAME_PREFIX}-{i}");
@@ -322,9 +489,154 @@ impl GitSyncResources {
             }
         }
 
+        if git_syncs.iter().any(|git_sync| git_sync.sync_on_request) {
+            // Annotations exposed via environment variables are not updated, so a volume is used.
+            let sync_trigger_volume = VolumeBuilder::new(SYNC_TRIGGER_VOLUME_NAME)
+                .downward_api(DownwardAPIVolumeSource {
+                    items: Some(vec![DownwardAPIVolumeFile {
+                        path: "annotations".to_string(),
+                        field_ref: Some(ObjectFieldSelector {
+                            field_path: "metadata.annotations".to_string(),
+                            ..ObjectFieldSelector::default()
+                        }),
+                        ..DownwardAPIVolumeFile::default()
+                    }]),
+                    ..DownwardAPIVolumeSource::default()
+                })
+                .build();
+            resources.git_sync_trigger_volumes.push(sync_trigger_volume);
+        }
+
         Ok(resources)
     }
 
+    /// Validates the settings of the given `GitSync` which are not checked by the CRD schema.
+    fn validate_git_sync(git_sync: &GitSync) -> Result<(), Error> {
+        if let Some(port) = git_sync.http_port {
+            ensure!(port != 0, InvalidHttpPortSnafu { port });
+        }
+
+        for path in &git_sync.sparse_checkout {
+            ensure!(
+                !path.trim().is_empty() && !path.contains(['\n', '\r']),
+                InvalidSparseCheckoutPathSnafu { path }
+            );
+        }
+
+        match &git_sync.pin {
+            Some(Pin::Commit(commit)) => ensure!(
+                matches!(commit.len(), 40 | 64) && commit.chars().all(|c| c.is_ascii_hexdigit()),
+                InvalidCommitHashSnafu { commit }
+            ),
+            Some(Pin::Tag(tag)) => ensure!(is_valid_tag_name(tag), InvalidTagNameSnafu { tag }),
+            None => {}
+        }
+
+        // The user is rendered into a shell function of the credential helper.
+        if let Some(Credentials::TokenFile(token_file)) = &git_sync.credentials {
+            let user = &token_file.user;
+            ensure!(
+                !user.is_empty()
+                    && user
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-' | '@')),
+                InvalidTokenUserSnafu { user }
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Creates a shell command which writes the given paths to the sparse-checkout file
+    fn create_sparse_checkout_file_command(paths: &[String]) -> String {
+        let quoted_paths = paths
+            .iter()
+            .map(|path| format!("'{}'", path.replace('\'', r"'\''")))
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        format!("printf '%s\\n' {quoted_paths} > {GIT_SYNC_SPARSE_CHECKOUT_FILE}")
+    }
+
+    /// Returns the RBAC rule which allows the exechook of
+    /// [`Self::create_report_commit_exechook_command`] to annotate its Pod
+    ///
+    /// The name of the Pod is not known in advance, so the rule cannot be restricted to it.
+    fn report_commit_policy_rule() -> PolicyRule {
+        PolicyRule {
+            api_groups: Some(vec![String::new()]),
+            resources: Some(vec!["pods".to_owned()]),
+            verbs: vec!["patch".to_owned()],
+            ..PolicyRule::default()
+        }
+    }
+
+    /// Creates a shell command which writes the exechook script for git-sync
+    ///
+    /// git-sync runs the script after every successful synchronization with the hash of the
+    /// synchronized commit in `GITSYNC_HASH`. The script sets the hash as annotation of the Pod.
+    fn create_report_commit_exechook_command() -> String {
+        format!(
+            r#"cat > {REPORT_COMMIT_EXECHOOK} << 'EOF'
+#!/bin/bash
+set -euo pipefail
+SERVICE_ACCOUNT_DIR=/var/run/secrets/kubernetes.io/serviceaccount
+curl --silent --show-error --fail --request PATCH \
+  --cacert "$SERVICE_ACCOUNT_DIR/ca.crt" \
+  --header "Authorization: Bearer $(cat "$SERVICE_ACCOUNT_DIR/token")" \
+  --header 'Content-Type: application/merge-patch+json' \
+  --data '{{"metadata":{{"annotations":{{"'"$COMMIT_ANNOTATION_KEY"'":"'"$GITSYNC_HASH"'"}}}}}}' \
+  "https://kubernetes.default.svc/api/v1/namespaces/$POD_NAMESPACE/pods/$POD_NAME" > /dev/null
+EOF
+chmod +x {REPORT_COMMIT_EXECHOOK}"#
+        )
+    }
+
+    /// Creates a shell command which runs the given git-sync command in the background until the
+    /// container is terminated
+    fn create_background_git_sync_command(git_sync_command: &str, sync_on_request: bool) -> String {
+        if sync_on_request {
+            format!(
+                "{COMMON_BASH_TRAP_FUNCTIONS}
+prepare_signal_handlers
+{git_sync_command} &
+git_sync_pid=$!
+{sync_request_watcher} &
+wait_for_termination $git_sync_pid",
+                sync_request_watcher = Self::create_sync_request_watcher_command()
+            )
+        } else {
+            format!(
+                "{COMMON_BASH_TRAP_FUNCTIONS}
+prepare_signal_handlers
+{git_sync_command} &
+wait_for_termination $!"
+            )
+        }
+    }
+
+    /// Creates a shell command which sends `SIGHUP` to git-sync whenever the annotation
+    /// [`SYNC_REQUESTED_ANNOTATION_KEY`] of the Pod changes
+    ///
+    /// The PID of git-sync is expected in `git_sync_pid`.
+    fn create_sync_request_watcher_command() -> String {
+        format!(
+            r#"(
+    set +x
+    read_sync_request() {{ grep "^{SYNC_REQUESTED_ANNOTATION_KEY}=" {SYNC_TRIGGER_MOUNT_PATH}/annotations || true; }}
+    last_sync_request=$(read_sync_request)
+    while sleep 5; do
+        sync_request=$(read_sync_request)
+        if [ "$sync_request" != "$last_sync_request" ]; then
+            last_sync_request=$sync_request
+            echo "Synchronization requested"
+            kill -HUP $git_sync_pid
+        fi
+    done
+)"#
+        )
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn create_git_sync_container(
         container_name: &str,
@@ -335,9 +647,17 @@ impl GitSyncResources {
         volume_mounts: &[VolumeMount],
         container_log_config: &ContainerLogConfig,
         ca_cert_path: Option<&str>,
+        token_file_path: Option<&str>,
     ) -> Result<k8s_openapi::api::core::v1::Container, Error> {
-        let container = ContainerBuilder::new(container_name)
-            .context(InvalidContainerNameSnafu)?
+        let mut container_builder =
+            ContainerBuilder::new(container_name).context(InvalidContainerNameSnafu)?;
+
+        if let (Some(port), false) = (git_sync.http_port, one_time) {
+            // Port names must be unique within the Pod, so the container name is used.
+            container_builder.add_container_port(container_name, port.into());
+        }
+
+        let container = container_builder
             .image_from_product_image(resolved_product_image)
             .command(vec![
                 "/bin/bash".to_string(),
@@ -352,6 +672,7 @@ impl GitSyncResources {
                 one_time,
                 container_log_config,
                 ca_cert_path,
+                token_file_path,
             )])
             .add_env_vars(env_vars)
             .add_volume_mounts(volume_mounts.to_vec())
@@ -368,16 +689,21 @@ impl GitSyncResources {
         Ok(container)
     }
 
-    fn create_git_sync_shell_script(
-        container_name: &str,
+    /// Creates the git-sync arguments which are defined by the typed `GitSync` fields and cannot
+    /// be overridden with `gitSyncConf`
+    fn create_internal_git_sync_args(
         git_sync: &GitSync,
         one_time: bool,
-        container_log_config: &ContainerLogConfig,
-        ca_cert_path: Option<&str>,
-    ) -> String {
-        let internal_args = BTreeMap::from([
+    ) -> BTreeMap<String, String> {
+        let git_ref = match &git_sync.pin {
+            Some(Pin::Commit(commit)) => commit.to_ascii_lowercase(),
+            Some(Pin::Tag(tag)) => tag.clone(),
+            None => git_sync.branch.clone(),
+        };
+
+        let mut internal_args = BTreeMap::from([
             ("--repo".to_string(), git_sync.repo.as_str().to_owned()),
-            ("--ref".to_string(), git_sync.branch.clone()),
+            ("--ref".to_string(), git_ref),
             ("--depth".to_string(), git_sync.depth.to_string()),
             (
                 "--period".to_string(),
@@ -388,6 +714,48 @@ impl GitSyncResources {
             ("--one-time".to_string(), one_time.to_string()),
         ]);
 
+        if let Some(submodules) = git_sync.submodules {
+            internal_args.insert("--submodules".to_string(), submodules.to_string());
+        }
+
+        if !git_sync.sparse_checkout.is_empty() {
+            internal_args.insert(
+                "--sparse-checkout-file".to_string(),
+                GIT_SYNC_SPARSE_CHECKOUT_FILE.to_string(),
+            );
+        }
+
+        if git_sync.report_commit {
+            internal_args.insert(
+                "--exechook-command".to_string(),
+                REPORT_COMMIT_EXECHOOK.to_string(),
+            );
+        }
+
+        if !one_time {
+            if let Some(port) = git_sync.http_port {
+                internal_args.insert("--http-bind".to_string(), format!(":{port}"));
+                internal_args.insert("--http-metrics".to_string(), "true".to_string());
+            }
+
+            if git_sync.sync_on_request {
+                internal_args.insert("--sync-on-signal".to_string(), "SIGHUP".to_string());
+            }
+        }
+
+        internal_args
+    }
+
+    fn create_git_sync_shell_script(
+        container_name: &str,
+        git_sync: &GitSync,
+        one_time: bool,
+        container_log_config: &ContainerLogConfig,
+        ca_cert_path: Option<&str>,
+        token_file_path: Option<&str>,
+    ) -> String {
+        let internal_args = Self::create_internal_git_sync_args(git_sync, one_time);
+
         let mut internal_git_config = BTreeMap::from([(
             GIT_SYNC_SAFE_DIR_OPTION.to_owned(),
             GIT_SYNC_ROOT_DIR.to_owned(),
@@ -397,6 +765,20 @@ impl GitSyncResources {
             internal_git_config.insert(GIT_SSL_CA_INFO_CONFIG_KEY.to_owned(), path.to_owned());
         }
 
+        if let (Some(path), Some(Credentials::TokenFile(token_file))) =
+            (token_file_path, &git_sync.credentials)
+        {
+            // Git runs the credential helper for every authentication, so the token is re-read
+            // from the file and rotated tokens are used without restarting git-sync.
+            internal_git_config.insert(
+                GIT_CREDENTIAL_HELPER_CONFIG_KEY.to_owned(),
+                format!(
+                    "!f() {{ echo username={user}; echo password=$(cat {path}); }}; f",
+                    user = token_file.user
+                ),
+            );
+        }
+
         // Tls defaults to webPki but if the user has *explicitly* set this to
         // none then we honour this by deactivating the ssl check.
         if let Some(TlsVerification::None {}) = git_sync.tls.tls.as_ref().map(|t| &t.verification) {
@@ -471,26 +853,93 @@ impl GitSyncResources {
             shell_script.push('\n');
         }
 
+        if !git_sync.sparse_checkout.is_empty() {
+            shell_script.push_str(&Self::create_sparse_checkout_file_command(
+                &git_sync.sparse_checkout,
+            ));
+            shell_script.push('\n');
+        }
+
+        if git_sync.report_commit {
+            shell_script.push_str(&Self::create_report_commit_exechook_command());
+            shell_script.push('\n');
+        }
+
         let git_sync_command = format!("/stackable/git-sync {args_string}");
 
         if one_time {
             shell_script.push_str(&git_sync_command);
         } else {
-            // Run the git-sync command in the background
-            write!(
-                shell_script,
-                "{COMMON_BASH_TRAP_FUNCTIONS}
-prepare_signal_handlers
-{git_sync_command} &
-wait_for_termination $!"
-            )
-            .expect("We can always write to a String");
+            shell_script.push_str(&Self::create_background_git_sync_command(
+                &git_sync_command,
+                git_sync.sync_on_request,
+            ));
         }
 
         shell_script
     }
 }
 
+/// The commit of a repository which a Pod has synchronized
+#[derive(Clone, Debug, Deserialize, Eq, JsonSchema, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitSyncCommitStatus {
+    /// The name of the Pod
+    pub pod: String,
+
+    /// The synchronized repository
+    pub repo: Url,
+
+    /// The hash of the synchronized commit
+    pub commit: String,
+}
+
+impl GitSyncCommitStatus {
+    /// Collects the commits which were reported in the annotations of the given Pods.
+    ///
+    /// Only `GitSync`s with `reportCommit` enabled are considered and Pods which have not reported
+    /// a commit yet are skipped. The result is intended to be used in the status of a custom
+    /// resource.
+    pub fn from_pods(git_syncs: &[GitSync], pods: &[Pod]) -> Vec<Self> {
+        pods.iter()
+            .flat_map(|pod| {
+                git_syncs
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, git_sync)| git_sync.report_commit)
+                    .filter_map(move |(i, git_sync)| {
+                        let commit = pod
+                            .annotations()
+                            .get(&format!("{COMMIT_ANNOTATION_KEY_PREFIX}-{i}"))?;
+                        Some(Self {
+                            pod: pod.name_any(),
+                            repo: git_sync.repo.clone(),
+                            commit: commit.clone(),
+                        })
+                    })
+            })
+            .collect()
+    }
+}
+
+/// Checks if the given tag name is a valid Git reference name which can be safely used in a shell
+/// command.
+///
+/// This is a stricter variant of the rules of `git check-ref-format`.
+fn is_valid_tag_name(tag: &str) -> bool {
+    !tag.is_empty()
+        && tag
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-' | '+' | '/'))
+        && !tag.starts_with(['-', '.', '/'])
+        && !tag.ends_with(['.', '/'])
+        // Git uses the suffix `.lock` for its lock files.
+        && tag.strip_suffix(".lock").is_none()
+        && !tag.contains("..")
+        && !tag.contains("//")
+        && !tag.contains("/.")
+}
+
 #[cfg(test)]
 mod tests {
     use rstest::rstest;
@@ -1223,23 +1672,16 @@ secret:
     }
 
     #[test]
-    #[allow(clippy::too_many_lines)]
-    fn test_git_sync_ca_cert() {
-        let git_sync_spec = r#"
-          # GitSync using SSH
-          - repo: ssh://git@github.com/stackabletech/repo.git
-            branch: trunk
-            gitFolder: ""
-            depth: 3
-            wait: 1m
-            tls:
-              verification:
-                server:
-                  caCert:
-                    secretClass: git-tls-ca
-            gitSyncConf:
-              --rev: HEAD
-          "#;
+    fn test_git_sync_github_app() {
+        let git_sync_spec = r"
+          - repo: https://github.example.com/stackabletech/repo
+            credentials:
+              githubApp:
+                applicationId: 123456
+                installationId: 7890
+                privateKeySecretName: github-app
+                baseUrl: https://github.example.com/api/v3/
+          ";
 
         let git_syncs: Vec<GitSync> = yaml_from_str_singleton_map(git_sync_spec).unwrap();
 
@@ -1253,66 +1695,21 @@ secret:
             pull_secrets: None,
         };
 
-        let extra_env_vars =
-            EnvVarSet::new().with_value(&EnvVarName::from_str_unsafe("VAR1"), "value1");
-
-        let extra_volume_mounts = [VolumeMount {
-            name: "extra-volume".to_string(),
-            mount_path: "/mnt/extra-volume".to_string(),
-            ..VolumeMount::default()
-        }];
-
         let git_sync_resources = GitSyncResources::new(
             &git_syncs,
             &resolved_product_image,
-            &Vec::from(extra_env_vars),
-            &extra_volume_mounts,
+            &[],
+            &[],
             "log-volume",
             &validate(default_container_log_config()).unwrap(),
         )
         .unwrap();
 
-        assert!(git_sync_resources.is_git_sync_enabled());
-
-        assert_eq!(1, git_sync_resources.git_sync_containers.len());
-
         assert_eq!(
-            r#"args:
+            r"args:
 - |-
-  mkdir --parents /stackable/log/git-sync-0 && exec > >(tee /stackable/log/git-sync-0/container.stdout.log) 2> >(tee /stackable/log/git-sync-0/container.stderr.log >&2)
-
-  prepare_signal_handlers()
-  {
-      unset term_child_pid
-      unset term_kill_needed
-      trap 'handle_term_signal' TERM
-  }
-
-  handle_term_signal()
-  {
-      if [ "${term_child_pid}" ]; then
-          kill -TERM "${term_child_pid}" 2>/dev/null
-      else
-          term_kill_needed="yes"
-      fi
-  }
-
-  wait_for_termination()
-  {
-      set +e
-      term_child_pid=$1
-      if [[ -v term_kill_needed ]]; then
-          kill -TERM "${term_child_pid}" 2>/dev/null
-      fi
-      wait ${term_child_pid} 2>/dev/null
-      trap - TERM
-      wait ${term_child_pid} 2>/dev/null
-      set -e
-  }
-
-  prepare_signal_handlers
-  /stackable/git-sync --depth=3 --git-config='http.sslCAInfo:/stackable/gitca-0/ca.crt,safe.directory:/tmp/git' --link=current --one-time=false --period=60s --ref=trunk --repo=ssh://git@github.com/stackabletech/repo.git --rev=HEAD --root=/tmp/git &
-  wait_for_termination $!
+  mkdir --parents /stackable/log/git-sync-0-init && exec > >(tee /stackable/log/git-sync-0-init/container.stdout.log) 2> >(tee /stackable/log/git-sync-0-init/container.stderr.log >&2)
+  /stackable/git-sync --depth=1 --git-config='safe.directory:/tmp/git' --link=current --one-time=true --period=20s --ref=main --repo=https://github.example.com/stackabletech/repo --root=/tmp/git
 command:
 - /bin/bash
 - -x
@@ -1320,11 +1717,17 @@ command:
 - pipefail
 - -c
 env:
-- name: VAR1
-  value: value1
+- name: GITSYNC_GITHUB_APP_APPLICATION_ID
+  value: '123456'
+- name: GITSYNC_GITHUB_APP_INSTALLATION_ID
+  value: '7890'
+- name: GITSYNC_GITHUB_APP_PRIVATE_KEY_FILE
+  value: /stackable/gitapp-0/privateKey
+- name: GITSYNC_GITHUB_BASE_URL
+  value: https://github.example.com/api/v3/
 image: oci.stackable.tech/sdp/product:latest
 imagePullPolicy: Always
-name: git-sync-0
+name: git-sync-0-init
 resources:
   limits:
     cpu: 200m
@@ -1337,29 +1740,275 @@ volumeMounts:
   name: content-from-git-0
 - mountPath: /stackable/log
   name: log-volume
-- mountPath: /mnt/extra-volume
-  name: extra-volume
-- mountPath: /stackable/gitca-0
-  name: ca-cert-0
-"#,
-            serde_yaml::to_string(&git_sync_resources.git_sync_containers.first()).unwrap()
+- mountPath: /stackable/gitapp-0
+  name: github-app-key-0
+",
+            serde_yaml::to_string(&git_sync_resources.git_sync_init_containers.first()).unwrap()
         );
 
-        assert_eq!(1, git_sync_resources.git_sync_init_containers.len());
-
         assert_eq!(
-            r"args:
-- |-
-  mkdir --parents /stackable/log/git-sync-0-init && exec > >(tee /stackable/log/git-sync-0-init/container.stdout.log) 2> >(tee /stackable/log/git-sync-0-init/container.stderr.log >&2)
-  /stackable/git-sync --depth=3 --git-config='http.sslCAInfo:/stackable/gitca-0/ca.crt,safe.directory:/tmp/git' --link=current --one-time=true --period=60s --ref=trunk --repo=ssh://git@github.com/stackabletech/repo.git --rev=HEAD --root=/tmp/git
-command:
-- /bin/bash
-- -x
-- -euo
-- pipefail
-- -c
-env:
-- name: VAR1
+            "- name: github-app-key-0
+  secret:
+    optional: false
+    secretName: github-app
+",
+            serde_yaml::to_string(&git_sync_resources.git_credential_volumes).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_git_sync_token_file() {
+        let git_sync_spec = r"
+          - repo: https://gitlab.example.com/stackabletech/repo
+            credentials:
+              tokenFile:
+                secretName: git-token
+                user: oauth2
+          ";
+
+        let git_syncs: Vec<GitSync> = yaml_from_str_singleton_map(git_sync_spec).unwrap();
+
+        let resolved_product_image = ResolvedProductImage {
+            image: "oci.stackable.tech/sdp/product:latest".to_string(),
+            app_version_label_value: "1.0.0-latest"
+                .parse()
+                .expect("static app version label is always valid"),
+            product_version: "1.0.0".to_string(),
+            image_pull_policy: "Always".to_string(),
+            pull_secrets: None,
+        };
+
+        let git_sync_resources = GitSyncResources::new(
+            &git_syncs,
+            &resolved_product_image,
+            &[],
+            &[],
+            "log-volume",
+            &validate(default_container_log_config()).unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            r"args:
+- |-
+  mkdir --parents /stackable/log/git-sync-0-init && exec > >(tee /stackable/log/git-sync-0-init/container.stdout.log) 2> >(tee /stackable/log/git-sync-0-init/container.stderr.log >&2)
+  /stackable/git-sync --depth=1 --git-config='credential.helper:!f() { echo username=oauth2; echo password=$(cat /stackable/gittoken-0/token); }; f,safe.directory:/tmp/git' --link=current --one-time=true --period=20s --ref=main --repo=https://gitlab.example.com/stackabletech/repo --root=/tmp/git
+command:
+- /bin/bash
+- -x
+- -euo
+- pipefail
+- -c
+env: []
+image: oci.stackable.tech/sdp/product:latest
+imagePullPolicy: Always
+name: git-sync-0-init
+resources:
+  limits:
+    cpu: 200m
+    memory: 64Mi
+  requests:
+    cpu: 100m
+    memory: 64Mi
+volumeMounts:
+- mountPath: /tmp/git
+  name: content-from-git-0
+- mountPath: /stackable/log
+  name: log-volume
+- mountPath: /stackable/gittoken-0
+  name: git-token-0
+",
+            serde_yaml::to_string(&git_sync_resources.git_sync_init_containers.first()).unwrap()
+        );
+
+        assert_eq!(
+            "- name: git-token-0
+  secret:
+    optional: false
+    secretName: git-token
+",
+            serde_yaml::to_string(&git_sync_resources.git_credential_volumes).unwrap()
+        );
+    }
+
+    #[rstest]
+    #[case("x-access-token", true)]
+    #[case("first.last@example.com", true)]
+    #[case("", false)]
+    #[case("user; reboot", false)]
+    #[case("user:password", false)]
+    fn test_git_sync_token_user(#[case] user: &str, #[case] expect_ok: bool) {
+        let git_sync_spec = format!(
+            "
+- repo: https://github.com/stackabletech/repo1
+  credentials:
+    tokenFile:
+      secretName: git-token
+      user: \"{user}\"
+          "
+        );
+
+        let git_syncs: Vec<GitSync> = yaml_from_str_singleton_map(&git_sync_spec).unwrap();
+
+        let resolved_product_image = ResolvedProductImage {
+            image: "oci.stackable.tech/sdp/product:latest".to_string(),
+            app_version_label_value: "1.0.0-latest"
+                .parse()
+                .expect("static app version label is always valid"),
+            product_version: "1.0.0".to_string(),
+            image_pull_policy: "Always".to_string(),
+            pull_secrets: None,
+        };
+
+        let git_sync_resources = GitSyncResources::new(
+            &git_syncs,
+            &resolved_product_image,
+            &[],
+            &[],
+            "log-volume",
+            &validate(default_container_log_config()).unwrap(),
+        );
+        assert_eq!(expect_ok, git_sync_resources.is_ok());
+    }
+
+    #[test]
+    #[allow(clippy::too_many_lines)]
+    fn test_git_sync_ca_cert() {
+        let git_sync_spec = r#"
+          # GitSync using SSH
+          - repo: ssh://git@github.com/stackabletech/repo.git
+            branch: trunk
+            gitFolder: ""
+            depth: 3
+            wait: 1m
+            tls:
+              verification:
+                server:
+                  caCert:
+                    secretClass: git-tls-ca
+            gitSyncConf:
+              --rev: HEAD
+          "#;
+
+        let git_syncs: Vec<GitSync> = yaml_from_str_singleton_map(git_sync_spec).unwrap();
+
+        let resolved_product_image = ResolvedProductImage {
+            image: "oci.stackable.tech/sdp/product:latest".to_string(),
+            app_version_label_value: "1.0.0-latest"
+                .parse()
+                .expect("static app version label is always valid"),
+            product_version: "1.0.0".to_string(),
+            image_pull_policy: "Always".to_string(),
+            pull_secrets: None,
+        };
+
+        let extra_env_vars =
+            EnvVarSet::new().with_value(&EnvVarName::from_str_unsafe("VAR1"), "value1");
+
+        let extra_volume_mounts = [VolumeMount {
+            name: "extra-volume".to_string(),
+            mount_path: "/mnt/extra-volume".to_string(),
+            ..VolumeMount::default()
+        }];
+
+        let git_sync_resources = GitSyncResources::new(
+            &git_syncs,
+            &resolved_product_image,
+            &Vec::from(extra_env_vars),
+            &extra_volume_mounts,
+            "log-volume",
+            &validate(default_container_log_config()).unwrap(),
+        )
+        .unwrap();
+
+        assert!(git_sync_resources.is_git_sync_enabled());
+
+        assert_eq!(1, git_sync_resources.git_sync_containers.len());
+
+        assert_eq!(
+            r#"args:
+- |-
+  mkdir --parents /stackable/log/git-sync-0 && exec > >(tee /stackable/log/git-sync-0/container.stdout.log) 2> >(tee /stackable/log/git-sync-0/container.stderr.log >&2)
+
+  prepare_signal_handlers()
+  {
+      unset term_child_pid
+      unset term_kill_needed
+      trap 'handle_term_signal' TERM
+  }
+
+  handle_term_signal()
+  {
+      if [ "${term_child_pid}" ]; then
+          kill -TERM "${term_child_pid}" 2>/dev/null
+      else
+          term_kill_needed="yes"
+      fi
+  }
+
+  wait_for_termination()
+  {
+      set +e
+      term_child_pid=$1
+      if [[ -v term_kill_needed ]]; then
+          kill -TERM "${term_child_pid}" 2>/dev/null
+      fi
+      wait ${term_child_pid} 2>/dev/null
+      trap - TERM
+      wait ${term_child_pid} 2>/dev/null
+      set -e
+  }
+
+  prepare_signal_handlers
+  /stackable/git-sync --depth=3 --git-config='http.sslCAInfo:/stackable/gitca-0/ca.crt,safe.directory:/tmp/git' --link=current --one-time=false --period=60s --ref=trunk --repo=ssh://git@github.com/stackabletech/repo.git --rev=HEAD --root=/tmp/git &
+  wait_for_termination $!
+command:
+- /bin/bash
+- -x
+- -euo
+- pipefail
+- -c
+env:
+- name: VAR1
+  value: value1
+image: oci.stackable.tech/sdp/product:latest
+imagePullPolicy: Always
+name: git-sync-0
+resources:
+  limits:
+    cpu: 200m
+    memory: 64Mi
+  requests:
+    cpu: 100m
+    memory: 64Mi
+volumeMounts:
+- mountPath: /tmp/git
+  name: content-from-git-0
+- mountPath: /stackable/log
+  name: log-volume
+- mountPath: /mnt/extra-volume
+  name: extra-volume
+- mountPath: /stackable/gitca-0
+  name: ca-cert-0
+"#,
+            serde_yaml::to_string(&git_sync_resources.git_sync_containers.first()).unwrap()
+        );
+
+        assert_eq!(1, git_sync_resources.git_sync_init_containers.len());
+
+        assert_eq!(
+            r"args:
+- |-
+  mkdir --parents /stackable/log/git-sync-0-init && exec > >(tee /stackable/log/git-sync-0-init/container.stdout.log) 2> >(tee /stackable/log/git-sync-0-init/container.stderr.log >&2)
+  /stackable/git-sync --depth=3 --git-config='http.sslCAInfo:/stackable/gitca-0/ca.crt,safe.directory:/tmp/git' --link=current --one-time=true --period=60s --ref=trunk --repo=ssh://git@github.com/stackabletech/repo.git --rev=HEAD --root=/tmp/git
+command:
+- /bin/bash
+- -x
+- -euo
+- pipefail
+- -c
+env:
+- name: VAR1
   value: value1
 image: oci.stackable.tech/sdp/product:latest
 imagePullPolicy: Always
@@ -1434,6 +2083,467 @@ name: ca-cert-0
         );
     }
 
+    #[test]
+    fn test_git_sync_sparse_checkout_submodules_and_pin() {
+        let git_sync_spec = r#"
+          - repo: https://github.com/stackabletech/monorepo
+            gitFolder: dags
+            sparseCheckout:
+              - /dags/
+              - "!/dags/tests/"
+              - /it's quoted/
+            submodules: shallow
+            pin:
+              commit: 0123456789ABCDEF0123456789abcdef01234567
+            gitSyncConf:
+              --submodules: internal option which should be ignored
+          "#;
+
+        let git_syncs: Vec<GitSync> = yaml_from_str_singleton_map(git_sync_spec).unwrap();
+
+        let resolved_product_image = ResolvedProductImage {
+            image: "oci.stackable.tech/sdp/product:latest".to_string(),
+            app_version_label_value: "1.0.0-latest"
+                .parse()
+                .expect("static app version label is always valid"),
+            product_version: "1.0.0".to_string(),
+            image_pull_policy: "Always".to_string(),
+            pull_secrets: None,
+        };
+
+        let git_sync_resources = GitSyncResources::new(
+            &git_syncs,
+            &resolved_product_image,
+            &[],
+            &[],
+            "log-volume",
+            &validate(default_container_log_config()).unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            r"args:
+- |-
+  mkdir --parents /stackable/log/git-sync-0-init && exec > >(tee /stackable/log/git-sync-0-init/container.stdout.log) 2> >(tee /stackable/log/git-sync-0-init/container.stderr.log >&2)
+  printf '%s\n' '/dags/' '!/dags/tests/' '/it'\''s quoted/' > /tmp/sparse-checkout
+  /stackable/git-sync --depth=1 --git-config='safe.directory:/tmp/git' --link=current --one-time=true --period=20s --ref=0123456789abcdef0123456789abcdef01234567 --repo=https://github.com/stackabletech/monorepo --root=/tmp/git --sparse-checkout-file=/tmp/sparse-checkout --submodules=shallow
+command:
+- /bin/bash
+- -x
+- -euo
+- pipefail
+- -c
+env: []
+image: oci.stackable.tech/sdp/product:latest
+imagePullPolicy: Always
+name: git-sync-0-init
+resources:
+  limits:
+    cpu: 200m
+    memory: 64Mi
+  requests:
+    cpu: 100m
+    memory: 64Mi
+volumeMounts:
+- mountPath: /tmp/git
+  name: content-from-git-0
+- mountPath: /stackable/log
+  name: log-volume
+",
+            serde_yaml::to_string(&git_sync_resources.git_sync_init_containers.first()).unwrap()
+        );
+
+        assert_eq!(
+            "/stackable/app/git-0/current/dags",
+            git_sync_resources
+                .git_content_folders_as_string()
+                .first()
+                .unwrap()
+        );
+    }
+
+    #[rstest]
+    #[case("tag: v1.0.0", true)]
+    #[case("tag: release/2024-01+build.1", true)]
+    #[case("commit: 0123456789abcdef0123456789abcdef01234567", true)]
+    #[case(
+        "commit: 0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef",
+        true
+    )]
+    #[case("tag: \"\"", false)]
+    #[case("tag: -v1", false)]
+    #[case("tag: v1..2", false)]
+    #[case("tag: v1.lock", false)]
+    #[case("tag: v1/", false)]
+    #[case("tag: v1;reboot", false)]
+    #[case("tag: v1 2", false)]
+    #[case("commit: 0123456", false)]
+    #[case("commit: 0123456789abcdef0123456789abcdef0123456g", false)]
+    fn test_git_sync_pin(#[case] pin: &str, #[case] expect_ok: bool) {
+        let git_sync_spec = format!(
+            "
+- repo: https://github.com/stackabletech/repo1
+  pin:
+    {pin}
+          "
+        );
+
+        let git_syncs: Vec<GitSync> = yaml_from_str_singleton_map(&git_sync_spec).unwrap();
+
+        let resolved_product_image = ResolvedProductImage {
+            image: "oci.stackable.tech/sdp/product:latest".to_string(),
+            app_version_label_value: "1.0.0-latest"
+                .parse()
+                .expect("static app version label is always valid"),
+            product_version: "1.0.0".to_string(),
+            image_pull_policy: "Always".to_string(),
+            pull_secrets: None,
+        };
+
+        let git_sync_resources = GitSyncResources::new(
+            &git_syncs,
+            &resolved_product_image,
+            &[],
+            &[],
+            "log-volume",
+            &validate(default_container_log_config()).unwrap(),
+        );
+        assert_eq!(expect_ok, git_sync_resources.is_ok());
+    }
+
+    #[rstest]
+    #[case("[/dags/]", true)]
+    #[case("[\"\"]", false)]
+    #[case("[\"  \"]", false)]
+    #[case("[\"/dags/\\n/plugins/\"]", false)]
+    fn test_git_sync_sparse_checkout(#[case] sparse_checkout: &str, #[case] expect_ok: bool) {
+        let git_sync_spec = format!(
+            "
+- repo: https://github.com/stackabletech/repo1
+  sparseCheckout: {sparse_checkout}
+          "
+        );
+
+        let git_syncs: Vec<GitSync> = yaml_from_str_singleton_map(&git_sync_spec).unwrap();
+
+        let resolved_product_image = ResolvedProductImage {
+            image: "oci.stackable.tech/sdp/product:latest".to_string(),
+            app_version_label_value: "1.0.0-latest"
+                .parse()
+                .expect("static app version label is always valid"),
+            product_version: "1.0.0".to_string(),
+            image_pull_policy: "Always".to_string(),
+            pull_secrets: None,
+        };
+
+        let git_sync_resources = GitSyncResources::new(
+            &git_syncs,
+            &resolved_product_image,
+            &[],
+            &[],
+            "log-volume",
+            &validate(default_container_log_config()).unwrap(),
+        );
+        assert_eq!(expect_ok, git_sync_resources.is_ok());
+    }
+
+    #[test]
+    #[expect(clippy::too_many_lines)]
+    fn test_git_sync_report_commit_http_port_and_sync_on_request() {
+        let git_sync_spec = r"
+          - repo: https://github.com/stackabletech/repo
+            reportCommit: true
+            httpPort: 8090
+            syncOnRequest: true
+          ";
+
+        let git_syncs: Vec<GitSync> = yaml_from_str_singleton_map(git_sync_spec).unwrap();
+
+        let resolved_product_image = ResolvedProductImage {
+            image: "oci.stackable.tech/sdp/product:latest".to_string(),
+            app_version_label_value: "1.0.0-latest"
+                .parse()
+                .expect("static app version label is always valid"),
+            product_version: "1.0.0".to_string(),
+            image_pull_policy: "Always".to_string(),
+            pull_secrets: None,
+        };
+
+        let git_sync_resources = GitSyncResources::new(
+            &git_syncs,
+            &resolved_product_image,
+            &[],
+            &[],
+            "log-volume",
+            &validate(default_container_log_config()).unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            vec![PolicyRule {
+                api_groups: Some(vec![String::new()]),
+                resources: Some(vec!["pods".to_owned()]),
+                verbs: vec!["patch".to_owned()],
+                ..PolicyRule::default()
+            }],
+            git_sync_resources.git_sync_policy_rules
+        );
+
+        assert_eq!(
+            r#"args:
+- |-
+  mkdir --parents /stackable/log/git-sync-0 && exec > >(tee /stackable/log/git-sync-0/container.stdout.log) 2> >(tee /stackable/log/git-sync-0/container.stderr.log >&2)
+  cat > /tmp/report-commit << 'EOF'
+  #!/bin/bash
+  set -euo pipefail
+  SERVICE_ACCOUNT_DIR=/var/run/secrets/kubernetes.io/serviceaccount
+  curl --silent --show-error --fail --request PATCH \
+    --cacert "$SERVICE_ACCOUNT_DIR/ca.crt" \
+    --header "Authorization: Bearer $(cat "$SERVICE_ACCOUNT_DIR/token")" \
+    --header 'Content-Type: application/merge-patch+json' \
+    --data '{"metadata":{"annotations":{"'"$COMMIT_ANNOTATION_KEY"'":"'"$GITSYNC_HASH"'"}}}' \
+    "https://kubernetes.default.svc/api/v1/namespaces/$POD_NAMESPACE/pods/$POD_NAME" > /dev/null
+  EOF
+  chmod +x /tmp/report-commit
+
+  prepare_signal_handlers()
+  {
+      unset term_child_pid
+      unset term_kill_needed
+      trap 'handle_term_signal' TERM
+  }
+
+  handle_term_signal()
+  {
+      if [ "${term_child_pid}" ]; then
+          kill -TERM "${term_child_pid}" 2>/dev/null
+      else
+          term_kill_needed="yes"
+      fi
+  }
+
+  wait_for_termination()
+  {
+      set +e
+      term_child_pid=$1
+      if [[ -v term_kill_needed ]]; then
+          kill -TERM "${term_child_pid}" 2>/dev/null
+      fi
+      wait ${term_child_pid} 2>/dev/null
+      trap - TERM
+      wait ${term_child_pid} 2>/dev/null
+      set -e
+  }
+
+  prepare_signal_handlers
+  /stackable/git-sync --depth=1 --exechook-command=/tmp/report-commit --git-config='safe.directory:/tmp/git' --http-bind=:8090 --http-metrics=true --link=current --one-time=false --period=20s --ref=main --repo=https://github.com/stackabletech/repo --root=/tmp/git --sync-on-signal=SIGHUP &
+  git_sync_pid=$!
+  (
+      set +x
+      read_sync_request() { grep "^git-sync.stackable.tech/sync-requested=" /stackable/git-sync-trigger/annotations || true; }
+      last_sync_request=$(read_sync_request)
+      while sleep 5; do
+          sync_request=$(read_sync_request)
+          if [ "$sync_request" != "$last_sync_request" ]; then
+              last_sync_request=$sync_request
+              echo "Synchronization requested"
+              kill -HUP $git_sync_pid
+          fi
+      done
+  ) &
+  wait_for_termination $git_sync_pid
+command:
+- /bin/bash
+- -x
+- -euo
+- pipefail
+- -c
+env:
+- name: COMMIT_ANNOTATION_KEY
+  value: git-sync.stackable.tech/commit-0
+- name: POD_NAME
+  valueFrom:
+    fieldRef:
+      fieldPath: metadata.name
+- name: POD_NAMESPACE
+  valueFrom:
+    fieldRef:
+      fieldPath: metadata.namespace
+image: oci.stackable.tech/sdp/product:latest
+imagePullPolicy: Always
+name: git-sync-0
+ports:
+- containerPort: 8090
+  name: git-sync-0
+resources:
+  limits:
+    cpu: 200m
+    memory: 64Mi
+  requests:
+    cpu: 100m
+    memory: 64Mi
+volumeMounts:
+- mountPath: /tmp/git
+  name: content-from-git-0
+- mountPath: /stackable/log
+  name: log-volume
+- mountPath: /stackable/git-sync-trigger
+  name: git-sync-trigger
+"#,
+            serde_yaml::to_string(&git_sync_resources.git_sync_containers.first()).unwrap()
+        );
+
+        assert_eq!(
+            r#"args:
+- |-
+  mkdir --parents /stackable/log/git-sync-0-init && exec > >(tee /stackable/log/git-sync-0-init/container.stdout.log) 2> >(tee /stackable/log/git-sync-0-init/container.stderr.log >&2)
+  cat > /tmp/report-commit << 'EOF'
+  #!/bin/bash
+  set -euo pipefail
+  SERVICE_ACCOUNT_DIR=/var/run/secrets/kubernetes.io/serviceaccount
+  curl --silent --show-error --fail --request PATCH \
+    --cacert "$SERVICE_ACCOUNT_DIR/ca.crt" \
+    --header "Authorization: Bearer $(cat "$SERVICE_ACCOUNT_DIR/token")" \
+    --header 'Content-Type: application/merge-patch+json' \
+    --data '{"metadata":{"annotations":{"'"$COMMIT_ANNOTATION_KEY"'":"'"$GITSYNC_HASH"'"}}}' \
+    "https://kubernetes.default.svc/api/v1/namespaces/$POD_NAMESPACE/pods/$POD_NAME" > /dev/null
+  EOF
+  chmod +x /tmp/report-commit
+  /stackable/git-sync --depth=1 --exechook-command=/tmp/report-commit --git-config='safe.directory:/tmp/git' --link=current --one-time=true --period=20s --ref=main --repo=https://github.com/stackabletech/repo --root=/tmp/git
+command:
+- /bin/bash
+- -x
+- -euo
+- pipefail
+- -c
+env:
+- name: COMMIT_ANNOTATION_KEY
+  value: git-sync.stackable.tech/commit-0
+- name: POD_NAME
+  valueFrom:
+    fieldRef:
+      fieldPath: metadata.name
+- name: POD_NAMESPACE
+  valueFrom:
+    fieldRef:
+      fieldPath: metadata.namespace
+image: oci.stackable.tech/sdp/product:latest
+imagePullPolicy: Always
+name: git-sync-0-init
+resources:
+  limits:
+    cpu: 200m
+    memory: 64Mi
+  requests:
+    cpu: 100m
+    memory: 64Mi
+volumeMounts:
+- mountPath: /tmp/git
+  name: content-from-git-0
+- mountPath: /stackable/log
+  name: log-volume
+- mountPath: /stackable/git-sync-trigger
+  name: git-sync-trigger
+"#,
+            serde_yaml::to_string(&git_sync_resources.git_sync_init_containers.first()).unwrap()
+        );
+
+        assert_eq!(
+            r"- downwardAPI:
+    items:
+    - fieldRef:
+        fieldPath: metadata.annotations
+      path: annotations
+  name: git-sync-trigger
+",
+            serde_yaml::to_string(&git_sync_resources.git_sync_trigger_volumes).unwrap()
+        );
+    }
+
+    #[rstest]
+    #[case(8090, 8091, true)]
+    #[case(8090, 8090, false)]
+    #[case(0, 8091, false)]
+    fn test_git_sync_http_port(
+        #[case] http_port_1: u16,
+        #[case] http_port_2: u16,
+        #[case] expect_ok: bool,
+    ) {
+        let git_sync_spec = format!(
+            "
+- repo: https://github.com/stackabletech/repo1
+  httpPort: {http_port_1}
+- repo: https://github.com/stackabletech/repo2
+  httpPort: {http_port_2}
+          "
+        );
+
+        let git_syncs: Vec<GitSync> = yaml_from_str_singleton_map(&git_sync_spec).unwrap();
+
+        let resolved_product_image = ResolvedProductImage {
+            image: "oci.stackable.tech/sdp/product:latest".to_string(),
+            app_version_label_value: "1.0.0-latest"
+                .parse()
+                .expect("static app version label is always valid"),
+            product_version: "1.0.0".to_string(),
+            image_pull_policy: "Always".to_string(),
+            pull_secrets: None,
+        };
+
+        let git_sync_resources = GitSyncResources::new(
+            &git_syncs,
+            &resolved_product_image,
+            &[],
+            &[],
+            "log-volume",
+            &validate(default_container_log_config()).unwrap(),
+        );
+        assert_eq!(expect_ok, git_sync_resources.is_ok());
+    }
+
+    #[test]
+    fn test_git_sync_commit_status_from_pods() {
+        let git_sync_spec = r"
+          - repo: https://github.com/stackabletech/repo1
+            reportCommit: true
+          - repo: https://github.com/stackabletech/repo2
+          - repo: https://github.com/stackabletech/repo3
+            reportCommit: true
+          ";
+
+        let git_syncs: Vec<GitSync> = yaml_from_str_singleton_map(git_sync_spec).unwrap();
+
+        let pods: Vec<Pod> = serde_yaml::from_str(
+            r"
+            - metadata:
+                name: pod-0
+                annotations:
+                  git-sync.stackable.tech/commit-0: 0123456789abcdef0123456789abcdef01234567
+                  git-sync.stackable.tech/commit-1: ignored
+                  git-sync.stackable.tech/commit-2: 89abcdef0123456789abcdef0123456789abcdef
+            - metadata:
+                name: pod-1
+            ",
+        )
+        .unwrap();
+
+        assert_eq!(
+            vec![
+                GitSyncCommitStatus {
+                    pod: "pod-0".to_string(),
+                    repo: "https://github.com/stackabletech/repo1".parse().unwrap(),
+                    commit: "0123456789abcdef0123456789abcdef01234567".to_string(),
+                },
+                GitSyncCommitStatus {
+                    pod: "pod-0".to_string(),
+                    repo: "https://github.com/stackabletech/repo3".parse().unwrap(),
+                    commit: "89abcdef0123456789abcdef0123456789abcdef".to_string(),
+                },
+            ],
+            GitSyncCommitStatus::from_pods(&git_syncs, &pods)
+        );
+    }
+
     #[rstest]
     // https with tls/null --> deactivate: Ok
     #[case(
@@ -2,19 +2,42 @@
 
 use std::{cmp, fmt::Write, ops::Mul};
 
-use snafu::{ResultExt, Snafu};
+use indexmap::IndexMap;
+use snafu::{OptionExt, ResultExt, Snafu, ensure};
+use stackable_shared::time::Duration;
 
 use crate::{
-    builder::{self, pod::container::ContainerBuilder},
-    commons::product_image_selection::ResolvedProductImage,
+    builder::{
+        self,
+        pod::{container::ContainerBuilder, volume::VolumeMountBuilder},
+    },
+    commons::{
+        product_image_selection::ResolvedProductImage,
+        secret_class::{SecretClassVolumeError, SecretClassVolumeProvisionParts},
+        tls_verification::{
+            CaCert, Tls, TlsClientDetails, TlsClientDetailsError, TlsServerVerification,
+            TlsVerification,
+        },
+    },
+    constants::secret::SECRET_BASE_PATH,
     k8s_openapi::{
-        api::core::v1::{Container, ResourceRequirements},
+        api::core::v1::{Container, ResourceRequirements, Volume, VolumeMount},
         apimachinery::pkg::api::resource::Quantity,
     },
     kube::Resource,
     memory::{BinaryMultiple, MemoryQuantity},
-    product_logging::spec::{
-        AutomaticContainerLogConfig, ContainerLogConfig, ContainerLogConfigChoice, LogLevel,
+    product_logging::{
+        spec::{
+            AppenderConfig, AutomaticContainerLogConfig, ContainerLogConfig,
+            ContainerLogConfigChoice, LogFileFormat, LogLevel, LogRateLimit, LogRedaction,
+            LogRetention, LogSink, LogSinkConnection, OpenSearchLogSink,
+        },
+        vector::{
+            Codec, ElasticsearchBulk, Encoding, FileSource, Framing, FramingMethod, HttpClient,
+            LogSchema, MultilineConfig, MultilineMode, OpentelemetryProtocol,
+            PROCESSED_FILES_PREFIX, SecretBackend, Sink, SinkAuth, SinkKind, SinkTls, Source,
+            Transform, VectorConfig,
+        },
     },
     role_utils::RoleGroupRef,
 };
@@ -44,6 +67,34 @@ const VECTOR_AGGREGATOR_CM_KEY: &str = "ADDRESS";
 /// Name of the env var in the vector container that holds the vector aggregator address
 const VECTOR_AGGREGATOR_ENV_NAME: &str = "VECTOR_AGGREGATOR_ADDRESS";
 
+/// Name of the Vector transform whose output is sent to the log sink
+pub const VECTOR_SINK_INPUT: &str = "extended_logs";
+
+/// Name of the Vector secret backend which provides the credentials of the log sink
+const LOG_SINK_CREDENTIALS_SECRET_BACKEND: &str = "log_sink_credentials";
+
+/// Name of the Vector transform which converts the events into the OTLP JSON format
+const OTLP_TRANSFORM: &str = "otlp_logs";
+
+/// Default port of the OTLP/HTTP receiver of the OpenTelemetry collector
+const OTLP_HTTP_DEFAULT_PORT: u16 = 4318;
+
+/// Default port of Grafana Loki
+const LOKI_DEFAULT_PORT: u16 = 3100;
+
+/// Default port of OpenSearch and Elasticsearch
+const OPENSEARCH_DEFAULT_PORT: u16 = 9200;
+
+/// Subdirectory of the log directory containing the metrics written by the log cleaners, see
+/// [`log_cleaner_shell_command`]
+pub const LOG_RETENTION_METRICS_DIR: &str = "_log-retention";
+
+/// Minimum size of a log quota
+pub const MIN_LOG_QUOTA: MemoryQuantity = MemoryQuantity {
+    value: 1.0,
+    unit: BinaryMultiple::Mebi,
+};
+
 #[derive(Debug, Snafu)]
 pub enum LoggingError {
     #[snafu(display("failed to create container"))]
@@ -55,6 +106,56 @@ pub enum LoggingError {
     AddVolumeMounts {
         source: builder::pod::container::Error,
     },
+
+    #[snafu(display(
+        "the discovery ConfigMap of the Vector aggregator is required to send the logs to the aggregator"
+    ))]
+    MissingVectorAggregatorConfigMap,
+
+    #[snafu(display("failed to create the volume for the log sink credentials"))]
+    LogSinkCredentialsVolume { source: SecretClassVolumeError },
+
+    #[snafu(display("failed to create the volumes for the log sink TLS configuration"))]
+    LogSinkTlsVolumes { source: TlsClientDetailsError },
+
+    #[snafu(display("the redaction pattern {pattern:?} is not a valid regular expression"))]
+    InvalidRedactionPattern {
+        source: regex::Error,
+        pattern: String,
+    },
+
+    #[snafu(display(
+        "the Vector configuration contains no transform {VECTOR_SINK_INPUT:?} to apply the log rules to"
+    ))]
+    MissingVectorSinkInput,
+
+    #[snafu(display("the log size {size:?} is not a valid memory quantity"))]
+    ParseLogQuotaSize {
+        source: crate::memory::Error,
+        size: String,
+    },
+
+    #[snafu(display("the log quota {max_size} must be at least {MIN_LOG_QUOTA}"))]
+    LogQuotaTooSmall { max_size: MemoryQuantity },
+
+    #[snafu(display(
+        "the maximum log file size {max_file_size} must be greater than zero and must not exceed the log quota {max_size}"
+    ))]
+    LogFileSizeOutOfRange {
+        max_file_size: MemoryQuantity,
+        max_size: MemoryQuantity,
+    },
+
+    #[snafu(display("the log cleanup interval must be at least one second"))]
+    LogCleanupIntervalTooShort,
+
+    #[snafu(display(
+        "the log file {log_file:?} does not match the configured file format, the extension must be {expected_extension:?}"
+    ))]
+    LogFileExtensionMismatch {
+        log_file: String,
+        expected_extension: String,
+    },
 }
 
 /// Calculate the size limit for the log volume.
@@ -168,6 +269,54 @@ pub fn capture_shell_output(
     log_dir: &str,
     container: &str,
     log_config: &AutomaticContainerLogConfig,
+) -> String {
+    let log_file_dir = format!("{log_dir}/{container}");
+
+    redirect_shell_output(
+        &log_file_dir,
+        &format!("{log_file_dir}/container.stdout.log"),
+        &format!("{log_file_dir}/container.stderr.log"),
+        log_config,
+    )
+}
+
+/// Create a Bash command like [`capture_shell_output`] which rotates the log files according to
+/// the given log quota
+///
+/// The output is split into files with a size of at most `max_file_size`, e.g.
+/// `container.000000.stdout.log`, `container.000001.stdout.log`, etc. The old files are not
+/// deleted by this command but by the cleaner started with [`log_cleaner_shell_command`].
+pub fn capture_rotated_shell_output(
+    log_dir: &str,
+    container: &str,
+    log_config: &AutomaticContainerLogConfig,
+    log_quota: &LogQuota,
+) -> String {
+    let log_file_dir = format!("{log_dir}/{container}");
+    let max_file_size = log_quota.max_file_size_in_bytes();
+
+    let rotated_log_file = |stream: &str| {
+        format!(
+            ">(split --line-bytes={max_file_size} --numeric-suffixes --suffix-length=6 \
+            --additional-suffix=.{stream}.log - {log_file_dir}/container.)"
+        )
+    };
+
+    redirect_shell_output(
+        &log_file_dir,
+        &rotated_log_file("stdout"),
+        &rotated_log_file("stderr"),
+        log_config,
+    )
+}
+
+/// Create a Bash command which redirects stdout and stderr to the given files according to the
+/// log configuration
+fn redirect_shell_output(
+    log_file_dir: &str,
+    stdout_file: &str,
+    stderr_file: &str,
+    log_config: &AutomaticContainerLogConfig,
 ) -> String {
     let root_log_level = log_config.root_log_level();
     let console_log_level = cmp::max(
@@ -187,15 +336,13 @@ pub fn capture_shell_output(
             .unwrap_or_default(),
     );
 
-    let log_file_dir = format!("{log_dir}/{container}");
-
     let stdout_redirect = match (
         console_log_level <= LogLevel::INFO,
         file_log_level <= LogLevel::INFO,
     ) {
-        (true, true) => format!(" > >(tee {log_file_dir}/container.stdout.log)"),
+        (true, true) => format!(" > >(tee {stdout_file})"),
         (true, false) => String::new(),
-        (false, true) => format!(" > {log_file_dir}/container.stdout.log"),
+        (false, true) => format!(" > {stdout_file}"),
         (false, false) => " > /dev/null".into(),
     };
 
@@ -203,9 +350,9 @@ pub fn capture_shell_output(
         console_log_level <= LogLevel::ERROR,
         file_log_level <= LogLevel::ERROR,
     ) {
-        (true, true) => format!(" 2> >(tee {log_file_dir}/container.stderr.log >&2)"),
+        (true, true) => format!(" 2> >(tee {stderr_file} >&2)"),
         (true, false) => String::new(),
-        (false, true) => format!(" 2> {log_file_dir}/container.stderr.log"),
+        (false, true) => format!(" 2> {stderr_file}"),
         (false, false) => " 2> /dev/null".into(),
     };
 
@@ -222,6 +369,167 @@ pub fn capture_shell_output(
     args.join(" && ")
 }
 
+/// Validated [`LogRetention`] of a container, see [`log_quota`]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct LogQuota {
+    /// Maximum size of all log files of the container
+    pub max_size: MemoryQuantity,
+    /// Maximum size of a file of the captured console output
+    pub max_file_size: MemoryQuantity,
+    /// Interval in which the quota is enforced
+    pub cleanup_interval: Duration,
+}
+
+impl LogQuota {
+    /// Return the maximum size of all log files in bytes
+    pub fn max_size_in_bytes(&self) -> u64 {
+        memory_quantity_in_bytes(self.max_size)
+    }
+
+    /// Return the maximum size of a file of the captured console output in bytes
+    pub fn max_file_size_in_bytes(&self) -> u64 {
+        memory_quantity_in_bytes(self.max_file_size)
+    }
+}
+
+fn memory_quantity_in_bytes(quantity: MemoryQuantity) -> u64 {
+    (quantity.scale_to(BinaryMultiple::Kibi).value * 1024.0) as u64
+}
+
+/// Validate the given retention policy
+///
+/// The quota must be at least [`MIN_LOG_QUOTA`], the maximum file size must be greater than zero
+/// and must not exceed the quota, and the cleanup interval must be at least one second.
+pub fn log_quota(retention: &LogRetention) -> Result<LogQuota, LoggingError> {
+    let max_size = parse_log_quota_size(&retention.max_size)?;
+    ensure!(
+        max_size >= MIN_LOG_QUOTA,
+        LogQuotaTooSmallSnafu { max_size }
+    );
+
+    let max_file_size = match &retention.max_file_size {
+        Some(max_file_size) => parse_log_quota_size(max_file_size)?,
+        None => max_size / 5.,
+    };
+    ensure!(
+        max_file_size.value > 0. && max_file_size <= max_size,
+        LogFileSizeOutOfRangeSnafu {
+            max_file_size,
+            max_size
+        }
+    );
+
+    let cleanup_interval = retention.cleanup_interval();
+    ensure!(
+        cleanup_interval.as_secs() >= 1,
+        LogCleanupIntervalTooShortSnafu
+    );
+
+    Ok(LogQuota {
+        max_size,
+        max_file_size,
+        cleanup_interval,
+    })
+}
+
+fn parse_log_quota_size(size: &Quantity) -> Result<MemoryQuantity, LoggingError> {
+    MemoryQuantity::try_from(size).context(ParseLogQuotaSizeSnafu {
+        size: size.0.clone(),
+    })
+}
+
+/// Create a Bash command which starts a cleaner in the background to enforce the given log quota
+///
+/// The cleaner checks the disk usage of the log directory of the container in the interval
+/// defined in the quota. If the quota is exceeded, then the oldest fully rotated log files are
+/// deleted until the disk usage is below the quota again. Active log files are never touched
+/// because they are still written to. Rotated files are recognized by their numeric suffix, e.g.
+/// `my-product.log4j.xml.1`, or by being superseded by a newer file written by
+/// [`capture_rotated_shell_output`]. Log events which were not yet shipped by Vector are
+/// discarded.
+///
+/// The cleaner writes the following metrics in the Prometheus text format to the file
+/// `{log_dir}/_log-retention/{container}.prom`, see [`LOG_RETENTION_METRICS_DIR`]:
+///
+/// * `stackable_log_retention_quota_bytes` - The log quota of the container
+/// * `stackable_log_retention_used_bytes` - The disk usage of the log files of the container
+/// * `stackable_log_retention_cleaned_files_total` - The number of cleaned up log files
+/// * `stackable_log_retention_cleaned_bytes_total` - The number of cleaned up bytes
+///
+/// The command can be combined with other commands using `&&` and should be used in long-running
+/// containers together with [`capture_rotated_shell_output`]. The log volume should be sized with
+/// [`calculate_log_volume_size_limit`] using the maximum size of the quota.
+pub fn log_cleaner_shell_command(log_dir: &str, container: &str, log_quota: &LogQuota) -> String {
+    let log_file_dir = format!("{log_dir}/{container}");
+    let metrics_dir = format!("{log_dir}/{LOG_RETENTION_METRICS_DIR}");
+    let metrics_file = format!("{metrics_dir}/{container}.prom");
+    let max_size = log_quota.max_size_in_bytes();
+    let interval = log_quota.cleanup_interval.as_secs();
+    let used_bytes = format!("$(du --summarize --block-size=1 {log_file_dir} | cut --fields=1)");
+    let labels = format!("{{container=\"{container}\"}}");
+    let oldest_rotated_log_file = oldest_rotated_log_file_shell_command(&log_file_dir);
+
+    format!(
+        "mkdir --parents {log_file_dir} {metrics_dir} && {{\n\
+        (\n\
+        set +e\n\
+        cleaned_files=0\n\
+        cleaned_bytes=0\n\
+        while true; do\n\
+        used_bytes={used_bytes}\n\
+        while [ \"$used_bytes\" -gt {max_size} ]; do\n\
+        oldest_file=$({oldest_rotated_log_file})\n\
+        if [ -z \"$oldest_file\" ]; then break; fi\n\
+        file_bytes=$(du --block-size=1 \"$oldest_file\" | cut --fields=1)\n\
+        rm --force \"$oldest_file\" || break\n\
+        cleaned_files=$((cleaned_files + 1))\n\
+        cleaned_bytes=$((cleaned_bytes + file_bytes))\n\
+        used_bytes={used_bytes}\n\
+        done\n\
+        printf '%s\\n' \\\n\
+        '# TYPE stackable_log_retention_quota_bytes gauge' \\\n\
+        'stackable_log_retention_quota_bytes{labels} {max_size}' \\\n\
+        '# TYPE stackable_log_retention_used_bytes gauge' \\\n\
+        'stackable_log_retention_used_bytes{labels} '\"$used_bytes\" \\\n\
+        '# TYPE stackable_log_retention_cleaned_files_total counter' \\\n\
+        'stackable_log_retention_cleaned_files_total{labels} '\"$cleaned_files\" \\\n\
+        '# TYPE stackable_log_retention_cleaned_bytes_total counter' \\\n\
+        'stackable_log_retention_cleaned_bytes_total{labels} '\"$cleaned_bytes\" \\\n\
+        > {metrics_file}.tmp && mv {metrics_file}.tmp {metrics_file}\n\
+        sleep {interval}\n\
+        done\n\
+        ) &\n\
+        }}"
+    )
+}
+
+/// Create a Bash command which prints the path of the oldest fully rotated log file in the given
+/// directory
+///
+/// A log file is fully rotated if it is not written to anymore:
+///
+/// * Files rolled over by the Log4j, Log4j 2, and logback appenders have a numeric suffix, e.g.
+///   `my-product.log4j.xml.1`, whereas the active file has none.
+/// * Files written by [`capture_rotated_shell_output`] are numbered consecutively, e.g.
+///   `container.000001.stdout.log`, and only the file with the highest number is active.
+///
+/// All other files are considered active. Nothing is printed if there is no rotated file.
+fn oldest_rotated_log_file_shell_command(log_file_dir: &str) -> String {
+    let split_files = |stream: &str| {
+        format!(
+            "find {log_file_dir} -maxdepth 1 -type f -name 'container.*.{stream}.log' \
+            -printf '%f %T@ %p\\n' | sort | head --lines=-1 | cut --delimiter=' ' --fields=2-"
+        )
+    };
+
+    format!(
+        "{{ find {log_file_dir} -type f -regex '.*\\.[0-9]+' -printf '%T@ %p\\n'; {stdout}; {stderr}; }} \
+        | sort --numeric-sort | head --lines=1 | cut --delimiter=' ' --fields=2-",
+        stdout = split_files("stdout"),
+        stderr = split_files("stderr"),
+    )
+}
+
 /// Create the content of a log4j properties file according to the given log configuration
 ///
 /// # Arguments
@@ -233,7 +541,8 @@ pub fn capture_shell_output(
 ///   exceeded. The value is set to 2 if the given value is lower (1 MiB for the active log
 ///   file and 1 MiB for the archived one).
 /// * `console_conversion_pattern` - Logback conversion pattern for the console appender
-/// * `config` - The logging configuration for the container
+/// * `config` - The logging configuration for the container; The log rules must be applied by
+///   Vector, see [`add_vector_log_rules`].
 ///
 /// # Example
 ///
@@ -344,12 +653,15 @@ log4j.appender.FILE.layout=org.apache.log4j.xml.XMLLayout
 ///
 /// * `log_dir` - Directory where the log files are stored
 /// * `log_file` - Name of the active log file; When the file is rolled over then a number is
-///   appended.
+///   appended. The extension must match the file format, so the name should be created with
+///   [`log4j2_log_file`]. Otherwise, an error is returned.
 /// * `max_size_in_mib` - Maximum size of all log files in MiB; This value can be slightly
 ///   exceeded. The value is set to 2 if the given value is lower (1 MiB for the active log
 ///   file and 1 MiB for the archived one).
 /// * `console_conversion_pattern` - Log4j2 conversion pattern for the console appender
-/// * `config` - The logging configuration for the container
+/// * `config` - The logging configuration for the container; Rate limits of loggers are applied
+///   with a `BurstFilter` and redaction rules with the `%replace` converter, see
+///   [`log4j2_log_file`]. Sampling must be applied by Vector, see [`add_vector_log_rules`].
 ///
 /// # Example
 ///
@@ -372,7 +684,6 @@ log4j.appender.FILE.layout=org.apache.log4j.xml.XMLLayout
 ///
 /// const STACKABLE_LOG_DIR: &str = "/stackable/log";
 /// const LOG4J2_CONFIG_FILE: &str = "log4j2.properties";
-/// const MY_PRODUCT_LOG_FILE: &str = "my-product.log4j2.xml";
 /// const MAX_LOG_FILE_SIZE_IN_MIB: u32 = 10;
 /// const CONSOLE_CONVERSION_PATTERN: &str = "%d{ISO8601} %-5p %m%n";
 ///
@@ -387,11 +698,12 @@ log4j.appender.FILE.layout=org.apache.log4j.xml.XMLLayout
 ///         LOG4J2_CONFIG_FILE,
 ///         product_logging::framework::create_log4j2_config(
 ///             &format!("{STACKABLE_LOG_DIR}/my-product"),
-///             MY_PRODUCT_LOG_FILE,
+///             &product_logging::framework::log4j2_log_file("my-product", log_config),
 ///             MAX_LOG_FILE_SIZE_IN_MIB,
 ///             CONSOLE_CONVERSION_PATTERN,
 ///             log_config,
-///         ),
+///         )
+///         .unwrap(),
 ///     );
 /// }
 ///
@@ -403,7 +715,9 @@ pub fn create_log4j2_config(
     max_size_in_mib: u32,
     console_conversion_pattern: &str,
     config: &AutomaticContainerLogConfig,
-) -> String {
+) -> Result<String, LoggingError> {
+    ensure_log_file_extension(log_file, log4j2_log_file_extension(config))?;
+
     let number_of_archived_log_files = 1;
 
     let logger_names = config
@@ -427,10 +741,21 @@ pub fn create_log4j2_config(
             let level = logger_config.level.to_log4j_literal();
             let _ = writeln!(output, "logger.{name}.name = {name}");
             let _ = writeln!(output, "logger.{name}.level = {level}");
+            if let Some(rate_limit) = &logger_config.rate_limit {
+                write_log4j2_burst_filter(&mut output, &format!("logger.{name}"), rate_limit);
+            }
             output
         });
+    let mut root_logger_filter = String::new();
+    if let Some(rate_limit) = config
+        .loggers
+        .get(AutomaticContainerLogConfig::ROOT_LOGGER)
+        .and_then(|root| root.rate_limit.as_ref())
+    {
+        write_log4j2_burst_filter(&mut root_logger_filter, "rootLogger", rate_limit);
+    }
 
-    format!(
+    Ok(format!(
         r"appenders = FILE, CONSOLE
 
 appender.CONSOLE.type = Console
@@ -445,7 +770,7 @@ appender.FILE.type = RollingFile
 appender.FILE.name = FILE
 appender.FILE.fileName = {log_dir}/{log_file}
 appender.FILE.filePattern = {log_dir}/{log_file}.%i
-appender.FILE.layout.type = XMLLayout
+{file_layout}
 appender.FILE.policies.type = Policies
 appender.FILE.policies.size.type = SizeBasedTriggeringPolicy
 appender.FILE.policies.size.size = {max_log_file_size_in_mib}MB
@@ -456,11 +781,31 @@ appender.FILE.filter.threshold.level = {file_log_level}
 {loggers}
 {logger_configs}
 rootLogger.level={root_log_level}
-rootLogger.appenderRefs = CONSOLE, FILE
+{root_logger_filter}rootLogger.appenderRefs = CONSOLE, FILE
 rootLogger.appenderRef.CONSOLE.ref = CONSOLE
 rootLogger.appenderRef.FILE.ref = FILE",
         max_log_file_size_in_mib =
             cmp::max(1, max_size_in_mib / (1 + number_of_archived_log_files)),
+        console_conversion_pattern =
+            log4j2_redacted_pattern(console_conversion_pattern, config.redactions.values()),
+        file_layout = if config.redactions.is_empty() {
+            match config.file_format() {
+                LogFileFormat::Xml => "appender.FILE.layout.type = XMLLayout".to_owned(),
+                LogFileFormat::Json => "appender.FILE.layout.type = JsonTemplateLayout
+appender.FILE.layout.eventTemplateUri = classpath:EcsLayout.json"
+                    .to_owned(),
+            }
+        } else {
+            format!(
+                "appender.FILE.layout.type = PatternLayout
+appender.FILE.layout.alwaysWriteExceptions = false
+appender.FILE.layout.pattern = {{\"@timestamp\":\"%d{{yyyy-MM-dd'T'HH:mm:ss.SSSXXX}}\",\"level\":\"%p\",\"logger\":\"%enc{{%c}}{{JSON}}\",\"message\":\"%enc{{{message}}}{{JSON}}\"}}%n",
+                message = log4j2_redacted_pattern(
+                    "%m%notEmpty{%n%throwable}",
+                    config.redactions.values()
+                ),
+            )
+        },
         root_log_level = config.root_log_level().to_log4j2_literal(),
         console_log_level = config
             .console
@@ -474,7 +819,44 @@ rootLogger.appenderRef.FILE.ref = FILE",
             .and_then(|file| file.level)
             .unwrap_or_default()
             .to_log4j2_literal(),
-    )
+    ))
+}
+
+/// Wrap the given Log4j 2 conversion pattern in a `%replace` converter for every redaction rule
+///
+/// The result is escaped for Log4j 2 properties files.
+fn log4j2_redacted_pattern<'a>(
+    pattern: &str,
+    redactions: impl IntoIterator<Item = &'a LogRedaction>,
+) -> String {
+    redactions
+        .into_iter()
+        .fold(pattern.to_owned(), |pattern, redaction| {
+            format!(
+                "%replace{{{pattern}}}{{{regex}}}{{{replacement}}}",
+                regex = redaction.pattern.replace('\\', "\\\\"),
+                replacement = redaction.replacement().replace('\\', "\\\\"),
+            )
+        })
+}
+
+/// Write a Log4j 2 `BurstFilter` which limits the rate of the events of the given logger
+///
+/// All events are rate limited regardless of their level, like in the Vector `throttle`
+/// transform.
+fn write_log4j2_burst_filter(output: &mut String, logger_prefix: &str, rate_limit: &LogRateLimit) {
+    let _ = writeln!(output, "{logger_prefix}.filter.burst.type = BurstFilter");
+    let _ = writeln!(output, "{logger_prefix}.filter.burst.level = FATAL");
+    let _ = writeln!(
+        output,
+        "{logger_prefix}.filter.burst.rate = {rate}",
+        rate = rate_limit.events_per_second
+    );
+    let _ = writeln!(
+        output,
+        "{logger_prefix}.filter.burst.maxBurst = {max_burst}",
+        max_burst = rate_limit.max_burst()
+    );
 }
 
 /// Create the content of a logback XML configuration file according to the given log configuration
@@ -483,12 +865,15 @@ rootLogger.appenderRef.FILE.ref = FILE",
 ///
 /// * `log_dir` - Directory where the log files are stored
 /// * `log_file` - Name of the active log file; When the file is rolled over then a number is
-///   appended.
+///   appended. The extension must match the file format, so the name should be created with
+///   [`logback_log_file`]. Otherwise, an error is returned.
 /// * `max_size_in_mib` - Maximum size of all log files in MiB; This value can be slightly
 ///   exceeded. The value is set to 2 if the given value is lower (1 MiB for the active log
 ///   file and 1 MiB for the archived one).
 /// * `console_conversion_pattern` - Logback conversion pattern for the console appender
-/// * `config` - The logging configuration for the container
+/// * `config` - The logging configuration for the container; Redaction rules are applied with the
+///   `%replace` converter, see [`logback_log_file`]. logback has no built-in filter for the other
+///   log rules, so they must be applied by Vector, see [`add_vector_log_rules`].
 /// * `additional_config` - Optional unstructured parameter to add special cases that are not
 ///   covered in the logging configuration. Must adhere to the inner logback XML schema as
 ///   shown in the example below. It is not parsed or checked and added as is to the `logback.xml`.
@@ -516,7 +901,6 @@ rootLogger.appenderRef.FILE.ref = FILE",
 ///
 /// const STACKABLE_LOG_DIR: &str = "/stackable/log";
 /// const LOGBACK_CONFIG_FILE: &str = "logback.xml";
-/// const MY_PRODUCT_LOG_FILE: &str = "my-product.log4j.xml";
 /// const MAX_LOG_FILE_SIZE_IN_MIB: u32 = 10;
 /// const CONSOLE_CONVERSION_PATTERN: &str = "%d{ISO8601} %-5p %m%n";
 /// const ADDITIONAL_CONFIG: &str =
@@ -533,12 +917,13 @@ rootLogger.appenderRef.FILE.ref = FILE",
 ///         LOGBACK_CONFIG_FILE,
 ///         product_logging::framework::create_logback_config(
 ///             &format!("{STACKABLE_LOG_DIR}/my-product"),
-///             MY_PRODUCT_LOG_FILE,
+///             &product_logging::framework::logback_log_file("my-product", log_config),
 ///             MAX_LOG_FILE_SIZE_IN_MIB,
 ///             CONSOLE_CONVERSION_PATTERN,
 ///             log_config,
 ///             Some(ADDITIONAL_CONFIG),
-///         ),
+///         )
+///         .unwrap(),
 ///     );
 /// }
 ///
@@ -551,7 +936,9 @@ pub fn create_logback_config(
     console_conversion_pattern: &str,
     config: &AutomaticContainerLogConfig,
     additional_config: Option<&str>,
-) -> String {
+) -> Result<String, LoggingError> {
+    ensure_log_file_extension(log_file, logback_log_file_extension(config))?;
+
     let number_of_archived_log_files = 1;
 
     let loggers = config
@@ -568,7 +955,7 @@ pub fn create_logback_config(
             output
         });
 
-    format!(
+    Ok(format!(
         r#"<configuration>
   <appender name="CONSOLE" class="ch.qos.logback.core.ConsoleAppender">
     <encoder>
@@ -581,9 +968,7 @@ pub fn create_logback_config(
 
   <appender name="FILE" class="ch.qos.logback.core.rolling.RollingFileAppender">
     <File>{log_dir}/{log_file}</File>
-    <encoder class="ch.qos.logback.core.encoder.LayoutWrappingEncoder">
-      <layout class="ch.qos.logback.classic.log4j.XMLLayout" />
-    </encoder>
+{file_encoder}
     <filter class="ch.qos.logback.classic.filter.ThresholdFilter">
       <level>{file_log_level}</level>
     </filter>
@@ -620,6 +1005,24 @@ pub fn create_logback_config(
 "#,
         max_log_file_size_in_mib =
             cmp::max(1, max_size_in_mib / (1 + number_of_archived_log_files)),
+        console_conversion_pattern =
+            logback_redacted_pattern(console_conversion_pattern, config.redactions.values()),
+        file_encoder = if config.redactions.is_empty() {
+            match config.file_format() {
+                LogFileFormat::Xml => LOGBACK_XML_ENCODER.to_owned(),
+                LogFileFormat::Json => LOGBACK_JSON_ENCODER.to_owned(),
+            }
+        } else {
+            format!(
+                r#"    <encoder>
+      <pattern>{{"@timestamp":"%d{{"yyyy-MM-dd'T'HH:mm:ss.SSSXXX"}}","level":"%level","logger":"%logger","message":"{message}"}}%n</pattern>
+    </encoder>"#,
+                message = logback_json_escaped_pattern(&logback_redacted_pattern(
+                    r"%replace(%msg%n%ex){'\r?\n$', ''}",
+                    config.redactions.values()
+                )),
+            )
+        },
         root_log_level = config.root_log_level().to_logback_literal(),
         console_log_level = config
             .console
@@ -634,9 +1037,226 @@ pub fn create_logback_config(
             .unwrap_or_default()
             .to_logback_literal(),
         additional_config = additional_config.unwrap_or("")
+    ))
+}
+
+/// Wrap the given logback conversion pattern in a `%replace` converter for every redaction rule
+///
+/// The result is escaped for logback XML configuration files.
+fn logback_redacted_pattern<'a>(
+    pattern: &str,
+    redactions: impl IntoIterator<Item = &'a LogRedaction>,
+) -> String {
+    let option = |value: &str| xml_escape(&value.replace('\'', "\\'"));
+
+    redactions
+        .into_iter()
+        .fold(pattern.to_owned(), |pattern, redaction| {
+            format!(
+                "%replace({pattern}){{'{regex}', '{replacement}'}}",
+                regex = option(&redaction.pattern),
+                replacement = option(redaction.replacement()),
+            )
+        })
+}
+
+/// Wrap the given logback conversion pattern in `%replace` converters, so that the output can be
+/// used as JSON string
+fn logback_json_escaped_pattern(pattern: &str) -> String {
+    // The backslashes must be escaped first, so that the backslashes of the other escape
+    // sequences are not escaped again.
+    [
+        (r"\\", r"\\\\"),
+        (r#"""#, r#"\\""#),
+        (r"\r", r"\\r"),
+        (r"\n", r"\\n"),
+        (r"\t", r"\\t"),
+    ]
+    .into_iter()
+    .fold(pattern.to_owned(), |pattern, (regex, replacement)| {
+        format!("%replace({pattern}){{'{regex}', '{replacement}'}}")
+    })
+}
+
+/// Escape the XML special characters in the given text
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Logback encoder for the file appender which writes events with the Log4j `XMLLayout`
+const LOGBACK_XML_ENCODER: &str = r#"    <encoder class="ch.qos.logback.core.encoder.LayoutWrappingEncoder">
+      <layout class="ch.qos.logback.classic.log4j.XMLLayout" />
+    </encoder>"#;
+
+/// Logback encoder for the file appender which writes one JSON object per event
+///
+/// The `JsonEncoder` is available since logback 1.3.8/1.4.8; the options are supported since
+/// logback 1.5.0.
+const LOGBACK_JSON_ENCODER: &str = r#"    <encoder class="ch.qos.logback.classic.encoder.JsonEncoder">
+      <withSequenceNumber>false</withSequenceNumber>
+      <withNanoseconds>false</withNanoseconds>
+      <withContext>false</withContext>
+      <withArguments>false</withArguments>
+      <withMessage>false</withMessage>
+      <withFormattedMessage>true</withFormattedMessage>
+    </encoder>"#;
+
+/// Return the name of a Log4j 2 log file which is picked up by the Vector agent
+///
+/// The extension depends on the configured file format and on whether messages are redacted (see
+/// [`AutomaticContainerLogConfig::redactions`]), so the file name must be passed to
+/// [`create_log4j2_config`] instead of a fixed one, which rejects a mismatching extension.
+///
+/// # Example
+///
+/// ```
+/// use stackable_operator::product_logging::{
+///     framework::log4j2_log_file,
+///     spec::{AutomaticContainerLogConfig, LogFileFormat},
+/// };
+///
+/// let mut log_config = AutomaticContainerLogConfig::default();
+/// assert_eq!(
+///     "my-product.log4j2.xml",
+///     log4j2_log_file("my-product", &log_config)
+/// );
+///
+/// log_config.file_format = Some(LogFileFormat::Json);
+/// assert_eq!(
+///     "my-product.log4j2.json",
+///     log4j2_log_file("my-product", &log_config)
+/// );
+/// ```
+pub fn log4j2_log_file(name: &str, config: &AutomaticContainerLogConfig) -> String {
+    format!("{name}{}", log4j2_log_file_extension(config))
+}
+
+fn log4j2_log_file_extension(config: &AutomaticContainerLogConfig) -> &'static str {
+    if !config.redactions.is_empty() {
+        return ".plain.json";
+    }
+    match config.file_format() {
+        LogFileFormat::Xml => ".log4j2.xml",
+        LogFileFormat::Json => ".log4j2.json",
+    }
+}
+
+/// Return the name of a logback log file which is picked up by the Vector agent
+///
+/// The extension depends on the configured file format and on whether messages are redacted (see
+/// [`AutomaticContainerLogConfig::redactions`]), so the file name must be passed to
+/// [`create_logback_config`] instead of a fixed one, which rejects a mismatching extension.
+pub fn logback_log_file(name: &str, config: &AutomaticContainerLogConfig) -> String {
+    format!("{name}{}", logback_log_file_extension(config))
+}
+
+fn logback_log_file_extension(config: &AutomaticContainerLogConfig) -> &'static str {
+    if !config.redactions.is_empty() {
+        return ".plain.json";
+    }
+    match config.file_format() {
+        LogFileFormat::Xml => ".log4j.xml",
+        LogFileFormat::Json => ".logback.json",
+    }
+}
+
+/// Ensure that the extension of the given log file matches the written format
+///
+/// Otherwise, the Vector agent would parse the log file with the wrong parser and drop the events.
+/// This happens for instance if an operator passes a fixed file name to [`create_log4j2_config`]
+/// or [`create_logback_config`] and redaction rules switch the layout to plain JSON.
+fn ensure_log_file_extension(log_file: &str, expected_extension: &str) -> Result<(), LoggingError> {
+    ensure!(
+        log_file.ends_with(expected_extension),
+        LogFileExtensionMismatchSnafu {
+            log_file,
+            expected_extension,
+        }
+    );
+    Ok(())
+}
+
+/// Return the log level for Go logging frameworks like zap, slog or logrus
+///
+/// These frameworks are usually configured with a single log level for all loggers and outputs.
+/// Therefore, the level is derived from the level of the root logger and the lower level of the
+/// console and file appenders. It can be converted with [`LogLevel::to_zap_literal`],
+/// [`LogLevel::to_slog_literal`] or [`LogLevel::to_logrus_literal`], e.g. to set a command line
+/// flag or an environment variable of the product.
+pub fn go_log_level(config: &AutomaticContainerLogConfig) -> LogLevel {
+    let appender_log_level = |appender: &Option<AppenderConfig>| {
+        appender
+            .as_ref()
+            .and_then(|appender| appender.level)
+            .unwrap_or_default()
+    };
+
+    cmp::max(
+        config.root_log_level(),
+        cmp::min(
+            appender_log_level(&config.console),
+            appender_log_level(&config.file),
+        ),
     )
 }
 
+/// Create the content of a zap JSON configuration file according to the given log configuration
+///
+/// The configuration can be unmarshalled into a `zap.Config`. The events are written as JSON to
+/// stderr and to the given log file, which is picked up by the Vector agent if its name ends with
+/// `.zap.json`. zap does not roll over log files, so the product must take care of it.
+///
+/// # Arguments
+///
+/// * `log_dir` - Directory where the log files are stored
+/// * `log_file` - Name of the log file, e.g. `my-product.zap.json`
+/// * `config` - The logging configuration for the container; zap supports only a single log
+///   level, see [`go_log_level`].
+///
+/// # Example
+///
+/// ```
+/// use stackable_operator::product_logging::{
+///     framework::create_zap_config, spec::AutomaticContainerLogConfig,
+/// };
+///
+/// let zap_config = create_zap_config(
+///     "/stackable/log/my-product",
+///     "my-product.zap.json",
+///     &AutomaticContainerLogConfig::default(),
+/// );
+///
+/// assert!(zap_config.contains(r#""level": "info""#));
+/// ```
+pub fn create_zap_config(
+    log_dir: &str,
+    log_file: &str,
+    config: &AutomaticContainerLogConfig,
+) -> String {
+    let zap_config = serde_json::json!({
+        "level": go_log_level(config).to_zap_literal(),
+        "encoding": "json",
+        "outputPaths": ["stderr", format!("{log_dir}/{log_file}")],
+        "errorOutputPaths": ["stderr"],
+        "encoderConfig": {
+            "timeKey": "time",
+            "levelKey": "level",
+            "nameKey": "logger",
+            "callerKey": "caller",
+            "messageKey": "msg",
+            "stacktraceKey": "stacktrace",
+            "levelEncoder": "lowercase",
+            "timeEncoder": "rfc3339nano",
+            "durationEncoder": "string",
+            "callerEncoder": "short",
+        },
+    });
+
+    serde_json::to_string_pretty(&zap_config).expect("the zap configuration must be serializable")
+}
+
 /// Create the content of a Vector configuration file in YAML format according to the given log
 /// configuration
 ///
@@ -694,6 +1314,67 @@ pub fn create_vector_config<T>(
     role_group: &RoleGroupRef<T>,
     config: Option<&AutomaticContainerLogConfig>,
 ) -> String
+where
+    T: Resource,
+{
+    default_vector_config(role_group, config).to_yaml()
+}
+
+/// Create the default Vector configuration according to the given log configuration
+///
+/// The configuration reads the log files of all containers, parses them according to their logging
+/// framework and ships the events to the Vector aggregator. It can be extended with
+/// product-specific sources and transforms, see [`VectorConfig`], and rendered with
+/// [`VectorConfig::to_yaml`].
+pub fn default_vector_config<T>(
+    role_group: &RoleGroupRef<T>,
+    config: Option<&AutomaticContainerLogConfig>,
+) -> VectorConfig
+where
+    T: Resource,
+{
+    let mut vector_config = base_vector_config(role_group, config);
+
+    // The sources of the JSON log files are always added, because a file source without
+    // matching files costs nothing.
+    vector_config
+        .add_file_parser(
+            "log4j2_json",
+            FileSource::new([format!("{STACKABLE_LOG_DIR}/*/*.log4j2.json")]),
+            LOG4J2_JSON_PARSER,
+        )
+        .add_file_parser(
+            "logback_json",
+            FileSource::new([format!("{STACKABLE_LOG_DIR}/*/*.logback.json")]),
+            LOGBACK_JSON_PARSER,
+        )
+        .add_file_parser(
+            "go_json",
+            FileSource::new([
+                format!("{STACKABLE_LOG_DIR}/*/*.zap.json"),
+                format!("{STACKABLE_LOG_DIR}/*/*.slog.json"),
+                format!("{STACKABLE_LOG_DIR}/*/*.logrus.json"),
+            ]),
+            GO_JSON_PARSER,
+        )
+        .add_file_parser(
+            "plain_json",
+            FileSource::new([format!("{STACKABLE_LOG_DIR}/*/*.plain.json")]),
+            PLAIN_JSON_PARSER,
+        );
+
+    vector_config
+}
+
+/// Create the Vector configuration for the log formats which were supported before the JSON log
+/// formats were added
+///
+/// Its output is pinned by a test, so that existing deployments are not affected by changes to
+/// the typed Vector configuration model.
+fn base_vector_config<T>(
+    role_group: &RoleGroupRef<T>,
+    config: Option<&AutomaticContainerLogConfig>,
+) -> VectorConfig
 where
     T: Resource,
 {
@@ -711,644 +1392,1441 @@ where
         LogLevel::FATAL | LogLevel::NONE => "false",
     };
 
-    format!(
-        r#"data_dir: {STACKABLE_LOG_DIR}/{VECTOR_STATE_DIR}
-
-log_schema:
-  host_key: pod
-
-sources:
-  vector:
-    type: internal_logs
-
-  files_stdout:
-    type: file
-    include:
-      - {STACKABLE_LOG_DIR}/*/*.stdout.log
-
-  files_stderr:
-    type: file
-    include:
-      - {STACKABLE_LOG_DIR}/*/*.stderr.log
-
-  files_log4j:
-    type: file
-    include:
-      - {STACKABLE_LOG_DIR}/*/*.log4j.xml
-    line_delimiter: "\r\n"
-    multiline:
-      mode: halt_before
-      start_pattern: ^<log4j:event
-      condition_pattern: ^<log4j:event
-      timeout_ms: 1000
-
-  files_log4j2:
-    type: file
-    include:
-      - {STACKABLE_LOG_DIR}/*/*.log4j2.xml
-    line_delimiter: "\r\n"
-
-  files_py:
-    type: file
-    include:
-      - {STACKABLE_LOG_DIR}/*/*.py.json
-
-  files_airlift:
-    type: file
-    include:
-      - {STACKABLE_LOG_DIR}/*/*.airlift.json
-
-  files_tracing_rs:
-    type: file
-    include:
-      - {STACKABLE_LOG_DIR}/*/*.tracing-rs.json
-
-  files_opa_json:
-    type: file
-    include:
-      - {STACKABLE_LOG_DIR}/opa/current
-      - {STACKABLE_LOG_DIR}/opa/test
-
-transforms:
-  processed_files_tracing_rs:
-    inputs:
-      - files_tracing_rs
-    type: remap
-    source: |
-      raw_message = string!(.message)
-
-      .timestamp = now()
-      .logger = ""
-      .level = "INFO"
-      .message = ""
-      .errors = []
-
-      parsed_event, err = parse_json(raw_message)
-      if err != null {{
-        error = "JSON not parsable: " + err
-        .errors = push(.errors, error)
-        log(error, level: "warn")
-        .message = raw_message
-      }} else if !is_object(parsed_event) {{
-        error = "Parsed event is not a JSON object."
-        .errors = push(.errors, error)
-        log(error, level: "warn")
-        .message = raw_message
-      }} else {{
-        event = object!(parsed_event)
-
-        timestamp_string, err = string(event.timestamp)
-        if err == null {{
-          parsed_timestamp, err = parse_timestamp(timestamp_string, "%+")
-          if err == null {{
-            .timestamp = parsed_timestamp
-          }} else {{
-            .errors = push(.errors, "Timestamp not parsable, trying current time instead: " + err)
-          }}
-        }}
-
-        .logger, err = string(event.target)
-        if err != null || is_empty(.logger) {{
-          .errors = push(.errors, "Logger/target not found.")
-        }}
-
-        level, err = string(event.level)
-        if err != null {{
-          .errors = push(.errors, "Level not found, using \"" + .level + "\" instead.")
-        }} else if !includes(["TRACE", "DEBUG", "INFO", "WARN", "ERROR", "FATAL"], upcase(level)) {{
-          .errors = push(.errors, "Level \"" + level + "\" unknown, using \"" + .level + "\" instead.")
-        }} else {{
-          .level = upcase(level)
-        }}
-
-        fields, err = object(event.fields)
-        if err != null {{
-          .errors = push(.errors, "Fields are not an object.")
-        }}
-
-        .message, err = string(fields.message)
-        if err != null || is_empty(.message) {{
-          .errors = push(.errors, "Message not found.")
-        }}
-
-        del(fields.message)
-
-        other_fields = encode_key_value(fields, field_delimiter: "\n")
-        .message = join!(compact([.message, other_fields]), "\n\n")
-      }}
-
-  processed_files_opa_json:
-    inputs:
-      - files_opa_json
-    type: remap
-    source: |
-      raw_message = string!(.message)
-
-      .timestamp = now()
-      .logger = ""
-      .level = "INFO"
-      .message = ""
-      .errors = []
-
-      parsed_event, err = parse_json(raw_message)
-      if err != null {{
-        error = "JSON not parsable: " + err
-        .errors = push(.errors, error)
-        log(error, level: "warn")
-        .message = raw_message
-      }} else if !is_object(parsed_event) {{
-        error = "Parsed event is not a JSON object."
-        .errors = push(.errors, error)
-        log(error, level: "warn")
-        .message = raw_message
-      }} else {{
-        event = object!(parsed_event)
-
-        property_timestamp_valid = false
-        timestamp_string, err = string(event.timestamp)
-        if err == null {{
-          parsed_timestamp, err = parse_timestamp(timestamp_string, "%Y-%m-%dT%H:%M:%S.%fZ")
-          if err == null {{
-            .timestamp = parsed_timestamp
-            property_timestamp_valid = true
-          }} else {{
-            .errors = push(.errors, "Timestamp not parsable, trying property time instead: " + err)
-          }}
-        }}
-        if !property_timestamp_valid {{
-          time_string, err = string(event.time)
-          if err == null {{
-            parsed_timestamp, err = parse_timestamp(time_string, "%Y-%m-%dT%H:%M:%SZ")
-            if err == null {{
-              .timestamp = parsed_timestamp
-            }} else {{
-              .errors = push(.errors, "Time not parsable, using current time instead: " + err)
-            }}
-          }} else {{
-            .errors = push(.errors, "Time not found, using current time instead.")
-          }}
-        }}
-
-        .logger, err = string(event.logger)
-        if err != null || is_empty(.logger) {{
-          .errors = push(.errors, "Logger not found.")
-        }}
-
-        level, err = string(event.level)
-        if err != null {{
-          .errors = push(.errors, "Level not found, using \"" + .level + "\" instead.")
-        }} else if !includes(["TRACE", "DEBUG", "INFO", "WARN", "ERROR", "FATAL"], upcase(level)) {{
-          .errors = push(.errors, "Level \"" + level + "\" unknown, using \"" + .level + "\" instead.")
-        }} else {{
-          .level = upcase(level)
-        }}
-
-        .message, err = string(event.msg)
-        if err != null || is_empty(.message) {{
-          .errors = push(.errors, "Message not found.")
-        }}
-
-        del(event.time)
-        del(event.timestamp)
-        del(event.level)
-        del(event.msg)
-
-        other_fields = encode_key_value(event, field_delimiter: "\n")
-        .message = join!(compact([.message, other_fields]), "\n\n")
-      }}
-
-  processed_files_stdout:
-    inputs:
-      - files_stdout
-    type: remap
-    source: |
-      .logger = "ROOT"
-      .level = "INFO"
-
-  processed_files_stderr:
-    inputs:
-      - files_stderr
-    type: remap
-    source: |
-      .logger = "ROOT"
-      .level = "ERROR"
-
-  processed_files_log4j:
-    inputs:
-      - files_log4j
-    type: remap
-    source: |
-      raw_message = string!(.message)
-
-      .timestamp = now()
-      .logger = ""
-      .level = "INFO"
-      .message = ""
-      .errors = []
-
-      # Wrap the event so that the log4j namespace is defined when parsing the event
-      wrapped_xml_event = "<root xmlns:log4j=\"http://jakarta.apache.org/log4j/\">" + raw_message + "</root>"
-      parsed_event, err = parse_xml(wrapped_xml_event)
-      if err != null {{
-        error = "XML not parsable: " + err
-        .errors = push(.errors, error)
-        log(error, level: "warn")
-        .message = raw_message
-      }} else {{
-        root = object!(parsed_event.root)
-        if !is_object(root.event) {{
-          error = "Parsed event contains no \"event\" tag."
-          .errors = push(.errors, error)
-          log(error, level: "warn")
-          .message = raw_message
-        }} else {{
-          if keys(root) != ["event"] {{
-            .errors = push(.errors, "Parsed event contains multiple tags: " + join!(keys(root), ", "))
-          }}
-          event = object!(root.event)
-
-          epoch_milliseconds, err = to_int(event.@timestamp)
-          if err == null && epoch_milliseconds != 0 {{
-            converted_timestamp, err = from_unix_timestamp(epoch_milliseconds, "milliseconds")
-            if err == null {{
-              .timestamp = converted_timestamp
-            }} else {{
-              .errors = push(.errors, "Time not parsable, using current time instead: " + err)
-            }}
-          }} else {{
-            .errors = push(.errors, "Timestamp not found, using current time instead.")
-          }}
-
-          .logger, err = string(event.@logger)
-          if err != null || is_empty(.logger) {{
-            .errors = push(.errors, "Logger not found.")
-          }}
-
-          level, err = string(event.@level)
-          if err != null {{
-            .errors = push(.errors, "Level not found, using \"" + .level + "\" instead.")
-          }} else if !includes(["TRACE", "DEBUG", "INFO", "WARN", "ERROR", "FATAL"], level) {{
-            .errors = push(.errors, "Level \"" + level + "\" unknown, using \"" + .level + "\" instead.")
-          }} else {{
-            .level = level
-          }}
-
-          message, err = string(event.message)
-          if err != null || is_empty(message) {{
-            .errors = push(.errors, "Message not found.")
-          }}
-          throwable = string(event.throwable) ?? ""
-          .message = join!(compact([message, throwable]), "\n")
-        }}
-      }}
-
-  processed_files_log4j2:
-    inputs:
-      - files_log4j2
-    type: remap
-    source: |
-      raw_message = string!(.message)
-
-      .timestamp = now()
-      .logger = ""
-      .level = "INFO"
-      .message = ""
-      .errors = []
-
-      event = {{}}
-      parsed_event, err = parse_xml(raw_message)
-      if err != null {{
-        error = "XML not parsable: " + err
-        .errors = push(.errors, error)
-        log(error, level: "warn")
-        .message = raw_message
-      }} else {{
-        if !is_object(parsed_event.Event) {{
-          error = "Parsed event contains no \"Event\" tag."
-          .errors = push(.errors, error)
-          log(error, level: "warn")
-          .message = raw_message
-        }} else {{
-          event = object!(parsed_event.Event)
-
-          tag_instant_valid = false
-          instant, err = object(event.Instant)
-          if err == null {{
-            epoch_nanoseconds, err = to_int(instant.@epochSecond) * 1_000_000_000 + to_int(instant.@nanoOfSecond)
-            if err == null && epoch_nanoseconds != 0 {{
-              converted_timestamp, err = from_unix_timestamp(epoch_nanoseconds, "nanoseconds")
-              if err == null {{
-                .timestamp = converted_timestamp
-                tag_instant_valid = true
-              }} else {{
-                .errors = push(.errors, "Instant invalid, trying property timeMillis instead: " + err)
-              }}
-            }} else {{
-              .errors = push(.errors, "Instant invalid, trying property timeMillis instead: " + err)
-            }}
-          }}
-          if !tag_instant_valid {{
-            epoch_milliseconds, err = to_int(event.@timeMillis)
-            if err == null && epoch_milliseconds != 0 {{
-              converted_timestamp, err = from_unix_timestamp(epoch_milliseconds, "milliseconds")
-              if err == null {{
-                .timestamp = converted_timestamp
-              }} else {{
-                .errors = push(.errors, "timeMillis not parsable, using current time instead: " + err)
-              }}
-            }} else {{
-              .errors = push(.errors, "timeMillis not parsable, using current time instead: " + err)
-            }}
-          }}
-
-          .logger, err = string(event.@loggerName)
-          if err != null || is_empty(.logger) {{
-            .errors = push(.errors, "Logger not found.")
-          }}
-
-          level, err = string(event.@level)
-          if err != null {{
-            .errors = push(.errors, "Level not found, using \"" + .level + "\" instead.")
-          }} else if !includes(["TRACE", "DEBUG", "INFO", "WARN", "ERROR", "FATAL"], level) {{
-            .errors = push(.errors, "Level \"" + level + "\" unknown, using \"" + .level + "\" instead.")
-          }} else {{
-            .level = level
-          }}
-
-          exception = null
-          thrown = event.Thrown
-          if is_object(thrown) {{
-            exception = "Exception"
-            thread, err = string(event.@thread)
-            if err == null && !is_empty(thread) {{
-              exception = exception + " in thread \"" + thread + "\""
-            }}
-            thrown_name, err = string(thrown.@name)
-            if err == null && !is_empty(exception) {{
-              exception = exception + " " + thrown_name
-            }}
-            message = string(thrown.@localizedMessage) ??
-              string(thrown.@message) ??
-              ""
-            if !is_empty(message) {{
-              exception = exception + ": " + message
-            }}
-            stacktrace_items = array(thrown.ExtendedStackTrace.ExtendedStackTraceItem) ?? []
-            stacktrace = ""
-            for_each(stacktrace_items) -> |_index, value| {{
-              stacktrace = stacktrace + "        "
-              class = string(value.@class) ?? ""
-              method = string(value.@method) ?? ""
-              if !is_empty(class) && !is_empty(method) {{
-                stacktrace = stacktrace + "at " + class + "." + method
-              }}
-              file = string(value.@file) ?? ""
-              line = string(value.@line) ?? ""
-              if !is_empty(file) && !is_empty(line) {{
-                stacktrace = stacktrace + "(" + file + ":" + line + ")"
-              }}
-              exact = to_bool(value.@exact) ?? false
-              location = string(value.@location) ?? ""
-              version = string(value.@version) ?? ""
-              if !is_empty(location) && !is_empty(version) {{
-                stacktrace = stacktrace + " "
-                if !exact {{
-                  stacktrace = stacktrace + "~"
-                }}
-                stacktrace = stacktrace + "[" + location + ":" + version + "]"
-              }}
-              stacktrace = stacktrace + "\n"
-            }}
-            if stacktrace != "" {{
-              exception = exception + "\n" + stacktrace
-            }}
-          }}
-
-          message, err = string(event.Message)
-          if err != null || is_empty(message) {{
-            message = null
-            .errors = push(.errors, "Message not found.")
-          }}
-          .message = join!(compact([message, exception]), "\n")
-        }}
-      }}
-
-  processed_files_py:
-    inputs:
-      - files_py
-    type: remap
-    source: |
-      raw_message = string!(.message)
-
-      .timestamp = now()
-      .logger = ""
-      .level = "INFO"
-      .message = ""
-      .errors = []
-
-      parsed_event, err = parse_json(raw_message)
-      if err != null {{
-        error = "JSON not parsable: " + err
-        .errors = push(.errors, error)
-        log(error, level: "warn")
-        .message = raw_message
-      }} else if !is_object(parsed_event) {{
-        error = "Parsed event is not a JSON object."
-        .errors = push(.errors, error)
-        log(error, level: "warn")
-        .message = raw_message
-      }} else {{
-        event = object!(parsed_event)
-
-        asctime, err = string(event.asctime)
-        if err == null {{
-          parsed_timestamp, err = parse_timestamp(asctime, "%F %T,%3f")
-          if err == null {{
-            .timestamp = parsed_timestamp
-          }} else {{
-            .errors = push(.errors, "Timestamp not parsable, using current time instead: "+ err)
-          }}
-        }} else {{
-          .errors = push(.errors, "Timestamp not found, using current time instead.")
-        }}
-
-        .logger, err = string(event.name)
-        if err != null || is_empty(.logger) {{
-          .errors = push(.errors, "Logger not found.")
-        }}
-
-        level, err = string(event.levelname)
-        if err != null {{
-          .errors = push(.errors, "Level not found, using \"" + .level + "\" instead.")
-        }} else if level == "DEBUG" {{
-          .level = "DEBUG"
-        }} else if level == "INFO" {{
-          .level = "INFO"
-        }} else if level == "WARNING" {{
-          .level = "WARN"
-        }} else if level == "ERROR" {{
-          .level = "ERROR"
-        }} else if level == "CRITICAL" {{
-          .level = "FATAL"
-        }} else {{
-          .errors = push(.errors, "Level \"" + level + "\" unknown, using \"" + .level + "\" instead.")
-        }}
-
-        .message, err = string(event.message)
-        if err != null || is_empty(.message) {{
-          .errors = push(.errors, "Message not found.")
-        }}
-      }}
-
-  processed_files_airlift:
-    inputs:
-      - files_airlift
-    type: remap
-    source: |
-      raw_message = string!(.message)
-
-      .timestamp = now()
-      .logger = ""
-      .level = "INFO"
-      .message = ""
-      .errors = []
-
-      parsed_event, err = parse_json(raw_message)
-      if err != null {{
-        error = "JSON not parsable: " + err
-        .errors = push(.errors, error)
-        log(error, level: "warn")
-        .message = raw_message
-      }} else if !is_object(parsed_event) {{
-        error = "Parsed event is not a JSON object."
-        .errors = push(.errors, error)
-        log(error, level: "warn")
-        .message = raw_message
-      }} else {{
-        event = object!(parsed_event)
-
-        timestamp_string, err = string(event.timestamp)
-        if err == null {{
-          parsed_timestamp, err = parse_timestamp(timestamp_string, "%Y-%m-%dT%H:%M:%S.%fZ")
-          if err == null {{
-            .timestamp = parsed_timestamp
-          }} else {{
-            .errors = push(.errors, "Timestamp not parsable, using current time instead: " + err)
-          }}
-        }} else {{
-          .errors = push(.errors, "Timestamp not found, using current time instead.")
-        }}
-
-        .logger, err = string(event.logger)
-        if err != null || is_empty(.logger) {{
-          .errors = push(.errors, "Logger not found.")
-        }}
-
-        level, err = string(event.level)
-        if err != null {{
-          .errors = push(.errors, "Level not found, using \"" + .level + "\" instead.")
-        }} else if !includes(["TRACE", "DEBUG", "INFO", "WARN", "ERROR", "FATAL"], level) {{
-          .errors = push(.errors, "Level \"" + level + "\" unknown, using \"" + .level + "\" instead.")
-        }} else {{
-          .level = level
-        }}
-
-        .thread = string(parsed_event.thread) ?? null
-
-        .message, err = string(event.message)
-        if err != null || is_empty(.message) {{
-          .errors = push(.errors, "Message not found.")
-        }}
-        stacktrace = string(event.stackTrace) ?? ""
-        .message = join!(compact([.message, stacktrace]), "\n\n")
-      }}
-
-  extended_logs_files:
-    inputs:
-      - processed_files_*
-    type: remap
-    source: |
-      del(.source_type)
-      if .errors == [] {{
-        del(.errors)
-      }}
-      . |= parse_regex!(.file, r'^{STACKABLE_LOG_DIR}/(?P<container>.*?)/(?P<file>.*?)$')
-
-  filtered_logs_vector:
-    inputs:
-      - vector
-    type: filter
-    condition: '{vector_log_level_filter_expression}'
-
-  extended_logs_vector:
-    inputs:
-      - filtered_logs_vector
-    type: remap
-    source: |
-      .container = "vector"
-      .level = .metadata.level
-      .logger = .metadata.module_path
-      if exists(.file) {{ .processed_file = del(.file) }}
-      del(.metadata)
-      del(.pid)
-      del(.source_type)
-
-  extended_logs:
-    inputs:
-      - extended_logs_*
-    type: remap
-    source: |
-      .namespace = "{namespace}"
-      .cluster = "{cluster_name}"
-      .role = "{role_name}"
-      .roleGroup = "{role_group_name}"
-
-sinks:
-  aggregator:
-    inputs:
-      - extended_logs
-    type: vector
-    address: ${VECTOR_AGGREGATOR_ENV_NAME}
+    let mut vector_config = VectorConfig::new(
+        format!("{STACKABLE_LOG_DIR}/{VECTOR_STATE_DIR}"),
+        LogSchema {
+            host_key: "pod".to_owned(),
+        },
+    );
+
+    vector_config
+        .add_source("vector", Source::InternalLogs)
+        .add_file_parser(
+            "stdout",
+            FileSource::new([format!("{STACKABLE_LOG_DIR}/*/*.stdout.log")]),
+            STDOUT_PARSER,
+        )
+        .add_file_parser(
+            "stderr",
+            FileSource::new([format!("{STACKABLE_LOG_DIR}/*/*.stderr.log")]),
+            STDERR_PARSER,
+        )
+        .add_file_parser(
+            "log4j",
+            FileSource::new([format!("{STACKABLE_LOG_DIR}/*/*.log4j.xml")])
+                .with_line_delimiter("\r\n")
+                .with_multiline(MultilineConfig {
+                    mode: MultilineMode::HaltBefore,
+                    start_pattern: "^<log4j:event".to_owned(),
+                    condition_pattern: "^<log4j:event".to_owned(),
+                    timeout_ms: 1000,
+                }),
+            LOG4J_PARSER,
+        )
+        .add_file_parser(
+            "log4j2",
+            FileSource::new([format!("{STACKABLE_LOG_DIR}/*/*.log4j2.xml")])
+                .with_line_delimiter("\r\n"),
+            LOG4J2_PARSER,
+        )
+        .add_file_parser(
+            "py",
+            FileSource::new([format!("{STACKABLE_LOG_DIR}/*/*.py.json")]),
+            PY_PARSER,
+        )
+        .add_file_parser(
+            "airlift",
+            FileSource::new([format!("{STACKABLE_LOG_DIR}/*/*.airlift.json")]),
+            AIRLIFT_PARSER,
+        )
+        .add_file_parser(
+            "tracing_rs",
+            FileSource::new([format!("{STACKABLE_LOG_DIR}/*/*.tracing-rs.json")]),
+            TRACING_RS_PARSER,
+        )
+        .add_file_parser(
+            "opa_json",
+            FileSource::new([
+                format!("{STACKABLE_LOG_DIR}/opa/current"),
+                format!("{STACKABLE_LOG_DIR}/opa/test"),
+            ]),
+            OPA_JSON_PARSER,
+        )
+        .add_transform(
+            "extended_logs_files",
+            Transform::remap(
+                [format!("{PROCESSED_FILES_PREFIX}*")],
+                format!(
+                    r"del(.source_type)
+if .errors == [] {{
+  del(.errors)
+}}
+. |= parse_regex!(.file, r'^{STACKABLE_LOG_DIR}/(?P<container>.*?)/(?P<file>.*?)$')
+"
+                ),
+            ),
+        )
+        .add_transform(
+            "filtered_logs_vector",
+            Transform::filter(["vector"], vector_log_level_filter_expression),
+        )
+        .add_transform(
+            "extended_logs_vector",
+            Transform::remap(["filtered_logs_vector"], VECTOR_LOG_TRANSFORM),
+        )
+        .add_transform(
+            "extended_logs",
+            Transform::remap(
+                ["extended_logs_*"],
+                format!(
+                    r#".namespace = "{namespace}"
+.cluster = "{cluster_name}"
+.role = "{role_name}"
+.roleGroup = "{role_group_name}"
 "#,
-        namespace = role_group.cluster.namespace.clone().unwrap_or_default(),
-        cluster_name = role_group.cluster.name,
-        role_name = role_group.role,
-        role_group_name = role_group.role_group
-    )
+                    namespace = role_group.cluster.namespace.clone().unwrap_or_default(),
+                    cluster_name = role_group.cluster.name,
+                    role_name = role_group.role,
+                    role_group_name = role_group.role_group
+                ),
+            ),
+        );
+
+    set_vector_log_sink(&mut vector_config, &LogSink::VectorAggregator {});
+
+    vector_config
 }
 
-/// Create the specification of the Vector log agent container.
-///
-/// The vector process is not running as PID 1, so a Kubernetes SIGTERM will be have no effect.
-/// Instead, the vector process can be shut down by creating a file below {STACKABLE_LOG_DIR}/{VECTOR_LOG_DIR},
-/// e.g. {STACKABLE_LOG_DIR}/{VECTOR_LOG_DIR}/{SHUTDOWN_FILE}. This way logs from the products will always be shipped,
-/// as the vector container will be the last one to terminate. A specific container must be chosen, which has the responsibility
-/// to create a file after it has properly shut down. It should be the one taking the longest to shut down.
-/// E.g. for hdfs the lifetime of vector will be bound to the datanode container and not to the zkfc container.
-/// We *could* have different shutdown trigger files for all application containers and wait for all containers
-/// to terminate, but that seems rather complicated and will be added once needed. Additionally, you should remove
-/// the shutdown marker file on startup of the application, as the application container can crash for any reason
-/// and get restarted. If you don't remove the shutdown file on startup, the vector container will crashloop forever,
-/// as it will start and shut down immediately after!
+/// Configures the Vector configuration to send the logs to the given log sink
 ///
-/// ```
-/// use stackable_operator::{
-///     builder::{
-///         meta::ObjectMetaBuilder,
-///         pod::{container::ContainerBuilder, resources::ResourceRequirementsBuilder, PodBuilder},
-///     },
-///     product_logging::{self, framework:: {create_vector_shutdown_file_command, remove_vector_shutdown_file_command}},
-///     utils::COMMON_BASH_TRAP_FUNCTIONS,
-/// };
-/// use k8s_openapi::apimachinery::pkg::api::resource::Quantity;
-/// # use stackable_operator::{
-/// #     commons::product_image_selection::ResolvedProductImage,
-/// #     config::fragment,
-/// #     product_logging::spec::{default_logging, Logging},
-/// # };
-/// # use strum::{Display, EnumIter};
-/// #
-/// # pub const STACKABLE_LOG_DIR: &str = "/stackable/log";
+/// Existing sinks are replaced. The sink receives the events of the [`VECTOR_SINK_INPUT`]
+/// transform. Credentials and CA certificates of direct log sinks are read from the volumes
+/// returned by [`log_sink_volumes`], which must be mounted into the Vector container, see
+/// [`vector_container_for_log_sink`].
+pub fn set_vector_log_sink(vector_config: &mut VectorConfig, log_sink: &LogSink) {
+    let auth = log_sink
+        .connection()
+        .and_then(|connection| connection.credentials.as_ref())
+        .map(|credentials| {
+            vector_config.add_secret_backend(
+                LOG_SINK_CREDENTIALS_SECRET_BACKEND,
+                SecretBackend::directory(log_sink_credentials_dir(&credentials.secret_class)),
+            );
+
+            SinkAuth::Basic {
+                user: format!("SECRET[{LOG_SINK_CREDENTIALS_SECRET_BACKEND}.user]"),
+                password: format!("SECRET[{LOG_SINK_CREDENTIALS_SECRET_BACKEND}.password]"),
+            }
+        });
+    let tls = log_sink
+        .connection()
+        .and_then(|connection| vector_sink_tls(&connection.tls));
+
+    vector_config.sinks.clear();
+
+    match log_sink {
+        LogSink::VectorAggregator {} => {
+            vector_config.add_sink(
+                "aggregator",
+                Sink::vector(
+                    [VECTOR_SINK_INPUT],
+                    format!("${VECTOR_AGGREGATOR_ENV_NAME}"),
+                ),
+            );
+        }
+        LogSink::Otlp(connection) => {
+            vector_config
+                .add_transform(
+                    OTLP_TRANSFORM,
+                    Transform::remap([VECTOR_SINK_INPUT], OTLP_CONVERTER),
+                )
+                .add_sink("otlp", otlp_sink(connection, auth, tls));
+        }
+        LogSink::Loki(connection) => {
+            let labels = [
+                ("namespace", "{{ namespace }}"),
+                ("cluster", "{{ cluster }}"),
+                ("role", "{{ role }}"),
+                ("role_group", "{{ roleGroup }}"),
+                ("container", "{{ container }}"),
+            ]
+            .into_iter()
+            .map(|(name, template)| (name.to_owned(), template.to_owned()))
+            .collect::<IndexMap<_, _>>();
+
+            vector_config.add_sink(
+                "loki",
+                Sink {
+                    inputs: vec![VECTOR_SINK_INPUT.to_owned()],
+                    kind: SinkKind::Loki {
+                        endpoint: connection.endpoint(LOKI_DEFAULT_PORT),
+                        encoding: Encoding { codec: Codec::Json },
+                        labels,
+                        auth,
+                        tls,
+                    },
+                },
+            );
+        }
+        LogSink::OpenSearch(OpenSearchLogSink { connection, index }) => {
+            vector_config.add_sink(
+                "opensearch",
+                Sink {
+                    inputs: vec![VECTOR_SINK_INPUT.to_owned()],
+                    kind: SinkKind::Elasticsearch {
+                        endpoints: vec![connection.endpoint(OPENSEARCH_DEFAULT_PORT)],
+                        bulk: ElasticsearchBulk {
+                            index: index.clone(),
+                        },
+                        auth,
+                        tls,
+                    },
+                },
+            );
+        }
+    }
+}
+
+/// Returns the Vector sink which sends the output of the [`OTLP_TRANSFORM`] to an OpenTelemetry
+/// collector
+fn otlp_sink(connection: &LogSinkConnection, auth: Option<SinkAuth>, tls: Option<SinkTls>) -> Sink {
+    Sink {
+        inputs: vec![OTLP_TRANSFORM.to_owned()],
+        kind: SinkKind::Opentelemetry {
+            protocol: OpentelemetryProtocol::Http(HttpClient {
+                uri: format!(
+                    "{endpoint}/v1/logs",
+                    endpoint = connection.endpoint(OTLP_HTTP_DEFAULT_PORT)
+                ),
+                encoding: Encoding { codec: Codec::Json },
+                framing: Some(Framing {
+                    method: FramingMethod::NewlineDelimited,
+                }),
+                headers: [("content-type".to_owned(), "application/json".to_owned())].into(),
+                auth,
+                tls,
+            }),
+        },
+    }
+}
+
+/// Returns the TLS options of a Vector sink for the given TLS configuration
+///
+/// TLS itself is enabled by the `https` scheme of the endpoint. Certificates trusted by the common
+/// web browsers are verified by default, so no options are required in this case.
+fn vector_sink_tls(tls_client_details: &TlsClientDetails) -> Option<SinkTls> {
+    match &tls_client_details.tls {
+        Some(Tls {
+            verification: TlsVerification::None {},
+        }) => Some(SinkTls {
+            verify_certificate: Some(false),
+            verify_hostname: Some(false),
+            ..SinkTls::default()
+        }),
+        Some(Tls {
+            verification:
+                TlsVerification::Server(TlsServerVerification {
+                    ca_cert: CaCert::SecretClass(_),
+                }),
+        }) => Some(SinkTls {
+            ca_file: tls_client_details.tls_ca_cert_mount_path(),
+            ..SinkTls::default()
+        }),
+        Some(Tls {
+            verification:
+                TlsVerification::Server(TlsServerVerification {
+                    ca_cert: CaCert::WebPki {},
+                }),
+        })
+        | None => None,
+    }
+}
+
+/// Log rules of a container which are applied by the Vector agent, see [`add_vector_log_rules`]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum VectorLogRules {
+    /// Apply all log rules, e.g. for products logging with logback or Log4j, which have no
+    /// built-in filters for them.
+    All,
+
+    /// Apply all log rules except the rate limits, which are already applied by the Log4j 2
+    /// `BurstFilter` rendered by [`create_log4j2_config`].
+    ExceptRateLimits,
+}
+
+/// Adds the sampling, rate limiting and redaction rules of the given container to the Vector
+/// configuration
+///
+/// The rules are applied by transforms which are inserted in front of the sinks, i.e. between
+/// the [`VECTOR_SINK_INPUT`] transform and its consumers. Therefore, the function must be called
+/// after [`default_vector_config`] and at most once per container.
+///
+/// Like in the logging frameworks, the rules of a logger also apply to its descendants, unless
+/// they are configured on their own. The rules of the root logger apply to all other loggers.
+/// Rate limits are approximated by a Vector `throttle` transform with a window of whole seconds.
+pub fn add_vector_log_rules(
+    vector_config: &mut VectorConfig,
+    container: &str,
+    config: &AutomaticContainerLogConfig,
+    rules: VectorLogRules,
+) -> Result<(), LoggingError> {
+    let mut stages = Vec::new();
+
+    for (logger, logger_config) in &config.loggers {
+        let exclude = || vector_log_rule_exclude_condition(container, logger, config);
+
+        if let (VectorLogRules::All, Some(rate_limit)) = (rules, &logger_config.rate_limit) {
+            let events_per_second = cmp::max(1, rate_limit.events_per_second);
+            let window_secs = cmp::max(1, rate_limit.max_burst() / events_per_second);
+            stages.push(
+                Transform::throttle(
+                    Vec::<String>::new(),
+                    events_per_second.saturating_mul(window_secs),
+                    window_secs,
+                )
+                .excluding(exclude()),
+            );
+        }
+
+        if let Some(sample_rate) = logger_config.sample_rate {
+            stages.push(
+                Transform::sample(Vec::<String>::new(), sample_rate.get()).excluding(exclude()),
+            );
+        }
+    }
+
+    if !config.redactions.is_empty() {
+        stages.push(Transform::remap(
+            Vec::<String>::new(),
+            vector_redaction_program(container, config.redactions.values())?,
+        ));
+    }
+
+    if stages.is_empty() {
+        return Ok(());
+    }
+
+    let previous_stage = vector_config
+        .transforms
+        .shift_remove(VECTOR_SINK_INPUT)
+        .context(MissingVectorSinkInputSnafu)?;
+
+    let prefix = format!(
+        "log_rules_{container}",
+        container = container.replace('-', "_")
+    );
+    let mut input = format!("{prefix}_input");
+    vector_config.add_transform(&input, previous_stage);
+
+    let last_index = stages.len() - 1;
+    for (index, mut stage) in stages.into_iter().enumerate() {
+        stage.inputs = vec![input];
+        input = if index == last_index {
+            VECTOR_SINK_INPUT.to_owned()
+        } else {
+            format!("{prefix}_{index}")
+        };
+        vector_config.add_transform(&input, stage);
+    }
+
+    Ok(())
+}
+
+/// Returns the VRL condition which matches all events which are not subject to the rules of the
+/// given logger
+fn vector_log_rule_exclude_condition(
+    container: &str,
+    logger: &str,
+    config: &AutomaticContainerLogConfig,
+) -> String {
+    let mut condition = format!(".container != {}", vrl_string(container));
+
+    let more_specific_loggers = if logger == AutomaticContainerLogConfig::ROOT_LOGGER {
+        config
+            .loggers
+            .keys()
+            .filter(|other| other.as_str() != AutomaticContainerLogConfig::ROOT_LOGGER)
+            .collect::<Vec<_>>()
+    } else {
+        let _ = write!(condition, " || !{}", vrl_logger_match(logger));
+        config
+            .loggers
+            .keys()
+            .filter(|other| other.starts_with(&format!("{logger}.")))
+            .collect()
+    };
+
+    for other in more_specific_loggers {
+        let _ = write!(condition, " || {}", vrl_logger_match(other));
+    }
+
+    condition
+}
+
+/// Returns the VRL condition which matches the events of the given logger and its descendants
+fn vrl_logger_match(logger: &str) -> String {
+    format!(
+        "(.logger == {logger} || starts_with(string(.logger) ?? \"\", {prefix}))",
+        logger = vrl_string(logger),
+        prefix = vrl_string(&format!("{logger}.")),
+    )
+}
+
+/// Returns the VRL program which redacts the messages of the given container
+fn vector_redaction_program<'a>(
+    container: &str,
+    redactions: impl IntoIterator<Item = &'a LogRedaction>,
+) -> Result<String, LoggingError> {
+    let mut program = format!(
+        "if .container == {container} {{\n  message = string(.message) ?? \"\"\n",
+        container = vrl_string(container)
+    );
+
+    for redaction in redactions {
+        regex::Regex::new(&redaction.pattern).context(InvalidRedactionPatternSnafu {
+            pattern: &redaction.pattern,
+        })?;
+        let _ = writeln!(
+            program,
+            "  message = replace(message, r'{pattern}', {replacement})",
+            pattern = redaction.pattern.replace('\'', "\\'"),
+            replacement = vrl_string(redaction.replacement()),
+        );
+    }
+
+    program.push_str("  .message = message\n}\n");
+    Ok(program)
+}
+
+/// Returns the given value as VRL string literal
+fn vrl_string(value: &str) -> String {
+    let mut literal = String::with_capacity(value.len() + 2);
+    literal.push('"');
+    for character in value.chars() {
+        match character {
+            '"' => literal.push_str("\\\""),
+            '\\' => literal.push_str("\\\\"),
+            '\n' => literal.push_str("\\n"),
+            '\r' => literal.push_str("\\r"),
+            '\t' => literal.push_str("\\t"),
+            character => literal.push(character),
+        }
+    }
+    literal.push('"');
+    literal
+}
+
+/// Returns the volumes which provide the credentials and the CA certificate of the log sink
+///
+/// The volumes must be added to the Pod of the Vector container. They are mounted by
+/// [`log_sink_volume_mounts`].
+pub fn log_sink_volumes(log_sink: &LogSink) -> Result<Vec<Volume>, LoggingError> {
+    let Some(connection) = log_sink.connection() else {
+        return Ok(Vec::new());
+    };
+
+    let mut volumes = Vec::new();
+
+    if let Some(credentials) = &connection.credentials {
+        volumes.push(
+            credentials
+                // We need the private credentials
+                .to_volume(
+                    &log_sink_credentials_volume_name(&credentials.secret_class),
+                    SecretClassVolumeProvisionParts::PublicPrivate,
+                )
+                .context(LogSinkCredentialsVolumeSnafu)?,
+        );
+    }
+
+    let (tls_volumes, _) = connection
+        .tls
+        .volumes_and_mounts()
+        .context(LogSinkTlsVolumesSnafu)?;
+    volumes.extend(tls_volumes);
+
+    Ok(volumes)
+}
+
+/// Returns the volume mounts for the volumes returned by [`log_sink_volumes`]
+pub fn log_sink_volume_mounts(log_sink: &LogSink) -> Vec<VolumeMount> {
+    let Some(connection) = log_sink.connection() else {
+        return Vec::new();
+    };
+
+    let mut volume_mounts = Vec::new();
+
+    if let Some(credentials) = &connection.credentials {
+        let secret_class = &credentials.secret_class;
+        volume_mounts.push(
+            VolumeMountBuilder::new(
+                log_sink_credentials_volume_name(secret_class),
+                log_sink_credentials_dir(secret_class),
+            )
+            .build(),
+        );
+    }
+
+    // The volume name must match the one used in `TlsClientDetails::volumes_and_mounts`
+    if let Some(secret_class) = connection.tls.tls_ca_cert_secret_class() {
+        volume_mounts.push(
+            VolumeMountBuilder::new(
+                format!("{secret_class}-ca-cert"),
+                format!("{SECRET_BASE_PATH}/{secret_class}"),
+            )
+            .build(),
+        );
+    }
+
+    volume_mounts
+}
+
+fn log_sink_credentials_volume_name(secret_class: &str) -> String {
+    format!("{secret_class}-log-sink-credentials")
+}
+
+/// The credentials are not mounted at `{SECRET_BASE_PATH}/{secret_class}` like the CA certificate,
+/// so that the same SecretClass can provide both.
+fn log_sink_credentials_dir(secret_class: &str) -> String {
+    format!("{SECRET_BASE_PATH}/log-sink-credentials/{secret_class}")
+}
+
+/// VRL program which processes lines written to stdout
+const STDOUT_PARSER: &str = r#".logger = "ROOT"
+.level = "INFO"
+"#;
+
+/// VRL program which processes lines written to stderr
+const STDERR_PARSER: &str = r#".logger = "ROOT"
+.level = "ERROR"
+"#;
+
+/// VRL program which parses events written by the Log4j `XMLLayout`
+const LOG4J_PARSER: &str = r#"raw_message = string!(.message)
+
+.timestamp = now()
+.logger = ""
+.level = "INFO"
+.message = ""
+.errors = []
+
+# Wrap the event so that the log4j namespace is defined when parsing the event
+wrapped_xml_event = "<root xmlns:log4j=\"http://jakarta.apache.org/log4j/\">" + raw_message + "</root>"
+parsed_event, err = parse_xml(wrapped_xml_event)
+if err != null {
+  error = "XML not parsable: " + err
+  .errors = push(.errors, error)
+  log(error, level: "warn")
+  .message = raw_message
+} else {
+  root = object!(parsed_event.root)
+  if !is_object(root.event) {
+    error = "Parsed event contains no \"event\" tag."
+    .errors = push(.errors, error)
+    log(error, level: "warn")
+    .message = raw_message
+  } else {
+    if keys(root) != ["event"] {
+      .errors = push(.errors, "Parsed event contains multiple tags: " + join!(keys(root), ", "))
+    }
+    event = object!(root.event)
+
+    epoch_milliseconds, err = to_int(event.@timestamp)
+    if err == null && epoch_milliseconds != 0 {
+      converted_timestamp, err = from_unix_timestamp(epoch_milliseconds, "milliseconds")
+      if err == null {
+        .timestamp = converted_timestamp
+      } else {
+        .errors = push(.errors, "Time not parsable, using current time instead: " + err)
+      }
+    } else {
+      .errors = push(.errors, "Timestamp not found, using current time instead.")
+    }
+
+    .logger, err = string(event.@logger)
+    if err != null || is_empty(.logger) {
+      .errors = push(.errors, "Logger not found.")
+    }
+
+    level, err = string(event.@level)
+    if err != null {
+      .errors = push(.errors, "Level not found, using \"" + .level + "\" instead.")
+    } else if !includes(["TRACE", "DEBUG", "INFO", "WARN", "ERROR", "FATAL"], level) {
+      .errors = push(.errors, "Level \"" + level + "\" unknown, using \"" + .level + "\" instead.")
+    } else {
+      .level = level
+    }
+
+    message, err = string(event.message)
+    if err != null || is_empty(message) {
+      .errors = push(.errors, "Message not found.")
+    }
+    throwable = string(event.throwable) ?? ""
+    .message = join!(compact([message, throwable]), "\n")
+  }
+}
+"#;
+
+/// VRL program which parses events written by the Log4j 2 `XMLLayout`
+const LOG4J2_PARSER: &str = r#"raw_message = string!(.message)
+
+.timestamp = now()
+.logger = ""
+.level = "INFO"
+.message = ""
+.errors = []
+
+event = {}
+parsed_event, err = parse_xml(raw_message)
+if err != null {
+  error = "XML not parsable: " + err
+  .errors = push(.errors, error)
+  log(error, level: "warn")
+  .message = raw_message
+} else {
+  if !is_object(parsed_event.Event) {
+    error = "Parsed event contains no \"Event\" tag."
+    .errors = push(.errors, error)
+    log(error, level: "warn")
+    .message = raw_message
+  } else {
+    event = object!(parsed_event.Event)
+
+    tag_instant_valid = false
+    instant, err = object(event.Instant)
+    if err == null {
+      epoch_nanoseconds, err = to_int(instant.@epochSecond) * 1_000_000_000 + to_int(instant.@nanoOfSecond)
+      if err == null && epoch_nanoseconds != 0 {
+        converted_timestamp, err = from_unix_timestamp(epoch_nanoseconds, "nanoseconds")
+        if err == null {
+          .timestamp = converted_timestamp
+          tag_instant_valid = true
+        } else {
+          .errors = push(.errors, "Instant invalid, trying property timeMillis instead: " + err)
+        }
+      } else {
+        .errors = push(.errors, "Instant invalid, trying property timeMillis instead: " + err)
+      }
+    }
+    if !tag_instant_valid {
+      epoch_milliseconds, err = to_int(event.@timeMillis)
+      if err == null && epoch_milliseconds != 0 {
+        converted_timestamp, err = from_unix_timestamp(epoch_milliseconds, "milliseconds")
+        if err == null {
+          .timestamp = converted_timestamp
+        } else {
+          .errors = push(.errors, "timeMillis not parsable, using current time instead: " + err)
+        }
+      } else {
+        .errors = push(.errors, "timeMillis not parsable, using current time instead: " + err)
+      }
+    }
+
+    .logger, err = string(event.@loggerName)
+    if err != null || is_empty(.logger) {
+      .errors = push(.errors, "Logger not found.")
+    }
+
+    level, err = string(event.@level)
+    if err != null {
+      .errors = push(.errors, "Level not found, using \"" + .level + "\" instead.")
+    } else if !includes(["TRACE", "DEBUG", "INFO", "WARN", "ERROR", "FATAL"], level) {
+      .errors = push(.errors, "Level \"" + level + "\" unknown, using \"" + .level + "\" instead.")
+    } else {
+      .level = level
+    }
+
+    exception = null
+    thrown = event.Thrown
+    if is_object(thrown) {
+      exception = "Exception"
+      thread, err = string(event.@thread)
+      if err == null && !is_empty(thread) {
+        exception = exception + " in thread \"" + thread + "\""
+      }
+      thrown_name, err = string(thrown.@name)
+      if err == null && !is_empty(exception) {
+        exception = exception + " " + thrown_name
+      }
+      message = string(thrown.@localizedMessage) ??
+        string(thrown.@message) ??
+        ""
+      if !is_empty(message) {
+        exception = exception + ": " + message
+      }
+      stacktrace_items = array(thrown.ExtendedStackTrace.ExtendedStackTraceItem) ?? []
+      stacktrace = ""
+      for_each(stacktrace_items) -> |_index, value| {
+        stacktrace = stacktrace + "        "
+        class = string(value.@class) ?? ""
+        method = string(value.@method) ?? ""
+        if !is_empty(class) && !is_empty(method) {
+          stacktrace = stacktrace + "at " + class + "." + method
+        }
+        file = string(value.@file) ?? ""
+        line = string(value.@line) ?? ""
+        if !is_empty(file) && !is_empty(line) {
+          stacktrace = stacktrace + "(" + file + ":" + line + ")"
+        }
+        exact = to_bool(value.@exact) ?? false
+        location = string(value.@location) ?? ""
+        version = string(value.@version) ?? ""
+        if !is_empty(location) && !is_empty(version) {
+          stacktrace = stacktrace + " "
+          if !exact {
+            stacktrace = stacktrace + "~"
+          }
+          stacktrace = stacktrace + "[" + location + ":" + version + "]"
+        }
+        stacktrace = stacktrace + "\n"
+      }
+      if stacktrace != "" {
+        exception = exception + "\n" + stacktrace
+      }
+    }
+
+    message, err = string(event.Message)
+    if err != null || is_empty(message) {
+      message = null
+      .errors = push(.errors, "Message not found.")
+    }
+    .message = join!(compact([message, exception]), "\n")
+  }
+}
+"#;
+
+/// VRL program which parses JSON events written by the Log4j 2 `JsonTemplateLayout` with the
+/// `EcsLayout.json` event template
+const LOG4J2_JSON_PARSER: &str = r#"raw_message = string!(.message)
+
+.timestamp = now()
+.logger = ""
+.level = "INFO"
+.message = ""
+.errors = []
+
+parsed_event, err = parse_json(raw_message)
+if err != null {
+  error = "JSON not parsable: " + err
+  .errors = push(.errors, error)
+  log(error, level: "warn")
+  .message = raw_message
+} else if !is_object(parsed_event) {
+  error = "Parsed event is not a JSON object."
+  .errors = push(.errors, error)
+  log(error, level: "warn")
+  .message = raw_message
+} else {
+  event = object!(parsed_event)
+
+  timestamp_string, err = string(event."@timestamp")
+  if err == null {
+    parsed_timestamp, err = parse_timestamp(timestamp_string, "%+")
+    if err == null {
+      .timestamp = parsed_timestamp
+    } else {
+      .errors = push(.errors, "Timestamp not parsable, using current time instead: " + err)
+    }
+  } else {
+    .errors = push(.errors, "Timestamp not found, using current time instead.")
+  }
+
+  .logger, err = string(event."log.logger")
+  if err != null || is_empty(.logger) {
+    .errors = push(.errors, "Logger not found.")
+  }
+
+  level, err = string(event."log.level")
+  if err != null {
+    .errors = push(.errors, "Level not found, using \"" + .level + "\" instead.")
+  } else if !includes(["TRACE", "DEBUG", "INFO", "WARN", "ERROR", "FATAL"], level) {
+    .errors = push(.errors, "Level \"" + level + "\" unknown, using \"" + .level + "\" instead.")
+  } else {
+    .level = level
+  }
+
+  exception = null
+  error_type = string(event."error.type") ?? ""
+  if !is_empty(error_type) {
+    exception = "Exception"
+    thread = string(event."process.thread.name") ?? ""
+    if !is_empty(thread) {
+      exception = exception + " in thread \"" + thread + "\""
+    }
+    stacktrace = string(event."error.stack_trace") ?? ""
+    if !is_empty(stacktrace) {
+      exception = exception + " " + stacktrace
+    } else {
+      exception = exception + " " + error_type
+      error_message = string(event."error.message") ?? ""
+      if !is_empty(error_message) {
+        exception = exception + ": " + error_message
+      }
+    }
+  }
+
+  message, err = string(event.message)
+  if err != null || is_empty(message) {
+    message = null
+    .errors = push(.errors, "Message not found.")
+  }
+  .message = join!(compact([message, exception]), "\n")
+}
+"#;
+
+/// VRL program which parses JSON events written by the logback `JsonEncoder`
+const LOGBACK_JSON_PARSER: &str = r#"raw_message = string!(.message)
+
+.timestamp = now()
+.logger = ""
+.level = "INFO"
+.message = ""
+.errors = []
+
+parsed_event, err = parse_json(raw_message)
+if err != null {
+  error = "JSON not parsable: " + err
+  .errors = push(.errors, error)
+  log(error, level: "warn")
+  .message = raw_message
+} else if !is_object(parsed_event) {
+  error = "Parsed event is not a JSON object."
+  .errors = push(.errors, error)
+  log(error, level: "warn")
+  .message = raw_message
+} else {
+  event = object!(parsed_event)
+
+  epoch_milliseconds, err = to_int(event.timestamp)
+  if err == null && epoch_milliseconds != 0 {
+    converted_timestamp, err = from_unix_timestamp(epoch_milliseconds, "milliseconds")
+    if err == null {
+      .timestamp = converted_timestamp
+    } else {
+      .errors = push(.errors, "Timestamp not parsable, using current time instead: " + err)
+    }
+  } else {
+    .errors = push(.errors, "Timestamp not parsable, using current time instead: " + err)
+  }
+
+  .logger, err = string(event.loggerName)
+  if err != null || is_empty(.logger) {
+    .errors = push(.errors, "Logger not found.")
+  }
+
+  level, err = string(event.level)
+  if err != null {
+    .errors = push(.errors, "Level not found, using \"" + .level + "\" instead.")
+  } else if !includes(["TRACE", "DEBUG", "INFO", "WARN", "ERROR"], level) {
+    .errors = push(.errors, "Level \"" + level + "\" unknown, using \"" + .level + "\" instead.")
+  } else {
+    .level = level
+  }
+
+  exception = null
+  throwable = event.throwable
+  if is_object(throwable) {
+    exception = "Exception"
+    thread, err = string(event.threadName)
+    if err == null && !is_empty(thread) {
+      exception = exception + " in thread \"" + thread + "\""
+    }
+    class_name = string(throwable.className) ?? ""
+    if !is_empty(class_name) {
+      exception = exception + " " + class_name
+    }
+    throwable_message = string(throwable.message) ?? ""
+    if !is_empty(throwable_message) {
+      exception = exception + ": " + throwable_message
+    }
+    steps = array(throwable.stepArray) ?? []
+    stacktrace = ""
+    for_each(steps) -> |_index, value| {
+      stacktrace = stacktrace + "        "
+      class = string(value.className) ?? ""
+      method = string(value.methodName) ?? ""
+      if !is_empty(class) && !is_empty(method) {
+        stacktrace = stacktrace + "at " + class + "." + method
+      }
+      file = string(value.fileName) ?? ""
+      line = to_string(value.lineNumber) ?? ""
+      if !is_empty(file) && !is_empty(line) {
+        stacktrace = stacktrace + "(" + file + ":" + line + ")"
+      }
+      stacktrace = stacktrace + "\n"
+    }
+    if stacktrace != "" {
+      exception = exception + "\n" + stacktrace
+    }
+  }
+
+  message = string(event.formattedMessage) ?? string(event.message) ?? ""
+  if is_empty(message) {
+    message = null
+    .errors = push(.errors, "Message not found.")
+  }
+  .message = join!(compact([message, exception]), "\n")
+}
+"#;
+
+/// VRL program which parses JSON events written by the Python logging framework
+const PY_PARSER: &str = r#"raw_message = string!(.message)
+
+.timestamp = now()
+.logger = ""
+.level = "INFO"
+.message = ""
+.errors = []
+
+parsed_event, err = parse_json(raw_message)
+if err != null {
+  error = "JSON not parsable: " + err
+  .errors = push(.errors, error)
+  log(error, level: "warn")
+  .message = raw_message
+} else if !is_object(parsed_event) {
+  error = "Parsed event is not a JSON object."
+  .errors = push(.errors, error)
+  log(error, level: "warn")
+  .message = raw_message
+} else {
+  event = object!(parsed_event)
+
+  asctime, err = string(event.asctime)
+  if err == null {
+    parsed_timestamp, err = parse_timestamp(asctime, "%F %T,%3f")
+    if err == null {
+      .timestamp = parsed_timestamp
+    } else {
+      .errors = push(.errors, "Timestamp not parsable, using current time instead: "+ err)
+    }
+  } else {
+    .errors = push(.errors, "Timestamp not found, using current time instead.")
+  }
+
+  .logger, err = string(event.name)
+  if err != null || is_empty(.logger) {
+    .errors = push(.errors, "Logger not found.")
+  }
+
+  level, err = string(event.levelname)
+  if err != null {
+    .errors = push(.errors, "Level not found, using \"" + .level + "\" instead.")
+  } else if level == "DEBUG" {
+    .level = "DEBUG"
+  } else if level == "INFO" {
+    .level = "INFO"
+  } else if level == "WARNING" {
+    .level = "WARN"
+  } else if level == "ERROR" {
+    .level = "ERROR"
+  } else if level == "CRITICAL" {
+    .level = "FATAL"
+  } else {
+    .errors = push(.errors, "Level \"" + level + "\" unknown, using \"" + .level + "\" instead.")
+  }
+
+  .message, err = string(event.message)
+  if err != null || is_empty(.message) {
+    .errors = push(.errors, "Message not found.")
+  }
+}
+"#;
+
+/// VRL program which parses JSON events written by Airlift
+const AIRLIFT_PARSER: &str = r#"raw_message = string!(.message)
+
+.timestamp = now()
+.logger = ""
+.level = "INFO"
+.message = ""
+.errors = []
+
+parsed_event, err = parse_json(raw_message)
+if err != null {
+  error = "JSON not parsable: " + err
+  .errors = push(.errors, error)
+  log(error, level: "warn")
+  .message = raw_message
+} else if !is_object(parsed_event) {
+  error = "Parsed event is not a JSON object."
+  .errors = push(.errors, error)
+  log(error, level: "warn")
+  .message = raw_message
+} else {
+  event = object!(parsed_event)
+
+  timestamp_string, err = string(event.timestamp)
+  if err == null {
+    parsed_timestamp, err = parse_timestamp(timestamp_string, "%Y-%m-%dT%H:%M:%S.%fZ")
+    if err == null {
+      .timestamp = parsed_timestamp
+    } else {
+      .errors = push(.errors, "Timestamp not parsable, using current time instead: " + err)
+    }
+  } else {
+    .errors = push(.errors, "Timestamp not found, using current time instead.")
+  }
+
+  .logger, err = string(event.logger)
+  if err != null || is_empty(.logger) {
+    .errors = push(.errors, "Logger not found.")
+  }
+
+  level, err = string(event.level)
+  if err != null {
+    .errors = push(.errors, "Level not found, using \"" + .level + "\" instead.")
+  } else if !includes(["TRACE", "DEBUG", "INFO", "WARN", "ERROR", "FATAL"], level) {
+    .errors = push(.errors, "Level \"" + level + "\" unknown, using \"" + .level + "\" instead.")
+  } else {
+    .level = level
+  }
+
+  .thread = string(parsed_event.thread) ?? null
+
+  .message, err = string(event.message)
+  if err != null || is_empty(.message) {
+    .errors = push(.errors, "Message not found.")
+  }
+  stacktrace = string(event.stackTrace) ?? ""
+  .message = join!(compact([.message, stacktrace]), "\n\n")
+}
+"#;
+
+/// VRL program which parses JSON events written by tracing-rs
+const TRACING_RS_PARSER: &str = r#"raw_message = string!(.message)
+
+.timestamp = now()
+.logger = ""
+.level = "INFO"
+.message = ""
+.errors = []
+
+parsed_event, err = parse_json(raw_message)
+if err != null {
+  error = "JSON not parsable: " + err
+  .errors = push(.errors, error)
+  log(error, level: "warn")
+  .message = raw_message
+} else if !is_object(parsed_event) {
+  error = "Parsed event is not a JSON object."
+  .errors = push(.errors, error)
+  log(error, level: "warn")
+  .message = raw_message
+} else {
+  event = object!(parsed_event)
+
+  timestamp_string, err = string(event.timestamp)
+  if err == null {
+    parsed_timestamp, err = parse_timestamp(timestamp_string, "%+")
+    if err == null {
+      .timestamp = parsed_timestamp
+    } else {
+      .errors = push(.errors, "Timestamp not parsable, trying current time instead: " + err)
+    }
+  }
+
+  .logger, err = string(event.target)
+  if err != null || is_empty(.logger) {
+    .errors = push(.errors, "Logger/target not found.")
+  }
+
+  level, err = string(event.level)
+  if err != null {
+    .errors = push(.errors, "Level not found, using \"" + .level + "\" instead.")
+  } else if !includes(["TRACE", "DEBUG", "INFO", "WARN", "ERROR", "FATAL"], upcase(level)) {
+    .errors = push(.errors, "Level \"" + level + "\" unknown, using \"" + .level + "\" instead.")
+  } else {
+    .level = upcase(level)
+  }
+
+  fields, err = object(event.fields)
+  if err != null {
+    .errors = push(.errors, "Fields are not an object.")
+  }
+
+  .message, err = string(fields.message)
+  if err != null || is_empty(.message) {
+    .errors = push(.errors, "Message not found.")
+  }
+
+  del(fields.message)
+
+  other_fields = encode_key_value(fields, field_delimiter: "\n")
+  .message = join!(compact([.message, other_fields]), "\n\n")
+}
+"#;
+
+/// VRL program which parses JSON events written by OPA
+const OPA_JSON_PARSER: &str = r#"raw_message = string!(.message)
+
+.timestamp = now()
+.logger = ""
+.level = "INFO"
+.message = ""
+.errors = []
+
+parsed_event, err = parse_json(raw_message)
+if err != null {
+  error = "JSON not parsable: " + err
+  .errors = push(.errors, error)
+  log(error, level: "warn")
+  .message = raw_message
+} else if !is_object(parsed_event) {
+  error = "Parsed event is not a JSON object."
+  .errors = push(.errors, error)
+  log(error, level: "warn")
+  .message = raw_message
+} else {
+  event = object!(parsed_event)
+
+  property_timestamp_valid = false
+  timestamp_string, err = string(event.timestamp)
+  if err == null {
+    parsed_timestamp, err = parse_timestamp(timestamp_string, "%Y-%m-%dT%H:%M:%S.%fZ")
+    if err == null {
+      .timestamp = parsed_timestamp
+      property_timestamp_valid = true
+    } else {
+      .errors = push(.errors, "Timestamp not parsable, trying property time instead: " + err)
+    }
+  }
+  if !property_timestamp_valid {
+    time_string, err = string(event.time)
+    if err == null {
+      parsed_timestamp, err = parse_timestamp(time_string, "%Y-%m-%dT%H:%M:%SZ")
+      if err == null {
+        .timestamp = parsed_timestamp
+      } else {
+        .errors = push(.errors, "Time not parsable, using current time instead: " + err)
+      }
+    } else {
+      .errors = push(.errors, "Time not found, using current time instead.")
+    }
+  }
+
+  .logger, err = string(event.logger)
+  if err != null || is_empty(.logger) {
+    .errors = push(.errors, "Logger not found.")
+  }
+
+  level, err = string(event.level)
+  if err != null {
+    .errors = push(.errors, "Level not found, using \"" + .level + "\" instead.")
+  } else if !includes(["TRACE", "DEBUG", "INFO", "WARN", "ERROR", "FATAL"], upcase(level)) {
+    .errors = push(.errors, "Level \"" + level + "\" unknown, using \"" + .level + "\" instead.")
+  } else {
+    .level = upcase(level)
+  }
+
+  .message, err = string(event.msg)
+  if err != null || is_empty(.message) {
+    .errors = push(.errors, "Message not found.")
+  }
+
+  del(event.time)
+  del(event.timestamp)
+  del(event.level)
+  del(event.msg)
+
+  other_fields = encode_key_value(event, field_delimiter: "\n")
+  .message = join!(compact([.message, other_fields]), "\n\n")
+}
+"#;
+
+/// VRL program which parses JSON events written by the Go logging frameworks zap, slog and logrus
+const GO_JSON_PARSER: &str = r#"raw_message = string!(.message)
+
+.timestamp = now()
+.logger = "ROOT"
+.level = "INFO"
+.message = ""
+.errors = []
+
+parsed_event, err = parse_json(raw_message)
+if err != null {
+  error = "JSON not parsable: " + err
+  .errors = push(.errors, error)
+  log(error, level: "warn")
+  .message = raw_message
+} else if !is_object(parsed_event) {
+  error = "Parsed event is not a JSON object."
+  .errors = push(.errors, error)
+  log(error, level: "warn")
+  .message = raw_message
+} else {
+  event = object!(parsed_event)
+
+  # slog and logrus use the key "time", zap uses "ts" by default.
+  timestamp = event.time
+  if timestamp == null {
+    timestamp = event.ts
+  }
+  if is_string(timestamp) {
+    parsed_timestamp, err = parse_timestamp(string!(timestamp), "%+")
+    if err == null {
+      .timestamp = parsed_timestamp
+    } else {
+      .errors = push(.errors, "Timestamp not parsable, using current time instead: " + err)
+    }
+  } else if is_float(timestamp) || is_integer(timestamp) {
+    epoch_seconds = to_float(timestamp) ?? 0.0
+    # zap writes the seconds since the epoch by default.
+    converted_timestamp, err = from_unix_timestamp(to_int(epoch_seconds * 1000), "milliseconds")
+    if err == null {
+      .timestamp = converted_timestamp
+    } else {
+      .errors = push(.errors, "Timestamp not parsable, using current time instead: " + err)
+    }
+  } else {
+    .errors = push(.errors, "Timestamp not found, using current time instead.")
+  }
+
+  logger = string(event.logger) ?? ""
+  if !is_empty(logger) {
+    .logger = logger
+  }
+
+  level, err = string(event.level)
+  if err != null {
+    .errors = push(.errors, "Level not found, using \"" + .level + "\" instead.")
+  } else {
+    level = upcase(level)
+    # slog levels can have an offset, e.g. "DEBUG-4" or "ERROR+2".
+    normalized_level = replace(level, r'[+-]\d+$', "")
+    if starts_with(level, "DEBUG-") {
+      normalized_level = "TRACE"
+    } else if starts_with(level, "ERROR+") || level == "PANIC" {
+      normalized_level = "FATAL"
+    } else if level == "DPANIC" {
+      normalized_level = "ERROR"
+    } else if level == "WARNING" {
+      normalized_level = "WARN"
+    }
+    if includes(["TRACE", "DEBUG", "INFO", "WARN", "ERROR", "FATAL"], normalized_level) {
+      .level = normalized_level
+    } else {
+      .errors = push(.errors, "Level \"" + level + "\" unknown, using \"" + .level + "\" instead.")
+    }
+  }
+
+  message, err = string(event.msg)
+  if err != null || is_empty(message) {
+    message = null
+    .errors = push(.errors, "Message not found.")
+  }
+
+  stacktrace = string(event.stacktrace) ?? ""
+
+  del(event.time)
+  del(event.ts)
+  del(event.logger)
+  del(event.level)
+  del(event.msg)
+  del(event.stacktrace)
+
+  other_fields = encode_key_value(event, field_delimiter: "\n")
+  .message = join!(compact([message, other_fields, stacktrace]), "\n\n")
+}
+"#;
+
+/// VRL program which parses JSON events with commonly used field names
+const PLAIN_JSON_PARSER: &str = r#"raw_message = string!(.message)
+
+.timestamp = now()
+.logger = "ROOT"
+.level = "INFO"
+.message = ""
+.errors = []
+
+parsed_event, err = parse_json(raw_message)
+if err != null {
+  error = "JSON not parsable: " + err
+  .errors = push(.errors, error)
+  log(error, level: "warn")
+  .message = raw_message
+} else if !is_object(parsed_event) {
+  error = "Parsed event is not a JSON object."
+  .errors = push(.errors, error)
+  log(error, level: "warn")
+  .message = raw_message
+} else {
+  event = object!(parsed_event)
+
+  timestamp = event."@timestamp"
+  if timestamp == null {
+    timestamp = event.timestamp
+  }
+  if timestamp == null {
+    timestamp = event.time
+  }
+  if timestamp == null {
+    timestamp = event.ts
+  }
+  if is_string(timestamp) {
+    parsed_timestamp, err = parse_timestamp(string!(timestamp), "%+")
+    if err == null {
+      .timestamp = parsed_timestamp
+    } else {
+      .errors = push(.errors, "Timestamp not parsable, using current time instead: " + err)
+    }
+  } else if is_float(timestamp) || is_integer(timestamp) {
+    epoch_seconds = to_float(timestamp) ?? 0.0
+    # Timestamps after the year 5138 in seconds are rather milliseconds.
+    if epoch_seconds > 100000000000.0 {
+      epoch_seconds = epoch_seconds * 0.001
+    }
+    converted_timestamp, err = from_unix_timestamp(to_int(epoch_seconds * 1000), "milliseconds")
+    if err == null {
+      .timestamp = converted_timestamp
+    } else {
+      .errors = push(.errors, "Timestamp not parsable, using current time instead: " + err)
+    }
+  } else {
+    .errors = push(.errors, "Timestamp not found, using current time instead.")
+  }
+
+  logger = event.logger
+  if logger == null {
+    logger = event.logger_name
+  }
+  if logger == null {
+    logger = event.name
+  }
+  logger = string(logger) ?? ""
+  if !is_empty(logger) {
+    .logger = logger
+  }
+
+  level = event.level
+  if level == null {
+    level = event.severity
+  }
+  if level == null {
+    level = event.lvl
+  }
+  level, err = string(level)
+  if err != null {
+    .errors = push(.errors, "Level not found, using \"" + .level + "\" instead.")
+  } else {
+    level = upcase(level)
+    normalized_level = level
+    if level == "WARNING" {
+      normalized_level = "WARN"
+    } else if level == "ERR" {
+      normalized_level = "ERROR"
+    } else if includes(["CRITICAL", "CRIT", "PANIC", "ALERT", "EMERGENCY"], level) {
+      normalized_level = "FATAL"
+    } else if level == "NOTICE" {
+      normalized_level = "INFO"
+    }
+    if includes(["TRACE", "DEBUG", "INFO", "WARN", "ERROR", "FATAL"], normalized_level) {
+      .level = normalized_level
+    } else {
+      .errors = push(.errors, "Level \"" + level + "\" unknown, using \"" + .level + "\" instead.")
+    }
+  }
+
+  message = event.message
+  if message == null {
+    message = event.msg
+  }
+  message, err = string(message)
+  if err != null || is_empty(message) {
+    message = null
+    .errors = push(.errors, "Message not found.")
+  }
+
+  del(event."@timestamp")
+  del(event.timestamp)
+  del(event.time)
+  del(event.ts)
+  del(event.logger)
+  del(event.logger_name)
+  del(event.name)
+  del(event.level)
+  del(event.severity)
+  del(event.lvl)
+  del(event.message)
+  del(event.msg)
+
+  other_fields = encode_key_value(event, field_delimiter: "\n")
+  .message = join!(compact([message, other_fields]), "\n\n")
+}
+"#;
+
+/// VRL program which converts the internal logs of Vector into the common log format
+const VECTOR_LOG_TRANSFORM: &str = r#".container = "vector"
+.level = .metadata.level
+.logger = .metadata.module_path
+if exists(.file) { .processed_file = del(.file) }
+del(.metadata)
+del(.pid)
+del(.source_type)
+"#;
+
+/// VRL program which converts the events into log records in the OTLP JSON format
+const OTLP_CONVERTER: &str = r#"timestamp = timestamp(.timestamp) ?? now()
+
+. = {
+  "resourceLogs": [{
+    "resource": {
+      "attributes": [
+        {"key": "k8s.namespace.name", "value": {"stringValue": string(.namespace) ?? ""}},
+        {"key": "k8s.pod.name", "value": {"stringValue": string(.pod) ?? ""}},
+        {"key": "k8s.container.name", "value": {"stringValue": string(.container) ?? ""}},
+        {"key": "stackable.cluster.name", "value": {"stringValue": string(.cluster) ?? ""}},
+        {"key": "stackable.role.name", "value": {"stringValue": string(.role) ?? ""}},
+        {"key": "stackable.role_group.name", "value": {"stringValue": string(.roleGroup) ?? ""}}
+      ]
+    },
+    "scopeLogs": [{
+      "scope": {"name": string(.logger) ?? ""},
+      "logRecords": [{
+        "timeUnixNano": to_string(to_unix_timestamp(timestamp, unit: "nanoseconds")),
+        "severityText": string(.level) ?? "",
+        "body": {"stringValue": string(.message) ?? ""},
+        "attributes": [
+          {"key": "log.file.name", "value": {"stringValue": string(.file) ?? ""}}
+        ]
+      }]
+    }]
+  }]
+}
+"#;
+
+/// Create the specification of the Vector log agent container.
+///
+/// The vector process is not running as PID 1, so a Kubernetes SIGTERM will be have no effect.
+/// Instead, the vector process can be shut down by creating a file below {STACKABLE_LOG_DIR}/{VECTOR_LOG_DIR},
+/// e.g. {STACKABLE_LOG_DIR}/{VECTOR_LOG_DIR}/{SHUTDOWN_FILE}. This way logs from the products will always be shipped,
+/// as the vector container will be the last one to terminate. A specific container must be chosen, which has the responsibility
+/// to create a file after it has properly shut down. It should be the one taking the longest to shut down.
+/// E.g. for hdfs the lifetime of vector will be bound to the datanode container and not to the zkfc container.
+/// We *could* have different shutdown trigger files for all application containers and wait for all containers
+/// to terminate, but that seems rather complicated and will be added once needed. Additionally, you should remove
+/// the shutdown marker file on startup of the application, as the application container can crash for any reason
+/// and get restarted. If you don't remove the shutdown file on startup, the vector container will crashloop forever,
+/// as it will start and shut down immediately after!
+///
+/// ```
+/// use stackable_operator::{
+///     builder::{
+///         meta::ObjectMetaBuilder,
+///         pod::{container::ContainerBuilder, resources::ResourceRequirementsBuilder, PodBuilder},
+///     },
+///     product_logging::{self, framework:: {create_vector_shutdown_file_command, remove_vector_shutdown_file_command}},
+///     utils::COMMON_BASH_TRAP_FUNCTIONS,
+/// };
+/// use k8s_openapi::apimachinery::pkg::api::resource::Quantity;
+/// # use stackable_operator::{
+/// #     commons::product_image_selection::ResolvedProductImage,
+/// #     config::fragment,
+/// #     product_logging::spec::{default_logging, Logging},
+/// # };
+/// # use strum::{Display, EnumIter};
+/// #
+/// # pub const STACKABLE_LOG_DIR: &str = "/stackable/log";
 /// #
 /// # #[derive(Clone, Display, Eq, EnumIter, Ord, PartialEq, PartialOrd)]
 /// # pub enum Container {
@@ -1418,6 +2896,62 @@ pub fn vector_container(
     resources: ResourceRequirements,
     vector_aggregator_config_map_name: &str,
 ) -> Result<Container, LoggingError> {
+    Ok(vector_container_builder(
+        image,
+        config_volume_name,
+        log_volume_name,
+        log_config,
+        Some(vector_aggregator_config_map_name),
+    )?
+    .resources(resources)
+    .build())
+}
+
+/// Create the specification of the Vector log agent container which sends the logs to the given
+/// log sink.
+///
+/// In contrast to [`vector_container`], the discovery ConfigMap of the Vector aggregator is only
+/// required if the logs are sent to the aggregator. For direct log sinks, the volumes returned by
+/// [`log_sink_volumes`] are mounted and must be added to the Pod. The Vector configuration must be
+/// configured accordingly with [`set_vector_log_sink`].
+pub fn vector_container_for_log_sink(
+    image: &ResolvedProductImage,
+    config_volume_name: &str,
+    log_volume_name: &str,
+    log_config: Option<&ContainerLogConfig>,
+    resources: ResourceRequirements,
+    log_sink: &LogSink,
+    vector_aggregator_config_map_name: Option<&str>,
+) -> Result<Container, LoggingError> {
+    let vector_aggregator_config_map_name = match log_sink {
+        LogSink::VectorAggregator {} => {
+            Some(vector_aggregator_config_map_name.context(MissingVectorAggregatorConfigMapSnafu)?)
+        }
+        _ => None,
+    };
+
+    let mut container_builder = vector_container_builder(
+        image,
+        config_volume_name,
+        log_volume_name,
+        log_config,
+        vector_aggregator_config_map_name,
+    )?;
+
+    container_builder
+        .add_volume_mounts(log_sink_volume_mounts(log_sink))
+        .context(AddVolumeMountsSnafu)?;
+
+    Ok(container_builder.resources(resources).build())
+}
+
+fn vector_container_builder(
+    image: &ResolvedProductImage,
+    config_volume_name: &str,
+    log_volume_name: &str,
+    log_config: Option<&ContainerLogConfig>,
+    vector_aggregator_config_map_name: Option<&str>,
+) -> Result<ContainerBuilder, LoggingError> {
     let log_level = if let Some(ContainerLogConfig {
         choice: Some(ContainerLogConfigChoice::Automatic(automatic_log_config)),
     }) = log_config
@@ -1427,8 +2961,8 @@ pub fn vector_container(
         LogLevel::INFO
     };
 
-    Ok(ContainerBuilder::new("vector")
-        .context(CreateContainerSnafu)?
+    let mut container_builder = ContainerBuilder::new("vector").context(CreateContainerSnafu)?;
+    container_builder
         .image_from_product_image(image)
         .command(vec![
             "/bin/bash".to_string(),
@@ -1454,18 +2988,23 @@ sleep 1
 kill $vector_pid
 "
         )])
-        .add_env_var("VECTOR_LOG", log_level.to_vector_literal())
-        .add_env_var_from_config_map(
+        .add_env_var("VECTOR_LOG", log_level.to_vector_literal());
+
+    if let Some(vector_aggregator_config_map_name) = vector_aggregator_config_map_name {
+        container_builder.add_env_var_from_config_map(
             VECTOR_AGGREGATOR_ENV_NAME,
             vector_aggregator_config_map_name,
             VECTOR_AGGREGATOR_CM_KEY,
-        )
+        );
+    }
+
+    container_builder
         .add_volume_mount(config_volume_name, STACKABLE_CONFIG_DIR)
         .context(AddVolumeMountsSnafu)?
         .add_volume_mount(log_volume_name, STACKABLE_LOG_DIR)
-        .context(AddVolumeMountsSnafu)?
-        .resources(resources)
-        .build())
+        .context(AddVolumeMountsSnafu)?;
+
+    Ok(container_builder)
 }
 
 /// Command to create a shutdown file for the vector container.
@@ -1513,7 +3052,23 @@ mod tests {
     use rstest::rstest;
 
     use super::*;
-    use crate::product_logging::spec::{AppenderConfig, LoggerConfig};
+    use crate::{
+        config::fragment,
+        k8s_openapi::api::core::v1::Pod,
+        kube::runtime::reflector::ObjectRef,
+        product_logging::spec::{
+            AppenderConfig, AutomaticContainerLogConfigFragment, LoggerConfig,
+        },
+        utils::yaml_from_str_singleton_map,
+    };
+
+    fn role_group() -> RoleGroupRef<Pod> {
+        RoleGroupRef {
+            cluster: ObjectRef::<Pod>::new("test-cluster").within("test-namespace"),
+            role: "role".into(),
+            role_group: "role-group".into(),
+        }
+    }
 
     #[rstest]
     #[case("0Mi", &[])]
@@ -1536,6 +3091,150 @@ mod tests {
         );
     }
 
+    #[rstest]
+    #[case("1Mi", None, None, "1Mi", "0.2Mi", 30)]
+    #[case("100Mi", Some("10Mi"), Some("5m"), "100Mi", "10Mi", 300)]
+    #[case("1Gi", Some("1Gi"), Some("1s"), "1Gi", "1Gi", 1)]
+    fn log_quota_from_retention(
+        #[case] max_size: &str,
+        #[case] max_file_size: Option<&str>,
+        #[case] cleanup_interval: Option<&str>,
+        #[case] expected_max_size: &str,
+        #[case] expected_max_file_size: &str,
+        #[case] expected_cleanup_interval_secs: u64,
+    ) {
+        let retention = LogRetention {
+            max_size: Quantity(max_size.into()),
+            max_file_size: max_file_size.map(|size| Quantity(size.into())),
+            cleanup_interval: cleanup_interval.map(|interval| interval.parse().unwrap()),
+        };
+
+        assert_eq!(
+            LogQuota {
+                max_size: expected_max_size.parse().unwrap(),
+                max_file_size: expected_max_file_size.parse().unwrap(),
+                cleanup_interval: Duration::from_secs(expected_cleanup_interval_secs),
+            },
+            log_quota(&retention).unwrap()
+        );
+    }
+
+    #[rstest]
+    #[case("100", None, None)]
+    #[case("1023Ki", None, None)]
+    #[case("100Mi", Some("invalid"), None)]
+    #[case("100Mi", Some("0Mi"), None)]
+    #[case("100Mi", Some("101Mi"), None)]
+    #[case("100Mi", None, Some("500ms"))]
+    fn log_quota_from_invalid_retention(
+        #[case] max_size: &str,
+        #[case] max_file_size: Option<&str>,
+        #[case] cleanup_interval: Option<&str>,
+    ) {
+        let retention = LogRetention {
+            max_size: Quantity(max_size.into()),
+            max_file_size: max_file_size.map(|size| Quantity(size.into())),
+            cleanup_interval: cleanup_interval.map(|interval| interval.parse().unwrap()),
+        };
+
+        assert!(log_quota(&retention).is_err());
+    }
+
+    #[test]
+    fn rotated_shell_output() {
+        let log_config = AutomaticContainerLogConfig {
+            console: Some(AppenderConfig {
+                level: Some(LogLevel::INFO),
+            }),
+            file: Some(AppenderConfig {
+                level: Some(LogLevel::ERROR),
+            }),
+            ..AutomaticContainerLogConfig::default()
+        };
+        let log_quota = LogQuota {
+            max_size: MemoryQuantity::from_mebi(10.),
+            max_file_size: MemoryQuantity::from_mebi(1.),
+            cleanup_interval: Duration::from_secs(30),
+        };
+
+        assert_eq!(
+            "mkdir --parents /stackable/log/product && \
+            exec 2> >(tee >(split --line-bytes=1048576 --numeric-suffixes --suffix-length=6 \
+            --additional-suffix=.stderr.log - /stackable/log/product/container.) >&2)",
+            capture_rotated_shell_output("/stackable/log", "product", &log_config, &log_quota)
+        );
+    }
+
+    #[test]
+    fn log_cleaner() {
+        let log_quota = LogQuota {
+            max_size: MemoryQuantity::from_mebi(10.),
+            max_file_size: MemoryQuantity::from_mebi(1.),
+            cleanup_interval: Duration::from_secs(30),
+        };
+
+        insta::assert_snapshot!(log_cleaner_shell_command(
+            "/stackable/log",
+            "product",
+            &log_quota
+        ));
+    }
+
+    #[rstest]
+    #[case::only_active_files(
+        &["container.stdout.log", "container.000000.stderr.log", "my-product.log4j.xml"],
+        None
+    )]
+    #[case::rolled_over_appender_file(
+        &["my-product.log4j.xml", "my-product.log4j.xml.1", "container.000000.stdout.log"],
+        Some("my-product.log4j.xml.1")
+    )]
+    #[case::rotated_split_file(
+        &["container.000001.stdout.log", "container.000000.stdout.log", "container.000000.stderr.log"],
+        Some("container.000000.stdout.log")
+    )]
+    #[case::oldest_rotated_file(
+        &["my-product.log4j.xml.2", "container.000000.stdout.log", "container.000001.stdout.log", "my-product.log4j.xml.1"],
+        Some("my-product.log4j.xml.2")
+    )]
+    fn oldest_rotated_log_file(#[case] files: &[&str], #[case] expected: Option<&str>) {
+        let log_file_dir = std::env::temp_dir().join(format!(
+            "stackable-operator-log-cleaner-{}-{}",
+            std::process::id(),
+            files.join("_")
+        ));
+        let _ = std::fs::remove_dir_all(&log_file_dir);
+        std::fs::create_dir_all(&log_file_dir).unwrap();
+        // The files are created in the given order with increasing modification times.
+        for (index, file) in files.iter().enumerate() {
+            let path = log_file_dir.join(file);
+            std::fs::write(&path, "log event\n").unwrap();
+            std::fs::File::options()
+                .write(true)
+                .open(&path)
+                .unwrap()
+                .set_modified(
+                    std::time::UNIX_EPOCH + std::time::Duration::from_secs(1_000 + index as u64),
+                )
+                .unwrap();
+        }
+
+        let output = std::process::Command::new("bash")
+            .arg("-c")
+            .arg(oldest_rotated_log_file_shell_command(
+                log_file_dir.to_str().unwrap(),
+            ))
+            .output()
+            .unwrap();
+        std::fs::remove_dir_all(&log_file_dir).unwrap();
+
+        assert!(output.status.success());
+        let expected = expected
+            .map(|file| format!("{}\n", log_file_dir.join(file).display()))
+            .unwrap_or_default();
+        assert_eq!(expected, String::from_utf8(output.stdout).unwrap());
+    }
+
     #[test]
     fn log4j2_config() {
         let log_config = AutomaticContainerLogConfig {
@@ -1543,6 +3242,8 @@ mod tests {
                 "ROOT".to_string(),
                 LoggerConfig {
                     level: LogLevel::INFO,
+                    rate_limit: None,
+                    sample_rate: None,
                 },
             )]
             .into_iter()
@@ -1553,6 +3254,8 @@ mod tests {
             file: Some(AppenderConfig {
                 level: Some(LogLevel::ERROR),
             }),
+            file_format: None,
+            redactions: BTreeMap::new(),
         };
 
         let log4j2_properties = create_log4j2_config(
@@ -1561,7 +3264,8 @@ mod tests {
             10,
             "%d{ISO8601} %-5p %m%n",
             &log_config,
-        );
+        )
+        .expect("should be a valid log configuration");
 
         assert!(log4j2_properties.contains("appenders = FILE, CONSOLE"));
         assert!(log4j2_properties.contains("appender.CONSOLE.filter.threshold.level = TRACE"));
@@ -1578,18 +3282,24 @@ mod tests {
                     "ROOT".to_string(),
                     LoggerConfig {
                         level: LogLevel::INFO,
+                        rate_limit: None,
+                        sample_rate: None,
                     },
                 ),
                 (
                     "test".to_string(),
                     LoggerConfig {
                         level: LogLevel::INFO,
+                        rate_limit: None,
+                        sample_rate: None,
                     },
                 ),
                 (
                     "test_2".to_string(),
                     LoggerConfig {
                         level: LogLevel::DEBUG,
+                        rate_limit: None,
+                        sample_rate: None,
                     },
                 ),
             ]
@@ -1601,6 +3311,8 @@ mod tests {
             file: Some(AppenderConfig {
                 level: Some(LogLevel::ERROR),
             }),
+            file_format: None,
+            redactions: BTreeMap::new(),
         };
 
         let log4j2_properties = create_log4j2_config(
@@ -1609,7 +3321,8 @@ mod tests {
             10,
             "%d{ISO8601} %-5p %m%n",
             &log_config,
-        );
+        )
+        .expect("should be a valid log configuration");
 
         assert!(log4j2_properties.contains("appenders = FILE, CONSOLE"));
         assert!(log4j2_properties.contains("appender.CONSOLE.filter.threshold.level = TRACE"));
@@ -1619,4 +3332,678 @@ mod tests {
         assert!(log4j2_properties.contains("logger.test.level = INFO"));
         assert!(log4j2_properties.contains("logger.test_2.level = DEBUG"));
     }
+
+    #[test]
+    fn log4j2_config_with_json_file_format() {
+        let log_config = AutomaticContainerLogConfig {
+            file_format: Some(LogFileFormat::Json),
+            ..AutomaticContainerLogConfig::default()
+        };
+
+        let log4j2_properties = create_log4j2_config(
+            &format!("{STACKABLE_LOG_DIR}/my-product"),
+            &log4j2_log_file("my-product", &log_config),
+            10,
+            "%d{ISO8601} %-5p %m%n",
+            &log_config,
+        )
+        .expect("should be a valid log configuration");
+
+        assert!(
+            log4j2_properties.contains(
+                "appender.FILE.fileName = /stackable/log/my-product/my-product.log4j2.json"
+            )
+        );
+        assert!(log4j2_properties.contains("appender.FILE.layout.type = JsonTemplateLayout"));
+        assert!(
+            log4j2_properties
+                .contains("appender.FILE.layout.eventTemplateUri = classpath:EcsLayout.json")
+        );
+        assert!(!log4j2_properties.contains("XMLLayout"));
+    }
+
+    #[rstest]
+    #[case::defaults(LogLevel::INFO, None, None, LogLevel::INFO)]
+    #[case::root_logger(
+        LogLevel::WARN,
+        Some(LogLevel::DEBUG),
+        Some(LogLevel::DEBUG),
+        LogLevel::WARN
+    )]
+    #[case::console(
+        LogLevel::TRACE,
+        Some(LogLevel::DEBUG),
+        Some(LogLevel::ERROR),
+        LogLevel::DEBUG
+    )]
+    #[case::file(
+        LogLevel::TRACE,
+        Some(LogLevel::ERROR),
+        Some(LogLevel::TRACE),
+        LogLevel::TRACE
+    )]
+    fn go_log_level_from_config(
+        #[case] root_log_level: LogLevel,
+        #[case] console_log_level: Option<LogLevel>,
+        #[case] file_log_level: Option<LogLevel>,
+        #[case] expected_log_level: LogLevel,
+    ) {
+        let log_config = AutomaticContainerLogConfig {
+            loggers: [(
+                AutomaticContainerLogConfig::ROOT_LOGGER.to_owned(),
+                LoggerConfig {
+                    level: root_log_level,
+                    ..LoggerConfig::default()
+                },
+            )]
+            .into(),
+            console: Some(AppenderConfig {
+                level: console_log_level,
+            }),
+            file: Some(AppenderConfig {
+                level: file_log_level,
+            }),
+            ..AutomaticContainerLogConfig::default()
+        };
+
+        assert_eq!(expected_log_level, go_log_level(&log_config));
+    }
+
+    #[test]
+    fn zap_config() {
+        let log_config = AutomaticContainerLogConfig {
+            loggers: [(
+                AutomaticContainerLogConfig::ROOT_LOGGER.to_owned(),
+                LoggerConfig {
+                    level: LogLevel::TRACE,
+                    ..LoggerConfig::default()
+                },
+            )]
+            .into(),
+            file: Some(AppenderConfig {
+                level: Some(LogLevel::TRACE),
+            }),
+            ..AutomaticContainerLogConfig::default()
+        };
+
+        let zap_config: serde_json::Value = serde_json::from_str(&create_zap_config(
+            &format!("{STACKABLE_LOG_DIR}/my-product"),
+            "my-product.zap.json",
+            &log_config,
+        ))
+        .unwrap();
+
+        assert_eq!("debug", zap_config["level"]);
+        assert_eq!(
+            serde_json::json!(["stderr", "/stackable/log/my-product/my-product.zap.json"]),
+            zap_config["outputPaths"]
+        );
+        assert_eq!("msg", zap_config["encoderConfig"]["messageKey"]);
+    }
+
+    fn log_config_with_rules() -> AutomaticContainerLogConfig {
+        let log_config =
+            yaml_from_str_singleton_map::<AutomaticContainerLogConfigFragment>(indoc::indoc! {r#"
+                loggers:
+                  ROOT:
+                    level: INFO
+                    rateLimit:
+                      eventsPerSecond: 100
+                  org.apache.kafka:
+                    level: INFO
+                    sampleRate: 10
+                  org.apache.kafka.clients:
+                    level: WARN
+                    rateLimit:
+                      eventsPerSecond: 5
+                      maxBurst: 20
+                redactions:
+                  password:
+                    pattern: (password=)\S+
+                    replacement: $1***
+                  token:
+                    pattern: "token: '[^']*'"
+            "#})
+            .unwrap();
+
+        fragment::validate(log_config).unwrap()
+    }
+
+    #[test]
+    fn log4j2_config_with_rate_limits() {
+        let log_config = log_config_with_rules();
+
+        let log4j2_properties = create_log4j2_config(
+            &format!("{STACKABLE_LOG_DIR}/my-product"),
+            &log4j2_log_file("my-product", &log_config),
+            10,
+            "%d{ISO8601} %-5p %m%n",
+            &log_config,
+        )
+        .expect("should be a valid log configuration");
+
+        assert!(log4j2_properties.contains(indoc::indoc! {"
+            logger.org.apache.kafka.clients.filter.burst.type = BurstFilter
+            logger.org.apache.kafka.clients.filter.burst.level = FATAL
+            logger.org.apache.kafka.clients.filter.burst.rate = 5
+            logger.org.apache.kafka.clients.filter.burst.maxBurst = 20
+        "}));
+        assert!(log4j2_properties.contains(indoc::indoc! {"
+            rootLogger.filter.burst.type = BurstFilter
+            rootLogger.filter.burst.level = FATAL
+            rootLogger.filter.burst.rate = 100
+            rootLogger.filter.burst.maxBurst = 1000
+        "}));
+        assert!(!log4j2_properties.contains("logger.org.apache.kafka.filter"));
+    }
+
+    #[rstest]
+    #[case::all("all", VectorLogRules::All)]
+    #[case::except_rate_limits("except_rate_limits", VectorLogRules::ExceptRateLimits)]
+    fn vector_log_rules(#[case] name: &str, #[case] rules: VectorLogRules) {
+        let mut vector_config = VectorConfig::default();
+        vector_config
+            .add_transform(
+                VECTOR_SINK_INPUT,
+                Transform::remap(["extended_logs_*"], ".cluster = \"test\""),
+            )
+            .add_sink(
+                "aggregator",
+                Sink::vector([VECTOR_SINK_INPUT], "vector-aggregator:6000"),
+            );
+
+        add_vector_log_rules(
+            &mut vector_config,
+            "my-product",
+            &log_config_with_rules(),
+            rules,
+        )
+        .unwrap();
+
+        insta::assert_snapshot!(format!("vector_log_rules_{name}"), vector_config.to_yaml());
+    }
+
+    #[test]
+    fn vector_log_rules_errors() {
+        let mut log_config = log_config_with_rules();
+
+        let mut vector_config = VectorConfig::default();
+        assert!(matches!(
+            add_vector_log_rules(
+                &mut vector_config,
+                "my-product",
+                &log_config,
+                VectorLogRules::All
+            ),
+            Err(LoggingError::MissingVectorSinkInput)
+        ));
+
+        // Without rules, the Vector configuration is not required to contain the sink input
+        assert!(
+            add_vector_log_rules(
+                &mut vector_config,
+                "my-product",
+                &AutomaticContainerLogConfig::default(),
+                VectorLogRules::All
+            )
+            .is_ok()
+        );
+
+        log_config.redactions.insert(
+            "invalid".to_owned(),
+            LogRedaction {
+                pattern: "(unclosed".to_owned(),
+                replacement: None,
+            },
+        );
+        let mut vector_config = default_vector_config(&role_group(), None);
+        assert!(matches!(
+            add_vector_log_rules(
+                &mut vector_config,
+                "my-product",
+                &log_config,
+                VectorLogRules::All
+            ),
+            Err(LoggingError::InvalidRedactionPattern { pattern, .. }) if pattern == "(unclosed"
+        ));
+    }
+
+    #[rstest]
+    #[case::xml(LogFileFormat::Xml, "my-product.log4j.xml", "log4j.XMLLayout")]
+    #[case::json(
+        LogFileFormat::Json,
+        "my-product.logback.json",
+        "ch.qos.logback.classic.encoder.JsonEncoder"
+    )]
+    fn logback_config_file_format(
+        #[case] file_format: LogFileFormat,
+        #[case] expected_log_file: &str,
+        #[case] expected_encoder: &str,
+    ) {
+        let log_config = AutomaticContainerLogConfig {
+            file_format: Some(file_format),
+            ..AutomaticContainerLogConfig::default()
+        };
+
+        let log_file = logback_log_file("my-product", &log_config);
+        assert_eq!(expected_log_file, log_file);
+
+        let logback_xml = create_logback_config(
+            &format!("{STACKABLE_LOG_DIR}/my-product"),
+            &log_file,
+            10,
+            "%d{ISO8601} %-5p %m%n",
+            &log_config,
+            None,
+        )
+        .expect("should be a valid log configuration");
+
+        assert!(logback_xml.contains(expected_encoder));
+    }
+
+    /// Redaction rules used to test that the logging frameworks never write sensitive
+    /// information to the log files
+    fn redaction_log_config() -> AutomaticContainerLogConfig {
+        AutomaticContainerLogConfig {
+            file_format: Some(LogFileFormat::Json),
+            redactions: BTreeMap::from([
+                (
+                    "password".to_owned(),
+                    LogRedaction {
+                        pattern: r"(password=)\S+".to_owned(),
+                        replacement: Some("$1***".to_owned()),
+                    },
+                ),
+                (
+                    "token".to_owned(),
+                    LogRedaction {
+                        pattern: r"Bearer [A-Za-z0-9.]+".to_owned(),
+                        replacement: None,
+                    },
+                ),
+            ]),
+            ..AutomaticContainerLogConfig::default()
+        }
+    }
+
+    /// Applies a Java regex replacement like `String.replaceAll` does
+    fn java_replace_all(text: &str, regex: &str, replacement: &str) -> String {
+        let mut rust_replacement = String::new();
+        let mut characters = replacement.chars().peekable();
+        while let Some(character) = characters.next() {
+            match character {
+                '\\' => match characters.next() {
+                    Some('$') => rust_replacement.push_str("$$"),
+                    Some(escaped) => rust_replacement.push(escaped),
+                    None => {}
+                },
+                '$' => {
+                    let mut group = String::new();
+                    while let Some(digit) = characters.next_if(char::is_ascii_digit) {
+                        group.push(digit);
+                    }
+                    let _ = write!(rust_replacement, "${{{group}}}");
+                }
+                character => rust_replacement.push(character),
+            }
+        }
+
+        regex::Regex::new(regex)
+            .unwrap()
+            .replace_all(text, rust_replacement.as_str())
+            .into_owned()
+    }
+
+    /// Returns the index of the delimiter which closes the group starting at `text`, ignoring
+    /// nested groups and quoted text
+    fn closing_delimiter(text: &str, open: char, close: char) -> usize {
+        let mut depth = 0;
+        let mut quoted = false;
+        let mut escaped = false;
+        for (index, character) in text.char_indices() {
+            match character {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '\'' if open == '(' => quoted = !quoted,
+                _ if quoted => {}
+                character if character == open => depth += 1,
+                character if character == close => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return index;
+                    }
+                }
+                _ => {}
+            }
+        }
+        panic!("unbalanced group in {text:?}");
+    }
+
+    /// Evaluates a Log4j 2 message pattern like `%replace{%m...}{regex}{replacement}` as written
+    /// in the properties file for an event without exception
+    fn evaluate_log4j2_pattern(pattern: &str, message: &str) -> String {
+        let Some(rest) = pattern.strip_prefix("%replace") else {
+            assert_eq!("%m%notEmpty{%n%throwable}", pattern);
+            return message.to_owned();
+        };
+
+        let mut options = Vec::new();
+        let mut rest = rest;
+        for _ in 0..3 {
+            let end = closing_delimiter(rest, '{', '}');
+            options.push(&rest[1..end]);
+            rest = &rest[end + 1..];
+        }
+        assert_eq!("", rest);
+
+        // The properties file format escapes backslashes
+        java_replace_all(
+            &evaluate_log4j2_pattern(options[0], message),
+            &options[1].replace(r"\\", r"\"),
+            &options[2].replace(r"\\", r"\"),
+        )
+    }
+
+    /// Evaluates a logback message pattern like `%replace(%msg...){'regex', 'replacement'}` as
+    /// written in the XML file for an event without exception
+    fn evaluate_logback_pattern(pattern: &str, message: &str) -> String {
+        let Some(rest) = pattern.strip_prefix("%replace") else {
+            assert_eq!("%msg%n%ex", pattern);
+            return format!("{message}\n");
+        };
+
+        let end = closing_delimiter(rest, '(', ')');
+        let inner = &rest[1..end];
+        let options = rest[end + 1..]
+            .strip_prefix("{'")
+            .and_then(|options| options.strip_suffix("'}"))
+            .unwrap();
+        let (regex, replacement) = options.split_once("', '").unwrap();
+        let unescape = |option: &str| {
+            option
+                .replace(r"\'", "'")
+                .replace("&lt;", "<")
+                .replace("&gt;", ">")
+                .replace("&amp;", "&")
+        };
+
+        java_replace_all(
+            &evaluate_logback_pattern(inner, message),
+            &unescape(regex),
+            &unescape(replacement),
+        )
+    }
+
+    const SENSITIVE_MESSAGE: &str =
+        "Connecting with password=s3cr3t and \"Bearer abc.def\"\n\tat C:\\path";
+    const REDACTED_MESSAGE: &str = "Connecting with password=*** and \"[REDACTED]\"\n\tat C:\\path";
+
+    #[test]
+    fn log4j2_config_with_redactions() {
+        let log_config = redaction_log_config();
+
+        let log_file = log4j2_log_file("my-product", &log_config);
+        assert_eq!("my-product.plain.json", log_file);
+
+        let log4j2_properties = create_log4j2_config(
+            &format!("{STACKABLE_LOG_DIR}/my-product"),
+            &log_file,
+            10,
+            "%d{ISO8601} %-5p %m%n",
+            &log_config,
+        )
+        .expect("should be a valid log configuration");
+
+        // The structured layouts cannot redact messages.
+        assert!(!log4j2_properties.contains("XMLLayout"));
+        assert!(!log4j2_properties.contains("JsonTemplateLayout"));
+
+        let console_pattern = log4j2_properties
+            .lines()
+            .find_map(|line| line.strip_prefix("appender.CONSOLE.layout.pattern = "))
+            .unwrap();
+        assert_eq!(
+            r"%replace{%replace{%d{ISO8601} %-5p %m%n}{(password=)\\S+}{$1***}}{Bearer [A-Za-z0-9.]+}{[REDACTED]}",
+            console_pattern
+        );
+
+        let file_pattern = log4j2_properties
+            .lines()
+            .find_map(|line| line.strip_prefix("appender.FILE.layout.pattern = "))
+            .unwrap();
+        let message_pattern = file_pattern
+            .strip_prefix(r#"{"@timestamp":"%d{yyyy-MM-dd'T'HH:mm:ss.SSSXXX}","level":"%p","logger":"%enc{%c}{JSON}","message":"%enc{"#)
+            .and_then(|pattern| pattern.strip_suffix(r#"}{JSON}"}%n"#))
+            .unwrap();
+
+        let written_message = evaluate_log4j2_pattern(message_pattern, SENSITIVE_MESSAGE);
+        assert_eq!(REDACTED_MESSAGE, written_message);
+    }
+
+    #[test]
+    fn logback_config_with_redactions() {
+        let log_config = redaction_log_config();
+
+        let log_file = logback_log_file("my-product", &log_config);
+        assert_eq!("my-product.plain.json", log_file);
+
+        let logback_xml = create_logback_config(
+            &format!("{STACKABLE_LOG_DIR}/my-product"),
+            &log_file,
+            10,
+            "%d{ISO8601} %-5p %m%n",
+            &log_config,
+            None,
+        )
+        .expect("should be a valid log configuration");
+
+        // The structured encoders cannot redact messages.
+        assert!(!logback_xml.contains("XMLLayout"));
+        assert!(!logback_xml.contains("JsonEncoder"));
+
+        let patterns = logback_xml
+            .lines()
+            .filter_map(|line| {
+                line.trim()
+                    .strip_prefix("<pattern>")
+                    .and_then(|line| line.strip_suffix("</pattern>"))
+            })
+            .collect::<Vec<_>>();
+        let [console_pattern, file_pattern] = patterns.as_slice() else {
+            panic!("expected a pattern for the console and the file appender");
+        };
+
+        assert_eq!(
+            r"%replace(%replace(%d{ISO8601} %-5p %m%n){'(password=)\S+', '$1***'}){'Bearer [A-Za-z0-9.]+', '[REDACTED]'}",
+            *console_pattern
+        );
+
+        let message_pattern = file_pattern
+            .strip_prefix(r#"{"@timestamp":"%d{"yyyy-MM-dd'T'HH:mm:ss.SSSXXX"}","level":"%level","logger":"%logger","message":""#)
+            .and_then(|pattern| pattern.strip_suffix(r#""}%n"#))
+            .unwrap();
+
+        // The message is written as JSON string
+        let written_message = evaluate_logback_pattern(message_pattern, SENSITIVE_MESSAGE);
+        let written_message: String =
+            serde_json::from_str(&format!("\"{written_message}\"")).unwrap();
+        assert_eq!(REDACTED_MESSAGE, written_message);
+    }
+
+    #[test]
+    fn log_configs_with_fixed_log_file_and_redactions() {
+        let log_config = redaction_log_config();
+
+        let log4j2_result = create_log4j2_config(
+            &format!("{STACKABLE_LOG_DIR}/my-product"),
+            "my-product.log4j2.xml",
+            10,
+            "%d{ISO8601} %-5p %m%n",
+            &log_config,
+        );
+        assert!(matches!(
+            log4j2_result,
+            Err(LoggingError::LogFileExtensionMismatch { expected_extension, .. })
+                if expected_extension == ".plain.json"
+        ));
+
+        let logback_result = create_logback_config(
+            &format!("{STACKABLE_LOG_DIR}/my-product"),
+            "my-product.log4j.xml",
+            10,
+            "%d{ISO8601} %-5p %m%n",
+            &log_config,
+            None,
+        );
+        assert!(matches!(
+            logback_result,
+            Err(LoggingError::LogFileExtensionMismatch { expected_extension, .. })
+                if expected_extension == ".plain.json"
+        ));
+    }
+
+    #[test]
+    fn vector_config() {
+        insta::assert_snapshot!(create_vector_config(&role_group(), None));
+    }
+
+    /// Ensures that the log formats of the former YAML template are still parsed as before.
+    #[test]
+    fn vector_config_is_unchanged() {
+        let expected = std::fs::read_to_string("fixtures/product_logging/vector.yaml").unwrap();
+        let expected: serde_yaml::Value = serde_yaml::from_str(&expected).unwrap();
+
+        let actual: serde_yaml::Value =
+            serde_yaml::from_str(&base_vector_config(&role_group(), None).to_yaml()).unwrap();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[rstest]
+    #[case::otlp("otlp", indoc::indoc! {"
+        otlp:
+          host: otel-collector.monitoring.svc
+    "})]
+    #[case::loki("loki", indoc::indoc! {"
+        loki:
+          host: loki.monitoring.svc
+          credentials:
+            secretClass: loki-credentials
+          tls:
+            verification:
+              server:
+                caCert:
+                  secretClass: tls
+    "})]
+    #[case::opensearch("opensearch", indoc::indoc! {"
+        openSearch:
+          host: opensearch.monitoring.svc
+          port: 9201
+          index: logs-%Y.%m.%d
+          tls:
+            verification:
+              none: {}
+    "})]
+    fn vector_log_sink(#[case] name: &str, #[case] log_sink: &str) {
+        let log_sink: LogSink = yaml_from_str_singleton_map(log_sink).unwrap();
+
+        let mut vector_config = VectorConfig::default();
+        set_vector_log_sink(&mut vector_config, &log_sink);
+
+        insta::assert_snapshot!(format!("vector_log_sink_{name}"), vector_config.to_yaml());
+    }
+
+    #[test]
+    fn log_sink_volumes_and_mounts() {
+        let log_sink: LogSink = yaml_from_str_singleton_map(indoc::indoc! {"
+            loki:
+              host: loki.monitoring.svc
+              credentials:
+                secretClass: loki-credentials
+              tls:
+                verification:
+                  server:
+                    caCert:
+                      secretClass: tls
+        "})
+        .unwrap();
+
+        let volume_names = log_sink_volumes(&log_sink)
+            .unwrap()
+            .into_iter()
+            .map(|volume| volume.name)
+            .collect::<Vec<_>>();
+        let volume_mounts = log_sink_volume_mounts(&log_sink)
+            .into_iter()
+            .map(|volume_mount| (volume_mount.name, volume_mount.mount_path))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            volume_names,
+            vec!["loki-credentials-log-sink-credentials", "tls-ca-cert"]
+        );
+        assert_eq!(
+            volume_mounts,
+            vec![
+                (
+                    "loki-credentials-log-sink-credentials".to_owned(),
+                    "/stackable/secrets/log-sink-credentials/loki-credentials".to_owned()
+                ),
+                (
+                    "tls-ca-cert".to_owned(),
+                    "/stackable/secrets/tls".to_owned()
+                )
+            ]
+        );
+        assert!(
+            log_sink_volumes(&LogSink::VectorAggregator {})
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn vector_container_for_log_sink_requires_aggregator_config_map() {
+        let image = ResolvedProductImage {
+            product_version: "1.0.0".to_owned(),
+            app_version_label_value: "1.0.0".parse().unwrap(),
+            image: "oci.stackable.tech/sdp/product:1.0.0".to_owned(),
+            image_pull_policy: "Always".to_owned(),
+            pull_secrets: None,
+        };
+
+        let result = vector_container_for_log_sink(
+            &image,
+            "config",
+            "log",
+            None,
+            ResourceRequirements::default(),
+            &LogSink::VectorAggregator {},
+            None,
+        );
+        assert!(matches!(
+            result,
+            Err(LoggingError::MissingVectorAggregatorConfigMap)
+        ));
+
+        let log_sink: LogSink =
+            yaml_from_str_singleton_map("otlp:\n  host: otel-collector.monitoring.svc\n").unwrap();
+        let container = vector_container_for_log_sink(
+            &image,
+            "config",
+            "log",
+            None,
+            ResourceRequirements::default(),
+            &log_sink,
+            None,
+        )
+        .unwrap();
+        assert!(
+            container
+                .env
+                .unwrap_or_default()
+                .iter()
+                .all(|env_var| env_var.name != VECTOR_AGGREGATOR_ENV_NAME)
+        );
+    }
 }
@@ -1,14 +1,21 @@
 //! Logging structure used within Custom Resource Definitions
 
-use std::{borrow::Cow, collections::BTreeMap, fmt::Display};
+use std::{borrow::Cow, collections::BTreeMap, fmt::Display, num::NonZeroU32};
 
 use educe::Educe;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use stackable_shared::time::Duration;
 
-use crate::config::{
-    fragment::{self, Fragment, FromFragment},
-    merge::{Atomic, Merge},
+use crate::{
+    commons::{
+        networking::HostName, secret_class::SecretClassVolume, tls_verification::TlsClientDetails,
+    },
+    config::{
+        fragment::{self, Fragment, FromFragment},
+        merge::{Atomic, Merge},
+    },
+    k8s_openapi::apimachinery::pkg::api::resource::Quantity,
 };
 
 /// Logging configuration
@@ -75,6 +82,16 @@ where
     /// Log configuration per container.
     #[fragment_attrs(serde(default))]
     pub containers: BTreeMap<T, ContainerLogConfig>,
+
+    /// Where the Vector log agent sends the logs to.
+    /// Defaults to the Vector aggregator announced in the discovery ConfigMap.
+    #[fragment_attrs(serde(default, skip_serializing_if = "Option::is_none"))]
+    pub sink: Option<LogSink>,
+
+    /// Retention policy per container.
+    /// The log files of a container are cleaned up if they exceed the configured quota.
+    #[fragment_attrs(serde(default, skip_serializing_if = "BTreeMap::is_empty"))]
+    pub retention: BTreeMap<T, LogRetention>,
 }
 
 impl<T> Logging<T>
@@ -88,6 +105,97 @@ where
             .map(Cow::Borrowed)
             .unwrap_or_default()
     }
+
+    /// Get the log sink, falling back to the Vector aggregator.
+    pub fn sink(&'_ self) -> Cow<'_, LogSink> {
+        self.sink.as_ref().map(Cow::Borrowed).unwrap_or_default()
+    }
+
+    /// Get the retention policy for `container` if one is configured.
+    pub fn retention_for_container(&self, container: &T) -> Option<&LogRetention> {
+        self.retention.get(container)
+    }
+}
+
+/// Destination of the logs collected by the Vector log agent
+#[derive(Clone, Debug, Deserialize, Eq, JsonSchema, PartialEq, Serialize, Educe)]
+#[educe(Default)]
+#[serde(rename_all = "camelCase")]
+pub enum LogSink {
+    /// Send the logs to the Vector aggregator announced in the discovery ConfigMap.
+    #[educe(Default)]
+    VectorAggregator {},
+
+    /// Send the logs to an OpenTelemetry collector using OTLP over HTTP.
+    Otlp(LogSinkConnection),
+
+    /// Push the logs to Grafana Loki.
+    Loki(LogSinkConnection),
+
+    /// Index the logs in OpenSearch or Elasticsearch.
+    OpenSearch(OpenSearchLogSink),
+}
+
+impl Atomic for LogSink {}
+
+impl LogSink {
+    /// Returns the connection of a direct sink, or [`None`] for the Vector aggregator.
+    pub fn connection(&self) -> Option<&LogSinkConnection> {
+        match self {
+            Self::VectorAggregator {} => None,
+            Self::Otlp(connection)
+            | Self::Loki(connection)
+            | Self::OpenSearch(OpenSearchLogSink { connection, .. }) => Some(connection),
+        }
+    }
+}
+
+/// Connection to a log sink
+#[derive(Clone, Debug, Deserialize, Eq, JsonSchema, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogSinkConnection {
+    /// Host of the log sink without any protocol or port. For example: `loki.monitoring.svc`.
+    pub host: HostName,
+
+    /// Port the log sink listens on.
+    /// If not specified, the default port of the sink type is used.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub port: Option<u16>,
+
+    /// Credentials used for HTTP basic authentication.
+    /// The [SecretClass](DOCS_BASE_URL_PLACEHOLDER/secret-operator/secretclass) must provide the
+    /// keys `user` and `password`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub credentials: Option<SecretClassVolume>,
+
+    /// Use a TLS connection. If not specified no TLS will be used.
+    #[serde(flatten)]
+    pub tls: TlsClientDetails,
+}
+
+impl LogSinkConnection {
+    /// Builds the endpoint URL, using `default_port` if no port is specified.
+    pub fn endpoint(&self, default_port: u16) -> String {
+        let scheme = if self.tls.uses_tls() { "https" } else { "http" };
+
+        format!(
+            "{scheme}://{host}:{port}",
+            host = self.host.as_url_host(),
+            port = self.port.unwrap_or(default_port)
+        )
+    }
+}
+
+/// Log sink which indexes the logs in OpenSearch or Elasticsearch
+#[derive(Clone, Debug, Deserialize, Eq, JsonSchema, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenSearchLogSink {
+    #[serde(flatten)]
+    pub connection: LogSinkConnection,
+
+    /// The index to write the logs to.
+    /// Placeholders of the Vector template syntax can be used, e.g. `logs-%Y.%m.%d`.
+    pub index: String,
 }
 
 /// Log configuration of the container
@@ -230,6 +338,16 @@ pub struct AutomaticContainerLogConfig {
     pub console: Option<AppenderConfig>,
     /// Configuration for the file appender
     pub file: Option<AppenderConfig>,
+    /// Format of the events written by the file appender.
+    /// `json` is only supported for Log4j 2 and logback and is ignored for Log4j.
+    /// Defaults to `xml`.
+    #[fragment_attrs(serde(default, skip_serializing_if = "Option::is_none"))]
+    pub file_format: Option<LogFileFormat>,
+    /// Rules to redact sensitive information in the log messages, e.g. passwords or tokens.
+    /// Log4j 2 and logback redact the messages before they are written to the log file, which is
+    /// then written as plain JSON regardless of the `fileFormat`.
+    #[fragment_attrs(serde(default, skip_serializing_if = "BTreeMap::is_empty"))]
+    pub redactions: BTreeMap<String, LogRedaction>,
 }
 
 impl Merge for AutomaticContainerLogConfigFragment {
@@ -252,6 +370,8 @@ impl Merge for AutomaticContainerLogConfigFragment {
         } else {
             self.file.clone_from(&defaults.file);
         }
+        self.file_format.merge(&defaults.file_format);
+        Merge::merge(&mut self.redactions, &defaults.redactions);
     }
 }
 
@@ -266,8 +386,34 @@ impl AutomaticContainerLogConfig {
             .map(|root| root.level)
             .unwrap_or_default()
     }
+
+    /// Return the format of the log file, falling back to XML
+    pub fn file_format(&self) -> LogFileFormat {
+        self.file_format.unwrap_or_default()
+    }
+}
+
+/// Format of the events written by the file appender, defaults to `xml`
+///
+/// `json` is only supported for Log4j 2 and logback and is ignored for Log4j.
+// This description replaces the one of the `fileFormat` field in the CRD, so it must be complete.
+#[derive(
+    Clone, Copy, Debug, Default, Deserialize, Eq, JsonSchema, PartialEq, Serialize, strum::Display,
+)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "camelCase")]
+pub enum LogFileFormat {
+    /// One XML element per event, e.g. written by the Log4j 2 `XMLLayout`
+    #[default]
+    Xml,
+
+    /// One JSON object per line, written by the Log4j 2 `JsonTemplateLayout` or the logback
+    /// `JsonEncoder`
+    Json,
 }
 
+impl Atomic for LogFileFormat {}
+
 /// Configuration of a logger
 #[derive(Clone, Debug, Default, Eq, Fragment, JsonSchema, PartialEq)]
 #[fragment(path_overrides(fragment = "crate::config::fragment"))]
@@ -290,6 +436,118 @@ pub struct LoggerConfig {
     /// The log level threshold.
     /// Log events with a lower log level are discarded.
     pub level: LogLevel,
+    /// Limits the rate of log events of this logger.
+    /// Log events exceeding the limit are discarded.
+    #[fragment_attrs(serde(default, skip_serializing_if = "Option::is_none"))]
+    pub rate_limit: Option<LogRateLimit>,
+    /// Only keep one out of `sampleRate` log events of this logger.
+    /// The other log events are discarded. Must be at least 1.
+    #[fragment_attrs(serde(default, skip_serializing_if = "Option::is_none"))]
+    pub sample_rate: Option<NonZeroU32>,
+}
+
+/// Rate limit of a logger
+#[derive(Clone, Debug, Deserialize, Eq, JsonSchema, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogRateLimit {
+    /// The average number of log events per second
+    pub events_per_second: u32,
+    /// The maximum number of log events in a burst; Defaults to 10 times `eventsPerSecond`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_burst: Option<u32>,
+}
+
+impl Atomic for LogRateLimit {}
+
+impl LogRateLimit {
+    /// Return the maximum number of log events in a burst, falling back to 10 times the rate
+    pub fn max_burst(&self) -> u32 {
+        self.max_burst
+            .unwrap_or_else(|| self.events_per_second.saturating_mul(10))
+    }
+}
+
+/// Rule to redact sensitive information in log messages
+#[derive(Clone, Debug, Default, Eq, Fragment, JsonSchema, PartialEq)]
+#[fragment(path_overrides(fragment = "crate::config::fragment"))]
+#[fragment_attrs(
+    derive(
+        Clone,
+        Debug,
+        Default,
+        Deserialize,
+        JsonSchema,
+        PartialEq,
+        Eq,
+        Merge,
+        Serialize
+    ),
+    merge(path_overrides(merge = "crate::config::merge")),
+    serde(rename_all = "camelCase")
+)]
+pub struct LogRedaction {
+    /// Regular expression matching the sensitive information
+    pub pattern: String,
+    /// Replacement for the matches; Capture groups can be referenced with `$1`, `$name`, etc.
+    /// Defaults to `[REDACTED]`.
+    pub replacement: Option<String>,
+}
+
+impl LogRedaction {
+    /// Default replacement for the matches of a redaction rule
+    pub const DEFAULT_REPLACEMENT: &'static str = "[REDACTED]";
+
+    /// Return the replacement, falling back to [`Self::DEFAULT_REPLACEMENT`]
+    pub fn replacement(&self) -> &str {
+        self.replacement
+            .as_deref()
+            .unwrap_or(Self::DEFAULT_REPLACEMENT)
+    }
+}
+
+/// Retention policy for the log files of a container
+#[derive(Clone, Debug, Fragment, JsonSchema, PartialEq)]
+#[fragment(path_overrides(fragment = "crate::config::fragment"))]
+#[fragment_attrs(
+    derive(
+        Clone,
+        Debug,
+        Default,
+        Deserialize,
+        JsonSchema,
+        PartialEq,
+        Merge,
+        Serialize
+    ),
+    merge(path_overrides(merge = "crate::config::merge")),
+    serde(rename_all = "camelCase")
+)]
+pub struct LogRetention {
+    /// Maximum size of all log files of the container, e.g. `100Mi`.
+    /// The oldest log files are deleted if this quota is exceeded.
+    pub max_size: Quantity,
+    /// Maximum size of a file of the captured console output before it is rotated;
+    /// Defaults to a fifth of `maxSize`.
+    #[fragment_attrs(serde(default, skip_serializing_if = "Option::is_none"))]
+    pub max_file_size: Option<Quantity>,
+    /// Interval in which the quota is enforced; Defaults to 30 seconds.
+    #[fragment_attrs(serde(default, skip_serializing_if = "Option::is_none"))]
+    pub cleanup_interval: Option<Duration>,
+}
+
+// `Eq` cannot be derived because `Quantity` only implements `PartialEq`. Quantities are compared
+// as strings, so the equality is reflexive.
+impl Eq for LogRetention {}
+
+impl LogRetention {
+    /// Default interval in which the quota is enforced
+    pub const DEFAULT_CLEANUP_INTERVAL: Duration = Duration::from_secs(30);
+
+    /// Return the cleanup interval, falling back to [`Self::DEFAULT_CLEANUP_INTERVAL`]
+    pub fn cleanup_interval(&self) -> Duration {
+        self.cleanup_interval
+            .unwrap_or(Self::DEFAULT_CLEANUP_INTERVAL)
+    }
 }
 
 /// Configuration of a log appender
@@ -416,6 +674,50 @@ impl LogLevel {
         }
         .into()
     }
+
+    /// Convert the log level to a string understood by zap
+    pub fn to_zap_literal(&self) -> String {
+        // zap has no trace level and cannot be turned off, so the nearest levels are used.
+        match self {
+            Self::TRACE | Self::DEBUG => "debug",
+            Self::INFO => "info",
+            Self::WARN => "warn",
+            Self::ERROR => "error",
+            Self::FATAL | Self::NONE => "fatal",
+        }
+        .into()
+    }
+
+    /// Convert the log level to a string understood by the Go package `log/slog`
+    pub fn to_slog_literal(&self) -> String {
+        // slog levels are integers with named levels in steps of 4; there is no named trace or
+        // fatal level, so they are expressed relative to the named ones.
+        match self {
+            Self::TRACE => "DEBUG-4",
+            Self::DEBUG => "DEBUG",
+            Self::INFO => "INFO",
+            Self::WARN => "WARN",
+            Self::ERROR => "ERROR",
+            Self::FATAL => "ERROR+4",
+            Self::NONE => "ERROR+5",
+        }
+        .into()
+    }
+
+    /// Convert the log level to a string understood by logrus
+    pub fn to_logrus_literal(&self) -> String {
+        // logrus cannot be turned off, so the highest level is used.
+        match self {
+            Self::TRACE => "trace",
+            Self::DEBUG => "debug",
+            Self::INFO => "info",
+            Self::WARN => "warning",
+            Self::ERROR => "error",
+            Self::FATAL => "fatal",
+            Self::NONE => "panic",
+        }
+        .into()
+    }
 }
 
 /// Create the default logging configuration
@@ -428,6 +730,8 @@ where
         containers: T::iter()
             .map(|container| (container, default_container_log_config()))
             .collect(),
+        sink: None,
+        retention: BTreeMap::new(),
     }
 }
 
@@ -440,6 +744,8 @@ pub fn default_container_log_config() -> ContainerLogConfigFragment {
                     AutomaticContainerLogConfig::ROOT_LOGGER.into(),
                     LoggerConfigFragment {
                         level: Some(LogLevel::INFO),
+                        rate_limit: None,
+                        sample_rate: None,
                     },
                 )]
                 .into(),
@@ -449,6 +755,8 @@ pub fn default_container_log_config() -> ContainerLogConfigFragment {
                 file: Some(AppenderConfigFragment {
                     level: Some(LogLevel::INFO),
                 }),
+                file_format: None,
+                redactions: BTreeMap::new(),
             },
         )),
     }
@@ -456,16 +764,21 @@ pub fn default_container_log_config() -> ContainerLogConfigFragment {
 
 #[cfg(test)]
 mod tests {
-    use std::collections::BTreeMap;
+    use std::{collections::BTreeMap, num::NonZeroU32};
+
+    use stackable_shared::time::Duration;
 
     use super::{
         AppenderConfig, AppenderConfigFragment, AutomaticContainerLogConfig,
         AutomaticContainerLogConfigFragment, ConfigMapLogConfig, ConfigMapLogConfigFragment,
         ContainerLogConfig, ContainerLogConfigChoice, ContainerLogConfigChoiceFragment,
         ContainerLogConfigFragment, CustomContainerLogConfig, CustomContainerLogConfigFragment,
-        LogLevel,
+        LogFileFormat, LogLevel, LogRetention, Logging, LoggingFragment,
+    };
+    use crate::{
+        config::{fragment, merge},
+        k8s_openapi::apimachinery::pkg::api::resource::Quantity,
     };
-    use crate::config::{fragment, merge};
 
     #[test]
     fn serialize_container_log_config() {
@@ -483,6 +796,8 @@ mod tests {
                         file: Some(AppenderConfigFragment {
                             level: Some(LogLevel::WARN),
                         }),
+                        file_format: None,
+                        redactions: BTreeMap::new(),
                     },
                 )),
             })
@@ -519,6 +834,8 @@ mod tests {
                         file: Some(AppenderConfigFragment {
                             level: Some(LogLevel::WARN),
                         }),
+                        file_format: None,
+                        redactions: BTreeMap::new(),
                     },
                 )),
             },
@@ -553,6 +870,8 @@ mod tests {
                         loggers: BTreeMap::new(),
                         console: None,
                         file: None,
+                        file_format: None,
+                        redactions: BTreeMap::new(),
                     },
                 )),
             },
@@ -586,17 +905,23 @@ mod tests {
                 loggers: BTreeMap::new(),
                 console: None,
                 file: None,
+                file_format: None,
+                redactions: BTreeMap::new(),
             },
             merge::merge(
                 AutomaticContainerLogConfigFragment {
                     loggers: BTreeMap::new(),
                     console: None,
                     file: None,
+                    file_format: None,
+                    redactions: BTreeMap::new(),
                 },
                 &AutomaticContainerLogConfigFragment {
                     loggers: BTreeMap::new(),
                     console: None,
                     file: None,
+                    file_format: None,
+                    redactions: BTreeMap::new(),
                 }
             )
         );
@@ -611,6 +936,8 @@ mod tests {
                 file: Some(AppenderConfigFragment {
                     level: Some(LogLevel::WARN),
                 }),
+                file_format: None,
+                redactions: BTreeMap::new(),
             },
             merge::merge(
                 AutomaticContainerLogConfigFragment {
@@ -621,11 +948,15 @@ mod tests {
                     file: Some(AppenderConfigFragment {
                         level: Some(LogLevel::WARN),
                     }),
+                    file_format: None,
+                    redactions: BTreeMap::new(),
                 },
                 &AutomaticContainerLogConfigFragment {
                     loggers: BTreeMap::new(),
                     console: None,
                     file: None,
+                    file_format: None,
+                    redactions: BTreeMap::new(),
                 }
             )
         );
@@ -640,12 +971,16 @@ mod tests {
                 file: Some(AppenderConfigFragment {
                     level: Some(LogLevel::WARN),
                 }),
+                file_format: None,
+                redactions: BTreeMap::new(),
             },
             merge::merge(
                 AutomaticContainerLogConfigFragment {
                     loggers: BTreeMap::new(),
                     console: None,
                     file: None,
+                    file_format: None,
+                    redactions: BTreeMap::new(),
                 },
                 &AutomaticContainerLogConfigFragment {
                     loggers: BTreeMap::new(),
@@ -655,6 +990,8 @@ mod tests {
                     file: Some(AppenderConfigFragment {
                         level: Some(LogLevel::WARN),
                     }),
+                    file_format: None,
+                    redactions: BTreeMap::new(),
                 }
             )
         );
@@ -669,6 +1006,8 @@ mod tests {
                 file: Some(AppenderConfigFragment {
                     level: Some(LogLevel::ERROR),
                 }),
+                file_format: None,
+                redactions: BTreeMap::new(),
             },
             merge::merge(
                 AutomaticContainerLogConfigFragment {
@@ -677,6 +1016,8 @@ mod tests {
                     file: Some(AppenderConfigFragment {
                         level: Some(LogLevel::ERROR),
                     }),
+                    file_format: None,
+                    redactions: BTreeMap::new(),
                 },
                 &AutomaticContainerLogConfigFragment {
                     loggers: BTreeMap::new(),
@@ -686,11 +1027,54 @@ mod tests {
                     file: Some(AppenderConfigFragment {
                         level: Some(LogLevel::WARN),
                     }),
+                    file_format: None,
+                    redactions: BTreeMap::new(),
                 }
             )
         );
     }
 
+    #[test]
+    fn merge_automatic_container_log_config_file_format() {
+        let json = AutomaticContainerLogConfigFragment {
+            file_format: Some(LogFileFormat::Json),
+            ..AutomaticContainerLogConfigFragment::default()
+        };
+        let xml = AutomaticContainerLogConfigFragment {
+            file_format: Some(LogFileFormat::Xml),
+            ..AutomaticContainerLogConfigFragment::default()
+        };
+
+        assert_eq!(
+            json,
+            merge::merge(AutomaticContainerLogConfigFragment::default(), &json)
+        );
+        assert_eq!(json, merge::merge(json.clone(), &xml));
+        assert_eq!(
+            json,
+            serde_json::from_str::<AutomaticContainerLogConfigFragment>(
+                "{\"fileFormat\":\"json\"}"
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn deserialize_sample_rate() {
+        let deserialize = |sample_rate: u32| {
+            serde_json::from_str::<AutomaticContainerLogConfigFragment>(&format!(
+                "{{\"loggers\":{{\"ROOT\":{{\"sampleRate\":{sample_rate}}}}}}}"
+            ))
+        };
+
+        assert_eq!(
+            NonZeroU32::new(10),
+            deserialize(10).unwrap().loggers["ROOT"].sample_rate
+        );
+        // Keeping one out of zero events is meaningless.
+        assert!(deserialize(0).is_err());
+    }
+
     #[test]
     fn merge_container_log_config() {
         // overriding automatic config + default custom config -> overriding automatic config
@@ -705,6 +1089,8 @@ mod tests {
                         file: Some(AppenderConfigFragment {
                             level: Some(LogLevel::WARN),
                         }),
+                        file_format: None,
+                        redactions: BTreeMap::new(),
                     },
                 )),
             },
@@ -719,6 +1105,8 @@ mod tests {
                             file: Some(AppenderConfigFragment {
                                 level: Some(LogLevel::WARN),
                             }),
+                            file_format: None,
+                            redactions: BTreeMap::new(),
                         },
                     )),
                 },
@@ -746,6 +1134,8 @@ mod tests {
                         file: Some(AppenderConfigFragment {
                             level: Some(LogLevel::WARN),
                         }),
+                        file_format: None,
+                        redactions: BTreeMap::new(),
                     },
                 )),
             },
@@ -758,6 +1148,8 @@ mod tests {
                             file: Some(AppenderConfigFragment {
                                 level: Some(LogLevel::WARN),
                             }),
+                            file_format: None,
+                            redactions: BTreeMap::new(),
                         },
                     )),
                 },
@@ -769,6 +1161,8 @@ mod tests {
                                 level: Some(LogLevel::INFO),
                             }),
                             file: Some(AppenderConfigFragment { level: None }),
+                            file_format: None,
+                            redactions: BTreeMap::new(),
                         },
                     )),
                 }
@@ -789,6 +1183,8 @@ mod tests {
                         file: Some(AppenderConfig {
                             level: Some(LogLevel::WARN)
                         }),
+                        file_format: None,
+                        redactions: BTreeMap::new(),
                     }
                 ))
             },
@@ -802,6 +1198,8 @@ mod tests {
                         file: Some(AppenderConfigFragment {
                             level: Some(LogLevel::WARN),
                         }),
+                        file_format: None,
+                        redactions: BTreeMap::new(),
                     },
                 )),
             })
@@ -831,4 +1229,44 @@ mod tests {
             .unwrap()
         );
     }
+
+    #[test]
+    fn validate_log_retention() {
+        let logging_fragment: LoggingFragment<String> = serde_yaml::from_str(
+            "
+            enableVectorAgent: true
+            retention:
+              product:
+                maxSize: 100Mi
+                cleanupInterval: 1m
+            ",
+        )
+        .unwrap();
+        let logging = fragment::validate::<Logging<String>>(logging_fragment).unwrap();
+
+        assert_eq!(
+            Some(&LogRetention {
+                max_size: Quantity("100Mi".into()),
+                max_file_size: None,
+                cleanup_interval: Some(Duration::from_minutes_unchecked(1)),
+            }),
+            logging.retention_for_container(&"product".to_string())
+        );
+        assert_eq!(
+            Duration::from_minutes_unchecked(1),
+            logging.retention["product"].cleanup_interval()
+        );
+        assert_eq!(None, logging.retention_for_container(&"vector".to_string()));
+
+        let logging_fragment_without_max_size: LoggingFragment<String> = serde_yaml::from_str(
+            "
+            enableVectorAgent: true
+            retention:
+              product:
+                maxFileSize: 10Mi
+            ",
+        )
+        .unwrap();
+        assert!(fragment::validate::<Logging<String>>(logging_fragment_without_max_size).is_err());
+    }
 }
@@ -0,0 +1,561 @@
+//! Typed model of the Vector agent configuration
+//!
+//! The model covers the parts of the [Vector configuration](https://vector.dev/docs/reference/configuration/)
+//! which are used by the log aggregation framework. The default configuration is built by
+//! [`default_vector_config`](crate::product_logging::framework::default_vector_config) and can be
+//! extended with product-specific sources, transforms and sinks before it is rendered with
+//! [`VectorConfig::to_yaml`].
+//!
+//! # Example
+//!
+//! ```
+//! use stackable_operator::product_logging::{
+//!     framework::default_vector_config,
+//!     vector::{FileSource, Transform},
+//! };
+//! # use stackable_operator::{
+//! #     k8s_openapi::api::core::v1::Pod,
+//! #     kube::runtime::reflector::ObjectRef,
+//! #     role_utils::RoleGroupRef,
+//! # };
+//! # let role_group = RoleGroupRef {
+//! #     cluster: ObjectRef::<Pod>::new("test-cluster"),
+//! #     role: "role".into(),
+//! #     role_group: "role-group".into(),
+//! # };
+//!
+//! let mut vector_config = default_vector_config(&role_group, None);
+//!
+//! // Parse the audit log of the product. The processed events are picked up by the default
+//! // transforms and shipped to the aggregator.
+//! vector_config.add_file_parser(
+//!     "audit",
+//!     FileSource::new(["/stackable/log/product/audit.json"]),
+//!     r#"
+//! .logger = "audit"
+//! .level = "INFO"
+//! .errors = []
+//! "#,
+//! );
+//!
+//! // Drop noisy health check logs before they are shipped.
+//! vector_config.add_transform(
+//!     "filtered_logs_health_checks",
+//!     Transform::filter(["extended_logs"], r#".logger != "health""#),
+//! );
+//!
+//! let yaml = vector_config.to_yaml();
+//! ```
+
+use indexmap::IndexMap;
+use serde::Serialize;
+
+/// Prefix of file sources which are parsed by a transform with the [`PROCESSED_FILES_PREFIX`].
+pub const FILES_PREFIX: &str = "files_";
+
+/// Prefix of transforms which parse file sources. The output of all transforms with this prefix is
+/// enriched with the container and file name and shipped to the aggregator.
+pub const PROCESSED_FILES_PREFIX: &str = "processed_files_";
+
+/// A Vector configuration consisting of sources, transforms and sinks
+///
+/// Components are rendered in the order in which they were added. Empty sections are omitted, so
+/// that the model can also be used for partial configuration files which Vector merges with the
+/// main configuration file. Such partial configurations can be created with
+/// [`VectorConfig::default`].
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
+pub struct VectorConfig {
+    /// The directory used for persisting Vector state, e.g. the file checkpoints.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data_dir: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub log_schema: Option<LogSchema>,
+
+    /// Secret backends which can be referenced in component options with `SECRET[<backend>.<key>]`
+    #[serde(skip_serializing_if = "IndexMap::is_empty")]
+    pub secret: IndexMap<String, SecretBackend>,
+
+    #[serde(skip_serializing_if = "IndexMap::is_empty")]
+    pub sources: IndexMap<String, Source>,
+
+    #[serde(skip_serializing_if = "IndexMap::is_empty")]
+    pub transforms: IndexMap<String, Transform>,
+
+    #[serde(skip_serializing_if = "IndexMap::is_empty")]
+    pub sinks: IndexMap<String, Sink>,
+}
+
+impl VectorConfig {
+    /// Creates an empty configuration which stores its state in `data_dir`.
+    pub fn new(data_dir: impl Into<String>, log_schema: LogSchema) -> Self {
+        Self {
+            data_dir: Some(data_dir.into()),
+            log_schema: Some(log_schema),
+            ..Self::default()
+        }
+    }
+
+    /// Adds a secret backend. An existing backend with the same name is replaced.
+    pub fn add_secret_backend(
+        &mut self,
+        name: impl Into<String>,
+        secret_backend: SecretBackend,
+    ) -> &mut Self {
+        self.secret.insert(name.into(), secret_backend);
+        self
+    }
+
+    /// Adds a source. An existing source with the same name is replaced.
+    pub fn add_source(&mut self, name: impl Into<String>, source: Source) -> &mut Self {
+        self.sources.insert(name.into(), source);
+        self
+    }
+
+    /// Adds a transform. An existing transform with the same name is replaced.
+    pub fn add_transform(&mut self, name: impl Into<String>, transform: Transform) -> &mut Self {
+        self.transforms.insert(name.into(), transform);
+        self
+    }
+
+    /// Adds a sink. An existing sink with the same name is replaced.
+    pub fn add_sink(&mut self, name: impl Into<String>, sink: Sink) -> &mut Self {
+        self.sinks.insert(name.into(), sink);
+        self
+    }
+
+    /// Adds a file source named `files_<name>` and a remap transform named
+    /// `processed_files_<name>` which parses the events of this source with the given
+    /// [VRL](https://vector.dev/docs/reference/vrl/) program.
+    ///
+    /// The program is expected to set the fields `timestamp`, `logger`, `level`, `message` and
+    /// `errors` like the built-in parsers do. The parsed events are then processed in the same way
+    /// as the events of the built-in file sources.
+    pub fn add_file_parser(
+        &mut self,
+        name: &str,
+        file_source: FileSource,
+        vrl_program: impl Into<String>,
+    ) -> &mut Self {
+        let source_name = format!("{FILES_PREFIX}{name}");
+
+        self.add_transform(
+            format!("{PROCESSED_FILES_PREFIX}{name}"),
+            Transform::remap([source_name.clone()], vrl_program),
+        );
+        self.add_source(source_name, Source::File(file_source))
+    }
+
+    /// Renders the configuration in YAML format.
+    pub fn to_yaml(&self) -> String {
+        // The model only contains strings, sequences and maps with string keys, so serialization
+        // cannot fail.
+        serde_yaml::to_string(self).expect("the Vector config must be serializable to YAML")
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct LogSchema {
+    /// The name of the event field which is set to the host name, i.e. the Pod name.
+    pub host_key: String,
+}
+
+/// A [Vector secret backend](https://vector.dev/docs/reference/configuration/global-options/#secret)
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SecretBackend {
+    /// Secrets read from the files in a directory, the file names are the keys of the secrets
+    Directory {
+        path: String,
+        remove_trailing_whitespace: bool,
+    },
+}
+
+impl SecretBackend {
+    /// Creates a backend which reads the secrets from the files in the given directory. Trailing
+    /// whitespace, e.g. a final newline, is removed from the secrets.
+    pub fn directory(path: impl Into<String>) -> Self {
+        Self::Directory {
+            path: path.into(),
+            remove_trailing_whitespace: true,
+        }
+    }
+}
+
+/// A [Vector source](https://vector.dev/docs/reference/configuration/sources/)
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Source {
+    /// Logs of Vector itself
+    InternalLogs,
+
+    /// Events read line by line from files
+    File(FileSource),
+}
+
+/// Configuration of a [file source](https://vector.dev/docs/reference/configuration/sources/file/)
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
+pub struct FileSource {
+    /// Glob patterns of the files to read
+    pub include: Vec<String>,
+
+    /// The delimiter of lines, defaults to `\n` in Vector
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line_delimiter: Option<String>,
+
+    /// Aggregation of multiple lines into a single event
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub multiline: Option<MultilineConfig>,
+}
+
+impl FileSource {
+    /// Creates a file source which reads the files matching the given glob patterns.
+    pub fn new(include: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Self {
+            include: include.into_iter().map(Into::into).collect(),
+            ..Self::default()
+        }
+    }
+
+    pub fn with_line_delimiter(mut self, line_delimiter: impl Into<String>) -> Self {
+        self.line_delimiter = Some(line_delimiter.into());
+        self
+    }
+
+    pub fn with_multiline(mut self, multiline: MultilineConfig) -> Self {
+        self.multiline = Some(multiline);
+        self
+    }
+}
+
+/// Configuration to aggregate multiple lines into a single event
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct MultilineConfig {
+    pub mode: MultilineMode,
+    pub start_pattern: String,
+    pub condition_pattern: String,
+    pub timeout_ms: u64,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MultilineMode {
+    ContinueThrough,
+    ContinuePast,
+    HaltBefore,
+    HaltWith,
+}
+
+/// A [Vector transform](https://vector.dev/docs/reference/configuration/transforms/)
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct Transform {
+    /// Names of the components whose events are processed. Wildcards are supported.
+    pub inputs: Vec<String>,
+
+    #[serde(flatten)]
+    pub kind: TransformKind,
+}
+
+impl Transform {
+    /// Creates a transform which modifies events with the given VRL program.
+    pub fn remap(
+        inputs: impl IntoIterator<Item = impl Into<String>>,
+        source: impl Into<String>,
+    ) -> Self {
+        Self {
+            inputs: inputs.into_iter().map(Into::into).collect(),
+            kind: TransformKind::Remap {
+                source: source.into(),
+            },
+        }
+    }
+
+    /// Creates a transform which only forwards events matching the given VRL condition.
+    pub fn filter(
+        inputs: impl IntoIterator<Item = impl Into<String>>,
+        condition: impl Into<String>,
+    ) -> Self {
+        Self {
+            inputs: inputs.into_iter().map(Into::into).collect(),
+            kind: TransformKind::Filter {
+                condition: condition.into(),
+            },
+        }
+    }
+
+    /// Creates a transform which forwards at most `threshold` events per `window_secs` seconds.
+    pub fn throttle(
+        inputs: impl IntoIterator<Item = impl Into<String>>,
+        threshold: u32,
+        window_secs: u32,
+    ) -> Self {
+        Self {
+            inputs: inputs.into_iter().map(Into::into).collect(),
+            kind: TransformKind::Throttle {
+                threshold,
+                window_secs,
+                exclude: None,
+            },
+        }
+    }
+
+    /// Creates a transform which forwards only one out of `rate` events.
+    pub fn sample(inputs: impl IntoIterator<Item = impl Into<String>>, rate: u32) -> Self {
+        Self {
+            inputs: inputs.into_iter().map(Into::into).collect(),
+            kind: TransformKind::Sample {
+                rate,
+                exclude: None,
+            },
+        }
+    }
+
+    /// Excludes the events matching the given VRL condition from throttling or sampling, so that
+    /// they are always forwarded.
+    ///
+    /// Other transforms are not changed.
+    pub fn excluding(mut self, condition: impl Into<String>) -> Self {
+        if let TransformKind::Throttle { exclude, .. } | TransformKind::Sample { exclude, .. } =
+            &mut self.kind
+        {
+            *exclude = Some(condition.into());
+        }
+        self
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TransformKind {
+    Remap {
+        source: String,
+    },
+    Filter {
+        condition: String,
+    },
+    Throttle {
+        threshold: u32,
+        window_secs: u32,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        exclude: Option<String>,
+    },
+    Sample {
+        rate: u32,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        exclude: Option<String>,
+    },
+}
+
+/// A [Vector sink](https://vector.dev/docs/reference/configuration/sinks/)
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct Sink {
+    /// Names of the components whose events are sent. Wildcards are supported.
+    pub inputs: Vec<String>,
+
+    #[serde(flatten)]
+    pub kind: SinkKind,
+}
+
+impl Sink {
+    /// Creates a sink which sends events to another Vector instance, e.g. the aggregator.
+    pub fn vector(
+        inputs: impl IntoIterator<Item = impl Into<String>>,
+        address: impl Into<String>,
+    ) -> Self {
+        Self {
+            inputs: inputs.into_iter().map(Into::into).collect(),
+            kind: SinkKind::Vector {
+                address: address.into(),
+            },
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SinkKind {
+    /// Another Vector instance
+    Vector { address: String },
+
+    /// An OpenTelemetry collector
+    Opentelemetry { protocol: OpentelemetryProtocol },
+
+    /// Grafana Loki
+    Loki {
+        endpoint: String,
+        encoding: Encoding,
+        labels: IndexMap<String, String>,
+
+        #[serde(skip_serializing_if = "Option::is_none")]
+        auth: Option<SinkAuth>,
+
+        #[serde(skip_serializing_if = "Option::is_none")]
+        tls: Option<SinkTls>,
+    },
+
+    /// Elasticsearch or OpenSearch
+    Elasticsearch {
+        endpoints: Vec<String>,
+        bulk: ElasticsearchBulk,
+
+        #[serde(skip_serializing_if = "Option::is_none")]
+        auth: Option<SinkAuth>,
+
+        #[serde(skip_serializing_if = "Option::is_none")]
+        tls: Option<SinkTls>,
+    },
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum OpentelemetryProtocol {
+    Http(HttpClient),
+}
+
+/// Options of sinks which send the events via HTTP
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct HttpClient {
+    pub uri: String,
+    pub encoding: Encoding,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub framing: Option<Framing>,
+
+    #[serde(skip_serializing_if = "IndexMap::is_empty")]
+    pub headers: IndexMap<String, String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auth: Option<SinkAuth>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tls: Option<SinkTls>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct Encoding {
+    pub codec: Codec,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Codec {
+    Json,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct Framing {
+    pub method: FramingMethod,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FramingMethod {
+    NewlineDelimited,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct ElasticsearchBulk {
+    /// The index to write the events to
+    pub index: String,
+}
+
+/// Authentication of a sink
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(tag = "strategy", rename_all = "snake_case")]
+pub enum SinkAuth {
+    Basic { user: String, password: String },
+}
+
+/// TLS options of a sink
+///
+/// TLS is enabled by the `https` scheme of the endpoint, these options only configure the
+/// verification of the server certificate.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
+pub struct SinkTls {
+    /// Path of the CA certificate used to verify the server certificate
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ca_file: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verify_certificate: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verify_hostname: Option<bool>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn add_file_parser() {
+        let mut vector_config = VectorConfig::new(
+            "/stackable/log/_vector-state",
+            LogSchema {
+                host_key: "pod".to_owned(),
+            },
+        );
+
+        vector_config.add_file_parser(
+            "audit",
+            FileSource::new(["/stackable/log/product/audit.json"]),
+            ".level = \"INFO\"\n",
+        );
+
+        assert_eq!(
+            vector_config.sources.get("files_audit"),
+            Some(&Source::File(FileSource::new([
+                "/stackable/log/product/audit.json"
+            ])))
+        );
+        assert_eq!(
+            vector_config.transforms.get("processed_files_audit"),
+            Some(&Transform::remap(["files_audit"], ".level = \"INFO\"\n"))
+        );
+    }
+
+    #[test]
+    fn to_yaml() {
+        let mut vector_config = VectorConfig::new(
+            "/stackable/log/_vector-state",
+            LogSchema {
+                host_key: "pod".to_owned(),
+            },
+        );
+
+        vector_config
+            .add_source("vector", Source::InternalLogs)
+            .add_transform(
+                "filtered_logs_vector",
+                Transform::filter(["vector"], "true"),
+            )
+            .add_sink(
+                "aggregator",
+                Sink::vector(["filtered_logs_vector"], "$VECTOR_AGGREGATOR_ADDRESS"),
+            );
+
+        assert_eq!(
+            vector_config.to_yaml(),
+            indoc::indoc! {"
+                data_dir: /stackable/log/_vector-state
+                log_schema:
+                  host_key: pod
+                sources:
+                  vector:
+                    type: internal_logs
+                transforms:
+                  filtered_logs_vector:
+                    inputs:
+                    - vector
+                    type: filter
+                    condition: 'true'
+                sinks:
+                  aggregator:
+                    inputs:
+                    - filtered_logs_vector
+                    type: vector
+                    address: $VECTOR_AGGREGATOR_ADDRESS
+            "}
+        );
+    }
+}
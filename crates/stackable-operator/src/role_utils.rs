@@ -94,10 +94,10 @@ use serde::{Deserialize, Serialize};
 use snafu::{OptionExt, ResultExt, Snafu};
 
 use crate::{
-    commons::pdb::PdbConfig,
+    commons::{pdb::PdbConfig, scheduling::SchedulingConfig},
     config::{
         fragment::{self, FromFragment},
-        merge::Merge,
+        merge::{Merge, merge},
     },
     utils::crds::raw_object_schema,
 };
@@ -163,6 +163,13 @@ pub struct CommonConfiguration<Config, CommonConfig, ConfigOverrides> {
     #[schemars(schema_with = "raw_object_schema")]
     pub pod_overrides: PodTemplateSpec,
 
+    // No docs needed, as we flatten this struct.
+    //
+    // Use [`Role::get_merged_scheduling_config`] to get the settings with the roleGroup values
+    // merged on top of the role values.
+    #[serde(flatten, default)]
+    pub scheduling: SchedulingConfig,
+
     // No docs needed, as we flatten this struct.
     //
     // This field is product-specific and can contain e.g. jvmArgumentOverrides.
@@ -343,6 +350,29 @@ pub struct Role<
     pub role_groups: HashMap<String, RoleGroup<Config, CommonConfig, ConfigOverrides>>,
 }
 
+impl<Config, ConfigOverrides, RoleConfig, CommonConfig>
+    Role<Config, ConfigOverrides, RoleConfig, CommonConfig>
+where
+    RoleConfig: Default + JsonSchema + Serialize,
+    CommonConfig: Default + JsonSchema + Serialize,
+    ConfigOverrides: Default + JsonSchema + Serialize,
+{
+    /// Merges the [`SchedulingConfig`] of the given role group on top of the one of the role.
+    pub fn get_merged_scheduling_config(
+        &self,
+        role_group: &str,
+    ) -> Result<SchedulingConfig, Error> {
+        let from_role_group = &self
+            .role_groups
+            .get(role_group)
+            .with_context(|| MissingRoleGroupSnafu { role_group })?
+            .config
+            .scheduling;
+
+        Ok(merge(from_role_group.clone(), &self.config.scheduling))
+    }
+}
+
 impl<Config, ConfigOverrides, RoleConfig>
     Role<Config, ConfigOverrides, RoleConfig, JavaCommonConfig>
 where
@@ -651,6 +681,38 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_merge_scheduling_config() {
+        let entire_role: Role<(), EmptyConfigOverrides> = serde_yaml::from_str(
+            "
+            priorityClassName: high-priority
+            runtimeClassName: gvisor
+            roleGroups:
+              default:
+                priorityClassName: low-priority
+                terminationGracePeriod: 2m
+            ",
+        )
+        .expect("Failed to parse role");
+
+        let merged_scheduling_config = entire_role
+            .get_merged_scheduling_config("default")
+            .expect("Failed to merge scheduling config");
+
+        assert_eq!(
+            merged_scheduling_config,
+            SchedulingConfig {
+                priority_class_name: Some("low-priority".to_owned()),
+                runtime_class_name: Some("gvisor".to_owned()),
+                termination_grace_period: Some(
+                    stackable_shared::time::Duration::from_minutes_unchecked(2)
+                ),
+                ..SchedulingConfig::default()
+            }
+        );
+        assert!(entire_role.get_merged_scheduling_config("missing").is_err());
+    }
+
     #[test]
     fn replica_counts_with_all_replicas_set() {
         let replicas = [Some(3), Some(2), Some(5)];
@@ -0,0 +1,598 @@
+//! Golden file tests for the objects which are built by reconcilers.
+//!
+//! A golden file test reads a custom resource together with the objects it references, e.g.
+//! AuthenticationClasses, S3Connections or ConfigMaps, from an input YAML file, runs the build
+//! functions of the operator and compares all built objects, as they would be passed to
+//! [`ClusterResources::add`], with a checked-in golden YAML file:
+//!
+//! ```ignore
+//! use stackable_operator::test_utils::golden::{ReconcileInput, ReconcileSnapshot};
+//!
+//! #[tokio::test]
+//! async fn simple_cluster() {
+//!     let mut input = ReconcileInput::<v1alpha1::TrinoCluster>::from_file(
+//!         "fixtures/golden/simple-cluster/input.yaml",
+//!     )
+//!     .expect("the input is valid");
+//!     let client = input.fake_api_server().client();
+//!
+//!     let mut snapshot = ReconcileSnapshot::new();
+//!     for object in build_objects(&input.resource, &client).await {
+//!         snapshot.add(&object).expect("the object has a kind and name");
+//!     }
+//!
+//!     snapshot.assert_matches_golden_file("fixtures/golden/simple-cluster/output.yaml");
+//! }
+//! ```
+//!
+//! If the objects do not match, then the test fails with a diff between the golden file and the
+//! built objects. The golden files are created or updated instead if the environment variable
+//! [`UPDATE_GOLDEN_FILES_ENV_VAR`] is set to `1`, e.g. with
+//! `UPDATE_GOLDEN_FILES=1 cargo test`. The changes of the golden files should be reviewed before
+//! they are committed.
+//!
+//! Golden files are meant for the complete output of a reconciliation, which depends on several
+//! referenced objects and spans many Kubernetes objects. They are plain YAML files next to the
+//! input, so they can be read, reviewed and applied like any other manifest, e.g. in integration
+//! tests. For the output of a single function, e.g. a rendered config file or one built object,
+//! [insta](https://insta.rs) snapshots are the better fit: they are kept next to the test, and
+//! `cargo insta review` offers an interactive review of the changes.
+//!
+//! [`ClusterResources::add`]: crate::cluster_resources::ClusterResources::add
+
+use std::{
+    collections::BTreeMap,
+    fmt::Write as _,
+    path::{Path, PathBuf},
+};
+
+use kube::{
+    Resource,
+    core::{DynamicObject, TypeMeta},
+};
+use serde::{Serialize, de::DeserializeOwned};
+use serde_json::Value;
+use similar::{ChangeTag, DiffTag, TextDiff};
+use snafu::{OptionExt, ResultExt, Snafu};
+
+use crate::{client::fake::FakeApiServer, test_utils::serialize_to_yaml_with_singleton_map};
+
+/// The environment variable which enables updating the golden files if set to `1`
+pub const UPDATE_GOLDEN_FILES_ENV_VAR: &str = "UPDATE_GOLDEN_FILES";
+
+/// The number of unchanged lines which are shown around the changed lines of a diff
+const DIFF_CONTEXT_LINES: usize = 3;
+
+type Result<T, E = Error> = std::result::Result<T, E>;
+
+#[derive(Debug, Snafu)]
+pub enum Error {
+    #[snafu(display("failed to read the file {path:?}"))]
+    ReadFile {
+        source: std::io::Error,
+        path: PathBuf,
+    },
+
+    #[snafu(display("failed to write the golden file {path:?}"))]
+    WriteGoldenFile {
+        source: std::io::Error,
+        path: PathBuf,
+    },
+
+    #[snafu(display("failed to parse the input YAML"))]
+    ParseInput { source: serde_yaml::Error },
+
+    #[snafu(display("the input contains no {kind} but exactly one is required"))]
+    MissingResource { kind: String },
+
+    #[snafu(display("the input contains more than one {kind} but exactly one is required"))]
+    AmbiguousResource { kind: String },
+
+    #[snafu(display("failed to deserialize the {kind} {name:?}"))]
+    DeserializeObject {
+        source: serde_json::Error,
+        kind: String,
+        name: String,
+    },
+
+    #[snafu(display("failed to serialize the object"))]
+    SerializeObject { source: serde_json::Error },
+
+    #[snafu(display("failed to serialize the object {object} to YAML"))]
+    SerializeYaml {
+        source: serde_yaml::Error,
+        object: String,
+    },
+
+    #[snafu(display("the object has no {key}"))]
+    MissingObjectKey { key: &'static str },
+
+    #[snafu(display("the object {object} was added more than once"))]
+    DuplicateObject { object: String },
+
+    #[snafu(display(
+        "the golden file {path:?} does not exist, run the test with {UPDATE_GOLDEN_FILES_ENV_VAR}=1 \
+        to create it"
+    ))]
+    MissingGoldenFile { path: PathBuf },
+
+    #[snafu(display(
+        "the built objects do not match the golden file {path:?}, run the test with \
+        {UPDATE_GOLDEN_FILES_ENV_VAR}=1 to update it\n\n{diff}"
+    ))]
+    GoldenFileMismatch { path: PathBuf, diff: String },
+}
+
+/// The input of a golden file test: A custom resource and the objects it references
+pub struct ReconcileInput<K> {
+    /// The custom resource which is reconciled
+    pub resource: K,
+
+    /// All other objects of the input, e.g. AuthenticationClasses, S3Connections or ConfigMaps
+    pub referenced_objects: Vec<DynamicObject>,
+}
+
+impl<K> ReconcileInput<K>
+where
+    K: Resource<DynamicType = ()> + Serialize + DeserializeOwned,
+{
+    /// Reads the input from the given YAML file, see [`ReconcileInput::from_yaml`].
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let yaml = std::fs::read_to_string(path).context(ReadFileSnafu { path })?;
+
+        Self::from_yaml(&yaml)
+    }
+
+    /// Parses the input from a multi-document YAML string.
+    ///
+    /// Exactly one document must be of the kind `K`, which becomes the [`Self::resource`]. All
+    /// other documents become the [`Self::referenced_objects`]. Enums are deserialized as singleton
+    /// maps like in
+    /// [`yaml_from_str_singleton_map`](crate::utils::yaml_from_str_singleton_map).
+    pub fn from_yaml(yaml: &str) -> Result<Self> {
+        let api_version = K::api_version(&());
+        let kind = K::kind(&());
+
+        let mut resources = Vec::new();
+        let mut referenced_objects = Vec::new();
+
+        for document in serde_yaml::Deserializer::from_str(yaml) {
+            let object: Option<DynamicObject> =
+                serde_yaml::with::singleton_map_recursive::deserialize(document)
+                    .context(ParseInputSnafu)?;
+            // Empty documents, e.g. after a trailing document separator, are skipped.
+            let Some(object) = object else {
+                continue;
+            };
+
+            match &object.types {
+                Some(TypeMeta {
+                    api_version: object_api_version,
+                    kind: object_kind,
+                }) if object_api_version == &api_version && object_kind == &kind => {
+                    resources.push(object);
+                }
+                _ => referenced_objects.push(object),
+            }
+        }
+
+        let resource = match resources.as_slice() {
+            [resource] => deserialize_object(resource)?,
+            [] => return MissingResourceSnafu { kind }.fail(),
+            _ => return AmbiguousResourceSnafu { kind }.fail(),
+        };
+
+        Ok(Self {
+            resource,
+            referenced_objects,
+        })
+    }
+
+    /// Returns the referenced objects of the type `T`.
+    pub fn referenced<T>(&self) -> Result<Vec<T>>
+    where
+        T: Resource<DynamicType = ()> + DeserializeOwned,
+    {
+        let api_version = T::api_version(&());
+        let kind = T::kind(&());
+
+        self.referenced_objects
+            .iter()
+            .filter(|object| {
+                object
+                    .types
+                    .as_ref()
+                    .is_some_and(|types| types.api_version == api_version && types.kind == kind)
+            })
+            .map(deserialize_object)
+            .collect()
+    }
+
+    /// Returns a [`FakeApiServer`] which contains the resource and all referenced objects, so that
+    /// the referenced objects can be resolved with its [`Client`](crate::client::Client).
+    ///
+    /// The [`Self::resource`] is replaced with the stored one, which contains the fields set by
+    /// the API server like the UID, which is for instance required for owner references.
+    ///
+    /// This function must be called within a Tokio runtime.
+    pub fn fake_api_server(&mut self) -> FakeApiServer {
+        let server = FakeApiServer::new();
+
+        for object in &self.referenced_objects {
+            server.insert_dynamic(object);
+        }
+        self.resource = server.insert(&self.resource);
+
+        server
+    }
+}
+
+/// The objects built by a reconciler which are compared with a golden file
+#[derive(Debug, Default)]
+pub struct ReconcileSnapshot {
+    /// The objects sorted by their API version, kind, namespace and name, so that the golden files
+    /// do not depend on the order in which the objects are built
+    objects: BTreeMap<ObjectKey, Value>,
+}
+
+#[derive(Debug, Eq, Ord, PartialEq, PartialOrd)]
+struct ObjectKey {
+    api_version: String,
+    kind: String,
+    namespace: Option<String>,
+    name: String,
+}
+
+impl std::fmt::Display for ObjectKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Self {
+            api_version,
+            kind,
+            namespace,
+            name,
+        } = self;
+
+        match namespace {
+            Some(namespace) => write!(f, "{api_version}/{kind} {namespace}/{name}"),
+            None => write!(f, "{api_version}/{kind} {name}"),
+        }
+    }
+}
+
+impl ReconcileSnapshot {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a built object to the snapshot.
+    ///
+    /// The object must have an `apiVersion`, a `kind` and a name. Every object can only be added
+    /// once.
+    pub fn add<T>(&mut self, object: &T) -> Result<()>
+    where
+        T: Serialize,
+    {
+        let object = serde_json::to_value(object).context(SerializeObjectSnafu)?;
+
+        let field = |pointer: &str| object.pointer(pointer).and_then(Value::as_str);
+        let key = ObjectKey {
+            api_version: field("/apiVersion")
+                .context(MissingObjectKeySnafu { key: "apiVersion" })?
+                .to_owned(),
+            kind: field("/kind")
+                .context(MissingObjectKeySnafu { key: "kind" })?
+                .to_owned(),
+            namespace: field("/metadata/namespace").map(ToOwned::to_owned),
+            name: field("/metadata/name")
+                .context(MissingObjectKeySnafu {
+                    key: "metadata/name",
+                })?
+                .to_owned(),
+        };
+
+        if self.objects.contains_key(&key) {
+            return DuplicateObjectSnafu {
+                object: key.to_string(),
+            }
+            .fail();
+        }
+        self.objects.insert(key, object);
+
+        Ok(())
+    }
+
+    /// Returns the snapshot as a multi-document YAML string.
+    ///
+    /// Enums are serialized as singleton maps like in [`serialize_to_yaml_with_singleton_map`].
+    pub fn to_yaml(&self) -> Result<String> {
+        let documents = self
+            .objects
+            .iter()
+            .map(|(key, object)| {
+                serialize_to_yaml_with_singleton_map(object).context(SerializeYamlSnafu {
+                    object: key.to_string(),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(documents.join("---\n"))
+    }
+
+    /// Compares the snapshot with the given golden file.
+    ///
+    /// If the environment variable [`UPDATE_GOLDEN_FILES_ENV_VAR`] is set to `1`, then the golden
+    /// file is created or updated instead.
+    pub fn compare_with_golden_file(&self, path: impl AsRef<Path>) -> Result<()> {
+        let update =
+            std::env::var(UPDATE_GOLDEN_FILES_ENV_VAR).is_ok_and(|value| value.trim() == "1");
+
+        self.compare_or_update_golden_file(path.as_ref(), update)
+    }
+
+    /// Compares the snapshot with the given golden file like
+    /// [`ReconcileSnapshot::compare_with_golden_file`] and panics with a readable diff if they do
+    /// not match.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the snapshot does not match the golden file or the golden file cannot be read or
+    /// written.
+    // The function is meant to be called in tests, where a panic with the formatted diff is more
+    // readable than the debug output of the error.
+    #[expect(clippy::panic)]
+    pub fn assert_matches_golden_file(&self, path: impl AsRef<Path>) {
+        if let Err(error) = self.compare_with_golden_file(path) {
+            panic!("{error}");
+        }
+    }
+
+    fn compare_or_update_golden_file(&self, path: &Path, update: bool) -> Result<()> {
+        let actual = self.to_yaml()?;
+
+        if update {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent).context(WriteGoldenFileSnafu { path })?;
+            }
+            return std::fs::write(path, actual).context(WriteGoldenFileSnafu { path });
+        }
+
+        let expected = match std::fs::read_to_string(path) {
+            Ok(expected) => expected,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+                return MissingGoldenFileSnafu { path }.fail();
+            }
+            Err(error) => return Err(error).context(ReadFileSnafu { path }),
+        };
+
+        if expected == actual {
+            Ok(())
+        } else {
+            GoldenFileMismatchSnafu {
+                path,
+                diff: diff_lines(&expected, &actual),
+            }
+            .fail()
+        }
+    }
+}
+
+/// Returns a line-based diff in the style of a unified diff where removed lines of `expected` are
+/// prefixed with `-` and added lines of `actual` with `+`.
+fn diff_lines(expected: &str, actual: &str) -> String {
+    let text_diff = TextDiff::from_lines(expected, actual);
+    let expected_line_count = text_diff.old_slices().len();
+
+    // Only the changed lines and their context are shown, skipped lines are indicated by `...`.
+    let mut diff = String::new();
+    let mut shown_until = 0;
+    for group in text_diff.grouped_ops(DIFF_CONTEXT_LINES) {
+        if group.iter().all(|op| op.tag() == DiffTag::Equal) {
+            continue;
+        }
+        let (Some(first_op), Some(last_op)) = (group.first(), group.last()) else {
+            continue;
+        };
+        if first_op.old_range().start > shown_until {
+            diff.push_str("...\n");
+        }
+        shown_until = last_op.old_range().end;
+
+        for change in group.iter().flat_map(|op| text_diff.iter_changes(op)) {
+            let tag = match change.tag() {
+                ChangeTag::Equal => ' ',
+                ChangeTag::Delete => '-',
+                ChangeTag::Insert => '+',
+            };
+            let line = change.value().trim_end_matches(['\n', '\r']);
+            let _ = writeln!(diff, "{tag} {line}");
+        }
+    }
+    if !diff.is_empty() && shown_until < expected_line_count {
+        diff.push_str("...\n");
+    }
+
+    diff
+}
+
+fn deserialize_object<T>(object: &DynamicObject) -> Result<T>
+where
+    T: DeserializeOwned,
+{
+    let value = serde_json::to_value(object).context(SerializeObjectSnafu)?;
+
+    T::deserialize(value).context(DeserializeObjectSnafu {
+        kind: object
+            .types
+            .as_ref()
+            .map(|types| types.kind.clone())
+            .unwrap_or_default(),
+        name: object.metadata.name.clone().unwrap_or_default(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use k8s_openapi::api::core::v1::{ConfigMap, Service, ServicePort, ServiceSpec};
+    use rstest::rstest;
+
+    use super::*;
+    use crate::{
+        builder::meta::ObjectMetaBuilder,
+        client::Client,
+        crd::{authentication::core::v1alpha1::AuthenticationClass, listener::v1alpha1::Listener},
+    };
+
+    const INPUT: &str = "fixtures/golden/input.yaml";
+    const OUTPUT: &str = "fixtures/golden/output.yaml";
+
+    /// Builds the objects of a made-up operator which exposes the Listener with a Service and
+    /// writes the referenced configuration into a ConfigMap.
+    async fn build_objects(listener: &Listener, client: &Client) -> (Service, ConfigMap) {
+        let name = listener.metadata.name.as_deref().unwrap();
+        let namespace = listener.metadata.namespace.as_deref().unwrap();
+        let metadata = || {
+            ObjectMetaBuilder::new()
+                .name(name)
+                .namespace(namespace)
+                .ownerreference_from_resource(listener, None, Some(true))
+                .unwrap()
+                .build()
+        };
+
+        let authentication_class = client.get::<AuthenticationClass>("tls", &()).await.unwrap();
+        let config = client
+            .get::<ConfigMap>(&format!("{name}-config"), namespace)
+            .await
+            .unwrap();
+
+        let service = Service {
+            metadata: metadata(),
+            spec: Some(ServiceSpec {
+                ports: Some(
+                    listener
+                        .spec
+                        .ports
+                        .iter()
+                        .flatten()
+                        .map(|port| ServicePort {
+                            name: Some(port.name.clone()),
+                            port: port.port,
+                            protocol: port.protocol.clone(),
+                            ..ServicePort::default()
+                        })
+                        .collect(),
+                ),
+                ..ServiceSpec::default()
+            }),
+            ..Service::default()
+        };
+
+        let mut data = config.data.unwrap_or_default();
+        data.insert(
+            "authentication-provider".to_owned(),
+            authentication_class.spec.provider.to_string(),
+        );
+        let config_map = ConfigMap {
+            metadata: metadata(),
+            data: Some(data),
+            ..ConfigMap::default()
+        };
+
+        (service, config_map)
+    }
+
+    #[tokio::test]
+    async fn golden_file() {
+        let mut input = ReconcileInput::<Listener>::from_file(INPUT).unwrap();
+        assert_eq!(input.referenced::<AuthenticationClass>().unwrap().len(), 1);
+        let client = input.fake_api_server().client();
+
+        let (service, config_map) = build_objects(&input.resource, &client).await;
+
+        // The order of the objects does not matter.
+        let mut snapshot = ReconcileSnapshot::new();
+        snapshot.add(&service).unwrap();
+        snapshot.add(&config_map).unwrap();
+        snapshot.assert_matches_golden_file(OUTPUT);
+
+        let error = snapshot.add(&service).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "the object v1/Service default/simple was added more than once"
+        );
+    }
+
+    #[test]
+    fn update_golden_file() {
+        let directory =
+            std::env::temp_dir().join(format!("stackable-operator-golden-{}", std::process::id()));
+        let path = directory.join("output.yaml");
+
+        let config_map = |value: &str| ConfigMap {
+            metadata: ObjectMetaBuilder::new().name("test").build(),
+            data: Some(BTreeMap::from([
+                ("unchanged".to_owned(), "value".to_owned()),
+                ("changed".to_owned(), value.to_owned()),
+            ])),
+            ..ConfigMap::default()
+        };
+        let mut snapshot = ReconcileSnapshot::new();
+        snapshot.add(&config_map("old")).unwrap();
+
+        let error = snapshot
+            .compare_or_update_golden_file(&path, false)
+            .unwrap_err();
+        assert!(matches!(error, Error::MissingGoldenFile { .. }));
+
+        snapshot.compare_or_update_golden_file(&path, true).unwrap();
+        snapshot
+            .compare_or_update_golden_file(&path, false)
+            .unwrap();
+
+        let mut changed_snapshot = ReconcileSnapshot::new();
+        changed_snapshot.add(&config_map("new")).unwrap();
+        let error = changed_snapshot
+            .compare_or_update_golden_file(&path, false)
+            .unwrap_err();
+        let Error::GoldenFileMismatch { diff, .. } = error else {
+            panic!("expected a mismatch but got {error}");
+        };
+        assert_eq!(
+            diff,
+            "  apiVersion: v1\n  data:\n-   changed: old\n+   changed: new\n    unchanged: value\n  \
+            kind: ConfigMap\n  metadata:\n...\n"
+        );
+
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[rstest]
+    #[case::equal("a\nb\n", "a\nb\n", "")]
+    #[case::added("a\n", "a\nb\n", "  a\n+ b\n")]
+    #[case::removed("a\nb\n", "b\n", "- a\n  b\n")]
+    #[case::replaced("a\nb\nc\n", "a\nx\nc\n", "  a\n- b\n+ x\n  c\n")]
+    #[case::context(
+        "1\n2\n3\n4\n5\n6\n7\n8\n9\n10\n",
+        "1\n2\n3\n4\n5\nchanged\n7\n8\n9\n10\n",
+        "...\n  3\n  4\n  5\n- 6\n+ changed\n  7\n  8\n  9\n...\n"
+    )]
+    fn line_diff(#[case] expected: &str, #[case] actual: &str, #[case] diff: &str) {
+        assert_eq!(diff_lines(expected, actual), diff);
+    }
+
+    #[rstest]
+    #[case::missing(
+        "apiVersion: v1\nkind: ConfigMap\nmetadata:\n  name: test\n",
+        "the input contains no Listener but exactly one is required"
+    )]
+    #[case::ambiguous(
+        "apiVersion: listeners.stackable.tech/v1alpha1\nkind: Listener\nmetadata:\n  name: a\n---\n\
+        apiVersion: listeners.stackable.tech/v1alpha1\nkind: Listener\nmetadata:\n  name: b\n",
+        "the input contains more than one Listener but exactly one is required"
+    )]
+    fn invalid_input(#[case] yaml: &str, #[case] message: &str) {
+        let error = ReconcileInput::<Listener>::from_yaml(yaml).err().unwrap();
+        assert_eq!(error.to_string(), message);
+    }
+}
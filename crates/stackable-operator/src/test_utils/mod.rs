@@ -1,3 +1,6 @@
+#[cfg(any(feature = "test-support", test))]
+pub mod golden;
+
 /// Please use only in tests, as we have non-ideal error handling in case serde_yaml produced
 /// non-utf8 output.
 pub fn serialize_to_yaml_with_singleton_map<S>(input: &S) -> Result<String, serde_yaml::Error>
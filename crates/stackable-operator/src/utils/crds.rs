@@ -25,6 +25,17 @@ pub fn raw_object_list_schema(_: &mut schemars::generate::SchemaGenerator) -> Sc
     })
 }
 
+pub fn raw_optional_object_list_schema(_: &mut schemars::generate::SchemaGenerator) -> Schema {
+    json_schema!({
+        "type": "array",
+        "nullable": true,
+        "items": {
+            "type": "object",
+            "x-kubernetes-preserve-unknown-fields": true,
+        }
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use k8s_openapi::api::core::v1::PodTemplateSpec;
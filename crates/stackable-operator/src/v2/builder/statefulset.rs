@@ -1,5 +1,7 @@
 use std::collections::BTreeMap;
 
+use k8s_openapi::jiff::Timestamp;
+
 use crate::{
     kvp::Annotations,
     v2::types::kubernetes::{ConfigMapName, SecretName},
@@ -57,6 +59,36 @@ pub fn restarter_ignore_secret_annotations(
     )
 }
 
+/// Creates `restarter.stackable.tech/rotated-secret.{i}` annotations with the value
+/// `<secret_name>@<rotated_at>` for each given rotated Secret.
+///
+/// Adding these annotations to the Pod template triggers a rolling restart of the StatefulSet
+/// whenever one of the Secrets is rotated, e.g. by
+/// [`create_or_rotate_random_secret`](crate::commons::random_secret_creation::create_or_rotate_random_secret).
+/// This is useful in combination with [`restarter_ignore_secret_annotations`]: The Pods are only
+/// restarted on a rotation, but not when the previous value is removed from the Secret after the
+/// grace period. Indices start at 0 and are assigned in iteration order, so **do not merge the
+/// result with annotations from another call** — duplicate indices would overwrite each other.
+pub fn restarter_secret_rotation_annotations(
+    rotated_secrets: impl IntoIterator<Item = (SecretName, Timestamp)>,
+) -> Annotations {
+    let annotation_key_values = rotated_secrets
+        .into_iter()
+        .enumerate()
+        .map(|(i, (secret_name, rotated_at))| {
+            (
+                format!("restarter.stackable.tech/rotated-secret.{i}"),
+                format!("{secret_name}@{rotated_at}"),
+            )
+        })
+        .collect::<BTreeMap<_, _>>();
+
+    Annotations::try_from(annotation_key_values).expect(
+        "should contain only valid annotations because the annotation keys are statically \
+            defined apart from the index number and annotation values are not restricted.",
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -116,4 +148,33 @@ mod tests {
 
         assert_eq!(expected_annotations, actual_annotations.into());
     }
+
+    #[test]
+    fn rotated_secrets_produce_indexed_annotations() {
+        let rotated_secrets = [
+            (
+                SecretName::from_str_unsafe("first-secret"),
+                "2026-10-01T12:00:00Z".parse().unwrap(),
+            ),
+            (
+                SecretName::from_str_unsafe("second-secret"),
+                "2026-10-02T08:30:00Z".parse().unwrap(),
+            ),
+        ];
+
+        let actual_annotations = restarter_secret_rotation_annotations(rotated_secrets);
+
+        let expected_annotations = BTreeMap::from([
+            (
+                "restarter.stackable.tech/rotated-secret.0".to_owned(),
+                "first-secret@2026-10-01T12:00:00Z".to_owned(),
+            ),
+            (
+                "restarter.stackable.tech/rotated-secret.1".to_owned(),
+                "second-secret@2026-10-02T08:30:00Z".to_owned(),
+            ),
+        ]);
+
+        assert_eq!(expected_annotations, actual_annotations.into());
+    }
 }
@@ -8,13 +8,19 @@ use crate::{
     commons::product_image_selection::ResolvedProductImage,
     constant,
     k8s_openapi::api::core::v1::{Container, VolumeMount},
+    kube::Resource,
     product_logging::{
-        framework::VECTOR_CONFIG_FILE,
+        framework::{
+            LogQuota, LoggingError, VECTOR_CONFIG_FILE, default_vector_config, log_quota,
+            log_sink_volume_mounts, set_vector_log_sink,
+        },
         spec::{
             AppenderConfig, AutomaticContainerLogConfig, ConfigMapLogConfig,
-            ContainerLogConfigChoice, CustomContainerLogConfig, LogLevel, Logging,
+            ContainerLogConfigChoice, CustomContainerLogConfig, LogLevel, LogSink, Logging,
         },
+        vector::VectorConfig,
     },
+    role_utils::RoleGroupRef,
     v2::{
         builder::pod::container::{EnvVarName, EnvVarSet, new_container_builder},
         role_group_utils,
@@ -64,6 +70,22 @@ pub enum Error {
     ParseContainerName {
         source: crate::v2::macros::attributed_string_type::Error,
     },
+
+    #[snafu(display(
+        "the discovery ConfigMap of the Vector aggregator is required to send the logs to the aggregator"
+    ))]
+    MissingVectorAggregatorConfigMap,
+
+    #[snafu(display("invalid log retention policy for the container {container:?}"))]
+    InvalidLogRetention {
+        source: LoggingError,
+        container: String,
+    },
+
+    #[snafu(display("failed to add the volume mounts to the Vector container"))]
+    AddVolumeMounts {
+        source: crate::builder::pod::container::Error,
+    },
 }
 
 type Result<T, E = Error> = std::result::Result<T, E>;
@@ -79,14 +101,84 @@ pub enum ValidatedContainerLogConfigChoice {
 
 /// Validated [`ContainerLogConfigChoice`] for the Vector container
 ///
-/// It includes the discovery ConfigMap name of the Vector aggregator.
+/// It includes the sink of the logs.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct VectorContainerLogConfig {
     pub log_config: ValidatedContainerLogConfigChoice,
-    pub vector_aggregator_config_map_name: ConfigMapName,
+    pub sink: ValidatedLogSink,
+}
+
+/// Validated [`LogSink`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ValidatedLogSink {
+    /// The logs are sent to the Vector aggregator announced in the given discovery ConfigMap.
+    VectorAggregator { config_map_name: ConfigMapName },
+
+    /// The logs are sent directly to the given log sink, which is not
+    /// [`LogSink::VectorAggregator`].
+    Direct(LogSink),
+}
+
+/// Validates the log sink
+///
+/// The discovery ConfigMap name of the Vector aggregator is only required if the logs are sent to
+/// the aggregator.
+pub fn validate_log_sink<T>(
+    logging: &Logging<T>,
+    vector_aggregator_config_map_name: Option<&ConfigMapName>,
+) -> Result<ValidatedLogSink>
+where
+    T: Clone + Display + Ord,
+{
+    match logging.sink().into_owned() {
+        LogSink::VectorAggregator {} => Ok(ValidatedLogSink::VectorAggregator {
+            config_map_name: vector_aggregator_config_map_name
+                .context(MissingVectorAggregatorConfigMapSnafu)?
+                .clone(),
+        }),
+        log_sink => Ok(ValidatedLogSink::Direct(log_sink)),
+    }
+}
+
+/// Creates the content of the Vector config file [`VECTOR_CONFIG_FILE`]
+///
+/// The default Vector configuration is used and the logs are sent to the validated log sink. If
+/// the configuration must be extended, use [`default_vector_config`] and [`set_vector_sink`]
+/// instead.
+pub fn create_vector_config<T>(
+    role_group: &RoleGroupRef<T>,
+    vector_container_log_config: &VectorContainerLogConfig,
+) -> String
+where
+    T: Resource,
+{
+    let log_config = match &vector_container_log_config.log_config {
+        ValidatedContainerLogConfigChoice::Automatic(log_config) => Some(log_config),
+        ValidatedContainerLogConfigChoice::Custom(_) => None,
+    };
+
+    let mut vector_config = default_vector_config(role_group, log_config);
+    set_vector_sink(&mut vector_config, &vector_container_log_config.sink);
+
+    vector_config.to_yaml()
+}
+
+/// Configures the Vector configuration to send the logs to the validated log sink
+///
+/// Existing sinks are replaced. The Vector container returned by [`vector_container`] provides the
+/// environment variables and volume mounts which are required by the sink.
+pub fn set_vector_sink(vector_config: &mut VectorConfig, log_sink: &ValidatedLogSink) {
+    match log_sink {
+        ValidatedLogSink::VectorAggregator { .. } => {
+            set_vector_log_sink(vector_config, &LogSink::VectorAggregator {});
+        }
+        ValidatedLogSink::Direct(log_sink) => set_vector_log_sink(vector_config, log_sink),
+    }
 }
 
 /// Validates the log configuration of the container
+///
+/// The retention policy of the container is validated as well, see [`log_quota_for_container`].
 pub fn validate_logging_configuration_for_container<T>(
     logging: &Logging<T>,
     container: &T,
@@ -115,10 +207,38 @@ where
         ),
     };
 
+    log_quota_for_container(logging, container)?;
+
     Ok(validated_container_log_config_choice)
 }
 
+/// Validates the retention policy of the container
+///
+/// Returns [`None`] if no retention policy is configured for the container. Otherwise, the log
+/// files of the container should be rotated and cleaned up according to the returned quota, see
+/// [`capture_rotated_shell_output`](crate::product_logging::framework::capture_rotated_shell_output)
+/// and [`log_cleaner_shell_command`](crate::product_logging::framework::log_cleaner_shell_command).
+pub fn log_quota_for_container<T>(logging: &Logging<T>, container: &T) -> Result<Option<LogQuota>>
+where
+    T: Clone + Display + Ord,
+{
+    logging
+        .retention_for_container(container)
+        .map(log_quota)
+        .transpose()
+        .context(InvalidLogRetentionSnafu {
+            container: container.to_string(),
+        })
+}
+
 /// Builds the Vector container
+///
+/// The Vector config file [`VECTOR_CONFIG_FILE`] is loaded from the config volume, see
+/// [`create_vector_config`]. If the logs are sent to a direct log sink, the volumes returned by
+/// [`log_sink_volumes`](crate::product_logging::framework::log_sink_volumes) are mounted. These
+/// volumes must be added to the Pod.
+///
+/// An error is returned if the volume mounts of the log sink collide with the other mounts.
 pub fn vector_container(
     container_name: &ContainerName,
     image: &ResolvedProductImage,
@@ -127,7 +247,7 @@ pub fn vector_container(
     log_config_volume_name: &VolumeName,
     log_volume_name: &VolumeName,
     extra_env_vars: EnvVarSet,
-) -> Container {
+) -> Result<Container> {
     let log_level = if let ValidatedContainerLogConfigChoice::Automatic(log_config) =
         &vector_container_log_config.log_config
     {
@@ -161,21 +281,36 @@ pub fn vector_container(
             &resource_names.role_group_name,
         )
         .with_value(&ENV_VAR_NAME_ROLE_NAME, &resource_names.role_name)
-        .with_config_map_key_ref(
-            &ENV_VAR_NAME_VECTOR_AGGREGATOR_ADDRESS,
-            &vector_container_log_config.vector_aggregator_config_map_name,
-            &VECTOR_AGGREGATOR_CM_KEY,
-        )
-        .with_value(
-            &ENV_VAR_NAME_VECTOR_CONFIG_YAML,
-            format!("{STACKABLE_CONFIG_DIR}/{VECTOR_CONFIG_FILE}"),
-        )
         .with_value(
             &ENV_VAR_NAME_VECTOR_FILE_LOG_LEVEL,
             vector_file_log_level.to_vector_literal(),
         )
         .with_value(&ENV_VAR_NAME_VECTOR_LOG, log_level.to_vector_literal())
-        .merge(extra_env_vars);
+        .with_value(
+            &ENV_VAR_NAME_VECTOR_CONFIG_YAML,
+            format!("{STACKABLE_CONFIG_DIR}/{VECTOR_CONFIG_FILE}"),
+        );
+
+    let mut volume_mounts = vec![
+        VolumeMount {
+            mount_path: format!("{STACKABLE_CONFIG_DIR}/{VECTOR_CONFIG_FILE}"),
+            name: log_config_volume_name.to_string(),
+            read_only: Some(true),
+            sub_path: Some(VECTOR_CONFIG_FILE.to_owned()),
+            ..VolumeMount::default()
+        },
+        VolumeMount {
+            mount_path: STACKABLE_LOG_DIR.to_owned(),
+            name: log_volume_name.to_string(),
+            ..VolumeMount::default()
+        },
+    ];
+
+    let (sink_env_vars, sink_volume_mounts) =
+        sink_env_vars_and_volume_mounts(&vector_container_log_config.sink);
+    volume_mounts.extend(sink_volume_mounts);
+
+    let env_vars = env_vars.merge(sink_env_vars).merge(extra_env_vars);
 
     let resources = ResourceRequirementsBuilder::new()
         .with_cpu_request("250m")
@@ -184,7 +319,7 @@ pub fn vector_container(
         .with_memory_limit("128Mi")
         .build();
 
-    new_container_builder(container_name)
+    Ok(new_container_builder(container_name)
             .image_from_product_image(image)
             .command(vec![
                 "/bin/bash".to_string(),
@@ -206,48 +341,63 @@ pub fn vector_container(
                 vector_control_directory = format!("{STACKABLE_LOG_DIR}/{VECTOR_CONTROL_DIR}"),
             )])
             .add_env_vars(env_vars)
-            .add_volume_mounts([
-                VolumeMount {
-                    mount_path: format!(
-                        "{STACKABLE_CONFIG_DIR}/{VECTOR_CONFIG_FILE}"
-                    ),
-                    name: log_config_volume_name.to_string(),
-                    read_only: Some(true),
-                    sub_path: Some(VECTOR_CONFIG_FILE.to_owned()),
-                    ..VolumeMount::default()
-                },
-                VolumeMount {
-                    mount_path: STACKABLE_LOG_DIR.to_owned(),
-                    name: log_volume_name.to_string(),
-                    ..VolumeMount::default()
-                },
-            ])
-            .expect("The mount paths are statically defined and there should be no duplicates.")
+            .add_volume_mounts(volume_mounts)
+            .context(AddVolumeMountsSnafu)?
             .resources(resources)
-            .build()
+            .build())
+}
+
+/// Returns the environment variables and volume mounts of the Vector container which depend on the
+/// log sink
+fn sink_env_vars_and_volume_mounts(sink: &ValidatedLogSink) -> (EnvVarSet, Vec<VolumeMount>) {
+    match sink {
+        ValidatedLogSink::VectorAggregator { config_map_name } => (
+            EnvVarSet::new().with_config_map_key_ref(
+                &ENV_VAR_NAME_VECTOR_AGGREGATOR_ADDRESS,
+                config_map_name,
+                &VECTOR_AGGREGATOR_CM_KEY,
+            ),
+            Vec::new(),
+        ),
+        ValidatedLogSink::Direct(log_sink) => (EnvVarSet::new(), log_sink_volume_mounts(log_sink)),
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::str::FromStr;
+    use std::{
+        collections::{BTreeMap, BTreeSet},
+        str::FromStr,
+    };
 
+    use rstest::rstest;
     use serde_json::json;
+    use stackable_shared::time::Duration;
 
     use super::{
         ENV_VAR_NAME_CLUSTER_NAME, ENV_VAR_NAME_DATA_DIR, ENV_VAR_NAME_LOG_DIR,
         ENV_VAR_NAME_NAMESPACE, ENV_VAR_NAME_ROLE_GROUP_NAME, ENV_VAR_NAME_ROLE_NAME,
         ENV_VAR_NAME_VECTOR_AGGREGATOR_ADDRESS, ENV_VAR_NAME_VECTOR_CONFIG_YAML,
         ENV_VAR_NAME_VECTOR_FILE_LOG_LEVEL, ENV_VAR_NAME_VECTOR_LOG, ErrorDiscriminants,
-        VECTOR_AGGREGATOR_CM_KEY, ValidatedContainerLogConfigChoice, VectorContainerLogConfig,
+        VECTOR_AGGREGATOR_CM_KEY, ValidatedContainerLogConfigChoice, ValidatedLogSink,
+        VectorContainerLogConfig, create_vector_config, log_quota_for_container, validate_log_sink,
         validate_logging_configuration_for_container, vector_container,
     };
     use crate::{
         commons::product_image_selection::ResolvedProductImage,
+        k8s_openapi::{api::core::v1::Pod, apimachinery::pkg::api::resource::Quantity},
+        kube::runtime::reflector::ObjectRef,
         kvp::LabelValue,
-        product_logging::spec::{
-            AutomaticContainerLogConfig, ConfigMapLogConfig, ContainerLogConfig,
-            ContainerLogConfigChoice, CustomContainerLogConfig, Logging,
+        memory::MemoryQuantity,
+        product_logging::{
+            framework::LogQuota,
+            spec::{
+                AutomaticContainerLogConfig, ConfigMapLogConfig, ContainerLogConfig,
+                ContainerLogConfigChoice, CustomContainerLogConfig, LogRetention, LogSink, Logging,
+            },
         },
+        role_utils::RoleGroupRef,
+        utils::yaml_from_str_singleton_map,
         v2::{
             builder::pod::container::{EnvVarName, EnvVarSet},
             role_group_utils,
@@ -278,6 +428,8 @@ mod tests {
     fn test_validate_logging_configuration_for_container_ok_automatic_log_config() {
         let logging = Logging {
             enable_vector_agent: false,
+            sink: None,
+            retention: BTreeMap::new(),
             containers: [(
                 "container",
                 ContainerLogConfig {
@@ -300,6 +452,8 @@ mod tests {
     fn test_validate_logging_configuration_for_container_ok_custom_log_config() {
         let logging = Logging {
             enable_vector_agent: false,
+            sink: None,
+            retention: BTreeMap::new(),
             containers: [(
                 "container",
                 ContainerLogConfig {
@@ -326,10 +480,14 @@ mod tests {
     fn test_validate_logging_configuration_for_container_err_get_container_log_configuration() {
         let logging_without_container = Logging {
             enable_vector_agent: false,
+            sink: None,
+            retention: BTreeMap::new(),
             containers: [].into(),
         };
         let logging_without_container_log_config_choice = Logging {
             enable_vector_agent: false,
+            sink: None,
+            retention: BTreeMap::new(),
             containers: [("container", ContainerLogConfig { choice: None })].into(),
         };
 
@@ -353,6 +511,8 @@ mod tests {
     fn test_validate_logging_configuration_for_container_err_parse_container_name() {
         let logging = Logging {
             enable_vector_agent: false,
+            sink: None,
+            retention: BTreeMap::new(),
             containers: [(
                 "container",
                 ContainerLogConfig {
@@ -373,6 +533,60 @@ mod tests {
         );
     }
 
+    fn logging_with_retention(retention: LogRetention) -> Logging<&'static str> {
+        Logging {
+            enable_vector_agent: false,
+            sink: None,
+            retention: [("container", retention)].into(),
+            containers: [(
+                "container",
+                ContainerLogConfig {
+                    choice: Some(ContainerLogConfigChoice::Automatic(
+                        AutomaticContainerLogConfig::default(),
+                    )),
+                },
+            )]
+            .into(),
+        }
+    }
+
+    #[test]
+    fn test_log_quota_for_container() {
+        let logging = logging_with_retention(LogRetention {
+            max_size: Quantity("100Mi".to_owned()),
+            max_file_size: None,
+            cleanup_interval: None,
+        });
+
+        assert_eq!(
+            Some(LogQuota {
+                max_size: MemoryQuantity::from_mebi(100.),
+                max_file_size: MemoryQuantity::from_mebi(20.),
+                cleanup_interval: Duration::from_secs(30),
+            }),
+            log_quota_for_container(&logging, &"container").expect("should be a valid quota")
+        );
+        assert_eq!(
+            None,
+            log_quota_for_container(&logging, &"other-container").expect("should be a valid quota")
+        );
+    }
+
+    #[test]
+    fn test_validate_logging_configuration_for_container_err_invalid_log_retention() {
+        let logging = logging_with_retention(LogRetention {
+            max_size: Quantity("100Ki".to_owned()),
+            max_file_size: None,
+            cleanup_interval: None,
+        });
+
+        assert_eq!(
+            Err(ErrorDiscriminants::InvalidLogRetention),
+            validate_logging_configuration_for_container(&logging, &"container")
+                .map_err(ErrorDiscriminants::from)
+        );
+    }
+
     #[test]
     #[expect(clippy::too_many_lines)]
     fn test_vector_container() {
@@ -389,7 +603,9 @@ mod tests {
             log_config: ValidatedContainerLogConfigChoice::Automatic(
                 AutomaticContainerLogConfig::default(),
             ),
-            vector_aggregator_config_map_name: ConfigMapName::from_str_unsafe("vector-aggregator"),
+            sink: ValidatedLogSink::VectorAggregator {
+                config_map_name: ConfigMapName::from_str_unsafe("vector-aggregator"),
+            },
         };
 
         let resource_names = role_group_utils::ResourceNames {
@@ -406,7 +622,8 @@ mod tests {
             &VolumeName::from_str_unsafe("config"),
             &VolumeName::from_str_unsafe("log"),
             EnvVarSet::new().with_value(&EnvVarName::from_str_unsafe("CUSTOM_ENV"), "test"),
-        );
+        )
+        .expect("should be a valid container");
 
         assert_eq!(
             json!(
@@ -515,4 +732,225 @@ mod tests {
             serde_json::to_value(vector_container).expect("should be serializable")
         );
     }
+
+    #[test]
+    fn test_validate_log_sink() {
+        let mut logging = Logging::<&str> {
+            enable_vector_agent: true,
+            sink: None,
+            retention: BTreeMap::new(),
+            containers: [].into(),
+        };
+        let config_map_name = ConfigMapName::from_str_unsafe("vector-aggregator");
+
+        assert_eq!(
+            ValidatedLogSink::VectorAggregator {
+                config_map_name: config_map_name.clone()
+            },
+            validate_log_sink(&logging, Some(&config_map_name))
+                .expect("should be a valid log sink")
+        );
+        assert_eq!(
+            Err(ErrorDiscriminants::MissingVectorAggregatorConfigMap),
+            validate_log_sink(&logging, None).map_err(ErrorDiscriminants::from)
+        );
+
+        let log_sink: LogSink = yaml_from_str_singleton_map("loki:\n  host: loki.monitoring.svc\n")
+            .expect("valid log sink");
+        logging.sink = Some(log_sink.clone());
+
+        assert_eq!(
+            ValidatedLogSink::Direct(log_sink),
+            validate_log_sink(&logging, None).expect("should be a valid log sink")
+        );
+    }
+
+    #[test]
+    fn test_vector_container_with_direct_log_sink() {
+        let image = ResolvedProductImage {
+            product_version: "1.0.0".to_owned(),
+            app_version_label_value: LabelValue::from_str("1.0.0-stackable0.0.0-dev")
+                .expect("should be a valid label value"),
+            image: "oci.stackable.tech/sdp/product:1.0.0-stackable0.0.0-dev".to_string(),
+            image_pull_policy: "Always".to_owned(),
+            pull_secrets: None,
+        };
+
+        // The same SecretClass provides the credentials and the CA certificate.
+        let log_sink: LogSink = yaml_from_str_singleton_map(indoc::indoc! {"
+            loki:
+              host: loki.monitoring.svc
+              credentials:
+                secretClass: loki
+              tls:
+                verification:
+                  server:
+                    caCert:
+                      secretClass: loki
+        "})
+        .expect("valid log sink");
+
+        let vector_container_log_config = VectorContainerLogConfig {
+            log_config: ValidatedContainerLogConfigChoice::Automatic(
+                AutomaticContainerLogConfig::default(),
+            ),
+            sink: ValidatedLogSink::Direct(log_sink),
+        };
+
+        let resource_names = role_group_utils::ResourceNames {
+            cluster_name: ClusterName::from_str_unsafe("test-cluster"),
+            role_name: RoleName::from_str_unsafe("role"),
+            role_group_name: RoleGroupName::from_str_unsafe("role-group"),
+        };
+
+        let vector_container = serde_json::to_value(
+            vector_container(
+                &ContainerName::from_str_unsafe("vector"),
+                &image,
+                &vector_container_log_config,
+                &resource_names,
+                &VolumeName::from_str_unsafe("config"),
+                &VolumeName::from_str_unsafe("log"),
+                EnvVarSet::new(),
+            )
+            .expect("should be a valid container"),
+        )
+        .expect("should be serializable");
+
+        let env = vector_container["env"]
+            .as_array()
+            .expect("env should be set");
+        assert!(
+            !env.iter()
+                .any(|env_var| env_var["name"] == "VECTOR_AGGREGATOR_ADDRESS")
+        );
+        assert!(env.contains(&json!({
+            "name": "VECTOR_CONFIG_YAML",
+            "value": "/stackable/config/vector.yaml",
+        })));
+
+        assert_eq!(
+            json!([
+                {
+                    "mountPath": "/stackable/config/vector.yaml",
+                    "name": "config",
+                    "readOnly": true,
+                    "subPath": "vector.yaml",
+                },
+                {
+                    "mountPath": "/stackable/log",
+                    "name": "log",
+                },
+                {
+                    "mountPath": "/stackable/secrets/log-sink-credentials/loki",
+                    "name": "loki-log-sink-credentials",
+                },
+                {
+                    "mountPath": "/stackable/secrets/loki",
+                    "name": "loki-ca-cert",
+                },
+            ]),
+            vector_container["volumeMounts"]
+        );
+    }
+
+    fn vector_container_log_config(sink: ValidatedLogSink) -> VectorContainerLogConfig {
+        VectorContainerLogConfig {
+            log_config: ValidatedContainerLogConfigChoice::Automatic(
+                AutomaticContainerLogConfig::default(),
+            ),
+            sink,
+        }
+    }
+
+    /// Returns the names of the environment variables which are referenced in the Vector config,
+    /// e.g. `$VECTOR_AGGREGATOR_ADDRESS` or `${VECTOR_AGGREGATOR_ADDRESS}`
+    fn referenced_env_vars(vector_config: &str) -> BTreeSet<String> {
+        vector_config
+            .split('$')
+            .skip(1)
+            .map(|reference| {
+                reference
+                    .trim_start_matches('{')
+                    .chars()
+                    .take_while(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || *c == '_')
+                    .collect::<String>()
+            })
+            .filter(|name| !name.is_empty())
+            .collect()
+    }
+
+    #[rstest]
+    #[case::vector_aggregator(
+        ValidatedLogSink::VectorAggregator {
+            config_map_name: ConfigMapName::from_str_unsafe("vector-aggregator"),
+        },
+        "aggregator"
+    )]
+    #[case::direct(
+        ValidatedLogSink::Direct(
+            yaml_from_str_singleton_map("loki:\n  host: loki.monitoring.svc\n")
+                .expect("valid log sink")
+        ),
+        "loki"
+    )]
+    fn test_create_vector_config(#[case] sink: ValidatedLogSink, #[case] expected_sink: &str) {
+        let vector_container_log_config = vector_container_log_config(sink);
+        let role_group = RoleGroupRef {
+            cluster: ObjectRef::<Pod>::new("test-cluster"),
+            role: "role".to_owned(),
+            role_group: "role-group".to_owned(),
+        };
+
+        let vector_config = create_vector_config(&role_group, &vector_container_log_config);
+
+        let parsed_vector_config: serde_yaml::Value =
+            serde_yaml::from_str(&vector_config).expect("should be valid YAML");
+        let sinks = parsed_vector_config["sinks"]
+            .as_mapping()
+            .expect("sinks should be defined");
+        assert_eq!(
+            vec![expected_sink],
+            sinks
+                .keys()
+                .map(|name| name.as_str().expect("should be a string"))
+                .collect::<Vec<_>>()
+        );
+
+        let image = ResolvedProductImage {
+            product_version: "1.0.0".to_owned(),
+            app_version_label_value: LabelValue::from_str("1.0.0-stackable0.0.0-dev")
+                .expect("should be a valid label value"),
+            image: "oci.stackable.tech/sdp/product:1.0.0-stackable0.0.0-dev".to_string(),
+            image_pull_policy: "Always".to_owned(),
+            pull_secrets: None,
+        };
+        let resource_names = role_group_utils::ResourceNames {
+            cluster_name: ClusterName::from_str_unsafe("test-cluster"),
+            role_name: RoleName::from_str_unsafe("role"),
+            role_group_name: RoleGroupName::from_str_unsafe("role-group"),
+        };
+        let vector_container = vector_container(
+            &ContainerName::from_str_unsafe("vector"),
+            &image,
+            &vector_container_log_config,
+            &resource_names,
+            &VolumeName::from_str_unsafe("config"),
+            &VolumeName::from_str_unsafe("log"),
+            EnvVarSet::new(),
+        )
+        .expect("should be a valid container");
+        let defined_env_vars = vector_container
+            .env
+            .unwrap_or_default()
+            .into_iter()
+            .map(|env_var| env_var.name)
+            .collect::<BTreeSet<_>>();
+
+        let referenced_env_vars = referenced_env_vars(&vector_config);
+        assert!(
+            referenced_env_vars.is_subset(&defined_env_vars),
+            "all referenced env vars {referenced_env_vars:?} must be defined in {defined_env_vars:?}"
+        );
+    }
 }
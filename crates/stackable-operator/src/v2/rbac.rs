@@ -4,7 +4,7 @@ use crate::{
         Resource as KubeApiResource,
         api::{
             core::v1::ServiceAccount,
-            rbac::v1::{ClusterRole, RoleBinding, RoleRef, Subject},
+            rbac::v1::{ClusterRole, PolicyRule, Role, RoleBinding, RoleRef, Subject},
         },
         apimachinery::pkg::apis::meta::v1::ObjectMeta,
     },
@@ -68,6 +68,52 @@ pub fn build_role_binding(
     }
 }
 
+/// Builds the namespaced [`Role`] with the given rules, named `<cluster_name>-role` after the
+/// given [`ResourceNames`]. The [`ServiceAccount`] from [`build_service_account`] is bound to it
+/// with [`build_role_binding_for_role`].
+///
+/// This is the infallible variant of the Role built by
+/// [`crate::commons::rbac::RbacPolicyBuilder`]. The rules are not validated.
+pub fn build_role(
+    owner: &(impl Resource<DynamicType = ()> + HasName + HasUid),
+    resource_names: &ResourceNames,
+    rules: Vec<PolicyRule>,
+    labels: Labels,
+) -> Role {
+    Role {
+        metadata: build_metadata(owner, resource_names.role_name().to_string(), labels),
+        rules: Some(rules),
+    }
+}
+
+/// Builds the [`RoleBinding`] of the [`ServiceAccount`] from [`build_service_account`] to the
+/// [`Role`] from [`build_role`], named `<cluster_name>-role-rolebinding`.
+pub fn build_role_binding_for_role(
+    owner: &(impl Resource<DynamicType = ()> + HasName + HasUid),
+    resource_names: &ResourceNames,
+    labels: Labels,
+) -> RoleBinding {
+    RoleBinding {
+        metadata: build_metadata(
+            owner,
+            resource_names.role_binding_for_role_name().to_string(),
+            labels,
+        ),
+        role_ref: RoleRef {
+            api_group: Some(Role::GROUP.to_owned()),
+            kind: Role::KIND.to_owned(),
+            name: resource_names.role_name().to_string(),
+        },
+        subjects: Some(vec![Subject {
+            kind: ServiceAccount::KIND.to_owned(),
+            name: resource_names.service_account_name().to_string(),
+            namespace: owner.namespace(),
+            // Left unset because the ServiceAccount kind is in the core API group.
+            api_group: None,
+        }]),
+    }
+}
+
 /// Common metadata of the RBAC resources: name, the owner's namespace, an owner reference on
 /// `owner` and the given labels.
 fn build_metadata(
@@ -90,8 +136,9 @@ mod tests {
     use crate::{
         k8s_openapi::{
             api::{
-                core::v1::ServiceAccount,
-                rbac::v1::{RoleBinding, RoleRef, Subject},
+                coordination::v1::Lease,
+                core::v1::{ConfigMap, ServiceAccount},
+                rbac::v1::{PolicyRule, RoleBinding, RoleRef, Subject},
             },
             apimachinery::pkg::apis::meta::v1::{ObjectMeta, OwnerReference},
         },
@@ -99,7 +146,9 @@ mod tests {
         kvp::Labels,
         v2::{
             HasName, HasUid,
-            rbac::{build_role_binding, build_service_account},
+            rbac::{
+                build_role, build_role_binding, build_role_binding_for_role, build_service_account,
+            },
             role_utils::ResourceNames,
             types::{
                 kubernetes::Uid,
@@ -264,4 +313,46 @@ mod tests {
             build_role_binding(&cluster, &resource_names(), labels())
         );
     }
+
+    #[test]
+    fn output_is_identical_to_v1_rbac_policy_builder() {
+        let cluster = Cluster::new();
+        let rules = vec![
+            PolicyRule {
+                api_groups: Some(vec![String::new()]),
+                resources: Some(vec!["configmaps".to_owned()]),
+                resource_names: Some(vec!["cluster-name-config".to_owned()]),
+                verbs: vec!["get".to_owned(), "watch".to_owned()],
+                ..PolicyRule::default()
+            },
+            PolicyRule {
+                api_groups: Some(vec!["coordination.k8s.io".to_owned()]),
+                resources: Some(vec!["leases".to_owned()]),
+                resource_names: Some(vec!["cluster-name-leader".to_owned()]),
+                verbs: vec!["get".to_owned(), "update".to_owned()],
+                ..PolicyRule::default()
+            },
+        ];
+
+        let v1_resources =
+            crate::commons::rbac::RbacPolicyBuilder::new(&cluster, "my-product", labels())
+                .without_product_cluster_role()
+                .grant::<ConfigMap>(&["get", "watch"], &["cluster-name-config"])
+                .grant::<Lease>(&["get", "update"], &["cluster-name-leader"])
+                .build()
+                .expect("should build the v1 RBAC resources");
+
+        assert_eq!(
+            v1_resources.role,
+            Some(build_role(&cluster, &resource_names(), rules, labels()))
+        );
+        assert_eq!(
+            v1_resources.role_bindings,
+            vec![build_role_binding_for_role(
+                &cluster,
+                &resource_names(),
+                labels()
+            )]
+        );
+    }
 }
@@ -9,11 +9,12 @@ use super::{
     builder::pod::container::EnvVarSet,
     jvm_argument_overrides::JvmArgumentOverrides,
     types::{
-        kubernetes::{ClusterRoleName, RoleBindingName, ServiceAccountName},
+        kubernetes::{ClusterRoleName, RoleBindingName, RoleName, ServiceAccountName},
         operator::{ClusterName, ProductName},
     },
 };
 use crate::{
+    commons::scheduling::SchedulingConfig,
     config::{
         fragment::{self, FromFragment},
         merge::{self, Merge, merge},
@@ -77,6 +78,12 @@ pub struct CommonConfiguration<Config, CommonConfig, ConfigOverrides> {
     #[schemars(schema_with = "raw_object_schema")]
     pub pod_overrides: PodTemplateSpec,
 
+    // No docs needed, as we flatten this struct.
+    //
+    // The role and roleGroup values are merged via [`Merge`] in [`with_validated_config`].
+    #[serde(flatten, default)]
+    pub scheduling: SchedulingConfig,
+
     // No docs needed, as we flatten this struct.
     //
     // This field is product-specific and can contain e.g. jvmArgumentOverrides.
@@ -226,6 +233,7 @@ pub struct RoleGroupConfig<Config, CommonConfig, ConfigOverrides> {
     pub env_overrides: EnvVarSet,
     pub cli_overrides: BTreeMap<String, String>,
     pub pod_overrides: PodTemplateSpec,
+    pub scheduling: SchedulingConfig,
     pub product_specific_common_config: CommonConfig,
 }
 
@@ -272,6 +280,10 @@ where
                 role.config.pod_overrides.clone(),
                 role_group.config.pod_overrides.clone(),
             ),
+            scheduling: merge(
+                role_group.config.scheduling.clone(),
+                &role.config.scheduling,
+            ),
             product_specific_common_config: merged_product_specific_common_config(
                 &role.config.product_specific_common_config,
                 role_group.config.product_specific_common_config.clone(),
@@ -360,6 +372,36 @@ impl ResourceNames {
             .expect("should be a valid RoleBinding name")
     }
 
+    /// The name of the Role with the rules of the stacklet
+    pub fn role_name(&self) -> RoleName {
+        const SUFFIX: &str = "-role";
+
+        // compile-time checks
+        const _: () = assert!(
+            ClusterName::MAX_LENGTH + SUFFIX.len() <= RoleName::MAX_LENGTH,
+            "The string `<cluster_name>-role` must not exceed the limit of Role names."
+        );
+        let _ = ClusterName::IS_RFC_1123_SUBDOMAIN_NAME;
+
+        RoleName::from_str(&format!("{}{SUFFIX}", self.cluster_name))
+            .expect("should be a valid Role name")
+    }
+
+    /// The name of the RoleBinding to the Role from [`Self::role_name`]
+    pub fn role_binding_for_role_name(&self) -> RoleBindingName {
+        const SUFFIX: &str = "-role-rolebinding";
+
+        // compile-time checks
+        const _: () = assert!(
+            ClusterName::MAX_LENGTH + SUFFIX.len() <= RoleBindingName::MAX_LENGTH,
+            "The string `<cluster_name>-role-rolebinding` must not exceed the limit of RoleBinding names."
+        );
+        let _ = ClusterName::IS_RFC_1123_SUBDOMAIN_NAME;
+
+        RoleBindingName::from_str(&format!("{}{SUFFIX}", self.cluster_name))
+            .expect("should be a valid RoleBinding name")
+    }
+
     pub fn cluster_role_name(&self) -> ClusterRoleName {
         const SUFFIX: &str = "-clusterrole";
 
@@ -394,7 +436,7 @@ mod tests {
             config_overrides::KeyValueConfigOverrides,
             role_utils::with_validated_config,
             types::{
-                kubernetes::{ClusterRoleName, RoleBindingName, ServiceAccountName},
+                kubernetes::{ClusterRoleName, RoleBindingName, RoleName, ServiceAccountName},
                 operator::{ClusterName, ProductName},
             },
         },
@@ -460,6 +502,10 @@ mod tests {
                 }),
                 ..PodTemplateSpec::default()
             },
+            scheduling: SchedulingConfig {
+                priority_class_name: override_value.map(str::to_owned),
+                ..SchedulingConfig::default()
+            },
             product_specific_common_config: CommonConfig {
                 property: override_value.map(str::to_owned),
             },
@@ -556,6 +602,14 @@ mod tests {
             RoleBindingName::from_str_unsafe("my-cluster-rolebinding"),
             resource_names.role_binding_name()
         );
+        assert_eq!(
+            RoleName::from_str_unsafe("my-cluster-role"),
+            resource_names.role_name()
+        );
+        assert_eq!(
+            RoleBindingName::from_str_unsafe("my-cluster-role-rolebinding"),
+            resource_names.role_binding_for_role_name()
+        );
         assert_eq!(
             ClusterRoleName::from_str_unsafe("my-product-clusterrole"),
             resource_names.cluster_role_name()
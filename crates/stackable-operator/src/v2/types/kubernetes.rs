@@ -96,6 +96,17 @@ attributed_string_type! {
     is_rfc_1123_dns_subdomain_name
 }
 
+attributed_string_type! {
+    RoleName,
+    "The name of a Role",
+    "opensearch-role",
+    // On the one hand, Roles must only contain characters that are allowed for DNS subdomain
+    // names, on the other hand, their length does not seem to be restricted – at least on Kind.
+    // However, 253 characters are sufficient for the Stackable operators, and to avoid problems on
+    // other Kubernetes providers, the length is restricted here.
+    is_rfc_1123_dns_subdomain_name
+}
+
 attributed_string_type! {
     RoleBindingName,
     "The name of a RoleBinding",
@@ -181,8 +192,8 @@ mod tests {
     use super::{
         ClusterRoleName, ConfigMapKey, ConfigMapName, ContainerName, DaemonSetName, DeploymentName,
         Hostname, ListenerClassName, ListenerName, NamespaceName, PersistentVolumeClaimName,
-        RoleBindingName, SecretClassName, SecretKey, SecretName, ServiceAccountName, ServiceName,
-        StatefulSetName, Uid, VolumeName,
+        RoleBindingName, RoleName, SecretClassName, SecretKey, SecretName, ServiceAccountName,
+        ServiceName, StatefulSetName, Uid, VolumeName,
     };
 
     #[test]
@@ -199,6 +210,7 @@ mod tests {
         NamespaceName::test_example();
         PersistentVolumeClaimName::test_example();
         RoleBindingName::test_example();
+        RoleName::test_example();
         SecretClassName::test_example();
         SecretKey::test_example();
         SecretName::test_example();
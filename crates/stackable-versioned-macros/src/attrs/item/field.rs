@@ -12,12 +12,12 @@ use crate::{
 /// Data stored in this struct is validated using darling's `and_then` attribute.
 /// During darlings validation, it is not possible to validate that action
 /// versions match up with declared versions on the container. This validation
-/// can be done using the associated [`FieldAttributes::validate_versions`][1]
+/// can be done using the associated [`FieldAttributes::resolve_versions`][1]
 /// function.
 ///
 /// Rules shared across fields and variants can be found [here][2].
 ///
-/// [1]: crate::attrs::item::FieldAttributes::validate_versions
+/// [1]: crate::attrs::item::FieldAttributes::resolve_versions
 /// [2]: crate::attrs::item::CommonItemAttributes
 #[derive(Debug, FromField)]
 #[darling(
@@ -71,8 +71,12 @@ impl FieldAttributes {
         Ok(self)
     }
 
-    pub fn validate_versions(&self, versions: &[VersionDefinition]) -> Result<()> {
-        self.common.validate_versions(versions)
+    pub fn resolve_versions(
+        &mut self,
+        versions: &[VersionDefinition],
+        idents: &FieldIdents,
+    ) -> Result<()> {
+        self.common.resolve_versions(versions, idents)
     }
 
     pub fn validate_nested_flag(&self, experimental_conversion_tracking: bool) -> Result<()> {
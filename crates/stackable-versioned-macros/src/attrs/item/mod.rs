@@ -1,7 +1,6 @@
 use std::{collections::BTreeMap, ops::Deref};
 
 use darling::{Error, FromMeta, Result, util::SpannedValue};
-use k8s_version::Version;
 use proc_macro2::Span;
 use quote::format_ident;
 use syn::{Attribute, Path, Type, spanned::Spanned};
@@ -9,6 +8,7 @@ use syn::{Attribute, Path, Type, spanned::Spanned};
 use crate::{
     codegen::{VersionDefinition, item::ItemStatus},
     utils::ItemIdents,
+    version::Version,
 };
 
 mod field;
@@ -54,7 +54,6 @@ impl CommonItemAttributes {
         let mut errors = Error::accumulator();
 
         errors.handle(self.validate_action_combinations(item_idents));
-        errors.handle(self.validate_action_order(item_idents));
         errors.handle(self.validate_item_name(item_idents));
         errors.handle(self.validate_added_action());
         errors.handle(self.validate_changed_action(item_idents));
@@ -63,33 +62,46 @@ impl CommonItemAttributes {
         errors.finish()
     }
 
-    pub fn validate_versions(&self, versions: &[VersionDefinition]) -> Result<()> {
+    /// Validates that all actions use versions declared on the module and resolves them to the
+    /// declared versions, which carry the ordering defined by the version format of the module.
+    ///
+    /// Because the order of versions is only known after the resolution, this also validates the
+    /// order of actions.
+    pub fn resolve_versions(
+        &mut self,
+        versions: &[VersionDefinition],
+        item_idents: &impl ItemIdents,
+    ) -> Result<()> {
         let mut errors = Error::accumulator();
 
-        if let Some(added) = &self.added
-            && !versions.iter().any(|v| v.inner == *added.since)
+        if let Some(added) = &mut self.added
+            && !resolve_version(&mut added.since, versions)
         {
             errors.push(Error::custom(
                     "the `added` action uses a version which is not declared via `#[versioned(version)]`",
                 ).with_span(&added.since.span()));
         }
 
-        for change in &self.changes {
-            if !versions.iter().any(|v| v.inner == *change.since) {
+        for change in &mut self.changes {
+            if !resolve_version(&mut change.since, versions) {
                 errors.push(Error::custom(
                     "the `changed` action uses a version which is not declared via `#[versioned(version)]`"
                 ).with_span(&change.since.span()));
             }
         }
 
-        if let Some(deprecated) = &self.deprecated
-            && !versions.iter().any(|v| v.inner == *deprecated.since)
+        if let Some(deprecated) = &mut self.deprecated
+            && !resolve_version(&mut deprecated.since, versions)
         {
             errors.push(Error::custom(
                     "the `deprecated` action uses a version which is not declared via `#[versioned(version)]`",
                 ).with_span(&deprecated.since.span()));
         }
 
+        // The order can only be validated if all versions could be resolved.
+        errors = errors.checkpoint()?;
+        errors.handle(self.validate_action_order(item_idents));
+
         errors.finish()
     }
 
@@ -141,8 +153,8 @@ impl CommonItemAttributes {
     /// - All `changed` actions must use a greater version than `added` but a
     ///   lesser version than `deprecated`.
     fn validate_action_order(&self, item_idents: &impl ItemIdents) -> Result<()> {
-        let added_version = self.added.as_ref().map(|a| *a.since);
-        let deprecated_version = self.deprecated.as_ref().map(|d| *d.since);
+        let added_version = self.added.as_ref().map(|a| &*a.since);
+        let deprecated_version = self.deprecated.as_ref().map(|d| &*d.since);
 
         // First, validate that the added version is less than the deprecated
         // version.
@@ -158,8 +170,8 @@ impl CommonItemAttributes {
         // Now, iterate over all changes and ensure that their versions are
         // between the added and deprecated version.
         if !self.changes.iter().all(|r| {
-            added_version.is_none_or(|a| a < *r.since)
-                && deprecated_version.is_none_or(|d| d > *r.since)
+            added_version.is_none_or(|a| *a < *r.since)
+                && deprecated_version.is_none_or(|d| *d > *r.since)
         }) {
             return Err(Error::custom(
                 "all changes must use versions higher than `added` and lower than `deprecated`",
@@ -307,7 +319,7 @@ impl CommonItemAttributes {
             let mut actions = BTreeMap::new();
 
             actions.insert(
-                *deprecated.since,
+                deprecated.since.into_inner(),
                 ItemStatus::Deprecation {
                     previous_ident: ident.clone(),
                     ident: deprecated_ident.clone(),
@@ -330,7 +342,7 @@ impl CommonItemAttributes {
                     .map_or_else(|| ty.clone(), |sv| sv.deref().clone());
 
                 actions.insert(
-                    *change.since,
+                    (*change.since).clone(),
                     ItemStatus::Change {
                         downgrade_with: change.downgrade_with.as_deref().cloned(),
                         upgrade_with: change.upgrade_with.as_deref().cloned(),
@@ -349,7 +361,7 @@ impl CommonItemAttributes {
             // added action if there is any.
             if let Some(added) = self.added {
                 actions.insert(
-                    *added.since,
+                    added.since.into_inner(),
                     ItemStatus::Addition {
                         default_fn: added.default_fn.deref().clone(),
                         ident,
@@ -380,7 +392,7 @@ impl CommonItemAttributes {
                     .map_or_else(|| ty.clone(), |sv| sv.deref().clone());
 
                 actions.insert(
-                    *change.since,
+                    (*change.since).clone(),
                     ItemStatus::Change {
                         downgrade_with: change.downgrade_with.as_deref().cloned(),
                         upgrade_with: change.upgrade_with.as_deref().cloned(),
@@ -399,7 +411,7 @@ impl CommonItemAttributes {
             // added action if there is any.
             if let Some(added) = self.added {
                 actions.insert(
-                    *added.since,
+                    added.since.into_inner(),
                     ItemStatus::Addition {
                         default_fn: added.default_fn.deref().clone(),
                         ident,
@@ -414,7 +426,7 @@ impl CommonItemAttributes {
                 let mut actions = BTreeMap::new();
 
                 actions.insert(
-                    *added.since,
+                    added.since.into_inner(),
                     ItemStatus::Addition {
                         default_fn: added.default_fn.deref().clone(),
                         ident: idents.original().clone(),
@@ -430,6 +442,22 @@ impl CommonItemAttributes {
     }
 }
 
+/// Resolves `since` to the declared version with the same name. Returns `false` if no such version
+/// is declared.
+fn resolve_version(since: &mut SpannedValue<Version>, versions: &[VersionDefinition]) -> bool {
+    // Versions are compared by name, because versions parsed from actions are not yet resolved
+    // according to the version format of the module.
+    let name = since.to_string();
+
+    match versions.iter().find(|v| v.inner.to_string() == name) {
+        Some(version) => {
+            *since = SpannedValue::new(version.inner.clone(), since.span());
+            true
+        }
+        None => false,
+    }
+}
+
 /// For the added() action
 ///
 /// Example usage:
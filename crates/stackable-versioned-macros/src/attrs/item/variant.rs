@@ -14,12 +14,12 @@ use crate::{
 /// Data stored in this struct is validated using darling's `and_then` attribute.
 /// During darlings validation, it is not possible to validate that action
 /// versions match up with declared versions on the container. This validation
-/// can be done using the associated [`VariantAttributes::validate_versions`][1]
+/// can be done using the associated [`VariantAttributes::resolve_versions`][1]
 /// function.
 ///
 /// Rules shared across fields and variants can be found [here][2].
 ///
-/// [1]: crate::attrs::item::VariantAttributes::validate_versions
+/// [1]: crate::attrs::item::VariantAttributes::resolve_versions
 /// [2]: crate::attrs::item::CommonItemAttributes
 #[derive(Debug, FromVariant)]
 #[darling(
@@ -71,7 +71,8 @@ impl VariantAttributes {
         errors.finish_with(self)
     }
 
-    pub fn validate_versions(&self, versions: &[VersionDefinition]) -> Result<()> {
-        self.common.validate_versions(versions)
+    pub fn resolve_versions(&mut self, versions: &[VersionDefinition]) -> Result<()> {
+        let variant_idents = VariantIdents::from(self.ident.clone());
+        self.common.resolve_versions(versions, &variant_idents)
     }
 }
@@ -5,11 +5,15 @@ use darling::{
     util::{Flag, Override as FlagOrOverride, SpannedValue},
 };
 use itertools::Itertools as _;
-use k8s_version::Version;
 use proc_macro2::TokenStream;
 use quote::{ToTokens, quote};
 use syn::{Path, parse_quote};
 
+use crate::{
+    utils::VersionExt as _,
+    version::{Version, VersionFormat},
+};
+
 #[derive(Debug, FromMeta)]
 #[darling(and_then = ModuleAttributes::validate)]
 pub struct ModuleAttributes {
@@ -37,25 +41,82 @@ impl ModuleAttributes {
             );
         }
 
-        let is_sorted = self.versions.iter().is_sorted_by_key(|v| v.name);
+        let version_format = &self.options.common.version_format;
+
+        for (position, version) in self.versions.iter_mut().enumerate() {
+            match version_format.resolve(&version.name, position) {
+                Ok(resolved) => version.name = SpannedValue::new(resolved, version.name.span()),
+                Err(err) => errors.push(err.with_span(&version.name.span())),
+            }
+        }
+
+        // Sorting and detecting duplicates requires all versions to be resolved.
+        errors = errors.checkpoint()?;
+
+        if !version_format.is_kubernetes() {
+            let KubernetesConfigOptions {
+                experimental_conversion_tracking,
+                enable_tracing,
+            } = &self.options.kubernetes;
+
+            if experimental_conversion_tracking.is_present() || enable_tracing.is_present() {
+                errors.push(Error::custom(
+                    "the `k8s` options can only be used with the Kubernetes version format",
+                ));
+            }
+        }
+
+        if let VersionFormat::Custom(_) = version_format {
+            let allow_unsorted = &self.options.common.allow_unsorted;
+            if allow_unsorted.is_present() {
+                errors.push(
+                    Error::custom(
+                        "the `allow_unsorted` option cannot be used with custom versions, because \
+                        they are ordered by their position",
+                    )
+                    .with_span(&allow_unsorted.span()),
+                );
+            }
+
+            // Custom version names are arbitrary strings, so the derived module name can be a
+            // keyword, which is not a valid identifier.
+            for version in self.versions.iter() {
+                let module = version.name.as_module_ident();
+                if syn::parse_str::<syn::Ident>(module.as_str()).is_err() {
+                    errors.push(
+                        Error::custom(format!(
+                            "version {version} results in the module name `{module}`, which is a \
+                            Rust keyword",
+                            version = *version.name,
+                            module = module.as_str()
+                        ))
+                        .with_span(&version.name.span()),
+                    );
+                }
+            }
+        }
+
+        let is_sorted = self.versions.iter().is_sorted_by_key(|v| &*v.name);
 
         // It needs to be sorted, even though the definition could be unsorted
         // (if allow_unsorted is set).
-        self.versions.sort_by_key(|lhs| lhs.name);
+        self.versions.sort_by(|lhs, rhs| lhs.name.cmp(&rhs.name));
 
         if !self.options.common.allow_unsorted.is_present() && !is_sorted {
-            let versions = self.versions.iter().map(|v| v.name).join(", ");
+            let versions = self.versions.iter().map(|v| &*v.name).join(", ");
 
             errors.push(Error::custom(format!(
                 "versions must be defined in ascending order: {versions}",
             )));
         }
 
+        // Custom versions are ordered by their position, which is why duplicates need to be
+        // detected by name.
         let duplicate_versions: Vec<_> = self
             .versions
             .iter()
-            .duplicates_by(|v| v.name)
-            .map(|v| v.name)
+            .duplicates_by(|v| v.name.to_string())
+            .map(|v| &*v.name)
             .collect();
 
         if !duplicate_versions.is_empty() {
@@ -64,6 +125,20 @@ impl ModuleAttributes {
             errors.push(Error::custom(format!(
                 "contains duplicate versions: {versions}",
             )));
+        } else if let Some(version) = self
+            .versions
+            .iter()
+            .duplicates_by(|v| v.name.as_module_ident())
+            .next()
+        {
+            errors.push(
+                Error::custom(format!(
+                    "version {version} results in the same module name `{module}` as another version",
+                    version = *version.name,
+                    module = version.name.as_module_ident().as_str()
+                ))
+                .with_span(&version.name.span()),
+            );
         }
 
         errors.finish_with(self)
@@ -83,6 +158,9 @@ pub struct ModuleOptions {
 pub struct ModuleCommonOptions {
     pub allow_unsorted: Flag,
     pub preserve_module: Flag,
+
+    #[darling(default)]
+    pub version_format: VersionFormat,
 }
 
 #[derive(Debug, Default, FromMeta)]
@@ -247,7 +325,8 @@ pub struct KubernetesConfigOptions {
 ///
 /// Supported arguments are:
 ///
-/// - `name` of the version, like `v1alpha1`.
+/// - `name` of the version, like `v1alpha1`. The format of the name depends on the
+///   `version_format` option of the module.
 /// - `deprecated` flag to mark that version as deprecated.
 /// - `skip` option to skip generating various pieces of code.
 /// - `doc` option to add version-specific documentation.
@@ -256,7 +335,7 @@ pub struct VersionArguments {
     pub deprecated: Option<FlagOrOverride<String>>,
     pub skip: Option<VersionSkipArguments>,
     pub doc: Option<String>,
-    pub name: Version,
+    pub name: SpannedValue<Version>,
 }
 
 #[derive(Clone, Debug, FromMeta)]
@@ -1,9 +1,11 @@
 use std::{collections::BTreeMap, ops::Bound};
 
-use k8s_version::Version;
 use syn::Type;
 
-use crate::codegen::{VersionDefinition, item::ItemStatus};
+use crate::{
+    codegen::{VersionDefinition, item::ItemStatus},
+    version::Version,
+};
 
 pub trait Neighbors<K, V>
 where
@@ -109,14 +111,14 @@ impl ChangesetExt for BTreeMap<Version, ItemStatus> {
             match self.get_neighbors(&version.inner) {
                 (None, Some(status)) => match status {
                     ItemStatus::Addition { .. } => {
-                        self.insert(version.inner, ItemStatus::NotPresent)
+                        self.insert(version.inner.clone(), ItemStatus::NotPresent)
                     }
                     ItemStatus::Change {
                         from_ident,
                         from_type,
                         ..
                     } => self.insert(
-                        version.inner,
+                        version.inner.clone(),
                         ItemStatus::NoChange {
                             previously_deprecated: false,
                             ident: from_ident.clone(),
@@ -124,7 +126,7 @@ impl ChangesetExt for BTreeMap<Version, ItemStatus> {
                         },
                     ),
                     ItemStatus::Deprecation { previous_ident, .. } => self.insert(
-                        version.inner,
+                        version.inner.clone(),
                         ItemStatus::NoChange {
                             previously_deprecated: false,
                             ident: previous_ident.clone(),
@@ -136,7 +138,7 @@ impl ChangesetExt for BTreeMap<Version, ItemStatus> {
                         ident,
                         ty,
                     } => self.insert(
-                        version.inner,
+                        version.inner.clone(),
                         ItemStatus::NoChange {
                             previously_deprecated: *previously_deprecated,
                             ident: ident.clone(),
@@ -164,7 +166,7 @@ impl ChangesetExt for BTreeMap<Version, ItemStatus> {
                     };
 
                     self.insert(
-                        version.inner,
+                        version.inner.clone(),
                         ItemStatus::NoChange {
                             previously_deprecated,
                             ident: ident.clone(),
@@ -185,7 +187,7 @@ impl ChangesetExt for BTreeMap<Version, ItemStatus> {
                             ..
                         } => (ident, ty, *previously_deprecated),
                         ItemStatus::NotPresent => {
-                            self.insert(version.inner, ItemStatus::NotPresent);
+                            self.insert(version.inner.clone(), ItemStatus::NotPresent);
                             continue;
                         }
                         // TODO (@NickLarsenNZ): Explain why it is unreachable, as it can be reached during testing.
@@ -194,7 +196,7 @@ impl ChangesetExt for BTreeMap<Version, ItemStatus> {
                     };
 
                     self.insert(
-                        version.inner,
+                        version.inner.clone(),
                         ItemStatus::NoChange {
                             previously_deprecated,
                             ident: ident.clone(),
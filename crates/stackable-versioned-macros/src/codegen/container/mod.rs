@@ -1,7 +1,6 @@
 use std::collections::HashMap;
 
 use darling::util::IdentString;
-use k8s_version::Version;
 use proc_macro2::{Span, TokenStream, TokenTree};
 use quote::format_ident;
 use syn::{Attribute, Ident};
@@ -14,6 +13,7 @@ use crate::{
         module::ModuleGenerationContext,
     },
     utils::ContainerIdentExt,
+    version::Version,
 };
 
 mod r#enum;
@@ -15,6 +15,7 @@ use crate::{
         },
         item::{ItemStatus, VersionedField},
     },
+    version::VersionFormat,
 };
 
 mod conversion;
@@ -24,6 +25,7 @@ impl Container {
     pub fn new_struct(
         item_struct: ItemStruct,
         versions: &[VersionDefinition],
+        version_format: &VersionFormat,
         experimental_conversion_tracking: bool,
     ) -> Result<Self> {
         let attributes = ContainerAttributes::from_attributes(&item_struct.attrs)?;
@@ -54,6 +56,14 @@ impl Container {
             ).with_span(&idents.original.span()));
         }
 
+        // CRDs require Kubernetes API versions, which is why CRD features are not available for
+        // other version formats.
+        if kubernetes_data.is_some() && !version_format.is_kubernetes() {
+            return Err(Error::custom(
+                "CRD features via `#[versioned(crd())]` can only be used with the Kubernetes version format"
+            ).with_span(&idents.original.span()));
+        }
+
         let options = ContainerOptions {
             skip_from: attributes.skip.from.is_present(),
             skip_object_from: attributes.skip.object_from.is_present(),
@@ -1,7 +1,6 @@
 use std::collections::BTreeMap;
 
 use darling::{FromField, Result, util::IdentString};
-use k8s_version::Version;
 use proc_macro2::TokenStream;
 use quote::quote;
 use syn::{Attribute, Field, Ident, Path, Type, spanned::Spanned};
@@ -15,6 +14,7 @@ use crate::{
         module::ModuleGenerationContext,
     },
     utils::{ItemIdentExt, ItemIdents},
+    version::Version,
 };
 
 #[derive(Debug)]
@@ -33,8 +33,7 @@ impl VersionedField {
         versions: &[VersionDefinition],
         experimental_conversion_tracking: bool,
     ) -> Result<Self> {
-        let field_attributes = FieldAttributes::from_field(&field)?;
-        field_attributes.validate_versions(versions)?;
+        let mut field_attributes = FieldAttributes::from_field(&field)?;
         field_attributes.validate_nested_flag(experimental_conversion_tracking)?;
 
         let field_span = field.span();
@@ -45,6 +44,8 @@ impl VersionedField {
         })?;
         let idents = FieldIdents::from(ident);
 
+        field_attributes.resolve_versions(versions, &idents)?;
+
         let changes = field_attributes
             .common
             .into_changeset(&idents, field.ty.clone());
@@ -1,7 +1,6 @@
 use std::collections::BTreeMap;
 
 use darling::{FromVariant, Result, util::IdentString};
-use k8s_version::Version;
 use proc_macro2::{Span, TokenStream};
 use quote::{format_ident, quote};
 use syn::{
@@ -16,6 +15,7 @@ use crate::{
         item::ItemStatus,
     },
     utils::ItemIdents,
+    version::Version,
 };
 
 pub struct VersionedVariant {
@@ -27,8 +27,8 @@ pub struct VersionedVariant {
 
 impl VersionedVariant {
     pub fn new(variant: Variant, versions: &[VersionDefinition]) -> Result<Self> {
-        let variant_attributes = VariantAttributes::from_variant(&variant)?;
-        variant_attributes.validate_versions(versions)?;
+        let mut variant_attributes = VariantAttributes::from_variant(&variant)?;
+        variant_attributes.resolve_versions(versions)?;
 
         let idents = VariantIdents::from(variant.ident);
 
@@ -1,9 +1,9 @@
 use darling::util::IdentString;
-use k8s_version::Version;
 
 use crate::{
     attrs::module::ModuleAttributes,
     utils::{VersionExt, doc_comments::DocComments},
+    version::Version,
 };
 
 pub mod changes;
@@ -19,7 +19,7 @@ pub struct VersionDefinition {
     /// Indicates that the generation of `From<OLD> for NEW` should be skipped.
     pub skip_from: bool,
 
-    /// A validated Kubernetes API version.
+    /// A validated version, using the version format of the module.
     pub inner: Version,
 
     /// The ident of the container.
@@ -53,12 +53,13 @@ impl From<&ModuleAttributes> for Vec<VersionDefinition> {
                     variant: v.name.as_variant_ident(),
                 },
                 deprecated: v.deprecated.as_ref().map(|r#override| {
-                    r#override
-                        .clone()
-                        .unwrap_or(format!("Version {version} is deprecated", version = v.name))
+                    r#override.clone().unwrap_or(format!(
+                        "Version {version} is deprecated",
+                        version = *v.name
+                    ))
                 }),
                 docs: v.doc.as_deref().into_doc_comments(),
-                inner: v.name,
+                inner: (*v.name).clone(),
             })
             .collect()
     }
@@ -2,7 +2,7 @@ use std::{collections::HashMap, ops::Not};
 
 use darling::{Error, Result, util::IdentString};
 use proc_macro2::TokenStream;
-use quote::quote;
+use quote::{format_ident, quote};
 use syn::{Item, ItemMod, ItemUse, Visibility, token::Pub};
 
 use crate::{
@@ -14,6 +14,7 @@ use crate::{
         VersionDefinition,
         container::{Container, ContainerTokens, VersionedContainerTokens},
     },
+    version::VersionFormat,
 };
 
 /// A versioned module.
@@ -69,6 +70,7 @@ impl Module {
                     if let Some(container) = errors.handle(Container::new_struct(
                         item_struct,
                         &versions,
+                        &module_attributes.options.common.version_format,
                         experimental_conversion_tracking,
                     )) {
                         containers.push(container);
@@ -226,6 +228,8 @@ impl Module {
             });
         }
 
+        outer_tokens.extend(self.generate_custom_version_order_test());
+
         if preserve_module {
             quote! {
                 #[automatically_derived]
@@ -242,6 +246,32 @@ impl Module {
         }
     }
 
+    /// Generates a test which ensures that custom versions are declared in ascending order.
+    ///
+    /// The macro orders custom versions by their position in the list of declared versions, as it
+    /// cannot evaluate the [`Ord`] implementation of the user-supplied version type. The generated
+    /// test verifies that both orders match.
+    fn generate_custom_version_order_test(&self) -> Option<TokenStream> {
+        let VersionFormat::Custom(version_type) = &self.options.common.version_format else {
+            return None;
+        };
+
+        let versioned_path = &*self.crates.versioned;
+        let test_ident = format_ident!(
+            "{module}_custom_version_order",
+            module = self.ident.as_str()
+        );
+        let versions = self.versions.iter().map(|v| v.inner.to_string());
+
+        Some(quote! {
+            #[cfg(test)]
+            #[test]
+            fn #test_ident() -> ::std::result::Result<(), #versioned_path::CustomVersionOrderError> {
+                #versioned_path::check_custom_version_order::<#version_type>(&[#(#versions),*])
+            }
+        })
+    }
+
     /// Optionally generates imports (which can be re-exports) located in submodules for the
     /// specified `version`.
     fn generate_submodule_imports(&self, version: &VersionDefinition) -> Option<TokenStream> {
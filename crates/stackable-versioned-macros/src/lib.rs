@@ -12,6 +12,7 @@ mod test_utils;
 mod attrs;
 mod codegen;
 mod utils;
+mod version;
 
 /// This macro enables generating versioned structs and enums.
 ///
@@ -40,13 +41,10 @@ mod utils;
 /// parameters: `name` and the `deprecated` flag. The `name` must be a valid
 /// (and supported) format.
 ///
-/// <div class="warning">
-///
-/// Currently, only [Kubernetes API versions][k8s-version-format] are supported.
-/// The macro checks each declared version and reports any error encountered
-/// during parsing.
-///
-/// </div>
+/// By default, versions use the [Kubernetes API version format][k8s-version-format].
+/// Other formats can be selected using the `version_format` option, see
+/// [Version Formats](#version-formats). The macro checks each declared version
+/// and reports any error encountered during parsing.
 ///
 /// It should be noted that the defined struct always represents the **latest**
 /// version, eg: when defining three versions `v1alpha1`, `v1beta1`, and `v1`,
@@ -132,7 +130,8 @@ mod utils;
 /// Additionally, it is ensured that each version is unique. Declaring the same
 /// version multiple times will result in an error. Furthermore, declaring the
 /// versions out-of-order is prohibited by default. It is possible to opt-out
-/// of this check by setting `options(allow_unsorted)`.
+/// of this check by setting `options(allow_unsorted)`, except for custom
+/// versions, which are ordered by their position.
 ///
 /// <div class="warning">
 ///
@@ -155,6 +154,74 @@ mod utils;
 /// }
 /// ```
 ///
+/// ## Version Formats
+///
+/// The following version formats are supported:
+///
+/// - `kubernetes` (default): Kubernetes API versions, like `v1alpha1` or `v2`.
+///   This is the only format which supports generating CRDs via
+///   `#[versioned(crd())]`.
+/// - `semver`: [SemVer][semver] versions, like `1.2.0` or `2.0.0-rc.1`.
+/// - `custom`: Versions of a user-supplied type, which needs to implement
+///   [`FromStr`](std::str::FromStr) and [`Ord`].
+///
+/// All formats support the same actions and generate the same `From`
+/// conversions. Version modules are named after the version, with characters
+/// which are not valid in identifiers replaced by underscores, eg. `1.2.0`
+/// results in the module `v1_2_0`. Custom versions which result in a Rust
+/// keyword as module name, like `type`, are rejected.
+///
+/// ```
+/// # use stackable_versioned_macros::versioned;
+/// #[versioned(
+///     version(name = "1.0.0"),
+///     version(name = "1.1.0"),
+///     options(version_format = "semver")
+/// )]
+/// mod versioned {
+///     struct Foo {
+///         #[versioned(added(since = "1.1.0"))]
+///         bar: usize,
+///     }
+/// }
+///
+/// let foo = v1_1_0::Foo::from(v1_0_0::Foo {});
+/// # assert_eq!(foo.bar, 0);
+/// ```
+///
+/// The macro cannot evaluate the [`Ord`] implementation of a custom version
+/// type. Instead, custom versions are ordered by the order in which they are
+/// declared, which is why they must be declared in ascending order. To catch
+/// mismatches, the macro generates a test which parses each declared version
+/// and ensures that they are ascending according to the [`Ord`]
+/// implementation.
+///
+/// ```
+/// # use stackable_versioned_macros::versioned;
+/// #[derive(PartialEq, Eq, PartialOrd, Ord)]
+/// pub struct ReleaseVersion(String);
+///
+/// impl std::str::FromStr for ReleaseVersion {
+///     type Err = std::convert::Infallible;
+///
+///     fn from_str(input: &str) -> Result<Self, Self::Err> {
+///         Ok(Self(input.to_owned()))
+///     }
+/// }
+///
+/// #[versioned(
+///     version(name = "2024-04"),
+///     version(name = "2025-01"),
+///     options(version_format(custom = "ReleaseVersion"))
+/// )]
+/// mod versioned {
+///     struct Foo {
+///         bar: usize,
+///     }
+/// }
+/// # fn main() {}
+/// ```
+///
 /// # Versioning Module
 ///
 /// The purpose of the macro is to version Kubernetes CustomResourceDefinitions
@@ -932,6 +999,7 @@ mod utils;
 /// [1]: https://docs.rs/schemars/latest/schemars/derive.JsonSchema.html
 /// [2]: https://docs.rs/kube/latest/kube/core/crd/fn.merge_crds.html
 /// [k8s-version-format]: https://kubernetes.io/docs/reference/using-api/#api-versioning
+/// [semver]: https://semver.org
 /// [k8s-crd-ver-deprecation]: https://kubernetes.io/docs/tasks/extend-kubernetes/custom-resources/custom-resource-definition-versioning/#version-deprecation
 #[proc_macro_attribute]
 pub fn versioned(attrs: TokenStream, input: TokenStream) -> TokenStream {
@@ -1,10 +1,11 @@
 use convert_case::{Case, Casing};
 use darling::util::IdentString;
-use k8s_version::Version;
 use proc_macro2::Span;
 use quote::format_ident;
 use syn::{Ident, Path};
 
+use crate::version::Version;
+
 pub mod doc_comments;
 
 pub trait VersionExt {
@@ -14,14 +15,50 @@ pub trait VersionExt {
 
 impl VersionExt for Version {
     fn as_variant_ident(&self) -> IdentString {
-        IdentString::new(Ident::new(
-            &self.to_string().to_case(Case::Pascal),
-            Span::call_site(),
-        ))
+        let variant = match self {
+            Self::Kubernetes(version) => version.to_string().to_case(Case::Pascal),
+            // Converting to Pascal case would remove the separators between the components of the
+            // version, which can result in the same ident for different versions (eg. `1.23.0` and
+            // `12.3.0`).
+            Self::SemVer(_) | Self::Custom(_) => {
+                let module = self.as_module_ident().to_string();
+                let mut chars = module.chars();
+                chars
+                    .next()
+                    .map(|first| first.to_uppercase().chain(chars).collect())
+                    .unwrap_or_default()
+            }
+        };
+
+        IdentString::new(Ident::new(&variant, Span::call_site()))
     }
 
     fn as_module_ident(&self) -> IdentString {
-        IdentString::new(Ident::new(&self.to_string(), Span::call_site()))
+        let module = match self {
+            Self::Kubernetes(version) => version.to_string(),
+            // Versions like `1.2.0` or `2024-01` are turned into `v1_2_0` and `v2024_01`.
+            Self::SemVer(_) | Self::Custom(_) => {
+                let module: String = self
+                    .to_string()
+                    .chars()
+                    .map(|c| {
+                        if c.is_ascii_alphanumeric() {
+                            c.to_ascii_lowercase()
+                        } else {
+                            '_'
+                        }
+                    })
+                    .collect();
+
+                if module.starts_with(|c: char| c.is_ascii_alphabetic()) {
+                    module
+                } else {
+                    format!("v{module}")
+                }
+            }
+        };
+
+        IdentString::new(Ident::new(&module, Span::call_site()))
     }
 }
 
@@ -0,0 +1,131 @@
+use std::{fmt::Display, str::FromStr};
+
+use darling::{Error, FromMeta, Result};
+use syn::Path;
+
+/// A version, either declared via `#[versioned(version(name = "..."))]` or referenced by an item
+/// action via `since = "..."`.
+///
+/// When parsed from the macro input, the format of the version is inferred from the string. Each
+/// version is then resolved against the [`VersionFormat`] of the module, see
+/// [`VersionFormat::resolve`]. The derived ordering is only meaningful between versions of the
+/// same format, which is ensured by the resolution.
+#[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Version {
+    Kubernetes(k8s_version::Version),
+    SemVer(semver::Version),
+    Custom(CustomVersion),
+}
+
+/// A version using a custom, user-supplied format.
+///
+/// The macro cannot evaluate the ordering of the user-supplied type. Instead, custom versions are
+/// ordered by their position in the list of declared versions.
+#[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct CustomVersion {
+    pub position: usize,
+    pub name: String,
+}
+
+impl FromMeta for Version {
+    fn from_string(value: &str) -> Result<Self> {
+        if let Ok(version) = k8s_version::Version::from_str(value) {
+            return Ok(Self::Kubernetes(version));
+        }
+
+        if let Ok(version) = semver::Version::parse(value) {
+            return Ok(Self::SemVer(version));
+        }
+
+        if value.is_empty() {
+            return Err(Error::custom("version must not be empty"));
+        }
+
+        Ok(Self::Custom(CustomVersion {
+            position: 0,
+            name: value.to_owned(),
+        }))
+    }
+}
+
+impl Display for Version {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Kubernetes(version) => version.fmt(f),
+            Self::SemVer(version) => version.fmt(f),
+            Self::Custom(version) => f.write_str(&version.name),
+        }
+    }
+}
+
+/// Supported version formats of a versioned module.
+///
+/// - `version_format = "kubernetes"`: Kubernetes API versions, like `v1alpha1`. This is the
+///   default and the only format which supports generating CRDs.
+/// - `version_format = "semver"`: SemVer versions, like `1.2.0`.
+/// - `version_format(custom = "path::to::Version")`: Versions of a user-supplied type, which needs
+///   to implement [`FromStr`] and [`Ord`].
+#[derive(Clone, Debug, Default)]
+pub enum VersionFormat {
+    #[default]
+    Kubernetes,
+    SemVer,
+    Custom(Path),
+}
+
+impl FromMeta for VersionFormat {
+    fn from_string(value: &str) -> Result<Self> {
+        match value {
+            "kubernetes" => Ok(Self::Kubernetes),
+            "semver" => Ok(Self::SemVer),
+            _ => Err(Error::unknown_value(value)),
+        }
+    }
+
+    fn from_list(items: &[darling::ast::NestedMeta]) -> Result<Self> {
+        #[derive(FromMeta)]
+        struct CustomFormat {
+            custom: Path,
+        }
+
+        CustomFormat::from_list(items).map(|format| Self::Custom(format.custom))
+    }
+}
+
+impl VersionFormat {
+    pub fn is_kubernetes(&self) -> bool {
+        matches!(self, Self::Kubernetes)
+    }
+
+    /// Resolves a declared `version` according to this format.
+    ///
+    /// Returns an error if the version doesn't use this format. Custom versions are assigned their
+    /// `position` in the list of declared versions, which defines their order.
+    pub fn resolve(&self, version: &Version, position: usize) -> Result<Version> {
+        match (self, version) {
+            (Self::Kubernetes, Version::Kubernetes(_)) | (Self::SemVer, Version::SemVer(_)) => {
+                Ok(version.clone())
+            }
+            (Self::Kubernetes, version) => k8s_version::Version::from_str(&version.to_string())
+                .map(Version::Kubernetes)
+                .map_err(|err| {
+                    Error::custom(format!(
+                        "invalid Kubernetes API version {version:?}: {err}",
+                        version = version.to_string()
+                    ))
+                }),
+            (Self::SemVer, version) => semver::Version::parse(&version.to_string())
+                .map(Version::SemVer)
+                .map_err(|err| {
+                    Error::custom(format!(
+                        "invalid SemVer version {version:?}: {err}",
+                        version = version.to_string()
+                    ))
+                }),
+            (Self::Custom(_), version) => Ok(Version::Custom(CustomVersion {
+                name: version.to_string(),
+                position,
+            })),
+        }
+    }
+}
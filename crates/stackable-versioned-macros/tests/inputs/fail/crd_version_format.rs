@@ -0,0 +1,23 @@
+use stackable_versioned_macros::versioned;
+
+#[versioned(
+    version(name = "1.0.0"),
+    version(name = "1.1.0"),
+    options(version_format = "semver")
+)]
+mod versioned {
+    #[versioned(crd(group = "stackable.tech", doc = "Test"))]
+    #[derive(
+        Clone,
+        Debug,
+        serde::Deserialize,
+        serde::Serialize,
+        schemars::JsonSchema,
+        kube::CustomResource,
+    )]
+    struct FooSpec {
+        bar: usize,
+    }
+}
+
+fn main() {}
@@ -0,0 +1,14 @@
+use stackable_versioned_macros::versioned;
+
+#[versioned(
+    version(name = "2024-04"),
+    version(name = "2023-10"),
+    options(allow_unsorted, version_format(custom = "crate::ReleaseVersion"))
+)]
+mod versioned {
+    struct Foo {
+        bar: usize,
+    }
+}
+
+fn main() {}
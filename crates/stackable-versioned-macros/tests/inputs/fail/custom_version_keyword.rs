@@ -0,0 +1,14 @@
+use stackable_versioned_macros::versioned;
+
+#[versioned(
+    version(name = "type"),
+    version(name = "self"),
+    options(version_format(custom = "crate::Release"))
+)]
+mod versioned {
+    struct Foo {
+        bar: usize,
+    }
+}
+
+fn main() {}
@@ -0,0 +1,15 @@
+use stackable_versioned_macros::versioned;
+
+#[versioned(
+    version(name = "1.0.0"),
+    version(name = "v1"),
+    options(version_format = "semver")
+)]
+mod versioned {
+    struct Foo {
+        #[versioned(added(since = "1.0"))]
+        bar: usize,
+    }
+}
+
+fn main() {}
@@ -0,0 +1,43 @@
+use stackable_versioned::versioned;
+// ---
+#[versioned(
+    version(name = "2023-10"),
+    version(name = "2024-04"),
+    version(name = "2025-01", deprecated),
+    options(version_format(custom = "crate::ReleaseVersion"))
+)]
+// ---
+pub mod versioned {
+    pub struct Payload {
+        endpoint: String,
+
+        #[versioned(added(since = "2024-04"))]
+        ttl_seconds: Option<u64>,
+
+        #[versioned(changed(since = "2025-01", from_name = "attempts"))]
+        retries: u32,
+    }
+}
+// ---
+fn main() {}
+
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+pub struct ReleaseVersion {
+    year: u16,
+    month: u8,
+}
+
+impl std::str::FromStr for ReleaseVersion {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let (year, month) = input
+            .split_once('-')
+            .ok_or_else(|| format!("release version {input:?} must be YEAR-MONTH"))?;
+
+        Ok(Self {
+            year: year.parse().map_err(|_| "invalid year".to_owned())?,
+            month: month.parse().map_err(|_| "invalid month".to_owned())?,
+        })
+    }
+}
@@ -0,0 +1,31 @@
+use stackable_versioned::versioned;
+// ---
+#[versioned(
+    version(name = "1.0.0"),
+    version(name = "1.1.0"),
+    version(name = "2.0.0-rc.1"),
+    version(name = "2.0.0"),
+    options(version_format = "semver")
+)]
+// ---
+pub mod versioned {
+    pub struct Config {
+        #[versioned(changed(since = "2.0.0-rc.1", from_name = "listen_port"))]
+        port: u16,
+
+        #[versioned(added(since = "1.1.0"))]
+        log_level: String,
+
+        #[versioned(deprecated(since = "2.0.0"))]
+        deprecated_legacy_mode: bool,
+    }
+
+    pub enum Mode {
+        Simple,
+
+        #[versioned(deprecated(since = "2.0.0"))]
+        DeprecatedLegacy,
+    }
+}
+// ---
+fn main() {}
@@ -25,6 +25,7 @@ mod inputs {
         // mod crate_overrides;
         // mod crate_overrides_only_kube;
         // mod crd_doc;
+        // mod custom_version;
         // mod docs;
         // mod downgrade_with;
         // mod enum_fields;
@@ -33,6 +34,7 @@ mod inputs {
         // mod renamed_field;
         // mod renamed_kind;
         // mod scale;
+        // mod semver;
         // mod shortnames;
         // mod submodule;
     }
@@ -40,10 +42,14 @@ mod inputs {
     mod fail {
         // mod applied_to_struct;
         // mod changed;
+        // mod crd_version_format;
+        // mod custom_version_allow_unsorted;
+        // mod custom_version_keyword;
         // mod deprecate;
         // mod spec_suffix;
         // mod unknown_version;
         // mod submodule_invalid_name;
+        // mod version_format;
     }
 }
 
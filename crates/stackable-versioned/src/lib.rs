@@ -7,8 +7,11 @@
 //!
 //! - Kubernetes API versions (eg: `v1alpha1`, `v1beta1`, `v1`, `v2`), with optional support for
 //!   generating CRDs.
+//! - SemVer versions (eg: `1.0.0`, `1.1.0`, `2.0.0-rc.1`).
+//! - Custom version formats, using a user-supplied type which implements [`FromStr`] and [`Ord`].
 //!
-//! Support will be extended to SemVer versions, as well as custom version formats in the future.
+//! SemVer and custom version formats support the same actions and conversions, but cannot be used
+//! to generate CRDs.
 //!
 //! See [`versioned`] for an in-depth usage guide and a list of supported arguments.
 //!
@@ -35,7 +38,7 @@
 //! ```
 //!
 //! Credit: Tua Xiong in <https://asciiart.website/art/4323>
-use std::collections::BTreeMap;
+use std::{collections::BTreeMap, fmt::Display, str::FromStr};
 
 use schemars::{Schema, json_schema};
 use snafu::{ErrorCompat, Snafu};
@@ -194,6 +197,53 @@ pub struct UnknownDesiredApiVersionError {
     pub api_version: String,
 }
 
+/// This error indicates that custom versions are not declared in ascending order.
+#[derive(Debug, Snafu)]
+pub enum CustomVersionOrderError {
+    #[snafu(display("failed to parse version {version:?}: {message}"))]
+    ParseVersion { version: String, message: String },
+
+    #[snafu(display(
+        "version {version:?} must be greater than the previously declared version {previous:?}"
+    ))]
+    NotAscending { version: String, previous: String },
+}
+
+/// Checks that custom `versions` are declared in strictly ascending order, according to the [`Ord`]
+/// implementation of `V`.
+///
+/// The [`versioned`] macro orders custom versions by the order in which they are declared. This
+/// function is called by a test generated by the macro to ensure that this order matches the order
+/// of the version type.
+pub fn check_custom_version_order<V>(versions: &[&str]) -> Result<(), CustomVersionOrderError>
+where
+    V: FromStr + Ord,
+    V::Err: Display,
+{
+    let mut previous: Option<(&str, V)> = None;
+
+    for version in versions {
+        let parsed = V::from_str(version).map_err(|err| CustomVersionOrderError::ParseVersion {
+            version: (*version).to_owned(),
+            message: err.to_string(),
+        })?;
+
+        if let Some((previous_version, previous_parsed)) = &previous
+            && *previous_parsed >= parsed
+        {
+            return NotAscendingSnafu {
+                version: *version,
+                previous: *previous_version,
+            }
+            .fail();
+        }
+
+        previous = Some((version, parsed));
+    }
+
+    Ok(())
+}
+
 pub fn jthong_path(parent: &str, child: &str) -> String {
     format!("{parent}.{child}")
 }
@@ -0,0 +1,118 @@
+use std::str::FromStr;
+
+use stackable_versioned::{CustomVersionOrderError, check_custom_version_order, versioned};
+
+#[versioned(
+    version(name = "1.0.0"),
+    version(name = "1.1.0"),
+    version(name = "2.0.0"),
+    options(version_format = "semver")
+)]
+mod config {
+    #[derive(Debug, PartialEq, Eq)]
+    pub struct Config {
+        #[versioned(changed(since = "2.0.0", from_name = "listen_port"))]
+        pub port: u16,
+
+        #[versioned(added(since = "1.1.0"))]
+        pub log_level: Option<String>,
+    }
+}
+
+#[versioned(
+    version(name = "2023-10"),
+    version(name = "2024-04"),
+    options(version_format(custom = "ReleaseVersion"))
+)]
+mod payload {
+    #[derive(Debug, PartialEq, Eq)]
+    pub struct Payload {
+        pub endpoint: String,
+
+        #[versioned(changed(since = "2024-04", from_name = "attempts"))]
+        pub retries: u32,
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ReleaseVersion {
+    year: u16,
+    month: u8,
+}
+
+impl FromStr for ReleaseVersion {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let (year, month) = input
+            .split_once('-')
+            .ok_or_else(|| format!("release version {input:?} must be YEAR-MONTH"))?;
+
+        Ok(Self {
+            year: year.parse().map_err(|_| format!("invalid year {year:?}"))?,
+            month: month
+                .parse()
+                .map_err(|_| format!("invalid month {month:?}"))?,
+        })
+    }
+}
+
+#[test]
+fn semver_conversion() {
+    let config = v1_0_0::Config { listen_port: 8080 };
+    let config: v1_1_0::Config = config.into();
+    let config: v2_0_0::Config = config.into();
+
+    assert_eq!(
+        config,
+        v2_0_0::Config {
+            port: 8080,
+            log_level: None
+        }
+    );
+
+    let config: v1_1_0::Config = config.into();
+    assert_eq!(
+        config,
+        v1_1_0::Config {
+            listen_port: 8080,
+            log_level: None
+        }
+    );
+}
+
+#[test]
+fn custom_version_conversion() {
+    let payload = v2023_10::Payload {
+        endpoint: "https://example.com".to_owned(),
+        attempts: 3,
+    };
+    let payload: v2024_04::Payload = payload.into();
+
+    assert_eq!(
+        payload,
+        v2024_04::Payload {
+            endpoint: "https://example.com".to_owned(),
+            retries: 3
+        }
+    );
+}
+
+#[test]
+fn custom_version_order() {
+    assert!(
+        check_custom_version_order::<ReleaseVersion>(&["2023-10", "2024-04", "2025-01"]).is_ok()
+    );
+    assert!(matches!(
+        check_custom_version_order::<ReleaseVersion>(&["2024-04", "2023-10"]),
+        Err(CustomVersionOrderError::NotAscending { .. })
+    ));
+    assert!(matches!(
+        check_custom_version_order::<ReleaseVersion>(&["2023-10", "2023-10"]),
+        Err(CustomVersionOrderError::NotAscending { .. })
+    ));
+    assert!(matches!(
+        check_custom_version_order::<ReleaseVersion>(&["2023"]),
+        Err(CustomVersionOrderError::ParseVersion { .. })
+    ));
+}